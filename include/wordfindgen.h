@@ -0,0 +1,43 @@
+/* wordfindgen.h - the C interface of libwordfindgen, built with cargo build --release --features ffi
+ *
+ * A puzzle from wordfindgen_generate stays valid until it's given to wordfindgen_free. Text goes in
+ * as utf-8 with its length, and a failed call returns NULL or 0 with the reason in wordfindgen_error.
+ */
+#ifndef WORDFINDGEN_H
+#define WORDFINDGEN_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct WordfindPuzzle WordfindPuzzle;
+
+/* a filled in puzzle from the words, one to a line, and the options, a json object of command line
+   options like {"size": 12, "hard": true}, which can be empty, NULL when it can't be made */
+WordfindPuzzle *wordfindgen_generate(const uint8_t *words, size_t words_len, const uint8_t *options, size_t options_len, uint64_t seed);
+
+/* columns and rows in the grid */
+size_t wordfindgen_width(const WordfindPuzzle *puzzle);
+size_t wordfindgen_height(const WordfindPuzzle *puzzle);
+
+/* copies the utf-8 letter in column x of row y, counted from 0, into buffer and returns its length
+   in bytes, nothing is copied when buffer is too small and 0 is returned outside the grid */
+size_t wordfindgen_cell(const WordfindPuzzle *puzzle, size_t x, size_t y, uint8_t *buffer, size_t buffer_len);
+
+/* the whole puzzle as json, with the words and where they are, free it with wordfindgen_string_free */
+char *wordfindgen_json(const WordfindPuzzle *puzzle);
+
+/* why the last call on this thread failed, NULL when nothing has */
+const char *wordfindgen_error(void);
+
+void wordfindgen_free(WordfindPuzzle *puzzle);
+void wordfindgen_string_free(char *text);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
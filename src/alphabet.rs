@@ -0,0 +1,134 @@
+// Alphabet - the writing systems fill_in has letters for
+//
+// The filler has to come from the same script as the words or they stand out at a glance
+//
+#[derive(Debug,Clone,Copy,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Alphabet {
+    Latin,
+    Greek,
+    Cyrillic,
+    Hebrew,
+    Arabic,
+    // for math worksheets where the entries are numbers
+    Digits,
+}
+
+impl Alphabet {
+    // from_name - the alphabet for a name given on the command line
+    pub fn from_name(name: &str) -> Option<Alphabet> {
+        match name.to_ascii_lowercase().as_str() {
+            "latin" => Some(Alphabet::Latin),
+            "greek" => Some(Alphabet::Greek),
+            "cyrillic" | "russian" => Some(Alphabet::Cyrillic),
+            "hebrew" => Some(Alphabet::Hebrew),
+            "arabic" => Some(Alphabet::Arabic),
+            "digits" | "numbers" => Some(Alphabet::Digits),
+            _ => None,
+        }
+    }
+    
+    // letters - the uppercase letters of the alphabet, the final forms of hebrew letters are left out
+    pub fn letters(&self) -> &'static str {
+        match self {
+            Alphabet::Latin => "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+            Alphabet::Greek => "ΑΒΓΔΕΖΗΘΙΚΛΜΝΞΟΠΡΣΤΥΦΧΨΩ",
+            Alphabet::Cyrillic => "АБВГДЕЁЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯ",
+            Alphabet::Hebrew => "אבגדהוזחטיכלמנסעפצקרשת",
+            Alphabet::Arabic => "ابتثجحخدذرزسشصضطظعغفقكلمنهوي",
+            Alphabet::Digits => "0123456789",
+        }
+    }
+    
+    // detect - the alphabet most of the letters in the words are from, latin when there are no letters
+    //          Digits count as their own alphabet, so a list of numbers is filled with digits
+    pub fn detect(words: &[String]) -> Alphabet {
+        let mut counts = [0usize; 6];
+        for c in words.iter().flat_map(|w| w.chars()).filter(|c| c.is_alphabetic() || c.is_ascii_digit()) {
+            let i = match c as u32 {
+                0x30..=0x39 => 5,
+                0x0370..=0x03ff | 0x1f00..=0x1fff => 1,
+                0x0400..=0x052f => 2,
+                0x0590..=0x05ff | 0xfb1d..=0xfb4f => 3,
+                0x0600..=0x06ff | 0x0750..=0x077f | 0xfb50..=0xfdff | 0xfe70..=0xfeff => 4,
+                _ => 0,
+            };
+            counts[i] += 1;
+        }
+        
+        // ties go to the earlier alphabet, so latin wins an empty list
+        let mut best = 0;
+        for (i, count) in counts.iter().enumerate() {
+            if *count > counts[best] { best = i; }
+        }
+        [Alphabet::Latin, Alphabet::Greek, Alphabet::Cyrillic, Alphabet::Hebrew, Alphabet::Arabic, Alphabet::Digits][best]
+    }
+}
+
+// Arabic presentation forms B, from U+FE80, as each plain letter and how many shaped forms it has
+const ARABIC_FORMS: [(char, u32); 36] = [
+    ('ء', 1), ('آ', 2), ('أ', 2), ('ؤ', 2), ('إ', 2), ('ئ', 4), ('ا', 2), ('ب', 4), ('ة', 2), ('ت', 4), ('ث', 4), ('ج', 4),
+    ('ح', 4), ('خ', 4), ('د', 2), ('ذ', 2), ('ر', 2), ('ز', 2), ('س', 4), ('ش', 4), ('ص', 4), ('ض', 4), ('ط', 4), ('ظ', 4),
+    ('ع', 4), ('غ', 4), ('ف', 4), ('ق', 4), ('ك', 4), ('ل', 4), ('م', 4), ('ن', 4), ('ه', 4), ('و', 2), ('ى', 2), ('ي', 4),
+];
+
+// plain_arabic - the text with arabic presentation forms, the initial, medial, final, and isolated
+//                shapes of each letter, turned back into the plain letters so each one gets a cell
+//                The renderer shapes the letters again, in a grid they show in their isolated form
+pub fn plain_arabic(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    for c in text.chars() {
+        let mut code = c as u32;
+        if !(0xfe80..=0xfefc).contains(&code) {
+            plain.push(c);
+            continue;
+        }
+        
+        // the lam alef ligatures at the end are two letters
+        if code >= 0xfef5 {
+            plain.push('ل');
+            plain.push(['آ', 'أ', 'إ', 'ا'][((code - 0xfef5) / 2) as usize]);
+            continue;
+        }
+        code -= 0xfe80;
+        for (letter, forms) in ARABIC_FORMS.iter() {
+            if code < *forms {
+                plain.push(*letter);
+                break;
+            }
+            code -= forms;
+        }
+    }
+    plain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|w| w.to_string()).collect()
+    }
+    
+    #[test]
+    fn detect_script() {
+        assert_eq!(Alphabet::detect(&words(&["ΑΛΦΑ", "ΒΗΤΑ"])), Alphabet::Greek);
+        assert_eq!(Alphabet::detect(&words(&["кошка", "собака", "TV"])), Alphabet::Cyrillic);
+        assert_eq!(Alphabet::detect(&words(&["שלום", "ספר"])), Alphabet::Hebrew);
+        assert_eq!(Alphabet::detect(&words(&["كتاب"])), Alphabet::Arabic);
+        assert_eq!(Alphabet::detect(&words(&["niño", "123"])), Alphabet::Latin);
+        assert_eq!(Alphabet::detect(&words(&["3.14159", "X2Y3"])), Alphabet::Digits);
+        assert_eq!(Alphabet::detect(&[]), Alphabet::Latin);
+        assert_eq!(Alphabet::Greek.letters().chars().count(), 24);
+        assert_eq!(Alphabet::Cyrillic.letters().chars().count(), 33);
+        assert_eq!(Alphabet::from_name("Russian"), Some(Alphabet::Cyrillic));
+    }
+    
+    #[test]
+    fn presentation_forms() {
+        // kitab written with the initial, medial, and final shapes
+        assert_eq!(plain_arabic("\u{fedb}\u{fe98}\u{fe8e}\u{fe90}"), "كتاب");
+        assert_eq!(plain_arabic("\u{fe80}\u{fef2}\u{fefb}"), "ءيلا");
+        assert_eq!(plain_arabic("shalom שלום"), "shalom שלום");
+    }
+}
@@ -1,28 +1,286 @@
 use std::env;
+use std::io::{self, IsTerminal, Write};
 use std::process;
-use wordfindgen::Config;
+use std::sync::Arc;
+use log::{LevelFilter, Log, Metadata, Record};
+use wordfindgen::{Command, ErrorFormat, ErrorKind, Progress, PuzzleStats, WordfindError};
+
+// Characters across the progress bar
+const BAR_WIDTH: usize = 30;
+
+// The logger the log messages of -v and -vv are printed with
+static STDERR_LOG: StderrLog = StderrLog;
+
+// StderrLog - prints the log messages on stderr, log::max_level picks which ones get through
+struct StderrLog;
+
+impl Log for StderrLog {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+    
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("{}", record.args());
+        }
+    }
+    
+    fn flush(&self) {}
+}
 
 // The main entry point to the program
 // This fn gathers the command line args into a config struct
 // Then calls the fn that generates the puzzle
 //
-// The first arg should be the name of a text file with the words to place in the puzzle
-// If a second argument is included the puzzle will be more difficult,
-//    by also placing words right to left (backwards)
+// The only positional arg is the name of a text file with the words to place in the puzzle
+// --hard makes the puzzle more difficult by also placing words right to left (backwards)
+// Run with --help to see the rest of the options
 //
 // The output csv can be opened, formatted, and printed from any spreadsheet program
 // It works best if the puzzle grid characters are centered vertically and horizontally with
 // borders drawn on all sides
 fn main() {
-    let config = Config::new(env::args()).unwrap_or_else(|err| {
-        eprintln!("There is a problem with your command line: {}", err);
-        process::exit(1);
+    let command = Command::from_args(env::args()).unwrap_or_else(|err| {
+        if json_errors() {
+            eprintln!("{}", WordfindError::Usage(err).to_json());
+        } else {
+            eprintln!("There is a problem with your command line: {}", err);
+            eprintln!("Run with --help to see the available options");
+        }
+        process::exit(ErrorKind::Usage.exit_code());
     });
     
-    if let Err(e) = wordfindgen::run(config) {
-        eprintln!("There was an error generating: {}", e);
-        process::exit(1);
+    let (mut config, book, convert) = match command {
+        Command::Generate(config) => (*config, false, None),
+        Command::Book(config) => (*config, true, None),
+        Command::Convert(config, puzzle) => (*config, false, Some(puzzle)),
+        Command::DryRun(config) => {
+            dry_run(*config);
+            return;
+        },
+        Command::Solve(config, grid) => {
+            solve(*config, &grid);
+            return;
+        },
+        Command::Play(config) => {
+            let error_format = config.error_format;
+            if let Err(err) = wordfindgen::play(*config, io::stdin().lock(), io::stdout()) {
+                fail(&err, error_format);
+            }
+            return;
+        },
+        Command::Serve(address) => {
+            eprintln!("Listening on http://{}", address);
+            if let Err(err) = wordfindgen::serve(&address) {
+                fail(&err, ErrorFormat::Text);
+            }
+            return;
+        },
+        Command::Help => {
+            print!("{}", wordfindgen::USAGE);
+            return;
+        },
+        Command::Version => {
+            println!("wordfindgen {}", env!("CARGO_PKG_VERSION"));
+            return;
+        },
+    };
+    
+    // -q prints nothing but errors, and stdout is kept clean when the puzzle itself is going there
+    let quiet = config.quiet;
+    let show_stats = config.stats;
+    let to_stdout = config.output.as_deref() == Some("-");
+    let error_format = config.error_format;
+    
+    // -v shows the info messages and -vv the debug ones too, -q turns off even the warnings
+    log::set_max_level(match config.verbose {
+        _ if quiet => LevelFilter::Off,
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        _ => LevelFilter::Debug,
+    });
+    if log::set_logger(&STDERR_LOG).is_err() {
+        log::set_max_level(LevelFilter::Off);
+    }
+    
+    // a progress bar on stderr, only when someone is watching it and it won't be in the way
+    // of the log messages
+    let show_progress = !quiet && config.verbose == 0 && io::stderr().is_terminal();
+    if show_progress {
+        config.progress = Some(Arc::new(draw_progress));
+    }
+    let result = match convert {
+        Some(puzzle) => wordfindgen::convert(config, &puzzle),
+        None if book => wordfindgen::run_book(config),
+        None => wordfindgen::run(config),
+    };
+    if show_progress {
+        eprint!("\r\x1b[K");
+    }
+    let summary = result.unwrap_or_else(|e| fail(&e, error_format));
+    
+    // the puzzle goes on stderr when it's been written to stdout
+    for shown in summary.shown.iter() {
+        if to_stdout {
+            eprintln!("{}", shown);
+        } else {
+            println!("{}", shown);
+        }
+    }
+    if quiet {
+        return;
+    }
+    if !summary.no_letters.is_empty() {
+        eprintln!("These lines have no letters once the punctuation is taken out and were left out: {}", summary.no_letters.join(", "));
+    }
+    if !summary.duplicates.is_empty() {
+        eprintln!("These words were already in the list and were only used once: {}", summary.duplicates.join(", "));
+    }
+    if !summary.skipped.is_empty() {
+        eprintln!("These words could not be placed and were left out: {}", summary.skipped.join(", "));
+    }
+    for (puzzle, density) in summary.sparse.iter() {
+        let which = if summary.puzzles > 1 { format!("puzzle {}", puzzle) } else { String::from("the puzzle") };
+        eprintln!("The words of {} cover only {:.0}% of the grid, under the --density asked for", which, density * 100.0);
+    }
+    for (puzzle, words) in summary.uncleared.iter() {
+        let which = if summary.puzzles > 1 { format!("puzzle {}", puzzle) } else { String::from("the puzzle") };
+        eprintln!("The letters of the words in {} also spell out {}, the fill couldn't get rid of them", which, words.join(", "));
+    }
+    
+    if show_stats {
+        print_stats(&summary.stats);
     }
     
-    println!("Done!");
+    if !to_stdout {
+        for file in summary.files.iter() {
+            println!("Wrote {}", file.display());
+        }
+        println!("Placed {} in {} after {} in {:.2}s", plural(summary.placed, "word"), plural(summary.puzzles, "puzzle"), plural(summary.attempts, "attempt"), summary.elapsed.as_secs_f32());
+        let mut sizes: Vec<String> = Vec::new();
+        for (width, height) in summary.sizes.iter() {
+            let size = format!("{} x {}", width, height);
+            if !sizes.contains(&size) {
+                sizes.push(size);
+            }
+        }
+        if !sizes.is_empty() {
+            let fill = summary.fill.iter().sum::<f32>() / summary.fill.len() as f32;
+            let seed = summary.seed.map(|seed| format!(", seed {}", seed)).unwrap_or_default();
+            println!("{} {}, {:.0}% of the cells used by the words{}", if sizes.len() == 1 { "Grid" } else { "Grids" }, sizes.join(", "), fill * 100.0, seed);
+        }
+        if let (Some(easiest), Some(hardest)) = (summary.levels.iter().min(), summary.levels.iter().max()) {
+            if easiest == hardest {
+                println!("Difficulty level {} of 5", easiest);
+            } else {
+                println!("Difficulty levels {} to {} of 5", easiest, hardest);
+            }
+        }
+        if !summary.ids.is_empty() {
+            println!("{} {}", if summary.ids.len() == 1 { "Puzzle ID" } else { "Puzzle IDs" }, summary.ids.join(", "));
+        }
+    }
+}
+
+// print_stats - the --stats report for each puzzle, on stderr so it stays out of a puzzle
+//               written to stdout
+fn print_stats(stats: &[PuzzleStats]) {
+    for (i, puzzle) in stats.iter().enumerate() {
+        if stats.len() > 1 {
+            eprintln!("Puzzle {}:", i + 1);
+        }
+        let directions: Vec<String> = puzzle.directions.iter().map(|(dir, count)| format!("{:?} {}", dir, count)).collect();
+        eprintln!("  words per direction: {}", directions.join(", "));
+        eprintln!("  overlapping cells: {}", puzzle.overlaps);
+        eprintln!("  filled before the random letters: {:.1}%", puzzle.density * 100.0);
+        eprintln!("  spots tried per word: {:.1} ({} for {})", puzzle.tries_per_word(), puzzle.tries, plural(puzzle.words, "word"));
+        eprintln!("  grids started over: {}", puzzle.regens);
+    }
+}
+
+// fail - report the error the way --error-format asks and exit with the code for its kind
+fn fail(err: &WordfindError, error_format: ErrorFormat) -> ! {
+    match error_format {
+        ErrorFormat::Text => eprintln!("There was an error generating: {}", err),
+        ErrorFormat::Json => eprintln!("{}", err.to_json()),
+    }
+    process::exit(err.kind().exit_code());
+}
+
+// json_errors - true when the command line asks for json errors, for reporting a command line
+//               that couldn't be parsed
+fn json_errors() -> bool {
+    let args: Vec<String> = env::args().collect();
+    let from_args = args.iter().any(|a| a == "--error-format=json") || args.windows(2).any(|pair| pair[0] == "--error-format" && pair[1] == "json");
+    from_args || env::var("WORDFINDGEN_ERROR_FORMAT").is_ok_and(|format| format == "json")
+}
+
+// dry_run - check the word list and report whether it's likely to fit, exiting with the placement
+//           exit code when it isn't
+fn dry_run(config: wordfindgen::Config) {
+    let quiet = config.quiet;
+    let error_format = config.error_format;
+    let report = wordfindgen::dry_run(config).unwrap_or_else(|e| fail(&e, error_format));
+    if !quiet {
+        println!("{} for a {} x {} grid, covering {:.0}% of it", plural(report.words, "word"), report.width, report.height, report.coverage * 100.0);
+        if !report.no_letters.is_empty() {
+            println!("These lines have no letters once the punctuation is taken out and would be left out: {}", report.no_letters.join(", "));
+        }
+        if !report.duplicates.is_empty() {
+            println!("These words are already in the list and would only be used once: {}", report.duplicates.join(", "));
+        }
+        if !report.skipped.is_empty() {
+            println!("These words didn't fit in a trial puzzle and would be left out: {}", report.skipped.join(", "));
+        }
+        if let Some(size) = report.roomier_size {
+            println!("The grid is crowded, --size {} would give the words more room", size);
+        }
+        match &report.failure {
+            Some(failure) => println!("The words probably won't fit: {}", failure),
+            None => println!("The words should fit, no files were written"),
+        }
+    }
+    if report.failure.is_some() {
+        process::exit(ErrorKind::Placement.exit_code());
+    }
+}
+
+// solve - print where each word is in the grid, exiting with the placement exit code when some of
+//         them aren't in it
+fn solve(config: wordfindgen::Config, grid: &str) {
+    let quiet = config.quiet;
+    let error_format = config.error_format;
+    let report = wordfindgen::solve(config, grid).unwrap_or_else(|e| fail(&e, error_format));
+    if !quiet {
+        for (word, places) in report.found.iter() {
+            let more = if places.len() > 1 { format!(", and {} more", plural(places.len() - 1, "place")) } else { String::new() };
+            println!("{}: ({}, {}) {:?}{}", word, places[0].x + 1, places[0].y + 1, places[0].dir, more);
+        }
+        if !report.missing.is_empty() {
+            println!("These words aren't in the {} x {} grid: {}", report.width, report.height, report.missing.join(", "));
+        }
+        if !report.hidden.is_empty() {
+            println!("Also in the grid:");
+            for (word, place) in report.hidden.iter() {
+                println!("  {}: ({}, {}) {:?}", word, place.x + 1, place.y + 1, place.dir);
+            }
+        }
+    }
+    if !report.missing.is_empty() {
+        process::exit(ErrorKind::Placement.exit_code());
+    }
+}
+
+// draw_progress - redraw the progress bar on the current line of stderr
+fn draw_progress(progress: Progress) {
+    let filled = BAR_WIDTH * progress.done / progress.total.max(1);
+    let puzzle = if progress.puzzles > 1 { format!("puzzle {} of {} ", progress.puzzle, progress.puzzles) } else { String::new() };
+    let mut stderr = io::stderr().lock();
+    let _ = write!(stderr, "\r{}[{}{}] {}/{} words, attempt {}\x1b[K", puzzle, "#".repeat(filled), "-".repeat(BAR_WIDTH - filled), progress.done, progress.total, progress.attempt);
+    let _ = stderr.flush();
+}
+
+// plural - a count and the noun, with an s unless there is exactly one
+fn plural(count: usize, noun: &str) -> String {
+    format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" })
 }
@@ -0,0 +1,212 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use super::{make_puzzle, start_timeout, Case, Config, Difficulty, Direction, Objective, PuzzleGrid, WordList, WordfindError};
+
+// Puzzle - the name library users see for a generated puzzle
+pub type Puzzle = PuzzleGrid;
+
+// PuzzleBuilder - fluent setup of a puzzle without going through the command line
+//
+// Puzzle::builder().size(25).hard(true).words(words).seed(42).build()?
+//
+#[derive(Default)]
+pub struct PuzzleBuilder {
+    config: Config,
+    words: Vec<String>,
+}
+
+impl PuzzleGrid {
+    pub fn builder() -> PuzzleBuilder {
+        PuzzleBuilder::default()
+    }
+}
+
+impl PuzzleBuilder {
+    // config - start from an existing config, the words file in it is ignored
+    pub fn config(mut self, config: Config) -> PuzzleBuilder {
+        self.config = config;
+        self
+    }
+    
+    // size - width and height of a square grid
+    pub fn size(mut self, size: usize) -> PuzzleBuilder {
+        self.config.width = size;
+        self.config.height = size;
+        self
+    }
+    
+    pub fn width(mut self, width: usize) -> PuzzleBuilder {
+        self.config.width = width;
+        self
+    }
+    
+    pub fn height(mut self, height: usize) -> PuzzleBuilder {
+        self.config.height = height;
+        self
+    }
+    
+    // hard - all eight directions, or the usual ones, without changing the rest of the config
+    pub fn hard(mut self, hard: bool) -> PuzzleBuilder {
+        self.config.difficulty = if hard { Difficulty::Hard } else { Difficulty::Medium };
+        self
+    }
+    
+    // directions - only place the words in these directions
+    pub fn directions(mut self, directions: &[Direction]) -> PuzzleBuilder {
+        self.config.directions = Some(directions.to_vec());
+        self
+    }
+    
+    // difficulty - one of the presets, see Difficulty::apply
+    pub fn difficulty(mut self, difficulty: Difficulty) -> PuzzleBuilder {
+        difficulty.apply(&mut self.config);
+        self
+    }
+    
+    // level - the level from 1 to 5 the puzzle should be rated at, see Config::level
+    pub fn level(mut self, level: u8) -> PuzzleBuilder {
+        self.config.level = Some(level);
+        self
+    }
+    
+    pub fn dense(mut self, dense: bool) -> PuzzleBuilder {
+        self.config.dense = dense;
+        self
+    }
+    
+    // optimize - move the words around for a better looking layout, see PuzzleGrid::optimize
+    pub fn optimize(mut self, objective: Objective) -> PuzzleBuilder {
+        self.config.optimize = Some(objective);
+        self
+    }
+    
+    pub fn maxtries(mut self, maxtries: usize) -> PuzzleBuilder {
+        self.config.maxtries = maxtries;
+        self
+    }
+    
+    pub fn candidates(mut self, candidates: usize) -> PuzzleBuilder {
+        self.config.candidates = candidates;
+        self
+    }
+    
+    pub fn seed(mut self, seed: u64) -> PuzzleBuilder {
+        self.config.seed = Some(seed);
+        self
+    }
+    
+    // title - the heading every output format puts above the grid
+    pub fn title<S: Into<String>>(mut self, title: S) -> PuzzleBuilder {
+        self.config.title = Some(title.into());
+        self
+    }
+    
+    pub fn subtitle<S: Into<String>>(mut self, subtitle: S) -> PuzzleBuilder {
+        self.config.subtitle = Some(subtitle.into());
+        self
+    }
+    
+    pub fn instructions<S: Into<String>>(mut self, instructions: S) -> PuzzleBuilder {
+        self.config.instructions = Some(instructions.into());
+        self
+    }
+    
+    // hidden_message - fill the leftover cells with this message in reading order before the random fill
+    pub fn hidden_message<S: Into<String>>(mut self, message: S) -> PuzzleBuilder {
+        self.config.hidden_message = Some(message.into());
+        self
+    }
+    
+    pub fn camouflage(mut self, camouflage: bool) -> PuzzleBuilder {
+        self.config.camouflage = camouflage;
+        self
+    }
+    
+    // fold_diacritics - hide the words in the grid without their accents, the word bank keeps them
+    pub fn fold_diacritics(mut self, fold: bool) -> PuzzleBuilder {
+        self.config.fold_diacritics = fold;
+        self
+    }
+    
+    // case - write the words and the filler in upper or lower case, or keep the case of the words
+    pub fn case(mut self, case: Case) -> PuzzleBuilder {
+        self.config.case = case;
+        self
+    }
+    
+    // rtl - the words read right to left, the easy directions are mirrored to match
+    pub fn rtl(mut self, rtl: bool) -> PuzzleBuilder {
+        self.config.rtl = rtl;
+        self
+    }
+    
+    // decoys - near misses of the words to write in before the fill
+    pub fn decoys(mut self, decoys: usize) -> PuzzleBuilder {
+        self.config.decoys = decoys;
+        self
+    }
+    
+    // words - add words to the puzzle, can be called more than once
+    pub fn words<I, S>(mut self, words: I) -> PuzzleBuilder
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.words.extend(words.into_iter().map(Into::into));
+        self
+    }
+    
+    // word_list - add the lines of a word list, with its clues, pins, and sections
+    pub fn word_list(mut self, list: &WordList) -> PuzzleBuilder {
+        self.words.extend(list.iter().cloned());
+        self
+    }
+    
+    // build - place the words and fill in the rest of the grid
+    //         Settings that can't work, like a word longer than the grid, come back as errors
+    pub fn build(mut self) -> Result<Puzzle, WordfindError> {
+        start_timeout(&mut self.config);
+        let mut rng = match self.config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        make_puzzle(&self.config, &self.words, &mut rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn build_puzzle() {
+        let puzzle = Puzzle::builder().size(12).hard(true).words(vec!["Hello", "Thanks"]).words(vec![String::from("Goodbye")]).seed(42).build().unwrap();
+        assert_eq!(puzzle.grid().len(), 12);
+        assert_eq!(puzzle.entries().len(), 3);
+        assert!(puzzle.grid().iter().flatten().all(|c| c != " "));
+        
+        let again = Puzzle::builder().size(12).hard(true).words(vec!["Hello", "Thanks", "Goodbye"]).seed(42).build().unwrap();
+        assert_eq!(puzzle.grid(), again.grid());
+        assert_eq!(puzzle.title(), None);
+        
+        let puzzle = Puzzle::builder().size(12).words(vec!["Hello"]).title("Greetings").instructions("Circle every word").build().unwrap();
+        assert_eq!(puzzle.title(), Some("Greetings"));
+        assert_eq!(puzzle.subtitle(), None);
+        assert_eq!(puzzle.instructions(), Some("Circle every word"));
+        
+        let list = crate::WordList::from_text("[Birds]\nowl | hoots\nhen\n");
+        let puzzle = Puzzle::builder().size(6).word_list(&list).seed(1).build().unwrap();
+        assert_eq!(puzzle.entries().len(), 2);
+        assert_eq!(puzzle.clue("OWL"), Some("hoots"));
+    }
+    
+    #[test]
+    fn build_errors() {
+        assert!(matches!(Puzzle::builder().size(10).build(), Err(WordfindError::NoWords)));
+        assert!(matches!(Puzzle::builder().size(0).words(vec!["a"]).build(), Err(WordfindError::InvalidSize { width: 0, height: 0 })));
+        match Puzzle::builder().size(4).words(vec!["Hello"]).build() {
+            Err(WordfindError::WordTooLong { word, width, height }) => assert_eq!((word.as_str(), width, height), ("Hello", 4, 4)),
+            _ => panic!("expected the word to be too long"),
+        }
+    }
+}
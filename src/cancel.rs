@@ -0,0 +1,47 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use crate::WordfindError;
+
+// CancelToken - stops a puzzle that is taking too long, from another thread or once a time limit passes
+//
+// Clones share the token, cancelling one of them stops every puzzle using any of them
+//
+#[derive(Debug,Clone,Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+    // when time runs out, and the time limit it came from for the error
+    deadline: Option<(Instant, Duration)>,
+}
+
+impl CancelToken {
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+    
+    // with_timeout - the same token, also stopping once timeout has passed from now
+    pub fn with_timeout(mut self, timeout: Duration) -> CancelToken {
+        self.deadline = Some((Instant::now() + timeout, timeout));
+        self
+    }
+    
+    // cancel - stop the puzzles using the token, they fail with WordfindError::Cancelled
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+    
+    pub fn is_cancelled(&self) -> bool {
+        self.check().is_err()
+    }
+    
+    // check - the error to stop with once the token is cancelled or its time has run out
+    pub fn check(&self) -> Result<(), WordfindError> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return Err(WordfindError::Cancelled);
+        }
+        match self.deadline {
+            Some((deadline, timeout)) if Instant::now() >= deadline => Err(WordfindError::TimedOut { timeout }),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,1477 @@
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::str::FromStr;
+use std::time::Duration;
+use super::{Alphabet, AnswerStyle, BankStyle, Case, Config, DensityMode, Difficulty, Direction, ErrorFormat, Objective, Shape, Theme, WordOrder, Format, DEFAULT_BLOCKLIST, LARGE_PRINT_SIZE, ENGLISH_FREQUENCIES};
+
+// USAGE - the text printed for --help
+pub const USAGE: &str = "\
+Usage: wordfindgen [OPTIONS] <WORDSFILE>...
+       wordfindgen book [OPTIONS] <DIR|MANIFEST>
+       wordfindgen solve [OPTIONS] <GRIDFILE> <WORDSFILE>...
+       wordfindgen convert [OPTIONS] <PUZZLEFILE>
+       wordfindgen play [OPTIONS] <WORDSFILE>...
+       wordfindgen serve [ADDRESS]
+
+Generates a word find puzzle from the words in WORDSFILE (one per line, - for stdin,
+or an http or https url when built with the http feature, https needs curl installed)
+and writes it to puzzle.csv, with the solution in answer_key.csv
+(pdf, html, and xlsx put the solution on a second page or sheet)
+The words of several files go in one puzzle, grouped by file in the word bank
+book makes a puzzle from each .txt file in DIR, or each file listed in MANIFEST,
+and writes them all to book.html (or book.pdf with --format pdf) with a contents
+page at the front, a puzzle on each page, and the answer keys at the back
+solve searches the grid in GRIDFILE, a csv, json, or text puzzle or a plain grid of letters,
+for the words in WORDSFILE in all eight directions and prints where each one starts, and with
+--dictionary lists the other words hiding in it too
+convert reads back a json or csv puzzle this program wrote and writes it in --format, with the
+words where they were
+play makes a puzzle and plays it in the terminal, a word is claimed by typing the column and row
+of its first and last letters, and the found words are shown in brackets with the time taken
+serve answers http requests on ADDRESS [default: 127.0.0.1:8080] when built with the serve
+feature, POST /puzzle a json object of the words, the options, and a seed, like
+{\"words\": [\"cat\", \"dog\"], \"options\": {\"size\": 12, \"format\": \"html\"}}, for the puzzle and
+its answer key in json, html, pdf, xlsx, ipuz, or markdown, json when no format is given
+
+Options:
+    --config <FILE>    read options from a toml file, size = 30 or title = \"Week 3\", the options
+                       given on the command line win
+                       Options can also be set with environment variables, WORDFINDGEN_SIZE=30
+                       or WORDFINDGEN_HARD=1, in between the config file and the command line
+    --hard             also place words backwards (right to left, bottom to top)
+    --difficulty <LEVEL> easy for left to right and top to bottom only, medium, hard for all eight
+                       directions, or expert for all eight packed together with decoys, a
+                       camouflage fill, and no word bank, options after it change that part
+                       [default: medium]
+    --level <N>        make puzzles rated at level N, 1 to 5, tuning the directions, packing,
+                       fill, and decoys until one comes out there, in place of --difficulty
+    --directions <LIST> only place words in these directions, a comma separated list of right,
+                       left, up, down, upright, upleft, downright, and downleft
+    --direction-weights <LIST> how often words go in each direction, like left=0.2,up=0.2 to
+                       make backwards words rarer, directions not listed count 1 and 0 is only
+                       used when a word fits nowhere else
+    --size <N>         width and height of the grid [default: 20]
+    --width <N>        width of the grid, for puzzles that aren't square
+    --height <N>       height of the grid, for puzzles that aren't square
+    --mask <FILE>      a shaped grid, FILE has a row of # for the cells to use and . for the ones
+                       to leave blank for each row of the grid, which is the size of the mask,
+                       and x for cells that get filler but no words
+    --reserve <COL,ROW,WIDTH,HEIGHT> keep the words out of an area of the grid, for clip art
+                       over a corner, its top left cell counted from 1, can be given more than once
+    --shape <SHAPE>    a shaped grid drawn for the grid size, circle, heart, star, or diamond
+    --shape-text <TEXT> a grid in the shape of TEXT in giant letters, like an initial or a short
+                       name, letters and digits only
+    --auto-size        pick the grid size from the length and number of the words
+    --auto-density <F> fraction of the grid the words should cover with --auto-size [default: 0.5]
+    --density <F>      fraction of the grid the words should cover before the fill, reached as
+                       --density-mode says
+    --density-mode <MODE> copies to write extra copies of the words, warn to only say when a
+                       puzzle falls short, size to pick the grid size for it, or pool to add
+                       words from --density-pool [default: copies]
+    --density-pool <FILE> extra words, one to a line, to add to the puzzle until it reaches
+                       --density, turns on --density-mode pool
+    --maxtries <N>     positions to try for each word before giving up, a quarter at random and
+                       the rest in order so the last few spots are found [default: 10000]
+    --grow <MAX>       make the grid bigger when the words don't fit, up to MAX wide or high
+    --skip-failed      leave out words that can't be placed instead of giving up
+    --no-backtrack     fail instead of searching every layout when random placement gets stuck
+    --dense            place words where they share the most letters with each other
+    --min-crossings <N> every word has to cross at least N of the others, for an interlocking
+                       puzzle
+    --balanced         spread the words evenly around the grid instead of letting them bunch up
+                       in one corner
+    --symmetric        --balanced with the words laid out to look the same turned upside down
+    --optimize <GOAL>  move the words around once they're in for a better looking layout, overlaps
+                       for words that cross a lot, centered for words in the middle of the grid, or
+                       symmetric for a layout that looks the same turned upside down
+    --no-overlap       never let words cross or share a letter
+    --bent             words turn a corner once instead of going in a straight line
+    --spangram <WORD>  the word from the list that has to reach across the grid from one edge to
+                       the other, turning a corner if it needs to
+    --require-edge <WORD> the word from the list that has to lie flush along one of the borders
+                       of the grid, can be given more than once
+    --crisscross       make a criss-cross to fill in instead of a word search, the words cross each
+                       other with the cells between them blacked out
+    --double           make two puzzles of the words laid out differently and write them into one
+                       file, side by side for text and a page each otherwise, for two to race
+    --reveal <PERCENT> circle that much of each word on the puzzle as hints, at least one letter
+    --spacing <N>      keep N blank cells between words, they never cross (or --kid-mode for 1)
+    --candidates <N>   generate N puzzles and keep the best looking one [default: 1]
+    --seed <N>         seed for the random number generator, makes the puzzle reproducible
+    --count <N>        make N different puzzles from the words, written to numbered files like
+                       puzzle_01.csv and answer_key_01.csv [default: 1]
+    --threads <N>      make the puzzles of a --count batch or a book N at a time
+                       [default: one for each core]
+    --dry-run          check the word list and whether the words are likely to fit the grid,
+                       without writing any files
+    --timeout <SECONDS> give up with an error when the puzzles take longer than this to make
+    --alphabet <NAME>  fill with latin, greek, cyrillic, hebrew, or arabic letters, or digits
+                       [default: the script of the words]
+    --fill-chars <LETTERS> letters to fill the rest of the grid with, repeat one to make it more common
+    --fill-chars-file <FILE> read the fill letters from FILE
+    --fill-frequencies <TABLE> pick fill letters as often as they appear in text, english or a
+                       file with a letter and its frequency on each line
+    --keep-chars <CHARS> punctuation to keep in the words, spaces, hyphens, and apostrophes are
+                       left out of the grid otherwise
+    --error-format <FORMAT> report errors as text or json, every problem in the word list is
+                       listed at once [default: text]
+    --wordlist-order <ORDER> print the word bank in the original, alpha, shuffled, or length order,
+                       or only for one format with the format first, like pdf=alpha
+                       [default: original]
+    --word-bank <STYLE> words, hidden to leave the word bank off the puzzle, count to show only
+                       how many words there are, or scrambled to mix up the letters of each word,
+                       the answer key always lists the words [default: words]
+    --min-length <N>   leave out words with fewer than N letters (or --min-len)
+    --max-length <N>   leave out words with more than N letters (or --max-len)
+    --max-words <N>    only use the first N words in the list
+    --random-words <N> only use N words picked at random from the list, like a dictionary file
+                       (or --random)
+    --column <COLUMN>  read the words file as csv or tsv with a row of headings, taking the
+                       words from the column with that heading or number from 1
+    --clue-column <COLUMN> with --column, print the clue in this column in place of each word
+    --theme <THEME>    use a built in word list in place of WORDSFILE, animals, countries,
+                       states, colors, or programming, with 15 of its words picked at random
+                       unless --max-words or --random-words says how many
+    --lang <CODE=FILE> make a puzzle of each language's words file, like --lang en=animals_en.txt
+                       --lang es=animals_es.txt, in place of WORDSFILE, each the same size with
+                       the words of the same line in the same spot where they fit, written to
+                       files named for the language like puzzle_es.csv
+    --match <PATTERN>  only use words matching the pattern, * is any letters, ? is one letter, and
+                       [abc] or [a-c] is one of the letters in the brackets
+    --dictionary <FILE> with solve, also look for every word in FILE at least --min-length letters
+                       long [default: 3], to find the words a puzzle spells by accident, the
+                       words file can be left out
+    --strict-duplicates stop with an error when a word is listed twice instead of using it once
+    --case <CASE>      write the puzzle in upper or lower case, or preserve the case of the words
+                       with mixed case filler [default: upper]
+    --rtl              the words read right to left, the easy directions are mirrored to match
+    --fold-accents     take the accents off the letters in the grid, the word bank keeps them
+    --camouflage       fill with the start of each word next to the real one and letters taken
+                       from the words, for expert puzzles
+    --decoys <N>       write N near misses of the words into the grid, like a word with one letter
+                       changed or its first few letters [default: 0]
+    --blocklist        re-roll random letters that spell a rude word in any direction
+    --blocklist-file <FILE> more words to keep out of the fill, one per line, turns on --blocklist
+    --hidden-message <TEXT> hide TEXT in the leftover cells, read left to right and top to bottom
+    --format <FORMAT>  csv, xlsx, text, md, json, ipuz, latex, html, html-interactive, svg,
+                       brf or braille for embossing or reading in braille, and pdf or png
+                       when built with those features [default: csv], or several with
+                       commas, like csv,pdf,json, to write the same puzzle in each
+    --delimiter <C>    csv field separator, e.g. ';' or tab [default: ,]
+    --margin <N>       blank csv columns to the left of the grid [default: 3]
+    --words-per-row <N> words on each line of the csv word list [default: 2]
+    --quote-all        quote every csv field
+    --bank-columns <N> columns of the word bank, or only for one format with the format first,
+                       like pdf=3 [default: 3 for pdf, png, svg, xlsx, html, and latex, 1 for
+                       text, and --words-per-row for csv]
+    --cell-size <N>    png grid cell size in pixels [default: 40]
+    --image-margin <N> png border size in pixels [default: 20]
+    --font-size <N>    png letter height in pixels [default: 28]
+    --large-print      24 point letters in bold black on white for low vision solvers, for pdf,
+                       html, and svg, with one puzzle to a page or an error if it won't fit
+    --letter-size <PT> pdf, html, and svg letter height in points, the cells and word bank are
+                       sized to match
+    --font <FAMILY>    pdf, html, and svg font, for pdf serif, sans, or mono
+    --high-contrast    bold letters, heavy borders, and answers in white on black
+    --numbered-answers number the words of the answer key at the cells they start at, with a
+                       legend of the numbers and words
+    --first-letters    bold the first letter of every word on the puzzle, a smaller hint than
+                       --reveal, for pdf, png, svg, html, latex, markdown, and xlsx
+    --template <FILE>  lay out the puzzle and answer key with a template of your own instead of
+                       the format, with {{title}}, {{grid}}, {{#words}}{{word}}{{/words}} and
+                       the other placeholders in the readme, the files get its extension
+    --title <TEXT>     heading above the grid [default: Word Find]
+    --subtitle <TEXT>  smaller second heading under the title
+    --instructions <TEXT> a line telling the solver what to do, under the headings
+    --no-answer-key    only write the puzzle, without the answer key
+    --combined         put the answer key in the puzzle file, a smaller copy beside the puzzle for
+                       svg and png, beside it for text, under it for csv, and on the next page
+                       for latex and braille
+    --verify           check every word is in the grid where it was placed and matches the answer
+                       key before writing anything
+    --refill           with convert, new random filler around the words, which stay where they
+                       are, from --seed when it's given
+    --answer-style <STYLE> how the csv and text answer keys show the filler letters,
+                       blank, lowercase, or dots [default: blank]
+    --output <FILE>    where to write the puzzle, - for stdout, which leaves out the answer key
+                       unless --answer-output is given [default: puzzle.<format>]
+    --answer-output <FILE> where to write the answer key [default: answer_key.<format>, or the
+                       --output name with _answer_key added when it has a {seed}, {title}, or
+                       {date} in it]
+    --answer-list <FILE> also write the start cell and direction of every word to FILE
+    --hint-file <FILE> also write the row and column of the first letter of every word to FILE
+    --output-dir <DIR> directory to write the files in, created if needed [default: .]
+                       The file names can have {date}, {seed}, and {title} in them, like
+                       --output {title}_{seed}.pdf, so runs don't write over each other
+    --force            write over output files that are already there instead of stopping
+    -v, --verbose      say what the generator is doing, like starting the grid over, -vv also
+                       says where every word went
+    -q, --quiet        print nothing but errors
+    --stats            print the words in each direction, the overlapping cells, how full the
+                       grid was before the fill, and the spots tried for each word
+    --show             print the puzzle and then its answer key with each word in its own
+                       color, to try out a word list without opening the files
+    -h, --help         print this help
+    -V, --version      print the version
+
+Exit codes:
+    0 the puzzle was made, 2 the command line or its settings can't work, 3 the word list has
+    problems, 4 the words didn't fit or solve didn't find them all, 5 a file couldn't be read or
+    written
+";
+
+// Where serve listens when it isn't given an address
+const SERVE_ADDRESS: &str = "127.0.0.1:8080";
+
+// Command - what the command line asked the program to do
+//
+pub enum Command {
+    Generate(Box<Config>),
+    // a puzzle book from a directory of word files or a manifest, see run_book
+    Book(Box<Config>),
+    // check the word list and whether it fits without writing anything, see dry_run
+    DryRun(Box<Config>),
+    // search the grid in the file for the words, see solve
+    Solve(Box<Config>, String),
+    // write a puzzle from a json or csv file in another format, see convert
+    Convert(Box<Config>, String),
+    // make a puzzle and play it in the terminal, see play
+    Play(Box<Config>),
+    // answer http requests for puzzles on the address, see serve
+    Serve(String),
+    Help,
+    Version,
+}
+
+impl Command {
+    // from_args - parse the command line, the first item is the program invocation and is skipped
+    //
+    // Options can be given as "--size 30" or "--size=30"
+    // Without a words file the words are read from stdin when something is piped into it
+    // WORDFINDGEN_ environment variables, like WORDFINDGEN_SIZE=30, are options that come before
+    // the command line so the flags win
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+        let mut args: Vec<String> = args.into_iter().collect();
+        if args.get(1).map(String::as_str) == Some("serve") {
+            return Command::parse(args, false);
+        }
+        let env = env_args(env::vars())?;
+        let at = if matches!(args.get(1).map(String::as_str), Some("book") | Some("solve") | Some("convert") | Some("play")) { 2 } else { args.len().min(1) };
+        args.splice(at..at, env);
+        Command::parse(args, !io::stdin().is_terminal())
+    }
+    
+    // parse - from_args, with piped saying whether the words can come from stdin
+    //         The options in a --config file go in front of the command line so the flags win
+    pub(crate) fn parse<I: IntoIterator<Item = String>>(args: I, piped: bool) -> Result<Command, String> {
+        let mut given: Vec<String> = args.into_iter().skip(1).collect();
+        // the options for serve come with each request
+        if given.first().map(String::as_str) == Some("serve") {
+            return match &given[1..] {
+                [] => Ok(Command::Serve(String::from(SERVE_ADDRESS))),
+                [help] if help == "-h" || help == "--help" => Ok(Command::Help),
+                [address] if !address.starts_with('-') => Ok(Command::Serve(address.clone())),
+                _ => Err(String::from("serve takes an address to listen on, the options come with each request")),
+            };
+        }
+        let book = given.first().map(String::as_str) == Some("book");
+        let solve = given.first().map(String::as_str) == Some("solve");
+        let convert = given.first().map(String::as_str) == Some("convert");
+        let play = given.first().map(String::as_str) == Some("play");
+        if book || solve || convert || play {
+            given.remove(0);
+        }
+        let mut file_options = Vec::new();
+        while let Some(i) = given.iter().position(|a| a == "--config" || a.starts_with("--config=")) {
+            let arg = given.remove(i);
+            let file = match arg.strip_prefix("--config=") {
+                Some(file) => file.to_string(),
+                None if i < given.len() => given.remove(i),
+                None => return Err(String::from("--config requires a value")),
+            };
+            let text = fs::read_to_string(&file).map_err(|e| format!("{}: {}", file, e))?;
+            file_options.extend(config_file_args(&text).map_err(|e| format!("{} {}", file, e))?);
+        }
+        file_options.extend(given);
+        
+        let mut args = file_options.into_iter();
+        let mut config = Config::default();
+        let mut wordsfiles = Vec::new();
+        let mut dry_run = false;
+        
+        while let Some(arg) = args.next() {
+            // split up --name=value so both forms are handled the same way
+            let (name, mut inline) = match arg.find('=') {
+                Some(i) if arg.starts_with("--") => (arg[..i].to_string(), Some(arg[i + 1..].to_string())),
+                _ => (arg.clone(), None),
+            };
+            let mut value = || inline.take().or_else(|| args.next()).ok_or(format!("{} requires a value", name));
+            
+            match name.as_str() {
+                "-h" | "--help" => return Ok(Command::Help),
+                "-V" | "--version" => return Ok(Command::Version),
+                "-v" | "--verbose" => config.verbose += 1,
+                "-vv" => config.verbose += 2,
+                "-q" | "--quiet" => config.quiet = true,
+                "--stats" => config.stats = true,
+                "--show" => config.show = true,
+                "--dry-run" => dry_run = true,
+                "--verify" => config.verify = true,
+                "--refill" => config.refill = true,
+                "--hard" => config.difficulty = Difficulty::Hard,
+                "--directions" => {
+                    let list = value()?;
+                    let directions = list.split(',').map(str::trim).filter(|d| !d.is_empty())
+                        .map(|d| Direction::from_name(d).ok_or(format!("unknown direction {}", d)))
+                        .collect::<Result<Vec<Direction>, String>>()?;
+                    if directions.is_empty() {
+                        return Err(format!("{} needs at least one direction", name));
+                    }
+                    config.directions = Some(directions);
+                },
+                "--direction-weights" => {
+                    for pair in value()?.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                        let (direction, weight) = pair.split_once('=').ok_or(format!("{} takes a list of direction=weight, like left=0.2,up=0.2", name))?;
+                        let direction = Direction::from_name(direction.trim()).ok_or(format!("unknown direction {}", direction))?;
+                        let weight: f32 = parse(&name, weight.trim())?;
+                        if !(weight >= 0.0 && weight.is_finite()) {
+                            return Err(format!("{} can't be negative", name));
+                        }
+                        config.direction_weights.push((direction, weight));
+                    }
+                },
+                "--difficulty" => {
+                    let difficulty = value()?;
+                    Difficulty::from_name(&difficulty).ok_or(format!("unknown difficulty {}", difficulty))?.apply(&mut config);
+                },
+                "--level" => {
+                    let level: u8 = parse(&name, &value()?)?;
+                    if !(1..=5).contains(&level) {
+                        return Err(format!("{} goes from 1 to 5", name));
+                    }
+                    config.level = Some(level);
+                },
+                "--size" => {
+                    config.width = parse(&name, &value()?)?;
+                    config.height = config.width;
+                },
+                "--width" => config.width = parse(&name, &value()?)?,
+                "--height" => config.height = parse(&name, &value()?)?,
+                "--auto-size" => config.auto_size = true,
+                "--auto-density" => {
+                    let density: f32 = parse(&name, &value()?)?;
+                    if !(density > 0.0 && density <= 1.0) {
+                        return Err(String::from("--auto-density is a fraction of the grid, more than 0 and up to 1"));
+                    }
+                    config.auto_density = density;
+                },
+                "--density" => {
+                    let density: f32 = parse(&name, &value()?)?;
+                    if !(density > 0.0 && density <= 1.0) {
+                        return Err(String::from("--density is a fraction of the grid, more than 0 and up to 1"));
+                    }
+                    config.target_density = Some(density);
+                },
+                "--density-mode" => {
+                    let mode = value()?;
+                    config.density_mode = DensityMode::from_name(&mode).ok_or(format!("unknown density mode {}", mode))?;
+                },
+                "--density-pool" => {
+                    let path = value()?;
+                    let text = fs::read_to_string(&path).map_err(|err| format!("can't read {}: {}", path, err))?;
+                    config.density_pool.extend(text.lines().map(str::trim).filter(|w| !w.is_empty()).map(String::from));
+                    config.density_mode = DensityMode::Pool;
+                },
+                "--grow" => config.grow_to = Some(parse(&name, &value()?)?),
+                "--skip-failed" => config.skip_failed = true,
+                "--no-backtrack" => config.backtrack = false,
+                "--maxtries" => config.maxtries = parse(&name, &value()?)?,
+                "--dense" => config.dense = true,
+                "--balanced" => config.balanced = true,
+                "--symmetric" => config.symmetric = true,
+                "--min-crossings" => config.min_crossings = parse(&name, &value()?)?,
+                "--optimize" => {
+                    let goal = value()?;
+                    config.optimize = Some(Objective::from_name(&goal).ok_or(format!("unknown layout goal {}", goal))?);
+                },
+                "--no-overlap" => config.spacing = Some(config.spacing.unwrap_or(0)),
+                "--spacing" => config.spacing = Some(parse(&name, &value()?)?),
+                "--kid-mode" => config.spacing = Some(1),
+                "--bent" => config.bent = true,
+                "--spangram" => config.spangram = Some(value()?),
+                "--require-edge" => config.edge_words.push(value()?),
+                "--crisscross" => config.crisscross = true,
+                "--double" => config.double = true,
+                "--reveal" => {
+                    let value = value()?;
+                    let percent: f32 = parse(&name, &value)?;
+                    if !(0.0..=100.0).contains(&percent) {
+                        return Err(format!("{} expects a percentage from 0 to 100, got {}", name, value));
+                    }
+                    config.reveal = percent / 100.0;
+                },
+                "--mask" => {
+                    let path = value()?;
+                    let (mask, reserved) = parse_mask(&fs::read_to_string(&path).map_err(|err| format!("can't read {}: {}", path, err))?)?;
+                    config.width = mask[0].len();
+                    config.height = mask.len();
+                    config.mask = Some(mask);
+                    config.reserved.extend(reserved.into_iter().map(|(x, y)| (x, y, 1, 1)));
+                },
+                "--reserve" => {
+                    let value = value()?;
+                    let numbers: Vec<usize> = value.split(',').map(|n| parse(&name, n.trim())).collect::<Result<_, _>>()?;
+                    match numbers.as_slice() {
+                        [column, row, width, height] if *column > 0 && *row > 0 => config.reserved.push((column - 1, row - 1, *width, *height)),
+                        _ => return Err(format!("{} expects the column and row of the top left cell from 1 and the width and height, like 1,1,4,3, got {}", name, value)),
+                    }
+                },
+                "--shape-text" => config.shape_text = Some(value()?),
+                "--shape" => {
+                    let shape = value()?;
+                    config.shape = Some(Shape::from_name(&shape).ok_or(format!("unknown shape {}", shape))?);
+                },
+                "--candidates" => {
+                    config.candidates = parse(&name, &value()?)?;
+                    if config.candidates == 0 {
+                        return Err(String::from("--candidates has to be at least 1"));
+                    }
+                },
+                "--alphabet" => {
+                    let alphabet = value()?;
+                    config.alphabet = Some(Alphabet::from_name(&alphabet).ok_or(format!("unknown alphabet {}", alphabet))?);
+                },
+                "--fill-chars" => config.fill_chars = Some(value()?),
+                "--fill-chars-file" => {
+                    let path = value()?;
+                    config.fill_chars = Some(fs::read_to_string(&path).map_err(|err| format!("can't read {}: {}", path, err))?);
+                },
+                "--fill-frequencies" => {
+                    let table = value()?;
+                    config.fill_frequencies = Some(match table.as_str() {
+                        "english" => ENGLISH_FREQUENCIES.to_vec(),
+                        _ => parse_frequencies(&fs::read_to_string(&table).map_err(|err| format!("can't read {}: {}", table, err))?)?,
+                    });
+                },
+                "--fold-accents" => config.fold_diacritics = true,
+                "--rtl" => config.rtl = true,
+                "--keep-chars" => config.keep_chars = Some(value()?),
+                "--error-format" => {
+                    let format = value()?;
+                    config.error_format = ErrorFormat::from_name(&format).ok_or(format!("unknown error format {}", format))?;
+                },
+                "--strict-duplicates" => config.strict_duplicates = true,
+                "--wordlist-order" => {
+                    let given = value()?;
+                    let (format, order) = for_format(&given)?;
+                    let order = WordOrder::from_name(order).ok_or(format!("unknown word list order {}", order))?;
+                    match format {
+                        Some(format) => config.format_orders.push((format, order)),
+                        None => config.word_order = order,
+                    }
+                },
+                "--word-bank" => {
+                    let style = value()?;
+                    config.bank_style = BankStyle::from_name(&style).ok_or(format!("unknown word bank style {}", style))?;
+                },
+                "--min-length" | "--min-len" => config.min_length = parse(&name, &value()?)?,
+                "--max-words" => config.max_words = Some(parse(&name, &value()?)?),
+                "--max-length" | "--max-len" => config.max_length = Some(parse(&name, &value()?)?),
+                "--random-words" | "--random" => {
+                    config.max_words = Some(parse(&name, &value()?)?);
+                    config.random_words = true;
+                },
+                "--match" => config.pattern = Some(value()?),
+                "--dictionary" => config.dictionary = Some(value()?),
+                "--column" => config.column = Some(value()?),
+                "--clue-column" => config.clue_column = Some(value()?),
+                "--lang" => {
+                    let value = value()?;
+                    let (code, file) = value.split_once('=').filter(|(c, f)| !c.trim().is_empty() && !f.trim().is_empty())
+                        .ok_or("--lang takes a language and its words file, like en=animals_en.txt")?;
+                    config.languages.push((code.trim().to_string(), file.trim().to_string()));
+                },
+                "--theme" => {
+                    let theme = value()?;
+                    config.theme = Some(Theme::from_name(&theme).ok_or(format!("unknown theme {}", theme))?);
+                },
+                "--case" => {
+                    let case = value()?;
+                    config.case = Case::from_name(&case).ok_or(format!("unknown case {}", case))?;
+                },
+                "--camouflage" => config.camouflage = true,
+                "--decoys" => config.decoys = parse(&name, &value()?)?,
+                "--blocklist" => {
+                    config.blocklist.get_or_insert_with(|| DEFAULT_BLOCKLIST.iter().map(|w| w.to_string()).collect());
+                },
+                "--blocklist-file" => {
+                    let path = value()?;
+                    let text = fs::read_to_string(&path).map_err(|err| format!("can't read {}: {}", path, err))?;
+                    config.blocklist.get_or_insert_with(|| DEFAULT_BLOCKLIST.iter().map(|w| w.to_string()).collect())
+                        .extend(text.lines().map(str::trim).filter(|w| !w.is_empty()).map(String::from));
+                },
+                "--hidden-message" => config.hidden_message = Some(value()?),
+                "--count" => {
+                    config.count = parse(&name, &value()?)?;
+                    if config.count == 0 {
+                        return Err(String::from("--count has to be at least 1"));
+                    }
+                },
+                "--threads" => config.threads = parse(&name, &value()?)?,
+                "--timeout" => {
+                    let seconds: f64 = parse(&name, &value()?)?;
+                    config.timeout = Some(Duration::try_from_secs_f64(seconds).map_err(|_| format!("{} expects a number of seconds, got {}", name, seconds))?);
+                },
+                "--seed" => config.seed = Some(parse(&name, &value()?)?),
+                "--delimiter" => {
+                    let delimiter = value()?;
+                    config.delimiter = match delimiter.as_str() {
+                        "tab" | "\\t" => '\t',
+                        _ if delimiter.chars().count() == 1 => delimiter.chars().next().unwrap(),
+                        _ => return Err(format!("--delimiter expects a single character or tab, got {}", delimiter)),
+                    };
+                },
+                "--margin" => config.margin = parse(&name, &value()?)?,
+                "--words-per-row" => config.words_per_row = parse(&name, &value()?)?,
+                "--quote-all" => config.quote_all = true,
+                "--bank-columns" => {
+                    let given = value()?;
+                    let (format, columns) = for_format(&given)?;
+                    let columns: usize = parse(&name, columns)?;
+                    if columns == 0 {
+                        return Err(String::from("--bank-columns has to be at least 1"));
+                    }
+                    match format {
+                        Some(format) => config.format_columns.push((format, columns)),
+                        None => config.print.bank_columns = Some(columns),
+                    }
+                },
+                "--cell-size" => config.image.cell_size = parse(&name, &value()?)?,
+                "--image-margin" => config.image.margin = parse(&name, &value()?)?,
+                "--font-size" => config.image.font_size = parse(&name, &value()?)?,
+                "--large-print" => {
+                    config.print.letter_size = config.print.letter_size.or(Some(LARGE_PRINT_SIZE));
+                    config.print.high_contrast = true;
+                },
+                "--letter-size" => {
+                    let size: f32 = parse(&name, &value()?)?;
+                    if !(size > 0.0 && size.is_finite()) {
+                        return Err(String::from("--letter-size has to be more than 0"));
+                    }
+                    config.print.letter_size = Some(size);
+                },
+                "--font" => config.print.font = Some(value()?),
+                "--high-contrast" => config.print.high_contrast = true,
+                "--numbered-answers" => config.print.numbered_answers = true,
+                "--first-letters" => config.print.first_letters = true,
+                "--template" => config.template = Some(value()?),
+                "--title" => config.title = Some(value()?),
+                "--subtitle" => config.subtitle = Some(value()?),
+                "--instructions" => config.instructions = Some(value()?),
+                "--no-answer-key" => config.answer_key = false,
+                "--combined" | "--teacher-edition" => config.combined = true,
+                "--answer-style" => {
+                    let style = value()?;
+                    config.answer_style = AnswerStyle::from_name(&style).ok_or(format!("unknown answer style {}", style))?;
+                },
+                "--output" => config.output = Some(value()?),
+                "--answer-output" => config.answer_output = Some(value()?),
+                "--answer-list" => config.answer_list = Some(value()?),
+                "--hint-file" => config.hint_file = Some(value()?),
+                "--output-dir" => config.output_dir = Some(value()?),
+                "--force" => config.force = true,
+                "--format" => {
+                    let mut formats: Vec<Format> = Vec::new();
+                    for format in value()?.split(',').map(str::trim) {
+                        let format = Format::from_name(format).ok_or(format!("unknown format {}", format))?;
+                        if !formats.contains(&format) {
+                            formats.push(format);
+                        }
+                    }
+                    config.format = formats.remove(0);
+                    config.more_formats = formats;
+                },
+                _ if name.starts_with('-') && name.len() > 1 => return Err(format!("unknown option {}", name)),
+                _ => wordsfiles.push(arg),
+            }
+        }
+        
+        if (config.width == 0 || config.height == 0) && !config.auto_size && config.mask.is_none() {
+            return Err(format!("a {} x {} puzzle has no room for any words, the size has to be at least 1", config.width, config.height));
+        }
+        if let Some(spacing) = config.spacing.filter(|s| *s >= config.width.max(config.height)) {
+            if !config.auto_size && config.mask.is_none() && config.grow_to.is_none() {
+                return Err(format!("--spacing {} keeps the words farther apart than a {} x {} puzzle has room for", spacing, config.width, config.height));
+            }
+        }
+        if !config.more_formats.is_empty() {
+            if book || config.double {
+                return Err(String::from("a book or --double is written in one format"));
+            }
+            if config.template.is_some() {
+                return Err(String::from("--template takes the place of the format, so it can't go with several formats"));
+            }
+            if config.output.as_deref() == Some("-") {
+                return Err(String::from("--output - sends one format to stdout"));
+            }
+        }
+        if book {
+            if dry_run {
+                return Err(String::from("--dry-run checks the word list of one puzzle, not a book"));
+            }
+            if wordsfiles.len() != 1 {
+                return Err(String::from("book takes one directory of word files or a manifest"));
+            }
+            config.format = match config.format {
+                Format::Csv | Format::Html => Format::Html,
+                Format::Pdf => Format::Pdf,
+                _ => return Err(String::from("a book is written as html or pdf")),
+            };
+            config.wordsfile = wordsfiles.remove(0);
+            return Ok(Command::Book(Box::new(config)));
+        }
+        if solve {
+            if dry_run {
+                return Err(String::from("--dry-run checks the word list of a new puzzle, solve searches one"));
+            }
+            if wordsfiles.len() < 2 && !(wordsfiles.len() == 1 && config.dictionary.is_some()) {
+                return Err(String::from("solve takes a grid file and a words file or --dictionary"));
+            }
+            let grid = wordsfiles.remove(0);
+            if !wordsfiles.is_empty() {
+                config.wordsfile = wordsfiles.remove(0);
+                config.more_wordsfiles = wordsfiles;
+            }
+            return Ok(Command::Solve(Box::new(config), grid));
+        }
+        if convert {
+            if wordsfiles.len() != 1 {
+                return Err(String::from("convert takes one puzzle file"));
+            }
+            return Ok(Command::Convert(Box::new(config), wordsfiles.remove(0)));
+        }
+        if config.refill {
+            return Err(String::from("--refill is for convert, to give a puzzle file new filler"));
+        }
+        if config.dictionary.is_some() {
+            return Err(String::from("--dictionary is for sweeping a grid with solve"));
+        }
+        if config.density_mode != DensityMode::Copies && config.target_density.is_none() {
+            return Err(String::from("--density-mode and --density-pool go with --density, to say how much of the grid to cover"));
+        }
+        if config.density_mode == DensityMode::Pool && config.density_pool.is_empty() {
+            return Err(String::from("--density-mode pool needs the extra words from --density-pool"));
+        }
+        if config.combined && !config.answer_key {
+            return Err(String::from("--combined puts the answer key in the puzzle file, so it can't go with --no-answer-key"));
+        }
+        if config.clue_column.is_some() && config.column.is_none() {
+            return Err(String::from("--clue-column goes with --column, to say where the words are"));
+        }
+        if !config.languages.is_empty() {
+            if !wordsfiles.is_empty() || config.theme.is_some() {
+                return Err(String::from("--lang is used in place of a words file"));
+            }
+            if play || dry_run || config.double || config.count > 1 {
+                return Err(String::from("--lang makes one puzzle for each language, it can't be played, checked with --dry-run, doubled, or batched"));
+            }
+            return Ok(Command::Generate(Box::new(config)));
+        }
+        if config.theme.is_some() {
+            if !wordsfiles.is_empty() {
+                return Err(String::from("--theme is used in place of a words file"));
+            }
+        } else {
+            if wordsfiles.is_empty() && piped && !play {
+                wordsfiles.push(String::from("-"));
+            }
+            if wordsfiles.is_empty() {
+                return Err(String::from("no input words file provided"));
+            }
+            config.wordsfile = wordsfiles.remove(0);
+            config.more_wordsfiles = wordsfiles;
+        }
+        if play {
+            if dry_run {
+                return Err(String::from("--dry-run checks the word list without making a puzzle to play"));
+            }
+            if config.wordsfile == "-" || config.more_wordsfiles.iter().any(|f| f == "-") {
+                return Err(String::from("play reads the cells from stdin, the words have to come from a file"));
+            }
+            return Ok(Command::Play(Box::new(config)));
+        }
+        if dry_run {
+            return Ok(Command::DryRun(Box::new(config)));
+        }
+        Ok(Command::Generate(Box::new(config)))
+    }
+}
+
+// config_file_args - the options in a toml config file as command line arguments, each key is an
+//                    option name and its value, true turns on a flag and false turns on the --no-
+//                    form if there is one, an array repeats the option for each value
+//                    Comments and [table] lines are skipped
+//
+// size = 30
+// title = "Spelling Week 3"
+// answer-key = false
+fn config_file_args(text: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() || line.starts_with('[') {
+            continue;
+        }
+        let error = |what: &str| format!("line {}: {}", i + 1, what);
+        let (key, value) = line.split_once('=').ok_or_else(|| error("expected key = value"))?;
+        let option = format!("--{}", key.trim().trim_matches('"').replace('_', "-"));
+        let value = value.trim();
+        let values = match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            Some(items) => items.split(',').map(str::trim).filter(|v| !v.is_empty()).collect(),
+            None => vec![value],
+        };
+        for value in values {
+            match value {
+                "true" => args.push(option.clone()),
+                "false" => {
+                    let negated = format!("--no-{}", &option[2..]);
+                    if USAGE.contains(&format!("{} ", negated)) {
+                        args.push(negated);
+                    }
+                },
+                _ => {
+                    args.push(option.clone());
+                    args.push(toml_string(value).ok_or_else(|| error(&format!("can't read the value {}", value)))?);
+                },
+            }
+        }
+    }
+    Ok(args)
+}
+
+// options_config - the config for options given as a json object, each key is a command line option
+//                  and its value, true turns on a flag and false the --no- form of it, like a
+//                  config file, for the callers that aren't a command line
+//
+// {"size": 12, "hard": true, "title": "Pets"}
+#[cfg(any(feature = "wasm", feature = "ffi"))]
+pub(crate) fn options_config(options: &str) -> Result<Config, crate::WordfindError> {
+    if options.trim().is_empty() {
+        return json_config(crate::load::Json::Object(Vec::new()));
+    }
+    json_config(crate::load::Json::parse(options)?)
+}
+
+// json_config - options_config for options that have already been read as json
+#[cfg(any(feature = "wasm", feature = "ffi", feature = "serve"))]
+pub(crate) fn json_config(options: crate::load::Json) -> Result<Config, crate::WordfindError> {
+    let fields = match options {
+        crate::load::Json::Object(fields) => fields,
+        _ => return Err(crate::WordfindError::Usage(String::from("the options have to be a json object"))),
+    };
+    let mut args = vec![String::from("wordfindgen")];
+    for (key, value) in fields {
+        let option = format!("--{}", key.replace('_', "-"));
+        let values = match value {
+            crate::load::Json::Array(items) => items,
+            value => vec![value],
+        };
+        for value in values {
+            match value {
+                crate::load::Json::Bool(true) => args.push(option.clone()),
+                crate::load::Json::Bool(false) if USAGE.contains(&format!("--no-{} ", &option[2..])) => args.push(format!("--no-{}", &option[2..])),
+                crate::load::Json::Bool(false) | crate::load::Json::Null => {},
+                crate::load::Json::Number(text) | crate::load::Json::Str(text) => {
+                    args.push(option.clone());
+                    args.push(text);
+                },
+                _ => return Err(crate::WordfindError::Usage(format!("{} can't be set to a json object", option))),
+            }
+        }
+    }
+    // the words come from the caller, the file name only keeps the command line happy
+    if !args.iter().any(|a| a == "--theme") {
+        args.push(String::from("-"));
+    }
+    match Command::parse(args, false).map_err(crate::WordfindError::Usage)? {
+        Command::Generate(config) => Ok(*config),
+        _ => Err(crate::WordfindError::Usage(String::from("the options can only set up a puzzle"))),
+    }
+}
+
+// env_args - the WORDFINDGEN_ environment variables as command line arguments, sorted by name
+//            WORDFINDGEN_OUTPUT_DIR=out is --output-dir out, a flag is turned on by 1, true, or yes
+//            and a value of 0, false, or no turns on the --no- form if there is one
+fn env_args<I: IntoIterator<Item = (String, String)>>(vars: I) -> Result<Vec<String>, String> {
+    let mut vars: Vec<(String, String)> = vars.into_iter()
+        .filter_map(|(key, value)| key.strip_prefix("WORDFINDGEN_").map(|name| (name.to_lowercase().replace('_', "-"), value)))
+        .collect();
+    vars.sort();
+    
+    let mut args = Vec::new();
+    for (name, value) in vars {
+        let option = format!("--{}", name);
+        if USAGE.contains(&format!("{} <", option)) {
+            args.push(option);
+            args.push(value);
+            continue;
+        }
+        match value.to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" => args.push(option),
+            "0" | "false" | "no" | "" => {
+                let negated = format!("--no-{}", name);
+                if USAGE.contains(&format!("{} ", negated)) {
+                    args.push(negated);
+                }
+            },
+            // left for the parser to report as an unknown option
+            _ if !USAGE.contains(&format!("{} ", option)) => args.push(option),
+            _ => return Err(format!("WORDFINDGEN_{} should be 1 or 0, got {}", name.to_uppercase().replace('-', "_"), value)),
+        }
+    }
+    Ok(args)
+}
+
+// strip_comment - the line up to a # that isn't inside a quoted string
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (i, c) in line.char_indices() {
+        match (c, quote) {
+            ('"', None) | ('\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            ('#', None) => return &line[..i],
+            _ => {},
+        }
+    }
+    line
+}
+
+// toml_string - a toml value as the text of a command line value, strings lose their quotes and
+//               have their escapes turned back into characters, numbers are passed as they are
+fn toml_string(value: &str) -> Option<String> {
+    if let Some(literal) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return Some(literal.to_string());
+    }
+    if let Some(quoted) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        let mut text = String::new();
+        let mut chars = quoted.chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                match chars.next()? {
+                    'n' => text.push('\n'),
+                    't' => text.push('\t'),
+                    c => text.push(c),
+                }
+            } else {
+                text.push(c);
+            }
+        }
+        return Some(text);
+    }
+    if value.parse::<f64>().is_ok() || value.replace('_', "").parse::<i64>().is_ok() {
+        return Some(value.replace('_', ""));
+    }
+    None
+}
+
+// for_format - the format and the value of an option given for only one format, like pdf=3, with
+//              no format for a value that's for all of them
+fn for_format(value: &str) -> Result<(Option<Format>, &str), String> {
+    match value.split_once('=') {
+        Some((format, value)) => Ok((Some(Format::from_name(format.trim()).ok_or(format!("unknown format {}", format))?), value.trim())),
+        None => Ok((None, value)),
+    }
+}
+
+// parse - convert an option's value, naming the option if it isn't valid
+fn parse<T: FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("{} expects a number, got {}", name, value))
+}
+
+// parse_frequencies - a letter frequency table, one letter and its frequency on each line
+//                     separated by a space or a comma, blank lines and lines starting with # are skipped
+//
+// E 12.7
+// T,9.1
+fn parse_frequencies(text: &str) -> Result<Vec<(char, f32)>, String> {
+    let mut table = Vec::new();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        let mut parts = line.split(|c: char| c == ',' || c.is_whitespace()).filter(|p| !p.is_empty());
+        let letter = parts.next().filter(|l| l.chars().count() == 1).and_then(|l| l.chars().next());
+        match (letter, parts.next().map(str::parse::<f32>), parts.next()) {
+            (Some(letter), Some(Ok(frequency)), None) => table.push((letter, frequency)),
+            _ => return Err(format!("expected a letter and a frequency, got {}", line)),
+        }
+    }
+    Ok(table)
+}
+
+// Mask - the cells of a mask file that can be used, and the ones of them marked x
+type Mask = (Vec<Vec<bool>>, Vec<(usize, usize)>);
+
+// parse_mask - the cells of a shaped grid, # for a cell that can be used and . or a space for one
+//              that's left blank, short rows are padded with blank cells and blank lines around
+//              the shape are skipped, with the cells marked x, which get filler but no words
+//
+// ..#..
+// .###.
+// #####
+fn parse_mask(text: &str) -> Result<Mask, String> {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let first = lines.iter().position(|l| !l.trim().is_empty()).ok_or("the mask is empty")?;
+    let last = lines.iter().rposition(|l| !l.trim().is_empty()).unwrap_or(first);
+    let mut mask = Vec::new();
+    let mut reserved = Vec::new();
+    for (y, line) in lines[first..=last].iter().enumerate() {
+        let row: Result<Vec<bool>, String> = line.chars().enumerate().map(|(x, c)| match c {
+            '#' => Ok(true),
+            'x' | 'X' => {
+                reserved.push((x, y));
+                Ok(true)
+            },
+            '.' | ' ' => Ok(false),
+            _ => Err(format!("masks are made of #, ., and x, got {}", c)),
+        }).collect();
+        mask.push(row?);
+    }
+    let width = mask.iter().map(Vec::len).max().unwrap_or(0);
+    for row in mask.iter_mut() {
+        row.resize(width, false);
+    }
+    Ok((mask, reserved))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+    
+    #[test]
+    fn flags_and_values() {
+        let config = match Command::from_args(args("wordfindgen --size=30 words.txt --hard --seed 5 --output out.csv")).unwrap() {
+            Command::Generate(config) => config,
+            _ => panic!("expected a generate command"),
+        };
+        assert_eq!(config.wordsfile, "words.txt");
+        assert_eq!((config.width, config.height), (30, 30));
+        assert_eq!(config.difficulty, Difficulty::Hard);
+        assert_eq!(config.seed, Some(5));
+        assert_eq!(config.output.as_deref(), Some("out.csv"));
+        assert_eq!(config.answer_output, None);
+        assert_eq!(config.format, Format::Csv);
+        assert_eq!(config.maxtries, 10000);
+        assert!(config.more_wordsfiles.is_empty());
+        
+        match Command::from_args(args("wordfindgen animals.txt --hard colors.txt fruit.txt")).unwrap() {
+            Command::Generate(config) => {
+                assert_eq!(config.wordsfile, "animals.txt");
+                assert_eq!(config.more_wordsfiles, ["colors.txt", "fruit.txt"]);
+            },
+            _ => panic!("expected a generate command"),
+        }
+        match Command::parse(args("wordfindgen --hard"), true).unwrap() {
+            Command::Generate(config) => assert_eq!(config.wordsfile, "-"),
+            _ => panic!("expected a generate command"),
+        }
+        match Command::parse(args("wordfindgen --theme Animals --random-words 10"), true).unwrap() {
+            Command::Generate(config) => {
+                assert_eq!(config.theme, Some(Theme::Animals));
+                assert_eq!(config.wordsfile, "");
+                assert_eq!(config.max_words, Some(10));
+            },
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen --theme cars")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --theme animals")).is_err());
+    }
+    
+    #[test]
+    fn languages() {
+        match Command::parse(args("wordfindgen --lang en=animals_en.txt --lang es=animals_es.txt --seed 3"), true).unwrap() {
+            Command::Generate(config) => {
+                assert_eq!(config.languages, vec![(String::from("en"), String::from("animals_en.txt")), (String::from("es"), String::from("animals_es.txt"))]);
+                assert_eq!(config.wordsfile, "");
+            },
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen --lang animals_en.txt")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --lang en=animals_en.txt")).is_err());
+        assert!(Command::from_args(args("wordfindgen --lang en=animals_en.txt --count 3")).is_err());
+        assert!(Command::from_args(args("wordfindgen play --lang en=animals_en.txt")).is_err());
+    }
+    
+    #[test]
+    fn spreadsheet_columns() {
+        match Command::from_args(args("wordfindgen vocab.csv --column Term --clue-column 3")).unwrap() {
+            Command::Generate(config) => assert_eq!((config.column.as_deref(), config.clue_column.as_deref()), (Some("Term"), Some("3"))),
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen vocab.csv --clue-column Definition")).is_err());
+    }
+    
+    #[test]
+    fn rectangle() {
+        match Command::from_args(args("wordfindgen words.txt --size 10 --width 25 --format HTML --no-answer-key --fold-accents")).unwrap() {
+            Command::Generate(config) => {
+                assert!(config.fold_diacritics);
+                assert_eq!((config.width, config.height), (25, 10));
+                assert_eq!(config.format, Format::Html);
+                assert!(!config.answer_key);
+            },
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen words.txt --format svg --teacher-edition")).unwrap() {
+            Command::Generate(config) => assert!(config.combined),
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --combined --no-answer-key")).is_err());
+    }
+    
+    #[test]
+    fn several_formats() {
+        match Command::from_args(args("wordfindgen words.txt --format csv,PDF,json,csv")).unwrap() {
+            Command::Generate(config) => {
+                assert_eq!(config.format, Format::Csv);
+                assert_eq!(config.more_formats, vec![Format::Pdf, Format::Json]);
+            },
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --format csv,pfd")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --format csv,json --output -")).is_err());
+        assert!(Command::from_args(args("wordfindgen book words --format pdf,html")).is_err());
+    }
+    
+    #[test]
+    fn text_to_stdout() {
+        match Command::from_args(args("wordfindgen words.txt --format text --output - --answer-style lowercase --fill-chars AEIOURSTLN")).unwrap() {
+            Command::Generate(config) => {
+                assert_eq!(config.fill_chars.as_deref(), Some("AEIOURSTLN"));
+                assert_eq!(config.format, Format::Text);
+                assert_eq!(config.answer_style, AnswerStyle::Lowercase);
+                assert_eq!(config.output.as_deref(), Some("-"));
+            },
+            _ => panic!("expected a generate command"),
+        }
+    }
+    
+    #[test]
+    fn csv_layout() {
+        match Command::from_args(args("wordfindgen words.txt --output-dir out --answer-output key.csv --answer-list key.txt --hint-file hints.txt")).unwrap() {
+            Command::Generate(config) => {
+                assert_eq!(config.output_dir.as_deref(), Some("out"));
+                assert_eq!(config.answer_list.as_deref(), Some("key.txt"));
+                assert_eq!(config.hint_file.as_deref(), Some("hints.txt"));
+                assert_eq!(config.answer_output.as_deref(), Some("key.csv"));
+            },
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen words.txt --delimiter tab --margin 0 --words-per-row 4 --quote-all")).unwrap() {
+            Command::Generate(config) => {
+                assert_eq!(config.delimiter, '\t');
+                assert_eq!((config.margin, config.words_per_row), (0, 4));
+                assert!(config.quote_all);
+                assert!(!config.camouflage);
+                assert!(!config.fold_diacritics);
+            },
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --delimiter ;;")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --fill-chars-file does_not_exist.txt")).is_err());
+    }
+    
+    #[test]
+    fn blocklists() {
+        let path = std::env::temp_dir().join("wordfindgen_blocklist.txt");
+        fs::write(&path, "drat\n\n  heck \n").unwrap();
+        let line = format!("wordfindgen words.txt --blocklist-file {} --blocklist", path.display());
+        match Command::from_args(args(&line)).unwrap() {
+            Command::Generate(config) => {
+                let blocklist = config.blocklist.unwrap();
+                assert_eq!(blocklist.len(), DEFAULT_BLOCKLIST.len() + 2);
+                assert_eq!(&blocklist[blocklist.len() - 2..], ["drat", "heck"]);
+            },
+            _ => panic!("expected a generate command"),
+        }
+        fs::remove_file(&path).unwrap();
+    }
+    
+    #[test]
+    fn shapes() {
+        match Command::from_args(args("wordfindgen words.txt --shape heart --size 25")).unwrap() {
+            Command::Generate(config) => assert_eq!((config.shape, config.width), (Some(Shape::Heart), 25)),
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --shape blob")).is_err());
+        match Command::from_args(args("wordfindgen words.txt --shape-text AJ")).unwrap() {
+            Command::Generate(config) => assert_eq!(config.shape_text.as_deref(), Some("AJ")),
+            _ => panic!("expected a generate command"),
+        }
+    }
+    
+    #[test]
+    fn masks() {
+        let (mask, reserved) = parse_mask("\n..#\n.###\n#####\n\n").unwrap();
+        assert_eq!(mask, vec![vec![false, false, true, false, false], vec![false, true, true, true, false], vec![true; 5]]);
+        assert!(reserved.is_empty());
+        assert_eq!(parse_mask("#x#\n.xx").unwrap(), (vec![vec![true; 3], vec![false, true, true]], vec![(1, 0), (1, 1), (2, 1)]));
+        assert!(parse_mask("#o#").is_err());
+        assert!(parse_mask("\n\n").is_err());
+    }
+    
+    #[test]
+    fn frequency_tables() {
+        assert_eq!(parse_frequencies("# spanish\nE 13.7\n\nA,12.5\n").unwrap(), vec![('E', 13.7), ('A', 12.5)]);
+        assert!(parse_frequencies("E").is_err());
+        assert!(parse_frequencies("EA 1.0").is_err());
+        assert!(parse_frequencies("E 1.0 2.0").is_err());
+        match Command::from_args(args("wordfindgen words.txt --fill-frequencies english")).unwrap() {
+            Command::Generate(config) => {
+                assert_eq!(config.fill_frequencies.unwrap().len(), 26);
+                assert_eq!(config.alphabet, None);
+            },
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen words.txt --alphabet hebrew --rtl")).unwrap() {
+            Command::Generate(config) => {
+                assert_eq!(config.alphabet, Some(Alphabet::Hebrew));
+                assert!(config.rtl);
+            },
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen numbers.txt --alphabet digits --keep-chars - --case lower")).unwrap() {
+            Command::Generate(config) => {
+                assert_eq!(config.alphabet, Some(Alphabet::Digits));
+                assert_eq!(config.keep_chars.as_deref(), Some("-"));
+                assert_eq!(config.case, Case::Lower);
+            },
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --alphabet klingon")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --case title")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --wordlist-order random")).is_err());
+        match Command::from_args(args("wordfindgen words.txt --reserve 1,1,4,3 --reserve 10,2,1,1")).unwrap() {
+            Command::Generate(config) => assert_eq!(config.reserved, vec![(0, 0, 4, 3), (9, 1, 1, 1)]),
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --reserve 0,1,4,3")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --reserve 1,1,4")).is_err());
+        match Command::from_args(args("wordfindgen words.txt --word-bank anagram")).unwrap() {
+            Command::Generate(config) => assert_eq!(config.bank_style, BankStyle::Scrambled),
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen words.txt --error-format json --strict-duplicates")).unwrap() {
+            Command::Generate(config) => {
+                assert_eq!(config.error_format, ErrorFormat::Json);
+                assert!(config.strict_duplicates);
+            },
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen vocab.txt --min-length 4 --random-words 20 --match s* --wordlist-order alpha --word-bank count")).unwrap() {
+            Command::Generate(config) => {
+                assert_eq!(config.word_order, WordOrder::Alphabetical);
+                assert_eq!(config.bank_style, BankStyle::Count);
+                assert_eq!((config.min_length, config.max_words, config.random_words), (4, Some(20), true));
+                assert_eq!(config.pattern.as_deref(), Some("s*"));
+                assert_eq!(config.max_length, None);
+            },
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen words.txt --count 30")).unwrap() {
+            Command::Generate(config) => assert_eq!((config.count, config.threads), (30, 0)),
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen words.txt --timeout 2.5")).unwrap() {
+            Command::Generate(config) => assert_eq!(config.timeout, Some(Duration::from_millis(2500))),
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --timeout -1")).is_err());
+        assert_eq!(Command::from_args(args("wordfindgen words.txt --count 0")).err().as_deref(), Some("--count has to be at least 1"));
+        assert_eq!(Command::from_args(args("wordfindgen words.txt --candidates 0")).err().as_deref(), Some("--candidates has to be at least 1"));
+        match Command::from_args(args("wordfindgen words.txt --difficulty expert --decoys 1")).unwrap() {
+            Command::Generate(config) => {
+                assert_eq!(config.difficulty, Difficulty::Expert);
+                assert_eq!((config.decoys, config.bank_style), (1, BankStyle::Hidden));
+            },
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --difficulty impossible")).is_err());
+        match Command::from_args(args("wordfindgen words.txt --level 3")).unwrap() {
+            Command::Generate(config) => assert_eq!(config.level, Some(3)),
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --level 6")).is_err());
+        match Command::from_args(args("wordfindgen words.txt --directions right,down,downright")).unwrap() {
+            Command::Generate(config) => assert_eq!(config.directions, Some(vec![Direction::Right, Direction::Down, Direction::DownRight])),
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen words.txt --direction-weights left=0.2,Up=0")).unwrap() {
+            Command::Generate(config) => assert_eq!(config.direction_weights, vec![(Direction::Left, 0.2), (Direction::Up, 0.0)]),
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --directions right,sideways")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --directions ,")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --direction-weights left")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --direction-weights left=-1")).is_err());
+        match Command::from_args(args("wordfindgen words.txt --spacing 2 --no-overlap")).unwrap() {
+            Command::Generate(config) => assert_eq!(config.spacing, Some(2)),
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --size 20 --spacing 50")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --size 20 --spacing 50 --auto-size")).is_ok());
+        match Command::from_args(args("wordfindgen words.txt --min-crossings 2")).unwrap() {
+            Command::Generate(config) => assert_eq!(config.min_crossings, 2),
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen words.txt --density 0.6 --density-mode size")).unwrap() {
+            Command::Generate(config) => assert_eq!((config.target_density, config.density_mode), (Some(0.6), DensityMode::Size)),
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --density 1.5")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --density-mode warn")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --density 0.5 --density-mode pool")).is_err());
+        match Command::from_args(args("wordfindgen words.txt --optimize symmetric")).unwrap() {
+            Command::Generate(config) => assert_eq!(config.optimize, Some(Objective::Symmetric)),
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --optimize pretty")).is_err());
+        match Command::from_args(args("wordfindgen words.txt --symmetric")).unwrap() {
+            Command::Generate(config) => assert!(config.symmetric && !config.balanced),
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen words.txt --spangram zoo")).unwrap() {
+            Command::Generate(config) => assert_eq!(config.spangram.as_deref(), Some("zoo")),
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen words.txt --require-edge owl --require-edge bat")).unwrap() {
+            Command::Generate(config) => assert_eq!(config.edge_words, ["owl", "bat"]),
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen words.txt --crisscross")).unwrap() {
+            Command::Generate(config) => assert!(config.crisscross),
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen words.txt --reveal 25")).unwrap() {
+            Command::Generate(config) => assert!((config.reveal - 0.25).abs() < f32::EPSILON),
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --reveal 150")).is_err());
+        match Command::from_args(args("wordfindgen words.txt --double")).unwrap() {
+            Command::Generate(config) => assert!(config.double),
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen words.txt --kid-mode --bent")).unwrap() {
+            Command::Generate(config) => assert_eq!((config.spacing, config.bent), (Some(1), true)),
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen words.txt --dry-run --verify")).unwrap() {
+            Command::DryRun(config) => assert_eq!((config.wordsfile.as_str(), config.verify), ("words.txt", true)),
+            _ => panic!("expected a dry run command"),
+        }
+        assert!(Command::from_args(args("wordfindgen book lists --dry-run")).is_err());
+        match Command::from_args(args("wordfindgen words.txt -vv -q")).unwrap() {
+            Command::Generate(config) => assert_eq!((config.verbose, config.quiet), (2, true)),
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen words.txt --stats --show")).unwrap() {
+            Command::Generate(config) => assert!(config.stats && config.show),
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen dictionary.txt --random 20 --min-len 5 --max-len 9")).unwrap() {
+            Command::Generate(config) => assert_eq!((config.min_length, config.max_length, config.max_words, config.random_words), (5, Some(9), Some(20), true)),
+            _ => panic!("expected a generate command"),
+        }
+    }
+    
+    #[test]
+    fn config_file() {
+        let text = "# weekly spelling puzzles\n[puzzle]\nsize = 15\nhard = true\ntitle = \"Week #3 \\\"Spelling\\\"\" # the title\nanswer_key = false\nseed = 1_000\ndense = false\n";
+        assert_eq!(config_file_args(text).unwrap(), ["--size", "15", "--hard", "--title", "Week #3 \"Spelling\"", "--no-answer-key", "--seed", "1000"]);
+        assert!(config_file_args("size 15").unwrap_err().starts_with("line 1:"));
+        
+        let path = std::env::temp_dir().join("wordfindgen_config_file.toml");
+        fs::write(&path, text).unwrap();
+        let line = format!("wordfindgen words.txt --config {} --size 12", path.to_string_lossy());
+        match Command::from_args(args(&line)).unwrap() {
+            Command::Generate(config) => {
+                assert_eq!((config.width, config.height), (12, 12));
+                assert!(config.difficulty == Difficulty::Hard && !config.answer_key);
+                assert_eq!(config.title.as_deref(), Some("Week #3 \"Spelling\""));
+                assert_eq!(config.wordsfile, "words.txt");
+            },
+            _ => panic!("expected a generate command"),
+        }
+        fs::remove_file(&path).unwrap();
+    }
+    
+    #[test]
+    fn print_options() {
+        match Command::from_args(args("wordfindgen words.txt --format pdf --letter-size 30 --large-print --font serif")).unwrap() {
+            Command::Generate(config) => assert_eq!(config.print, crate::PrintStyle { letter_size: Some(30.0), font: Some(String::from("serif")), high_contrast: true, bank_columns: None, numbered_answers: false, first_letters: false }),
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen words.txt --large-print")).unwrap() {
+            Command::Generate(config) => assert_eq!(config.print, crate::PrintStyle::large_print()),
+            _ => panic!("expected a generate command"),
+        }
+        match Command::from_args(args("wordfindgen words.txt --numbered-answers --first-letters")).unwrap() {
+            Command::Generate(config) => assert!(config.print.numbered_answers && config.print.first_letters),
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --letter-size 0")).is_err());
+        
+        match Command::from_args(args("wordfindgen words.txt --format pdf --bank-columns 2 --bank-columns pdf=4 --bank-columns csv=1 --wordlist-order alpha --wordlist-order pdf=length")).unwrap() {
+            Command::Generate(config) => {
+                assert_eq!(config.print.bank_columns, Some(2));
+                assert_eq!(config.print_style().bank_columns, Some(4));
+                assert_eq!(config.bank_order(), WordOrder::Length);
+                let text = Config { format: Format::Text, ..*config };
+                assert_eq!((text.print_style().bank_columns, text.bank_order()), (Some(2), WordOrder::Alphabetical));
+            },
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --bank-columns 0")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --bank-columns paper=2")).is_err());
+    }
+    
+    #[test]
+    fn template_option() {
+        match Command::from_args(args("wordfindgen words.txt --template school.html")).unwrap() {
+            Command::Generate(config) => {
+                assert_eq!(config.template.as_deref(), Some("school.html"));
+                assert_eq!(crate::render::output_extension(&config), "html");
+            },
+            _ => panic!("expected a generate command"),
+        }
+    }
+    
+    #[test]
+    fn book_command() {
+        match Command::from_args(args("wordfindgen book lists --format pdf --title Puzzles")).unwrap() {
+            Command::Book(config) => {
+                assert_eq!(config.wordsfile, "lists");
+                assert_eq!(config.format, Format::Pdf);
+            },
+            _ => panic!("expected a book command"),
+        }
+        match Command::from_args(args("wordfindgen book lists")).unwrap() {
+            Command::Book(config) => assert_eq!(config.format, Format::Html),
+            _ => panic!("expected a book command"),
+        }
+        assert!(Command::from_args(args("wordfindgen book lists --format svg")).is_err());
+        assert!(Command::from_args(args("wordfindgen book")).is_err());
+    }
+    
+    #[test]
+    fn solve_command() {
+        match Command::from_args(args("wordfindgen solve puzzle.csv words.txt more.txt --delimiter ;")).unwrap() {
+            Command::Solve(config, grid) => {
+                assert_eq!(grid, "puzzle.csv");
+                assert_eq!(config.wordsfile, "words.txt");
+                assert_eq!(config.more_wordsfiles, vec![String::from("more.txt")]);
+                assert_eq!(config.delimiter, ';');
+            },
+            _ => panic!("expected a solve command"),
+        }
+        assert!(Command::from_args(args("wordfindgen solve puzzle.csv")).is_err());
+        match Command::from_args(args("wordfindgen solve puzzle.csv --dictionary words.txt --min-length 5")).unwrap() {
+            Command::Solve(config, _) => {
+                assert_eq!(config.wordsfile, "");
+                assert_eq!(config.dictionary.as_deref(), Some("words.txt"));
+                assert_eq!(config.min_length, 5);
+            },
+            _ => panic!("expected a solve command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --dictionary big.txt")).is_err());
+        match Command::from_args(args("wordfindgen convert old.json --format pdf --refill")).unwrap() {
+            Command::Convert(config, puzzle) => {
+                assert_eq!(puzzle, "old.json");
+                assert_eq!(config.format, Format::Pdf);
+                assert!(config.refill);
+            },
+            _ => panic!("expected a convert command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --refill")).is_err());
+    }
+    
+    #[test]
+    fn environment() {
+        let vars = [("WORDFINDGEN_SIZE", "30"), ("HOME", "/root"), ("WORDFINDGEN_OUTPUT_DIR", "out"), ("WORDFINDGEN_HARD", "yes"), ("WORDFINDGEN_ANSWER_KEY", "0")];
+        let vars = vars.iter().map(|(k, v)| (k.to_string(), v.to_string()));
+        assert_eq!(env_args(vars).unwrap(), ["--no-answer-key", "--hard", "--output-dir", "out", "--size", "30"]);
+        assert!(env_args(vec![(String::from("WORDFINDGEN_HARD"), String::from("maybe"))]).is_err());
+    }
+    
+    #[test]
+    fn headings() {
+        let mut line = args("wordfindgen words.txt --title=Pets --instructions");
+        line.push(String::from("Find all of the pets"));
+        match Command::from_args(line).unwrap() {
+            Command::Generate(config) => {
+                assert_eq!(config.title.as_deref(), Some("Pets"));
+                assert_eq!(config.subtitle, None);
+                assert_eq!(config.instructions.as_deref(), Some("Find all of the pets"));
+                assert_eq!(config.hidden_message, None);
+                assert_eq!(config.fill_chars, None);
+            },
+            _ => panic!("expected a generate command"),
+        }
+    }
+    
+    #[test]
+    fn auto_size() {
+        match Command::from_args(args("wordfindgen words.txt --auto-size --auto-density 0.7 --decoys 4")).unwrap() {
+            Command::Generate(config) => {
+                assert!(config.auto_size);
+                assert_eq!(config.decoys, 4);
+                assert!((config.auto_density - 0.7).abs() < f32::EPSILON);
+            },
+            _ => panic!("expected a generate command"),
+        }
+        assert!(Command::from_args(args("wordfindgen words.txt --auto-size --auto-density 0")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --auto-size --auto-density 1.5")).is_err());
+    }
+    
+    #[test]
+    fn bad_command_lines() {
+        assert!(matches!(Command::from_args(args("wordfindgen --help")), Ok(Command::Help)));
+        assert!(matches!(Command::from_args(args("wordfindgen words.txt -V")), Ok(Command::Version)));
+        assert!(Command::parse(args("wordfindgen"), false).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --size big")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --size 0")).err().is_some_and(|err| err.contains("has no room")));
+        assert!(Command::from_args(args("wordfindgen words.txt --height 0")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --size")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --bogus")).is_err());
+        assert!(Command::from_args(args("wordfindgen words.txt --format doc")).is_err());
+    }
+    
+    #[test]
+    fn play_command() {
+        match Command::from_args(args("wordfindgen play words.txt --size 8 --hard")).unwrap() {
+            Command::Play(config) => {
+                assert_eq!(config.wordsfile, "words.txt");
+                assert_eq!(config.width, 8);
+            },
+            _ => panic!("expected a play command"),
+        }
+        assert!(Command::parse(args("wordfindgen play"), true).is_err());
+        assert!(Command::from_args(args("wordfindgen play -")).is_err());
+    }
+    
+    #[test]
+    fn serve_command() {
+        match Command::from_args(args("wordfindgen serve")).unwrap() {
+            Command::Serve(address) => assert_eq!(address, "127.0.0.1:8080"),
+            _ => panic!("expected a serve command"),
+        }
+        match Command::from_args(args("wordfindgen serve 0.0.0.0:3000")).unwrap() {
+            Command::Serve(address) => assert_eq!(address, "0.0.0.0:3000"),
+            _ => panic!("expected a serve command"),
+        }
+        assert!(Command::from_args(args("wordfindgen serve --size 12")).is_err());
+        assert!(Command::from_args(args("wordfindgen serve one two")).is_err());
+    }
+}
@@ -0,0 +1,234 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+use crate::render::escape_json;
+
+// WordfindError - everything that can go wrong while generating a puzzle
+//
+// Library users can match on the variant instead of picking apart the message
+//
+#[derive(Debug)]
+pub enum WordfindError {
+    // the word list was empty
+    NoWords,
+    // a grid with no rows or no columns
+    InvalidSize { width: usize, height: usize },
+    // the word is longer than both sides of the grid
+    WordTooLong { word: String, width: usize, height: usize },
+    // the words have too many letters for the cells of the grid to hold, even with some of them
+    // crossing, size is a square grid they'd fit in
+    TooManyLetters { letters: usize, cells: usize, size: usize },
+    // no room was found for the word after the given number of tries
+    PlacementFailed { word: String, tries: usize },
+    // the word can't be put in a grid at all
+    InvalidWord { word: String, reason: String },
+    // every problem found in the word list before placing anything
+    InvalidWordList { problems: Vec<WordProblem> },
+    // every fill letter was excluded
+    NoFillLetters,
+    // no directions were left for the words to go in
+    NoDirections,
+    // the Config::mask shape can't be used for the grid
+    InvalidMask { reason: String },
+    // a word didn't cross as many of the others as Config::min_crossings asks for
+    TooFewCrossings { word: String, crossings: usize },
+    // a placed word could not be found in the grid by the solver
+    WordNotFound { word: String },
+    // the answer key shows something else than the puzzle in a cell of a placed word, counted from 0
+    AnswerKeyMismatch { x: usize, y: usize },
+    // the hidden message has more letters than there are blank cells left to hide it in
+    MessageTooLong { letters: usize, blanks: usize },
+    // the requested output needs a cargo feature this build doesn't have
+    MissingFeature { feature: String },
+    // a word list url that couldn't be fetched
+    DownloadFailed { url: String, reason: String },
+    // Config::timeout ran out before the words were placed
+    TimedOut { timeout: Duration },
+    // the Config::cancel token was cancelled
+    Cancelled,
+    // the words only fit the grid one way, so the two puzzles of Config::double came out the same
+    SameLayout,
+    // a puzzle file given to solve or read back with from_json or from_csv has no grid in it, or
+    // isn't a puzzle at all
+    InvalidGrid { reason: String },
+    // the grid and word bank don't fit on one page with letters of PrintStyle::letter_size points
+    TooBigToPrint { width: usize, height: usize, letter_size: f32 },
+    // a letter of a pdf puzzle that the pdf's built in font doesn't have, like a Greek or Cyrillic one
+    NotInPdfFont { letter: char },
+    // an output file is already there and Config::force isn't set
+    FileExists { path: PathBuf },
+    // the command line couldn't be understood, the message from Command::from_args
+    Usage(String),
+    Io(io::Error),
+}
+
+// ErrorKind - the groups of errors the command line has a different exit code for, so a tool
+//             running it can tell what went wrong without reading the message
+//
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum ErrorKind {
+    // the command line or the settings in it can't work
+    Usage,
+    // something wrong with the word list, found before placing anything
+    Invalid,
+    // the words didn't fit, or the puzzle was stopped before they did
+    Placement,
+    // a file couldn't be read or written, was already there, or a url couldn't be downloaded
+    Io,
+}
+
+impl ErrorKind {
+    // exit_code - the code the command line exits with, 1 is left for anything else
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ErrorKind::Usage => 2,
+            ErrorKind::Invalid => 3,
+            ErrorKind::Placement => 4,
+            ErrorKind::Io => 5,
+        }
+    }
+    
+    // name - the kind in the json errors
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorKind::Usage => "usage",
+            ErrorKind::Invalid => "invalid",
+            ErrorKind::Placement => "placement",
+            ErrorKind::Io => "io",
+        }
+    }
+}
+
+impl fmt::Display for WordfindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WordfindError::NoWords => write!(f, "no words to place"),
+            WordfindError::InvalidSize { width, height } => write!(f, "a {} x {} puzzle has no room for any words", width, height),
+            WordfindError::WordTooLong { word, width, height } => write!(f, "{} is too long to fit in a {} x {} puzzle", word, width, height),
+            WordfindError::PlacementFailed { word, tries: _ } => write!(f, "{} could not be placed in the puzzle", word),
+            WordfindError::InvalidWord { word, reason } => write!(f, "{} can't be used in the puzzle: {}", word, reason),
+            WordfindError::InvalidWordList { problems } => {
+                write!(f, "the word list has {} problem{}", problems.len(), if problems.len() == 1 { "" } else { "s" })?;
+                for problem in problems.iter() {
+                    write!(f, "\n  {}", problem)?;
+                }
+                Ok(())
+            },
+            WordfindError::NoFillLetters => write!(f, "every fill letter has been excluded"),
+            WordfindError::NoDirections => write!(f, "there are no directions left for the words to go in"),
+            WordfindError::InvalidMask { reason } => write!(f, "the grid shape can't be used: {}", reason),
+            WordfindError::WordNotFound { word } => write!(f, "{} could not be found in the puzzle", word),
+            WordfindError::AnswerKeyMismatch { x, y } => write!(f, "the answer key doesn't match the puzzle at row {} column {}", y + 1, x + 1),
+            WordfindError::TooFewCrossings { word, crossings } => write!(f, "{} couldn't be made to cross {} of the other words", word, crossings),
+            WordfindError::MessageTooLong { letters, blanks } => write!(f, "the hidden message has {} letters but only {} cells are left after placing the words", letters, blanks),
+            WordfindError::MissingFeature { feature } => write!(f, "wordfindgen was built without the {} feature", feature),
+            WordfindError::DownloadFailed { url, reason } => write!(f, "couldn't download {}: {}", url, reason),
+            WordfindError::TimedOut { timeout } => write!(f, "gave up after {} seconds without fitting the words, try a bigger grid or fewer words", timeout.as_secs_f32()),
+            WordfindError::Cancelled => write!(f, "the puzzle was cancelled before it was finished"),
+            WordfindError::SameLayout => write!(f, "the words only fit the grid one way, so the two puzzles came out the same, try a bigger grid"),
+            WordfindError::InvalidGrid { reason } => write!(f, "the puzzle file can't be read: {}", reason),
+            WordfindError::TooBigToPrint { width, height, letter_size } => write!(f, "a {} x {} puzzle and its word bank don't fit on one page with {} point letters, try a smaller grid, fewer words, or a smaller --letter-size", width, height, letter_size),
+            WordfindError::NotInPdfFont { letter } => write!(f, "{} can't be printed in a pdf, its built in font only has the Latin letters, use --format html or text for this alphabet", letter),
+            WordfindError::FileExists { path } => write!(f, "{} is already there, pass --force to write over it", path.display()),
+            WordfindError::TooManyLetters { letters, cells, size } => write!(f, "the words have {} letters, too many for the {} cells of the grid, try --size {} or --auto-size", letters, cells, size),
+            WordfindError::Usage(message) => write!(f, "{}", message),
+            WordfindError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl WordfindError {
+    // kind - which group of errors this is in, for the exit code
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            WordfindError::InvalidSize { .. } | WordfindError::NoFillLetters | WordfindError::NoDirections | WordfindError::InvalidMask { .. } | WordfindError::MissingFeature { .. } | WordfindError::TooBigToPrint { .. } | WordfindError::NotInPdfFont { .. } | WordfindError::Usage(_) => ErrorKind::Usage,
+            WordfindError::NoWords | WordfindError::WordTooLong { .. } | WordfindError::TooManyLetters { .. } | WordfindError::InvalidWord { .. } | WordfindError::InvalidWordList { .. } | WordfindError::MessageTooLong { .. } | WordfindError::InvalidGrid { .. } => ErrorKind::Invalid,
+            WordfindError::PlacementFailed { .. } | WordfindError::WordNotFound { .. } | WordfindError::AnswerKeyMismatch { .. } | WordfindError::TooFewCrossings { .. } | WordfindError::TimedOut { .. } | WordfindError::Cancelled | WordfindError::SameLayout => ErrorKind::Placement,
+            WordfindError::DownloadFailed { .. } | WordfindError::FileExists { .. } | WordfindError::Io(_) => ErrorKind::Io,
+        }
+    }
+    
+    // to_json - the error as a json object for tools that wrap the command line, with its kind,
+    //           and the word and what's wrong with it when the error is about one word
+    //           Word list problems are listed one by one with their line numbers
+    pub fn to_json(&self) -> String {
+        let head = format!("\"error\":{},\"kind\":\"{}\"", escape_json(&self.to_string()), self.kind().name());
+        let word = |word: &str, reason: &str| format!("{{{},\"word\":{},\"reason\":{}}}", head, escape_json(word), escape_json(reason));
+        match self {
+            WordfindError::InvalidWordList { problems } => {
+                let problems: Vec<String> = problems.iter().map(|p| {
+                    let file = p.file.as_ref().map(|f| format!("\"file\":{},", escape_json(f))).unwrap_or_default();
+                    format!("{{{}\"line\":{},\"word\":{},\"reason\":{}}}", file, p.line, escape_json(&p.word), escape_json(&p.reason))
+                }).collect();
+                format!("{{{},\"problems\":[{}]}}", head, problems.join(","))
+            },
+            WordfindError::WordTooLong { word: w, width, height } => word(w, &format!("is too long to fit in a {} x {} puzzle", width, height)),
+            WordfindError::PlacementFailed { word: w, tries } => word(w, &format!("could not be placed in {} tries", tries)),
+            WordfindError::InvalidWord { word: w, reason } => word(w, reason),
+            WordfindError::WordNotFound { word: w } => word(w, "could not be found in the puzzle"),
+            WordfindError::TooFewCrossings { word: w, crossings } => word(w, &format!("couldn't be made to cross {} of the other words", crossings)),
+            _ => format!("{{{}}}", head),
+        }
+    }
+}
+
+impl Error for WordfindError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WordfindError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for WordfindError {
+    fn from(err: io::Error) -> WordfindError {
+        WordfindError::Io(err)
+    }
+}
+
+// WordProblem - something wrong with one word in the word list, found by validate_words
+//
+#[derive(Debug,Clone,PartialEq)]
+pub struct WordProblem {
+    // the word file the word is in, when the words came from more than one
+    pub file: Option<String>,
+    // line of the word file the word is on, counting from 1
+    pub line: usize,
+    pub word: String,
+    // what's wrong, worded to follow the word, like "is too long for a 10 x 10 puzzle"
+    pub reason: String,
+}
+
+impl fmt::Display for WordProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{} line {}: {} {}", file, self.line, self.word, self.reason),
+            None => write!(f, "line {}: {} {}", self.line, self.word, self.reason),
+        }
+    }
+}
+
+// ErrorFormat - how the command line reports errors
+//
+#[derive(Debug,Clone,Copy,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ErrorFormat {
+    // a sentence for people
+    Text,
+    // a json object for programs, see WordfindError::to_json
+    Json,
+}
+
+impl ErrorFormat {
+    // from_name - the format for a name given on the command line
+    pub fn from_name(name: &str) -> Option<ErrorFormat> {
+        match name.to_ascii_lowercase().as_str() {
+            "text" => Some(ErrorFormat::Text),
+            "json" => Some(ErrorFormat::Json),
+            _ => None,
+        }
+    }
+}
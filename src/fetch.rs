@@ -0,0 +1,141 @@
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::process;
+use crate::WordfindError;
+
+// Redirects followed before giving up, published spreadsheets usually redirect once or twice
+const MAX_REDIRECTS: usize = 5;
+
+// download - the text of the page at an http or https url
+//
+// Plain http is fetched here, std has no tls so https urls are handed to curl
+pub fn download(url: &str) -> Result<String, WordfindError> {
+    let mut url = url.to_string();
+    for _ in 0..=MAX_REDIRECTS {
+        if url.starts_with("https://") {
+            return curl(&url);
+        }
+        match http_get(&url)? {
+            Response::Body(body) => return Ok(body),
+            Response::Redirect(location) => url = location,
+        }
+    }
+    Err(failed(&url, "too many redirects"))
+}
+
+// Response - what an http request came back with
+enum Response {
+    Body(String),
+    Redirect(String),
+}
+
+// http_get - one GET request for an http:// url
+fn http_get(url: &str) -> Result<Response, WordfindError> {
+    let rest = url.strip_prefix("http://").ok_or_else(|| failed(url, "only http and https urls can be downloaded"))?;
+    let (host, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let address = if host.contains(':') { host.to_string() } else { format!("{}:80", host) };
+    
+    let mut stream = TcpStream::connect(&address)?;
+    stream.write_all(format!("GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: wordfindgen\r\nConnection: close\r\n\r\n", path, host).as_bytes())?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    
+    let split = response.windows(4).position(|w| w == b"\r\n\r\n").ok_or_else(|| failed(url, "the server sent back something that isn't http"))?;
+    let head = String::from_utf8_lossy(&response[..split]).to_string();
+    let body = &response[split + 4..];
+    let mut lines = head.lines();
+    let status: u16 = lines.next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| failed(url, "the server sent back something that isn't http"))?;
+    let header = |name: &str| head.lines().skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim().to_string());
+    
+    match status {
+        200..=299 => {
+            let body = match header("Transfer-Encoding") {
+                Some(encoding) if encoding.eq_ignore_ascii_case("chunked") => dechunk(body),
+                _ => body.to_vec(),
+            };
+            Ok(Response::Body(String::from_utf8_lossy(&body).to_string()))
+        },
+        300..=399 => match header("Location") {
+            Some(location) if location.starts_with('/') => Ok(Response::Redirect(format!("http://{}{}", host, location))),
+            Some(location) => Ok(Response::Redirect(location)),
+            None => Err(failed(url, &format!("the server sent a {} redirect with nowhere to go", status))),
+        },
+        _ => Err(failed(url, &format!("the server answered {}", status))),
+    }
+}
+
+// dechunk - the body of a response sent with chunked transfer encoding, put back together
+fn dechunk(mut body: &[u8]) -> Vec<u8> {
+    let mut joined = Vec::new();
+    while let Some(end) = body.windows(2).position(|w| w == b"\r\n") {
+        let size = String::from_utf8_lossy(&body[..end]);
+        let size = usize::from_str_radix(size.split(';').next().unwrap_or("").trim(), 16).unwrap_or(0);
+        let start = end + 2;
+        if size == 0 || start + size > body.len() {
+            break;
+        }
+        joined.extend_from_slice(&body[start..start + size]);
+        body = &body[(start + size + 2).min(body.len())..];
+    }
+    joined
+}
+
+// curl - download an https url with the curl command
+fn curl(url: &str) -> Result<String, WordfindError> {
+    let output = process::Command::new("curl").args(["-fsSL", url]).output()
+        .map_err(|_| failed(url, "https urls are downloaded with curl, which isn't installed"))?;
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(failed(url, if message.is_empty() { "curl couldn't download it" } else { &message }));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// failed - the error for a url that couldn't be downloaded
+fn failed(url: &str, reason: &str) -> WordfindError {
+    WordfindError::DownloadFailed { url: url.to_string(), reason: reason.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+    
+    // serve - answer one request on a local port with each of the responses in turn
+    fn serve(responses: Vec<&'static str>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request);
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        address
+    }
+    
+    #[test]
+    fn downloads() {
+        let address = serve(vec![
+            "HTTP/1.1 302 Found\r\nLocation: /words.txt\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\ncat\n\r\n4\r\ndog\n\r\n0\r\n\r\n",
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n",
+        ]);
+        assert_eq!(download(&format!("http://{}/list", address)).unwrap(), "cat\ndog\n");
+        let err = download(&format!("http://{}/gone", address)).unwrap_err();
+        assert_eq!(err.to_string(), format!("couldn't download http://{}/gone: the server answered 404", address));
+        assert!(download("ftp://example.com/words.txt").is_err());
+    }
+}
@@ -0,0 +1,158 @@
+// The C interface, for calling the generator from Swift, Kotlin, or anything else that can call C,
+// see include/wordfindgen.h
+//
+// A puzzle is handed out as a pointer that stays valid until it's given back to
+// wordfindgen_free, strings are utf-8 with their length, and a failed call returns null or 0 with
+// the reason in wordfindgen_error
+//
+// Every pointer passed in has to be one these functions handed out, or a buffer of the length
+// given with it, which is why they're unsafe and why clippy's safety doc lint is quiet here
+#![allow(clippy::missing_safety_doc)]
+
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::ptr;
+use std::slice;
+use crate::cli::options_config;
+use crate::{make_from_text, PuzzleGrid, WordfindError};
+
+thread_local! {
+    // why the last call on this thread failed, for wordfindgen_error
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+// wordfindgen_generate - a filled in puzzle from the words, one to a line, and the options, a json
+//                        object of command line options like {"size": 12}, which can be empty
+//                        Null when the puzzle can't be made
+#[no_mangle]
+pub unsafe extern "C" fn wordfindgen_generate(words: *const u8, words_len: usize, options: *const u8, options_len: usize, seed: u64) -> *mut PuzzleGrid {
+    let made = text(words, words_len).and_then(|words| {
+        let config = options_config(&text(options, options_len)?)?;
+        make_from_text(config, &words, seed)
+    });
+    match made {
+        Ok(puzzle) => Box::into_raw(Box::new(puzzle)),
+        Err(err) => {
+            set_error(&err);
+            ptr::null_mut()
+        },
+    }
+}
+
+// wordfindgen_width - columns in the grid, 0 for a null puzzle
+#[no_mangle]
+pub unsafe extern "C" fn wordfindgen_width(puzzle: *const PuzzleGrid) -> usize {
+    puzzle.as_ref().map_or(0, |p| p.width)
+}
+
+// wordfindgen_height - rows in the grid, 0 for a null puzzle
+#[no_mangle]
+pub unsafe extern "C" fn wordfindgen_height(puzzle: *const PuzzleGrid) -> usize {
+    puzzle.as_ref().map_or(0, |p| p.height)
+}
+
+// wordfindgen_cell - copy the letter in column x of row y, counted from 0, into the buffer and
+//                    return how many bytes it takes, a letter can be more than one byte of utf-8
+//                    Nothing is copied when the buffer is too small, call it again with a bigger
+//                    one, and 0 is returned for a cell outside the grid
+#[no_mangle]
+pub unsafe extern "C" fn wordfindgen_cell(puzzle: *const PuzzleGrid, x: usize, y: usize, buffer: *mut u8, buffer_len: usize) -> usize {
+    let cell = match puzzle.as_ref().and_then(|p| p.grid.get(y)).and_then(|row| row.get(x)) {
+        Some(cell) => cell.as_bytes(),
+        None => return 0,
+    };
+    if !buffer.is_null() && cell.len() <= buffer_len {
+        ptr::copy_nonoverlapping(cell.as_ptr(), buffer, cell.len());
+    }
+    cell.len()
+}
+
+// wordfindgen_json - the whole puzzle as render_json writes it, with the words and where they
+//                    are, as a string to give back to wordfindgen_string_free
+#[no_mangle]
+pub unsafe extern "C" fn wordfindgen_json(puzzle: *const PuzzleGrid) -> *mut c_char {
+    match puzzle.as_ref() {
+        Some(puzzle) => c_string(puzzle.render_json()),
+        None => ptr::null_mut(),
+    }
+}
+
+// wordfindgen_error - why the last call on this thread failed, null when nothing has, valid until
+//                     the next call that fails
+#[no_mangle]
+pub extern "C" fn wordfindgen_error() -> *const c_char {
+    LAST_ERROR.with(|error| error.borrow().as_ref().map_or(ptr::null(), |e| e.as_ptr()))
+}
+
+// wordfindgen_free - give back a puzzle from wordfindgen_generate, null is ignored
+#[no_mangle]
+pub unsafe extern "C" fn wordfindgen_free(puzzle: *mut PuzzleGrid) {
+    if !puzzle.is_null() {
+        drop(Box::from_raw(puzzle));
+    }
+}
+
+// wordfindgen_string_free - give back a string from wordfindgen_json, null is ignored
+#[no_mangle]
+pub unsafe extern "C" fn wordfindgen_string_free(text: *mut c_char) {
+    if !text.is_null() {
+        drop(CString::from_raw(text));
+    }
+}
+
+// text - the utf-8 text in a buffer, a null buffer is empty
+unsafe fn text(buffer: *const u8, len: usize) -> Result<String, WordfindError> {
+    if buffer.is_null() {
+        return Ok(String::new());
+    }
+    String::from_utf8(slice::from_raw_parts(buffer, len).to_vec()).map_err(|_| WordfindError::Usage(String::from("the text isn't utf-8")))
+}
+
+// c_string - the text as a string for C, which ends at its first nul
+fn c_string(text: String) -> *mut c_char {
+    let end = text.find('\0').unwrap_or(text.len());
+    CString::new(&text[..end]).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+// set_error - keep the error for wordfindgen_error
+fn set_error(err: &WordfindError) {
+    let message = CString::new(err.to_string().replace('\0', "")).ok();
+    LAST_ERROR.with(|error| *error.borrow_mut() = message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+    
+    #[test]
+    fn c_calls() {
+        let words = "cat\ndog\nowl\n";
+        let options = "{\"size\": 6, \"title\": \"Pets\"}";
+        unsafe {
+            let puzzle = wordfindgen_generate(words.as_ptr(), words.len(), options.as_ptr(), options.len(), 42);
+            assert!(!puzzle.is_null());
+            assert_eq!((wordfindgen_width(puzzle), wordfindgen_height(puzzle)), (6, 6));
+            
+            let mut letter = [0u8; 8];
+            assert_eq!(wordfindgen_cell(puzzle, 2, 3, letter.as_mut_ptr(), letter.len()), 1);
+            assert_eq!(letter[0] as char, (*puzzle).grid()[3][2].chars().next().unwrap());
+            assert_eq!(wordfindgen_cell(puzzle, 6, 0, letter.as_mut_ptr(), letter.len()), 0);
+            
+            let json = wordfindgen_json(puzzle);
+            assert!(CStr::from_ptr(json).to_str().unwrap().starts_with("{\"title\":\"Pets\""));
+            wordfindgen_string_free(json);
+            wordfindgen_free(puzzle);
+            
+            let puzzle = wordfindgen_generate(words.as_ptr(), words.len(), ptr::null(), 0, 1);
+            assert_eq!(wordfindgen_width(puzzle), 20);
+            wordfindgen_free(puzzle);
+            
+            let long = "hippopotamus\n";
+            let options = "{\"size\": 5}";
+            assert!(wordfindgen_generate(long.as_ptr(), long.len(), options.as_ptr(), options.len(), 1).is_null());
+            assert!(CStr::from_ptr(wordfindgen_error()).to_str().unwrap().contains("hippopotamus is too long"));
+        }
+    }
+}
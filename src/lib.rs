@@ -1,71 +1,435 @@
 extern crate rand;
+pub use rand::rngs::StdRng;
+pub use rand::{RngCore, SeedableRng};
+use std::cell::Cell;
+use std::collections::HashMap;
 use std::fs;
-use std::error::Error;
 use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use rand::Rng;
 use std::convert::TryFrom;
 use std::io::prelude::*;
 use rand::seq::SliceRandom;
+use rand::distributions::{Distribution, WeightedIndex};
+use log::{log, log_enabled, Level};
 
-// Config - configuration based on command line arguments
+mod alphabet;
+mod builder;
+mod cancel;
+mod cli;
+mod error;
+#[cfg(feature = "ffi")]
+mod ffi;
+#[cfg(feature = "http")]
+mod fetch;
+mod load;
+mod optimize;
+mod play;
+mod render;
+mod shape;
+mod solve;
+mod strategy;
+mod stream;
+mod theme;
+#[cfg(feature = "serve")]
+mod serve;
+#[cfg(feature = "wasm")]
+mod web;
+mod wordlist;
+pub use alphabet::{plain_arabic, Alphabet};
+pub use builder::{Puzzle, PuzzleBuilder};
+pub use cancel::CancelToken;
+pub use cli::{Command, USAGE};
+pub use error::{ErrorFormat, ErrorKind, WordProblem, WordfindError};
+pub use render::{AnswerStyle, BankStyle, BLOCK, Format, ImageOptions, PrintStyle, WordOrder, LARGE_PRINT_SIZE};
+pub use load::convert;
+pub use optimize::{Objective, OPTIMIZE_ROUNDS};
+pub use play::{play, PlayReport};
+pub use shape::{letter_mask, Shape};
+#[cfg(feature = "serve")]
+pub use serve::serve;
+pub use solve::{read_grid, solve, SolveReport};
+pub use stream::{generate_many, Puzzles};
+pub use strategy::{BacktrackingPlacement, BalancedPlacement, CrissCrossPlacement, MatchedPlacement, OverlapPlacement, PlacementStrategy, RandomPlacement};
+pub use theme::Theme;
+pub use wordlist::{WordList, WordStats};
+#[cfg(feature = "wasm")]
+pub use web::{generate_json, solve_json};
+
+// Config - configuration for generating a puzzle, usually built from the command line by Command::from_args
 //
-// The first arg should be the name of a text file with the words to place in the puzzle
-// If a second argument is included the puzzle will be more difficult,
-//    by also placing words right to left (backwards)
+// wordsfile is the name of a text file with the words to place in the puzzle, more_wordsfiles
+// are any other files whose words go in the same puzzle
+// difficulty picks the directions the words can go in, see Difficulty::apply for the rest of a preset
 //
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Config {
     pub wordsfile: String,
-    pub size: usize,
+    pub more_wordsfiles: Vec<String>,
+    // the column of a csv or tsv words file the words are in, by its heading or its number from 1,
+    // and the column of their clues, for a spreadsheet export, see table_words
+    pub column: Option<String>,
+    pub clue_column: Option<String>,
+    // a built in word list to use in place of the words file
+    pub theme: Option<Theme>,
+    // the language code and words file of each puzzle of a set of the same list in different
+    // languages, used in place of the words file, see run
+    pub languages: Vec<(String, String)>,
+    // dictionary file solve sweeps the grid with for words that aren't in the word list, one
+    // word per line
+    pub dictionary: Option<String>,
+    pub width: usize,
+    pub height: usize,
     pub maxtries: usize,
-    pub hard: bool,
+    pub difficulty: Difficulty,
+    // the level from 1 to 5 the difficulty_rating of each puzzle should come out at, the
+    // directions, packing, fill, and decoys are tuned until it does, see make_leveled
+    pub level: Option<u8>,
+    // exactly the directions the words can go in, in place of the ones the difficulty allows,
+    // rtl doesn't mirror them
+    pub directions: Option<Vec<Direction>>,
+    // how often a word goes in a direction next to the others, the ones not listed count 1, so
+    // backwards or diagonal words can be made rarer without leaving them out, see
+    // PuzzleGrid::set_direction_weights
+    pub direction_weights: Vec<(Direction, f32)>,
+    // fraction of grid cells that should be covered by placed words before random fill, and how
+    // the puzzle gets there
+    pub target_density: Option<f32>,
+    pub density_mode: DensityMode,
+    // extra words DensityMode::Pool adds to the puzzle until it's covered enough
+    pub density_pool: Vec<String>,
+    // number of times to throw away the grid and start over when too many words fail to place
+    pub max_regens: usize,
+    // fraction of the words allowed to fail before the grid is regenerated
+    pub max_failed_fraction: f32,
+    // letters that should never be used as random filler
+    pub exclude_fill: Vec<char>,
+    // letters with how often fill_in should pick each one, replaces fill_chars when set
+    pub fill_frequencies: Option<Vec<(char, f32)>>,
+    // letters fill_in picks from instead of A to Z, a letter listed more than once comes up more often
+    pub fill_chars: Option<String>,
+    // script to take the fill letters from, picked from the words when not set
+    pub alphabet: Option<Alphabet>,
+    // letters to fill the blank cells with in reading order before the random fill, spaces and punctuation are dropped
+    pub hidden_message: Option<String>,
+    // fill with partial copies of the words and letters taken from them, for expert puzzles
+    pub camouflage: bool,
+    // strip the accents off the words in the grid, the word bank still shows them
+    pub fold_diacritics: bool,
+    // mirror the directions so the easy ones read right to left, for hebrew and arabic word lists
+    pub rtl: bool,
+    // punctuation to keep in the words instead of leaving out, like "-" for negative numbers
+    pub keep_chars: Option<String>,
+    // the case the words and filler are written in
+    pub case: Case,
+    // how the command line reports errors, as a sentence or as json
+    pub error_format: ErrorFormat,
+    // stop with an error when a word is listed twice instead of leaving out the second one
+    pub strict_duplicates: bool,
+    // the order the word bank is printed in
+    pub word_order: WordOrder,
+    // the order of the word bank for one format in place of word_order, see bank_order
+    pub format_orders: Vec<(Format, WordOrder)>,
+    // what the puzzle shows in place of the word bank, the answer key always lists the words
+    pub bank_style: BankStyle,
+    // leave out words with fewer letters than this
+    pub min_length: usize,
+    // leave out words with more letters than this
+    pub max_length: Option<usize>,
+    // only use this many words from the list, the first ones or random ones with random_words
+    pub max_words: Option<usize>,
+    pub random_words: bool,
+    // only use words matching this pattern, * is any run of letters, ? any one letter, and [abc] any
+    // one of the letters in the brackets
+    pub pattern: Option<String>,
+    // number of near misses, like a word with one letter changed, to write in before the fill
+    pub decoys: usize,
+    // words the random fill must not spell out in any direction, usually DEFAULT_BLOCKLIST and any extras
+    pub blocklist: Option<Vec<String>>,
+    // blank columns to the left of the grid and word list in the csv output
+    pub margin: usize,
+    // separates the fields of the csv output, ';' or a tab suit locales where ',' is the decimal separator
+    pub delimiter: char,
+    // words on each line of the word list in the csv output
+    pub words_per_row: usize,
+    // put quotes around every field of the csv output, not only the ones that need them
+    pub quote_all: bool,
+    // blank rows above and below the grid in the csv output
+    pub pad_rows: usize,
+    // add column letters across the top and row numbers down the side of the grid
+    pub label: bool,
+    // check that every word can be found in the grid before it is written out
+    pub verify: bool,
+    // seed for the random number generator, the same seed and words produce the same puzzle
+    pub seed: Option<u64>,
+    // number of puzzles to make from the word list, each with its own layout, more than one are
+    // written to numbered files, puzzle_01.csv and answer_key_01.csv
+    pub count: usize,
+    // threads making the puzzles of a batch or book at once, 0 for one on each core
+    pub threads: usize,
+    // file the finished puzzle is written to, puzzle.<extension of the format> when not set
+    pub output: Option<String>,
+    // file the answer key is written to, answer_key.<extension of the format> when not set
+    pub answer_output: Option<String>,
+    // file to write the start cell and direction of every word to, not written when not set
+    pub answer_list: Option<String>,
+    // file to write the row and column of the first letter of every word to, hints to hand out to
+    // solvers who get stuck, not written when not set
+    pub hint_file: Option<String>,
+    // directory the output files go in, created if it doesn't exist, the working directory when not set
+    pub output_dir: Option<String>,
+    // write over output files that are already there instead of stopping with FileExists
+    pub force: bool,
+    // the columns of the word bank for one format in place of print.bank_columns, see print_style
+    pub format_columns: Vec<(Format, usize)>,
+    // what kind of file the puzzle and answer key are written as
+    pub format: Format,
+    // more formats to write the same puzzle in, each to files with its own extension
+    pub more_formats: Vec<Format>,
+    // shown above the grid by every output format
+    pub title: Option<String>,
+    pub subtitle: Option<String>,
+    pub instructions: Option<String>,
+    // write the answer key too, as its own file or as an extra page depending on the format
+    pub answer_key: bool,
+    // put the answer key in the same file as the puzzle for the formats that would write it to a
+    // second file, for a teacher's copy with both
+    pub combined: bool,
+    // convert fills the puzzle again with new filler, the words stay where they are
+    pub refill: bool,
+    // how the csv and text answer keys show the filler letters
+    pub answer_style: AnswerStyle,
+    // cell, margin, and letter sizes for png output
+    pub image: ImageOptions,
+    // letter size, font, and contrast for pdf, html, and svg output, see PrintStyle::large_print
+    pub print: PrintStyle,
+    // template file to lay out the puzzle and answer key with in place of the format, the files get
+    // the template's extension, see render/template.rs for the placeholders
+    pub template: Option<String>,
+    // pick the grid size from the word list instead of using width and height
+    pub auto_size: bool,
+    // fraction of the cells the words should cover when auto_size picks the grid size
+    pub auto_density: f32,
+    // fall back to an exhaustive search when random placement can't fit every word
+    pub backtrack: bool,
+    // number of puzzles to generate, the one with the best score is kept
+    pub candidates: usize,
+    pub score_weights: ScoreWeights,
+    // place each word where it shares the most letters with the words already placed
+    pub dense: bool,
+    // place each word where it keeps the words spread evenly around the middle of the grid instead
+    // of bunched up in a corner, and with symmetric also where the layout looks the same turned
+    // halfway round
+    pub balanced: bool,
+    pub symmetric: bool,
+    // every word has to cross at least this many of the others, the grid is started over until
+    // they do, and the words are placed where they share the most letters unless strategy is set
+    pub min_crossings: usize,
+    // once the words are in, move them around for OPTIMIZE_ROUNDS rounds to get more of this, see
+    // PuzzleGrid::optimize, pinned words and the spangram stay where they are
+    pub optimize: Option<Objective>,
+    // words never share a cell, and are kept this many blank cells apart, for young solvers who
+    // get confused by words that cross, 0 only stops them sharing cells
+    pub spacing: Option<usize>,
+    // the cells of the grid that can be used, a row of true and false for each row of the grid,
+    // for shaped puzzles like a tree or a pumpkin, the grid is the size of the mask when it's set
+    pub mask: Option<Vec<Vec<bool>>>,
+    // areas of the grid no word goes through, column, row, width, and height counting from 0 at
+    // the top left, still given filler, for clip art printed over the corner of the puzzle
+    pub reserved: Vec<(usize, usize, usize, usize)>,
+    // a built in shape drawn for the size of the grid, used when mask isn't set
+    pub shape: Option<Shape>,
+    // a short word or initial drawn in giant letters across the grid for its shape, with
+    // letter_mask, used when neither mask nor shape is set
+    pub shape_text: Option<String>,
+    // words turn a corner once instead of going in a straight line, the answer key shows where
+    pub bent: bool,
+    // one of the words that has to reach from one edge of the grid to the opposite one, turning a
+    // corner if it needs to, with the other words around it, like the theme word of a Strands puzzle
+    pub spangram: Option<String>,
+    // words from the list that have to lie flush along one of the borders of the grid, the way
+    // "WORD @ edge" pins them in the word list
+    pub edge_words: Vec<String>,
+    // make a criss-cross instead of a word search, the words go across and down through each other
+    // with every cell between them blacked out, the solver fills them in from a word bank grouped
+    // by length
+    pub crisscross: bool,
+    // make two puzzles of the words with them in different places and write both into one file,
+    // side by side for text and a page each otherwise, for two solvers to race each other
+    pub double: bool,
+    // fraction of the letters of each word circled on the puzzle as hints, rounded up so every word
+    // gets one, for solvers who can't manage a search with no help, 0 for none
+    pub reveal: f32,
+    // custom placement strategy, overrides dense when set
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub strategy: Option<Arc<dyn PlacementStrategy>>,
+    // leave words that can't be placed out of the puzzle instead of failing
+    pub skip_failed: bool,
+    // when the words don't fit, grow the grid 2 at a time until the longer side reaches this size
+    pub grow_to: Option<usize>,
+    // called with a Progress as each word is placed, from the threads of a batch at once
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub progress: Option<ProgressHook>,
+    // give up with WordfindError::TimedOut when the puzzles take longer than this, counted from
+    // the start of run, run_book, or PuzzleBuilder::build
+    pub timeout: Option<Duration>,
+    // give up with WordfindError::Cancelled once the token is cancelled from another thread
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub cancel: Option<CancelToken>,
+    // the puzzle of a batch the log messages are about, set for each puzzle of a Config::count batch
+    // so the messages from the threads making them can be told apart
+    pub log_puzzle: Option<usize>,
+    // called with each PlacementEvent as the words are tried and placed, for watching the
+    // generator at work step by step
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub events: Option<EventHook>,
+    // how much the command line prints, -v and -vv turn up the log messages and -q prints nothing
+    // but errors
+    pub verbose: usize,
+    pub quiet: bool,
+    // print the PuzzleStats of each puzzle when the command line finishes, for tuning maxtries and
+    // the grid size
+    pub stats: bool,
+    // print each puzzle and its answer key in color when the command line finishes, for trying out
+    // a word list without opening the files
+    pub show: bool,
 }
 
 impl Config {
-    pub fn new(mut args: std::env::Args) -> Result<Config, &'static str> {
-        // move past program invocation
-        args.next();
-        
-        let wordsfile = match args.next(){
-            Some(arg) => arg,
-            None => return Err("no input words file provided"),
-        };
-        
-        let hard = match args.next() {
-            Some(_arg) => true,
-            None => false,
-        };
-        
-        Ok(Config { wordsfile, size: 20, maxtries: 10000, hard })
+    // bank_order - the order of the word bank for the format being written
+    pub fn bank_order(&self) -> WordOrder {
+        self.format_orders.iter().rev().find(|(format, _)| *format == self.format).map_or(self.word_order, |(_, order)| *order)
     }
-}
-
-// PuzzleError - Just need a struct that implements Error
-//
-#[derive(Debug)]
-struct PuzzleError {
-    err: String,
-}
-
-impl PuzzleError {
-    pub fn new(err: String) -> PuzzleError {
-        PuzzleError { err }
+    
+    // print_style - the print style with the word bank columns for the format being written
+    pub fn print_style(&self) -> PrintStyle {
+        let columns = self.format_columns.iter().rev().find(|(format, _)| *format == self.format).map(|(_, columns)| *columns);
+        PrintStyle { bank_columns: columns.or(self.print.bank_columns), ..self.print.clone() }
     }
 }
 
-impl fmt::Display for PuzzleError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", &self.err)
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            wordsfile: String::new(),
+            more_wordsfiles: Vec::new(),
+            column: None,
+            clue_column: None,
+            theme: None,
+            languages: Vec::new(),
+            dictionary: None,
+            width: 20,
+            height: 20,
+            maxtries: 10000,
+            difficulty: Difficulty::Medium,
+            level: None,
+            directions: None,
+            direction_weights: Vec::new(),
+            target_density: None,
+            density_mode: DensityMode::Copies,
+            density_pool: Vec::new(),
+            max_regens: 0,
+            max_failed_fraction: 0.0,
+            exclude_fill: Vec::new(),
+            fill_chars: None,
+            alphabet: None,
+            fill_frequencies: None,
+            hidden_message: None,
+            camouflage: false,
+            fold_diacritics: false,
+            rtl: false,
+            keep_chars: None,
+            case: Case::Upper,
+            error_format: ErrorFormat::Text,
+            strict_duplicates: false,
+            word_order: WordOrder::Original,
+            format_orders: Vec::new(),
+            bank_style: BankStyle::Words,
+            min_length: 0,
+            max_length: None,
+            max_words: None,
+            random_words: false,
+            pattern: None,
+            decoys: 0,
+            blocklist: None,
+            margin: 3,
+            delimiter: ',',
+            words_per_row: 2,
+            quote_all: false,
+            pad_rows: 0,
+            label: false,
+            verify: false,
+            seed: None,
+            count: 1,
+            threads: 0,
+            output: None,
+            answer_output: None,
+            answer_list: None,
+            hint_file: None,
+            output_dir: None,
+            force: false,
+            format_columns: Vec::new(),
+            format: Format::Csv,
+            more_formats: Vec::new(),
+            title: None,
+            subtitle: None,
+            instructions: None,
+            answer_key: true,
+            combined: false,
+            refill: false,
+            answer_style: AnswerStyle::Blank,
+            image: ImageOptions::default(),
+            print: PrintStyle::default(),
+            template: None,
+            auto_size: false,
+            auto_density: 0.5,
+            backtrack: true,
+            candidates: 1,
+            score_weights: ScoreWeights::default(),
+            dense: false,
+            balanced: false,
+            symmetric: false,
+            min_crossings: 0,
+            optimize: None,
+            spacing: None,
+            mask: None,
+            reserved: Vec::new(),
+            shape: None,
+            shape_text: None,
+            bent: false,
+            spangram: None,
+            edge_words: Vec::new(),
+            crisscross: false,
+            double: false,
+            reveal: 0.0,
+            strategy: None,
+            skip_failed: false,
+            grow_to: None,
+            progress: None,
+            timeout: None,
+            cancel: None,
+            log_puzzle: None,
+            events: None,
+            verbose: 0,
+            quiet: false,
+            stats: false,
+            show: false,
+        }
     }
 }
 
-impl Error for PuzzleError {
-}
-
 // Direction - The 8 possible directions in which to place a word in the puzzle
 //
 // Clone and Copy are derived so that move isn't the default action when using assignment
 //
-#[derive(Debug,Clone,Copy)]
+#[derive(Debug,Clone,Copy,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Direction {
     Right,
     UpRight,
@@ -78,6 +442,9 @@ pub enum Direction {
 }
 
 impl Direction{
+    // All of the directions, in the same order as the enum
+    pub const ALL: [Direction; 8] = [Direction::Right, Direction::UpRight, Direction::Up, Direction::UpLeft, Direction::Left, Direction::DownLeft, Direction::Down, Direction::DownRight];
+    
     // The x and y increment values associated with each direction
     pub fn incrementors(&self) -> (i8, i8) {
         match self {
@@ -91,215 +458,5605 @@ impl Direction{
             Direction::DownRight => (1, 1),
         }
     }
+    
+    // from_name - the direction for a name given on the command line, like downright or down-right
+    pub fn from_name(name: &str) -> Option<Direction> {
+        let name: String = name.to_ascii_lowercase().chars().filter(|c| c.is_ascii_alphabetic()).collect();
+        match name.as_str() {
+            "right" => Some(Direction::Right),
+            "upright" => Some(Direction::UpRight),
+            "up" => Some(Direction::Up),
+            "upleft" => Some(Direction::UpLeft),
+            "left" => Some(Direction::Left),
+            "downleft" => Some(Direction::DownLeft),
+            "down" => Some(Direction::Down),
+            "downright" => Some(Direction::DownRight),
+            _ => None,
+        }
+    }
+    
+    // from_incrementors - the direction that moves by the given x and y increments
+    pub fn from_incrementors(x_inc: i8, y_inc: i8) -> Option<Direction> {
+        match (x_inc, y_inc) {
+            (1, 0) => Some(Direction::Right),
+            (1, -1) => Some(Direction::UpRight),
+            (0, -1) => Some(Direction::Up),
+            (-1, -1) => Some(Direction::UpLeft),
+            (-1, 0) => Some(Direction::Left),
+            (-1, 1) => Some(Direction::DownLeft),
+            (0, 1) => Some(Direction::Down),
+            (1, 1) => Some(Direction::DownRight),
+            _ => None,
+        }
+    }
+    
+    // rotated90 - the direction after turning the grid a quarter turn clockwise
+    pub fn rotated90(&self) -> Direction {
+        let (x_inc, y_inc) = self.incrementors();
+        Direction::from_incrementors(-y_inc, x_inc).unwrap()
+    }
+    
+    // mirrored - the direction after flipping the grid left to right
+    pub fn mirrored(&self) -> Direction {
+        let (x_inc, y_inc) = self.incrementors();
+        Direction::from_incrementors(-x_inc, y_inc).unwrap()
+    }
+    
+    // straight - whether the direction goes along a row or a column rather than a diagonal
+    pub fn straight(&self) -> bool {
+        let (x_inc, y_inc) = self.incrementors();
+        x_inc == 0 || y_inc == 0
+    }
+    
+    // reach - the most letters a word going this way can have in a width by height grid, a word
+    //         exactly this long runs from one edge to the other
+    pub fn reach(&self, width: usize, height: usize) -> usize {
+        match self.incrementors() {
+            (_, 0) => width,
+            (0, _) => height,
+            _ => width.min(height),
+        }
+    }
 }
 
-// PuzzleGrid - The main struct for holding and generating the puzzle
+// Expert puzzles have this many decoys unless Config::decoys asks for more
+const EXPERT_DECOYS: usize = 3;
+
+// Puzzles make_leveled makes looking for one at Config::level before it settles for the closest,
+// and the settings it steps through from the easiest puzzles to the hardest
+pub const LEVEL_TRIES: usize = 24;
+const LEVEL_STEPS: usize = 8;
+
+// The directions of the hardest steps of make_leveled and how often the words go in them, the
+// backwards diagonals are the hardest words to spot
+const BACKWARDS: [Direction; 4] = [Direction::UpLeft, Direction::DownLeft, Direction::Left, Direction::Up];
+const BACKWARDS_WEIGHTS: [(Direction, f32); 4] = [(Direction::UpLeft, 4.0), (Direction::DownLeft, 4.0), (Direction::Left, 2.0), (Direction::Up, 2.0)];
+
+// Difficulty - Named presets for how hard the puzzle is to solve
 //
-struct PuzzleGrid {
-    grid: Vec<Vec<String>>,
-    size: i8,
-    maxtries: usize,
-    dir_choices: Vec<Direction>,
-    entries: Vec<String>,
+#[derive(Debug,Clone,Copy,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Difficulty {
+    // left to right and top to bottom only, for early readers
+    Easy,
+    // forwards, up, and down the diagonals that read left to right, the usual word find
+    Medium,
+    // all eight directions, so words can read backwards
+    Hard,
+    // all eight directions packed together, with decoys, a camouflage fill, and no word bank
+    Expert,
 }
 
-impl PuzzleGrid {
-    pub fn new(size: i8, maxtries: usize, hard: bool) -> PuzzleGrid {
-        let s = usize::try_from(size).unwrap();
-        let grid: Vec<Vec<String>> = vec![vec![String::from(" "); s]; s];
-        let dir_choices = if hard {
-            vec![Direction::Right, Direction::UpRight, Direction::Up, Direction::UpLeft, Direction::Left, Direction::DownLeft, Direction::Down, Direction::DownRight]
-        } else {
-            vec![Direction::Right, Direction::UpRight, Direction::Up, Direction::Down, Direction::DownRight]
-        };
-        PuzzleGrid { grid, size, maxtries, dir_choices, entries: Vec::new() }
+impl Difficulty {
+    // from_name - the difficulty for a name given on the command line
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        match name.to_ascii_lowercase().as_str() {
+            "easy" => Some(Difficulty::Easy),
+            "medium" | "normal" => Some(Difficulty::Medium),
+            "hard" => Some(Difficulty::Hard),
+            "expert" => Some(Difficulty::Expert),
+            _ => None,
+        }
     }
     
-    // place - attempts to randomly place the given word into the puzzle
-    pub fn place(&mut self, word: &str) -> Result<(), Box<dyn Error>> {
-        let mut x = 0;
-        let mut y = 0;
-        let mut dir = Direction::Right;
-        let mut placed = false;
-        let mut sanitized_word = String::from(word);
-        sanitized_word.make_ascii_uppercase();
-        
-        // randomly select x, y, and direction until maxtries reached, or valid placement was found
-        for _ in 1..self.maxtries {
-            x = rand::thread_rng().gen_range(0, self.size);
-            y = rand::thread_rng().gen_range(0, self.size);
-            if let Some(d) = self.dir_choices.choose(&mut rand::thread_rng()) { dir = *d };
-            placed = self.placement_valid(&sanitized_word, &x, &y, &dir);
-            if placed { break; }
+    // directions - the directions the words can be placed in
+    pub fn directions(&self) -> Vec<Direction> {
+        match self {
+            Difficulty::Easy => vec![Direction::Right, Direction::Down],
+            Difficulty::Medium => vec![Direction::Right, Direction::UpRight, Direction::Up, Direction::Down, Direction::DownRight],
+            Difficulty::Hard | Difficulty::Expert => Direction::ALL.to_vec(),
         }
-        
-        if placed {
-            self.entries.push(sanitized_word.to_string());
-        
-            // place the word in the puzzle here
-            // probably could have directly returned to iterators over the indeces
-            let (x_indeces, y_indeces) = self.get_indeces(&sanitized_word, &x, &y, &dir);
-            let mut x_iter = x_indeces.iter();
-            let mut y_iter = y_indeces.iter();
-            
-            for char in sanitized_word.chars() {
-                let xi = x_iter.next().unwrap();
-                let yi = y_iter.next().unwrap();
-                self.grid[*yi][*xi] = char.to_string();
-            }
-            
-            Ok(())
-        } else {
-            Err(Box::new(PuzzleError::new(format!("{} could not be placed in the puzzle", word))))
-        }        
     }
     
-    // get_indeces - returns the Vec[x][y] for placement into the puzzle of each character in the word
-    //               There is likely a more elegant way to do this
-    pub fn get_indeces(&self, word: &str, x: &i8, y: &i8, dir: &Direction) -> (Vec<usize>, Vec<usize>) {
-        let (x_inc, y_inc) = dir.incrementors();
-        let mut x_indeces: Vec<usize> = Vec::with_capacity(word.len());
-        let mut y_indeces: Vec<usize> = Vec::with_capacity(word.len());
-        
-        let mut xi = *x;
-        let mut yi = *y;
-        for _ in word.chars() {
-            x_indeces.push(usize::try_from(xi).unwrap());
-            y_indeces.push(usize::try_from(yi).unwrap());
-            xi += x_inc;
-            yi += y_inc;
+    // apply - set the config up for the preset, the directions, whether the words are packed
+    //         together, the fill, decoys, and whether the word bank is shown
+    //         Options set after this change that part of the preset
+    pub fn apply(&self, config: &mut Config) {
+        let expert = *self == Difficulty::Expert;
+        config.difficulty = *self;
+        config.dense = expert;
+        config.camouflage = expert;
+        config.decoys = if expert { config.decoys.max(EXPERT_DECOYS) } else { 0 };
+        config.bank_style = if expert { BankStyle::Hidden } else { BankStyle::Words };
+    }
+}
+
+// Case - how the letters of the words and the filler are written
+//
+#[derive(Debug,Clone,Copy,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Case {
+    // all capitals, the usual word find
+    Upper,
+    // all small letters, for early readers learning lowercase
+    Lower,
+    // the words keep the case they were typed in and the filler is a mix of both
+    Preserve,
+}
+
+impl Case {
+    // from_name - the case for a name given on the command line
+    pub fn from_name(name: &str) -> Option<Case> {
+        match name.to_ascii_lowercase().as_str() {
+            "upper" => Some(Case::Upper),
+            "lower" => Some(Case::Lower),
+            "preserve" | "mixed" => Some(Case::Preserve),
+            _ => None,
         }
-        
-        (x_indeces, y_indeces)
     }
     
-    // placement_valid - returns true if the word fits at the given coordinates and direction with no collisions
-    //                   Lots of code duplication with get_indeces, likely a better way to do this.
-    fn placement_valid(&self, word: &str, x: &i8, y: &i8, dir: &Direction) -> bool {
-        let (x_inc, y_inc) = dir.incrementors();
-        let mut xi = *x;
-        let mut yi = *y;
-        for _ in word.chars() {
-            xi += x_inc;
-            yi += y_inc;
+    // apply - the text written in this case, preserve leaves it the way it is
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            Case::Upper => text.to_uppercase(),
+            Case::Lower => text.to_lowercase(),
+            Case::Preserve => text.to_string(),
         }
-        
-        if xi >= 0 && xi <= self.size && yi >= 0 && yi <= self.size {
-            // the word fits, now make sure it doesn't collide
-            let (x_indeces, y_indeces) = self.get_indeces(&word, &x, &y, &dir);
-            let mut x_iter = x_indeces.iter();
-            let mut y_iter = y_indeces.iter();
-            let space = String::from(" ");
-            
-            for char in word.chars() {
-                let xi = x_iter.next().unwrap();
-                let yi = y_iter.next().unwrap();
-                
-                // as long as the grid contains " " or a matching character there is no collision
-                if !(self.grid[*yi][*xi] == char.to_string() || self.grid[*yi][*xi] == space) {
-                    return false
-                }
-            }
-            true
-        } else {
-            false
+    }
+    
+    // fill_letters - a fill letter in this case, preserve keeps capitals and mixes in small letters
+    //                as the grid is filled
+    fn fill_letters(&self, c: char) -> Vec<char> {
+        match self {
+            Case::Lower => c.to_lowercase().collect(),
+            _ => c.to_uppercase().collect(),
         }
     }
+}
+
+// Placement - where a word was put in the grid
+//
+#[derive(Debug,Clone,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Placement {
+    pub word: String,
+    pub x: usize,
+    pub y: usize,
+    pub dir: Direction,
+    // for a bent word, the letter in the corner where it turns, counted from 0, and the direction
+    // the letters after it go in, None for a word in a straight line
+    pub bend: Option<(usize, Direction)>,
+}
+
+impl Placement {
+    // cells - the (x, y) coordinates of each letter of the word
+    pub fn cells(&self) -> Vec<(usize, usize)> {
+        self.path().into_iter().map(|(x, y)| (x as usize, y as usize)).collect()
+    }
     
-    // output - write the puzzle grid and words to a file in csv
-    pub fn output(&self, file_name: &str) -> Result<(), Box<dyn Error>> {
-        let mut file = fs::File::create(file_name)?;
-        
-        // puzzle grid
-        for v in self.grid.iter() {
-            file.write(b",,,")?;
-            file.write(v.join(",").as_bytes())?;
-            file.write(b"\n")?;
+    // path - cells, before checking that they're inside the grid
+    fn path(&self) -> Vec<(isize, isize)> {
+        let mut x = self.x as isize;
+        let mut y = self.y as isize;
+        let letters = letters(&self.word);
+        let mut path = Vec::with_capacity(letters.len());
+        for i in 0..letters.len() {
+            path.push((x, y));
+            let (x_inc, y_inc) = match self.bend {
+                Some((at, turn)) if i >= at => turn.incrementors(),
+                _ => self.dir.incrementors(),
+            };
+            x += x_inc as isize;
+            y += y_inc as isize;
         }
-        
-        // search words
-        file.write(b"\n\n\n")?;
-        let mut i = 0;
-        for entry in self.entries.iter() {
-            file.write(b",,,")?;
-            file.write(entry.as_bytes())?;
-            i += 1;
-            if i == 2 {
-                file.write(b"\n")?;
-                i = 0;
-            }
+        path
+    }
+}
+
+// The letters fill_in picks from by default
+const FILL_CHARS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// ENGLISH_FREQUENCIES - how often each letter shows up in english text, in percent
+//                       Pass it to PuzzleGrid::set_fill_frequencies for filler that looks like real words
+pub const ENGLISH_FREQUENCIES: [(char, f32); 26] = [
+    ('A', 8.2), ('B', 1.5), ('C', 2.8), ('D', 4.3), ('E', 12.7), ('F', 2.2), ('G', 2.0), ('H', 6.1), ('I', 7.0),
+    ('J', 0.15), ('K', 0.77), ('L', 4.0), ('M', 2.4), ('N', 6.7), ('O', 7.5), ('P', 1.9), ('Q', 0.095), ('R', 6.0),
+    ('S', 6.3), ('T', 9.1), ('U', 2.8), ('V', 0.98), ('W', 2.4), ('X', 0.15), ('Y', 2.0), ('Z', 0.074),
+];
+
+// Punctuation left out of words when they're written into the grid, along with any spaces
+const SEPARATORS: &str = "-'\u{2019}";
+
+// Upper bound on the extra placements attempted while working toward Config::target_density
+const MAX_DENSITY_ATTEMPTS: usize = 500;
+
+// DEFAULT_BLOCKLIST - words the fill shouldn't spell out on a puzzle meant for a classroom
+pub const DEFAULT_BLOCKLIST: &[&str] = &[
+    "ARSE", "ASS", "BITCH", "BOOB", "COCK", "CRAP", "CUNT", "DAMN", "DICK", "DILDO", "FART", "FUCK", "HELL", "JIZZ",
+    "NAZI", "PENIS", "PISS", "PORN", "POOP", "RAPE", "SEX", "SHAG", "SHIT", "SLUT", "TITS", "TWAT", "WANK", "WHORE",
+];
+
+// Upper bound on the rounds of re-rolled filler in clear_accidental_words
+const MAX_REROLLS: usize = 1000;
+
+// Times fill starts the filler over, decoys and all, when clear_unwanted_words can't clear it
+const FILL_RETRIES: usize = 5;
+
+// Upper bound on the positions tried by place_all_backtracking before giving up
+const MAX_BACKTRACK_STEPS: usize = 100_000;
+
+// Times the grid is started over, at least, when the words don't cross each other enough
+const CROSSING_REGENS: usize = 50;
+
+// Positions tried by place_all_backtracking between looks at the cancel token
+const CANCEL_CHECK_STEPS: usize = 1000;
+
+// Upper bound on the puzzles made for one of a Config::count batch while they match an earlier one,
+// and for the second layout of Config::double while its words are where the first one has them
+const MAX_REPEATS: usize = 20;
+
+// random_spot spends 1 / RANDOM_SHARE of maxtries on random positions before going through them
+// in order
+const RANDOM_SHARE: usize = 4;
+
+// Letters of the words for each cell of the grid past which they can't be expected to fit, words
+// that cross share a letter or two but rarely enough to make up for more than this
+const OVERFULL: f32 = 1.2;
+
+// Letters for each cell past which the words are likely to take many tries to fit
+const CROWDED: f32 = 0.8;
+
+// Sparsest fill auto_size makes a grid for, a smaller density would make the grid too big to hold
+const MIN_AUTO_DENSITY: f32 = 0.05;
+
+// Pin - Where a word has to go, from "HALLOWEEN @ (0,0) Down" in the word list
+//
+// The start cell is (column, row) counting from 0 at the top left, either half can be left out
+// "@ edge" lays the word flush along one of the borders, a row or column at the edge of the grid,
+// and can go with a start cell or direction, "OCEAN @ edge Left"
+//
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct Pin {
+    pub start: Option<(usize, usize)>,
+    pub direction: Option<Direction>,
+    pub edge: bool,
+}
+
+impl Pin {
+    // parse - the pin from the text after the @, "(0,0) Down", "(3, 4)", "down-right", or "edge"
+    pub fn parse(text: &str) -> Result<Pin, String> {
+        let text = text.trim();
+        let (start, rest) = match text.strip_prefix('(') {
+            Some(cell) => {
+                let (cell, rest) = cell.split_once(')').ok_or_else(|| String::from("has a start cell without a closing bracket"))?;
+                let (x, y) = cell.split_once(',').ok_or_else(|| String::from("has a start cell that isn't (column,row)"))?;
+                let x = x.trim().parse().map_err(|_| format!("has a start column that isn't a number, {}", x.trim()))?;
+                let y = y.trim().parse().map_err(|_| format!("has a start row that isn't a number, {}", y.trim()))?;
+                (Some((x, y)), rest.trim())
+            },
+            None => (None, text),
+        };
+        let (edge, rest) = match rest.get(..4) {
+            Some(word) if word.eq_ignore_ascii_case("edge") => (true, rest[4..].trim()),
+            _ => (false, rest),
+        };
+        let direction = match rest {
+            "" => None,
+            name => Some(Direction::from_name(name).ok_or_else(|| format!("has an unknown direction, {}", name))?),
+        };
+        if start.is_none() && direction.is_none() && !edge {
+            return Err(String::from("has an @ without a start cell, a direction, or edge after it"));
         }
-        
-        Ok(())
+        Ok(Pin { start, direction, edge })
     }
     
-    // fill_in - locate all blank grid entries and fill with a random letter
-    pub fn fill_in(&mut self) {
-        let chars = String::from("ABCDEFGHIJKLMNOPQRSTUVWXYZ");
-        let space = String::from(" ");
-        for v in self.grid.iter_mut() {
-            for i in v.iter_mut() {
-                if *i == space {
-                    let idx = rand::thread_rng().gen_range(0, chars.len());
-                    *i = chars.chars().nth(idx).unwrap().to_string();
+    // fits - the reason the pin can't work in a width by height grid for a word this many letters long
+    fn fits(&self, length: usize, width: usize, height: usize) -> Result<(), String> {
+        if let Some((x, y)) = self.start {
+            if x >= width || y >= height {
+                return Err(format!("starts outside a {} x {} puzzle", width, height));
+            }
+            if let Some(dir) = self.direction {
+                let (x_inc, y_inc) = dir.incrementors();
+                let steps = length as isize - 1;
+                let (x_end, y_end) = (x as isize + x_inc as isize * steps, y as isize + y_inc as isize * steps);
+                if x_end < 0 || y_end < 0 || x_end >= width as isize || y_end >= height as isize {
+                    return Err(format!("runs off the edge of a {} x {} puzzle going {:?}", width, height, dir));
                 }
             }
         }
+        if self.edge {
+            match self.direction {
+                Some(dir) if !dir.straight() => return Err(format!("can't lie along an edge going {:?}", dir)),
+                Some(dir) if length > dir.reach(width, height) => return Err(format!("is too long to lie along an edge of a {} x {} puzzle going {:?}", width, height, dir)),
+                None if length > width.max(height) => return Err(format!("is too long to lie along an edge of a {} x {} puzzle", width, height)),
+                _ => {},
+            }
+            if let Some((x, y)) = self.start {
+                if x != 0 && y != 0 && x != width - 1 && y != height - 1 {
+                    return Err(format!("starts away from the edges of a {} x {} puzzle", width, height));
+                }
+            }
+        }
+        Ok(())
     }
 }
 
-// run - the main runner. Creates the PuzzleGrid, places the words and outputs results
-pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
-    let words = fs::read_to_string(config.wordsfile)?;
-    
-    // validate that the words are all shorter than the grid size
-    for word in words.lines() {
-        if word.len() > config.size {
-            return Err(Box::new(PuzzleError::new(format!("{} is too long to fit in a {} x {} puzzle", word, config.size, config.size))));
+// PuzzleStats - Numbers gathered while generating a puzzle
+//
+#[derive(Debug,Clone,Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PuzzleStats {
+    // fraction of cells covered by placed words before random fill
+    pub density: f32,
+    // additional placements of already placed words made to reach the target density
+    pub extra_placements: usize,
+    // number of times the grid was thrown away and started over
+    pub regens: usize,
+    // misleading near misses and partial words written in before the fill
+    pub decoys: usize,
+    // how many words went in each direction, for the directions that were used
+    pub directions: Vec<(Direction, usize)>,
+    // cells shared by more than one word
+    pub overlaps: usize,
+    // words placed, and the spots checked to place them on every grid that was tried
+    pub words: usize,
+    pub tries: usize,
+    // words from the list spelled out a second time, and blocklisted words, that the fill couldn't
+    // get rid of because none of their letters are filler, like a word inside another one
+    pub uncleared: Vec<String>,
+}
+
+impl PuzzleStats {
+    // tries_per_word - the average number of spots checked for each word placed
+    pub fn tries_per_word(&self) -> f32 {
+        if self.words == 0 {
+            return 0.0;
         }
+        self.tries as f32 / self.words as f32
     }
+}
+
+// Metadata - How a puzzle was made, written into the csv, json, and pdf output so a puzzle can
+//            be made again exactly, the same version, seed, and options give the same puzzle
+//
+#[derive(Debug,Clone,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Metadata {
+    // version of wordfindgen that made the puzzle
+    pub version: String,
+    // seed the run started from, Config::seed or a random one when it wasn't set
+    pub seed: u64,
+    // which puzzle of a batch or book it was, counting from 1, None for a single puzzle
+    pub puzzle: Option<usize>,
+    pub width: usize,
+    pub height: usize,
+    // directions the words were allowed to go in
+    pub directions: Vec<Direction>,
+    // when the puzzle was made, in seconds since 1970 UTC
+    pub created: u64,
+}
+
+// utc_timestamp - seconds since 1970 as an ISO 8601 date and time, 2024-03-01T09:30:00Z
+pub(crate) fn utc_timestamp(created: u64) -> String {
+    let (days, seconds) = (created / 86400, created % 86400);
     
-    let mut puzzle = PuzzleGrid::new(i8::try_from(config.size).unwrap(), config.maxtries, config.hard);
-    
-    // place all of the words in the puzzle
-    for word in words.lines() {
-        puzzle.place(&word)?;
+    // days since 1970 to a calendar date, from Howard Hinnant's civil_from_days
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+impl Metadata {
+    // new - the metadata for a finished puzzle, made now
+    pub fn new(puzzle: &PuzzleGrid, seed: u64, number: Option<usize>) -> Metadata {
+        let created = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        Metadata { version: env!("CARGO_PKG_VERSION").to_string(), seed, puzzle: number, width: puzzle.width, height: puzzle.height, directions: puzzle.dir_choices.clone(), created }
     }
     
-    // output the answer key
-    puzzle.output("answer_key.csv")?;
-    
-    // fill empty grid spaces with random letters
-    puzzle.fill_in();
-    
-    // output the finished puzzle
-    puzzle.output("puzzle.csv")?;
+    // timestamp - created as an ISO 8601 date and time, 2024-03-01T09:30:00Z
+    pub fn timestamp(&self) -> String {
+        utc_timestamp(self.created)
+    }
     
-    Ok(())
+    // summary - everything on one line, for the csv comment row and the pdf subject
+    pub fn summary(&self) -> String {
+        let directions: Vec<String> = self.directions.iter().map(|d| format!("{:?}", d)).collect();
+        let puzzle = self.puzzle.map(|n| format!(" puzzle {},", n)).unwrap_or_default();
+        format!("wordfindgen {}, seed {},{} {} x {}, directions {}, made {}", self.version, self.seed, puzzle, self.width, self.height, directions.join(" "), self.timestamp())
+    }
 }
 
-// not really exhaustively tested... just needed to check a few pieces along the way
+// Progress - How far along a puzzle is, passed to Config::progress as the words are placed
+//
+#[derive(Debug,Clone,Copy,PartialEq,Default)]
+pub struct Progress {
+    // words tried on the current grid so far, placed or not, out of total
+    pub done: usize,
+    pub total: usize,
+    // words that made it into the current grid
+    pub placed: usize,
+    // the grid being filled, 1 for the first and one more each time the grid is started over
+    pub attempt: usize,
+    // the puzzle of a batch or book this is for, from 1, out of puzzles
+    pub puzzle: usize,
+    pub puzzles: usize,
+}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+// ProgressHook - a Config::progress callback, shared by the threads making a batch
+pub type ProgressHook = Arc<dyn Fn(Progress) + Send + Sync>;
 
-    #[test]
-    fn dir_incrementors_work() {
-        let mut dir = Direction::Right;
-        assert_eq!(dir.incrementors(), (1, 0));
-        dir = Direction::UpRight;
-        assert_eq!(dir.incrementors(), (1, -1));
+// PlacementEvent - A step of making a puzzle, passed to Config::events as it happens
+//
+#[derive(Debug,Clone,PartialEq)]
+pub enum PlacementEvent {
+    // a fresh grid is being filled, attempt 1 for the first and one more each time it starts over
+    GridStarted { attempt: usize },
+    // a word is about to be placed
+    Attempted { word: String },
+    // a spot checked for the word that it doesn't fit in
+    Rejected { word: String, x: usize, y: usize, dir: Direction, reason: Rejection },
+    // the word was written into the grid
+    Placed { placement: Placement },
+    // the backtracking search took the word back out to try the words before it somewhere else
+    Removed { placement: Placement },
+    // no spot was found for the word
+    Failed { word: String },
+    // the words are all in and the blank cells are being filled
+    FillStarted,
+}
+
+// Rejection - Why a word doesn't fit in a spot
+//
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum Rejection {
+    // part of the word would go off the edge of the grid
+    OffGrid,
+    // a cell is outside the shape of the mask
+    Masked,
+    // a cell is in one of the Config::reserved areas
+    Reserved,
+    // a cell already holds a different letter
+    Collision,
+    // the word would come within Config::spacing cells of another one
+    TooClose,
+    // the word doesn't cross another or touches one alongside in a criss-cross
+    CrissCross,
+}
+
+// EventHook - a Config::events callback, shared by the threads making a batch
+pub type EventHook = Arc<dyn Fn(&PlacementEvent) + Send + Sync>;
+
+// log - pass a message to the log crate, info for the retries and the search and debug for where
+//       each word went, the message is only put together when the level is turned on
+fn log<F: FnOnce() -> String>(config: &Config, level: Level, message: F) {
+    log_for(config.log_puzzle, level, message);
+}
+
+// log_for - log a message about the puzzle of a batch, or about the only puzzle when there's none
+fn log_for<F: FnOnce() -> String>(puzzle: Option<usize>, level: Level, message: F) {
+    if log_enabled!(level) {
+        match puzzle {
+            Some(puzzle) => log!(level, "puzzle {}: {}", puzzle, message()),
+            None => log!(level, "{}", message()),
+        }
+    }
+}
+
+// DifficultyRating - An estimate of how hard the finished puzzle is to solve, from
+//                    PuzzleGrid::difficulty_rating, the same puzzle always gets the same level
+//
+#[derive(Debug,Clone,Copy,PartialEq,Default)]
+pub struct DifficultyRating {
+    // 1 for the easiest puzzles to 5 for the hardest, for labelling the puzzles of a book
+    pub level: u8,
+    // the measures below weighed together, from 0 to 1
+    pub rating: f32,
+    // words that read backwards, right to left or bottom to top
+    pub backwards: usize,
+    // words on a diagonal
+    pub diagonal: usize,
+    // fraction of the word letters shared with another word
+    pub overlap: f32,
+    // fraction of the filler letters that are also in the words, lookalike filler is harder to see past
+    pub filler_similarity: f32,
+    // letters in the average word, short words are harder to spot
+    pub average_length: f32,
+}
+
+// ScoreWeights - How much each quality measure counts toward PuzzleGrid::score
+//
+#[derive(Debug,Clone,Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScoreWeights {
+    // shared cells per placed word
+    pub overlap: f32,
+    // fraction of the rows and columns that hold part of a word
+    pub spread: f32,
+    // fraction of the allowed directions that were actually used
+    pub variety: f32,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> ScoreWeights {
+        ScoreWeights { overlap: 1.0, spread: 1.0, variety: 1.0 }
+    }
+}
+
+// DensityMode - How a puzzle meets Config::target_density
+//
+#[derive(Debug,Clone,Copy,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DensityMode {
+    // write extra copies of the words into the grid, they make it busier without being in the
+    // word bank
+    Copies,
+    // leave the puzzle as it is and report the ones that came out too sparse in the Summary
+    Warn,
+    // pick the grid size so the words cover the target, the way auto_size does
+    Size,
+    // add words from Config::density_pool to the puzzle and its word bank
+    Pool,
+}
+
+impl DensityMode {
+    // from_name - the density mode for a name given on the command line
+    pub fn from_name(name: &str) -> Option<DensityMode> {
+        match name.to_ascii_lowercase().as_str() {
+            "copies" => Some(DensityMode::Copies),
+            "warn" => Some(DensityMode::Warn),
+            "size" | "auto-size" => Some(DensityMode::Size),
+            "pool" => Some(DensityMode::Pool),
+            _ => None,
+        }
+    }
+}
+
+// random_placement - the strategy a puzzle read back with serde places more words with, a
+//                    strategy is code so it isn't stored
+#[cfg(feature = "serde")]
+fn random_placement() -> Arc<dyn PlacementStrategy> {
+    Arc::new(RandomPlacement)
+}
+
+// PuzzleGrid - The main struct for holding and generating the puzzle
+//
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PuzzleGrid {
+    grid: Vec<Vec<String>>,
+    width: usize,
+    height: usize,
+    maxtries: usize,
+    dir_choices: Vec<Direction>,
+    // how often random_spot picks each direction, even when empty
+    dir_weights: Vec<(Direction, f32)>,
+    entries: Vec<String>,
+    failed: Vec<String>,
+    // words left out because they were already in the list
+    duplicates: Vec<String>,
+    // lines of the word list left out because nothing was left of them once the punctuation was
+    // taken out, like "--"
+    no_letters: Vec<String>,
+    placements: Vec<Placement>,
+    fill_chars: Vec<char>,
+    // how likely each of fill_chars is to be picked, every letter is equally likely when not set
+    fill_weights: Option<Vec<f32>>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "random_placement"))]
+    strategy: Arc<dyn PlacementStrategy>,
+    stats: PuzzleStats,
+    title: Option<String>,
+    subtitle: Option<String>,
+    instructions: Option<String>,
+    // (placed, printed) for words shown in the word bank differently than they're spelled in the grid
+    spellings: Vec<(String, String)>,
+    // indexes into entries in the order the word bank is printed, set by set_word_order
+    bank_order: Vec<usize>,
+    bank_style: BankStyle,
+    // how the pdf, html, and svg output is set, see set_print_style
+    print: PrintStyle,
+    // (printed, scrambled) for the words in the word bank, set by scramble_bank
+    scrambles: Vec<(String, String)>,
+    // (placed, clue) for words the word bank shows a clue for instead of the word
+    clues: Vec<(String, String)>,
+    // (placed, heading) for words listed under a section heading, in the order they were listed
+    sections: Vec<(String, String)>,
+    // the words read right to left, like hebrew and arabic
+    rtl: bool,
+    // punctuation sanitize keeps in the words
+    keep_chars: String,
+    case: Case,
+    // cells given random letters by fill_in, the only ones clear_accidental_words may change
+    filled: Vec<(usize, usize)>,
+    // the Config::progress callback and what it was last told, updated by place
+    #[cfg_attr(feature = "serde", serde(skip))]
+    progress: Option<(ProgressHook, Progress)>,
+    // Config::cancel, place gives up straight away once it's cancelled
+    #[cfg_attr(feature = "serde", serde(skip))]
+    cancel: Option<CancelToken>,
+    // Config::log_puzzle, for the messages about where place put each word
+    #[cfg_attr(feature = "serde", serde(skip))]
+    log_puzzle: Option<usize>,
+    // Config::events, told about each spot tried and word placed
+    #[cfg_attr(feature = "serde", serde(skip))]
+    events: Option<EventHook>,
+    // Config::spacing, blank cells kept around each word, words can share cells when not set
+    spacing: Option<usize>,
+    // spots placement_valid has checked, counted for PuzzleStats::tries
+    tries: Cell<usize>,
+    // Config::mask, the cells words and filler can go in, every cell when not set
+    mask: Option<Vec<Vec<bool>>>,
+    // Config::reserved, true for the cells no word can go through, empty when there are none
+    reserved: Vec<Vec<bool>>,
+    // Config::bent, place tries to turn each word a corner
+    bent: bool,
+    // the word place_spangram put edge to edge
+    spangram: Option<String>,
+    // Config::crisscross, words have to cross without touching and the blank cells aren't filled
+    crisscross: bool,
+    // cells of the placed words shown to the solver as hints, set by reveal
+    revealed: Vec<(usize, usize)>,
+    // how the puzzle was made, set by run and run_book once it's finished
+    metadata: Option<Metadata>,
+}
+
+impl PuzzleGrid {
+    pub fn new(width: usize, height: usize, maxtries: usize, difficulty: Difficulty) -> PuzzleGrid {
+        let grid: Vec<Vec<String>> = vec![vec![String::from(" "); width]; height];
+        let dir_choices = difficulty.directions();
+        PuzzleGrid {
+            grid,
+            width,
+            height,
+            maxtries,
+            dir_choices,
+            dir_weights: Vec::new(),
+            entries: Vec::new(),
+            failed: Vec::new(),
+            duplicates: Vec::new(),
+            no_letters: Vec::new(),
+            placements: Vec::new(),
+            fill_chars: FILL_CHARS.chars().collect(),
+            fill_weights: None,
+            strategy: Arc::new(RandomPlacement),
+            stats: PuzzleStats::default(),
+            title: None,
+            subtitle: None,
+            instructions: None,
+            spellings: Vec::new(),
+            bank_order: Vec::new(),
+            bank_style: BankStyle::Words,
+            print: PrintStyle::default(),
+            scrambles: Vec::new(),
+            clues: Vec::new(),
+            sections: Vec::new(),
+            rtl: false,
+            keep_chars: String::new(),
+            case: Case::Upper,
+            filled: Vec::new(),
+            progress: None,
+            cancel: None,
+            log_puzzle: None,
+            events: None,
+            spacing: None,
+            tries: Cell::new(0),
+            mask: None,
+            reserved: Vec::new(),
+            bent: false,
+            spangram: None,
+            crisscross: false,
+            revealed: Vec::new(),
+            metadata: None,
+        }
     }
     
-    #[test]
-    fn indeces(){
-        let puzzle = PuzzleGrid::new(20, 10000, true);
-        let x: i8 = 10;
-        let y: i8 = 10;
-        let dir = Direction::DownRight;
-        let (x_indeces, y_indeces) = puzzle.get_indeces("Thanks", &x, &y, &dir);
-        assert_eq!(x_indeces, [10, 11, 12, 13, 14, 15]);
-        assert_eq!(y_indeces, [10, 11, 12, 13, 14, 15]);
+    // grid - the rows of the puzzle, blank cells are " " until fill_in is called
+    pub fn grid(&self) -> &[Vec<String>] {
+        &self.grid
+    }
+    
+    // entries - the words that were placed, in the order they were placed
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+    
+    // word_bank - the entries as they're printed for the solver, the way they were typed with any
+    //             spaces, punctuation, or folded accents that were left out of the grid
+    //             The words are in the order set by set_word_order, the order they were placed otherwise
+    pub fn word_bank(&self) -> Vec<String> {
+        self.bank().into_iter().map(|(_, printed)| printed).collect()
+    }
+    
+    // bank - each entry along with how it's printed, in word bank order
+    //        Entries placed after set_word_order go at the end
+    fn bank(&self) -> Vec<(&String, String)> {
+        let mut order: Vec<usize> = self.bank_order.iter().cloned().filter(|i| *i < self.entries.len()).collect();
+        order.extend((0..self.entries.len()).filter(|i| !self.bank_order.contains(i)));
+        order.into_iter().map(|i| {
+            let entry = &self.entries[i];
+            match self.spellings.iter().find(|(placed, _)| placed == entry) {
+                Some((_, printed)) => (entry, printed.clone()),
+                None => (entry, entry.clone()),
+            }
+        }).collect()
+    }
+    
+    // set_bank_style - what the puzzle shows in place of the word bank, see puzzle_bank
+    pub fn set_bank_style(&mut self, style: BankStyle) {
+        self.bank_style = style;
+    }
+    
+    // set_print_style - the letter size, font, and contrast of the pdf, html, and svg output
+    pub fn set_print_style(&mut self, style: PrintStyle) {
+        self.print = style;
+    }
+    
+    // print_style - see set_print_style
+    pub fn print_style(&self) -> &PrintStyle {
+        &self.print
+    }
+    
+    // set_word_order - the order to print the word bank in, rng is only used to shuffle it
+    pub fn set_word_order(&mut self, order: WordOrder, rng: &mut dyn RngCore) {
+        // sorted by the printed words, which are in entries order until bank_order is set, with
+        // accented letters next to the plain ones
+        self.bank_order = Vec::new();
+        let bank: Vec<String> = self.word_bank().iter().map(|w| fold_diacritics(w).to_lowercase()).collect();
+        let mut bank_order: Vec<usize> = (0..self.entries.len()).collect();
+        match order {
+            WordOrder::Original => (),
+            WordOrder::Alphabetical => bank_order.sort_by_key(|i| bank[*i].clone()),
+            WordOrder::Shuffled => bank_order.shuffle(rng),
+            WordOrder::Length => bank_order.sort_by_key(|i| (letters(&self.entries[*i]).len(), bank[*i].clone())),
+        }
+        self.bank_order = bank_order;
+    }
+    
+    // placements - where each word was placed, including any extra copies made by densify
+    pub fn placements(&self) -> &[Placement] {
+        &self.placements
+    }
+    
+    // failed - words that could not be placed but were tolerated by Config::max_failed_fraction or Config::skip_failed
+    pub fn failed(&self) -> &[String] {
+        &self.failed
+    }
+    
+    // duplicates - words that were left out because the same word was earlier in the list, as typed
+    pub fn duplicates(&self) -> &[String] {
+        &self.duplicates
+    }
+    
+    // no_letters - lines of the word list that were left out because they had no letters, as typed
+    pub fn no_letters(&self) -> &[String] {
+        &self.no_letters
+    }
+    
+    // stats - numbers gathered while the puzzle was generated
+    pub fn stats(&self) -> &PuzzleStats {
+        &self.stats
+    }
+    
+    // metadata - the version, seed, and options the puzzle was made with, when run or run_book made it
+    pub fn metadata(&self) -> Option<&Metadata> {
+        self.metadata.as_ref()
+    }
+    
+    // title - the name of the puzzle shown above the grid
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+    
+    // subtitle - a second, smaller line under the title
+    pub fn subtitle(&self) -> Option<&str> {
+        self.subtitle.as_deref()
+    }
+    
+    // instructions - a line telling the player what to do, shown above the grid
+    pub fn instructions(&self) -> Option<&str> {
+        self.instructions.as_deref()
+    }
+    
+    pub fn set_title(&mut self, title: Option<String>) {
+        self.title = title;
+    }
+    
+    pub fn set_subtitle(&mut self, subtitle: Option<String>) {
+        self.subtitle = subtitle;
+    }
+    
+    pub fn set_instructions(&mut self, instructions: Option<String>) {
+        self.instructions = instructions;
+    }
+    
+    // rtl - true when the puzzle is laid out for a right to left script
+    pub fn rtl(&self) -> bool {
+        self.rtl
+    }
+    
+    // set_directions - place the words in only these directions from here on
+    pub fn set_directions(&mut self, directions: &[Direction]) -> Result<(), WordfindError> {
+        if directions.is_empty() {
+            return Err(WordfindError::NoDirections);
+        }
+        self.dir_choices = directions.to_vec();
+        Ok(())
     }
-}
\ No newline at end of file
+    
+    // set_direction_weights - pick the directions in proportion to their weights from here on,
+    //                         the ones not listed count 1 and one weighted 0 is only used when the
+    //                         word fits nowhere else
+    pub fn set_direction_weights(&mut self, weights: &[(Direction, f32)]) {
+        self.dir_weights = weights.iter().map(|(dir, weight)| (*dir, weight.max(0.0))).collect();
+    }
+    
+    // direction_weight - the weight of the direction, 1 when it wasn't given one
+    fn direction_weight(&self, dir: Direction) -> f32 {
+        self.dir_weights.iter().find(|(d, _)| *d == dir).map_or(1.0, |(_, weight)| *weight)
+    }
+    
+    // set_spacing - keep the words placed from here on from sharing cells with the others, and
+    //               this many blank cells away from them, or let them share cells again with None
+    pub fn set_spacing(&mut self, spacing: Option<usize>) {
+        self.spacing = spacing;
+    }
+    
+    // set_mask - only use the cells that are true in the mask, the rest stay blank through the fill
+    //            The mask has to be the size of the grid and have at least one cell to use
+    pub fn set_mask(&mut self, mask: &[Vec<bool>]) -> Result<(), WordfindError> {
+        if mask.len() != self.height || mask.iter().any(|row| row.len() != self.width) {
+            return Err(WordfindError::InvalidMask { reason: format!("it isn't the size of the {} x {} grid", self.width, self.height) });
+        }
+        if !mask.iter().flatten().any(|open| *open) {
+            return Err(WordfindError::InvalidMask { reason: String::from("it has no cells to use") });
+        }
+        self.mask = Some(mask.to_vec());
+        Ok(())
+    }
+    
+    // reserve - keep the words placed from here on out of a width by height area with its top left
+    //           cell at x, y, the filler still goes in it, and the part of it past the edge of the
+    //           grid is left off
+    pub fn reserve(&mut self, x: usize, y: usize, width: usize, height: usize) -> Result<(), WordfindError> {
+        if x >= self.width || y >= self.height || width == 0 || height == 0 {
+            return Err(WordfindError::InvalidMask { reason: format!("the reserved area at ({}, {}) has no cells in the {} x {} grid", x, y, self.width, self.height) });
+        }
+        if self.reserved.is_empty() {
+            self.reserved = vec![vec![false; self.width]; self.height];
+        }
+        for row in self.reserved[y..(y + height).min(self.height)].iter_mut() {
+            for cell in row[x..(x + width).min(self.width)].iter_mut() {
+                *cell = true;
+            }
+        }
+        Ok(())
+    }
+    
+    // reserved - whether the cell is in an area words can't go through, see reserve
+    pub fn reserved(&self, x: usize, y: usize) -> bool {
+        self.reserved.get(y).is_some_and(|row| row[x])
+    }
+    
+    // set_bent - let the words placed from here on turn a corner once, for the bent word puzzles where
+    //            a word is in two straight legs, words too short to bend still go in a straight line
+    pub fn set_bent(&mut self, bent: bool) {
+        self.bent = bent;
+    }
+    
+    // set_crisscross - lay the words placed from here on out as a criss-cross, across and down with
+    //                  each one crossing a word already placed and touching none of the others, and
+    //                  leave the cells between them blank for blocked instead of filling them
+    pub fn set_crisscross(&mut self, crisscross: bool) {
+        self.crisscross = crisscross;
+        if crisscross {
+            self.dir_choices = vec![Direction::Right, Direction::Down];
+        }
+    }
+    
+    // blocked - whether the cell is blacked out, the cells of a criss-cross no word goes through
+    pub fn blocked(&self, x: usize, y: usize) -> bool {
+        self.crisscross && self.usable(x, y) && self.grid[y][x] == " "
+    }
+    
+    // usable - whether the mask lets the cell be used
+    fn usable(&self, x: usize, y: usize) -> bool {
+        self.mask.as_ref().is_none_or(|mask| mask[y][x])
+    }
+    
+    // set_rtl - lay the puzzle out for a right to left script, the directions are mirrored so the
+    //           easy ones read right to left instead of left to right
+    pub fn set_rtl(&mut self, rtl: bool) {
+        if rtl != self.rtl {
+            self.dir_choices = self.dir_choices.iter().map(Direction::mirrored).collect();
+            self.rtl = rtl;
+        }
+    }
+    
+    // heading - the title, subtitle, and instructions that were set, in that order
+    pub fn heading(&self) -> Vec<&str> {
+        [&self.title, &self.subtitle, &self.instructions].iter().filter_map(|line| line.as_deref()).collect()
+    }
+    
+    // place - attempts to randomly place the given word into the puzzle
+    pub fn place(&mut self, word: &str, rng: &mut dyn RngCore) -> Result<(), WordfindError> {
+        if let Some(cancel) = &self.cancel {
+            cancel.check()?;
+        }
+        let sanitized_word = self.case.apply(&sanitize(word, &self.keep_chars));
+        self.emit(|| PlacementEvent::Attempted { word: sanitized_word.clone() });
+        
+        // with bent words on a word turns a corner if there's anywhere it can, or goes in a straight
+        // line if there isn't
+        if let Some(placement) = self.bent.then(|| self.bent_spot(&sanitized_word, rng)).flatten() {
+            log_for(self.log_puzzle, Level::Debug, || format!("placed {} at row {} column {} going {:?} and turning {:?}", sanitized_word, placement.y + 1, placement.x + 1, placement.dir, placement.bend.unwrap().1));
+            self.entries.push(sanitized_word);
+            self.write_placement(placement);
+            self.report_progress(true);
+            return Ok(());
+        }
+        
+        let result = match self.find_spot(&sanitized_word, rng) {
+            Some((x, y, dir)) => {
+                log_for(self.log_puzzle, Level::Debug, || format!("placed {} at row {} column {} going {:?}", sanitized_word, y + 1, x + 1, dir));
+                self.entries.push(sanitized_word.to_string());
+                self.write_word(&sanitized_word, &x, &y, &dir);
+                Ok(())
+            },
+            None => {
+                log_for(self.log_puzzle, Level::Debug, || format!("found no room for {} in {} tries", sanitized_word, self.maxtries));
+                self.emit(|| PlacementEvent::Failed { word: sanitized_word.clone() });
+                Err(WordfindError::PlacementFailed { word: word.to_string(), tries: self.maxtries })
+            },
+        };
+        self.report_progress(result.is_ok());
+        result
+    }
+    
+    // place_pinned - place the word where its pin says, at the start cell, in the direction, or both,
+    //                picking at random from the spots that fit the pin
+    //                A pinned direction is used even when the puzzle doesn't allow it otherwise, and a
+    //                word pinned to an edge goes in the straight directions the puzzle allows, or
+    //                across or down when it has none
+    pub fn place_pinned(&mut self, word: &str, pin: &Pin, rng: &mut dyn RngCore) -> Result<(), WordfindError> {
+        if let Some(cancel) = &self.cancel {
+            cancel.check()?;
+        }
+        let sanitized_word = self.case.apply(&sanitize(word, &self.keep_chars));
+        self.emit(|| PlacementEvent::Attempted { word: sanitized_word.clone() });
+        let directions = match pin.direction {
+            Some(dir) => vec![dir],
+            None if pin.edge => {
+                let straight: Vec<Direction> = self.dir_choices.iter().copied().filter(Direction::straight).collect();
+                if straight.is_empty() { vec![Direction::Right, Direction::Down] } else { straight }
+            },
+            None => self.dir_choices.clone(),
+        };
+        let starts: Vec<(usize, usize)> = match pin.start {
+            Some(start) => vec![start],
+            None => (0..self.height).flat_map(|y| (0..self.width).map(move |x| (x, y))).collect(),
+        };
+        let spots: Vec<(usize, usize, Direction)> = starts.iter()
+            .flat_map(|(x, y)| directions.iter().map(move |dir| (*x, *y, *dir)))
+            .filter(|(x, y, dir)| *x < self.width && *y < self.height && (!pin.edge || self.along_edge(*x, *y, *dir)) && self.placement_valid(&sanitized_word, x, y, dir))
+            .collect();
+        
+        let result = match spots.choose(rng) {
+            Some((x, y, dir)) => {
+                log_for(self.log_puzzle, Level::Debug, || format!("placed {} at row {} column {} going {:?} as pinned", sanitized_word, y + 1, x + 1, dir));
+                self.entries.push(sanitized_word.to_string());
+                self.write_word(&sanitized_word, x, y, dir);
+                Ok(())
+            },
+            None => {
+                self.emit(|| PlacementEvent::Failed { word: sanitized_word.clone() });
+                Err(WordfindError::PlacementFailed { word: word.to_string(), tries: starts.len() * directions.len() })
+            },
+        };
+        self.report_progress(result.is_ok());
+        result
+    }
+    
+    // along_edge - whether a word starting at x, y going in the direction lies flush along the
+    //              border, in the top or bottom row going across or the first or last column going
+    //              up or down
+    pub fn along_edge(&self, x: usize, y: usize, dir: Direction) -> bool {
+        match dir.incrementors() {
+            (_, 0) => y == 0 || y + 1 == self.height,
+            (0, _) => x == 0 || x + 1 == self.width,
+            _ => false,
+        }
+    }
+    
+    // place_all_pinned - place_pinned for each word and its pin, the words that didn't fit come back
+    pub fn place_all_pinned(&mut self, pinned: &[(String, Pin)], rng: &mut dyn RngCore) -> Vec<String> {
+        pinned.iter().filter(|(word, pin)| self.place_pinned(word, pin, rng).is_err()).map(|(word, _)| word.clone()).collect()
+    }
+    
+    // crossing_counts - how many of the other placed words each placement shares a cell with, in
+    //                   placement order
+    fn crossing_counts(&self) -> Vec<usize> {
+        let cells: Vec<Vec<(usize, usize)>> = self.placements.iter().map(Placement::cells).collect();
+        cells.iter().enumerate().map(|(i, word)| {
+            cells.iter().enumerate().filter(|(j, other)| *j != i && other.iter().any(|cell| word.contains(cell))).count()
+        }).collect()
+    }
+    
+    // lonely_words - the placed words that cross fewer than min of the others
+    fn lonely_words(&self, min: usize) -> Vec<String> {
+        if min == 0 {
+            return Vec::new();
+        }
+        self.placements.iter().zip(self.crossing_counts()).filter(|(_, crossings)| *crossings < min).map(|(p, _)| p.word.clone()).collect()
+    }
+    
+    // emit - pass an event to Config::events, the event is only put together when there's a hook
+    fn emit<F: FnOnce() -> PlacementEvent>(&self, event: F) {
+        if let Some(hook) = &self.events {
+            hook(&event());
+        }
+    }
+    
+    // report_progress - tell Config::progress another word was tried
+    fn report_progress(&mut self, placed: bool) {
+        if let Some((hook, progress)) = &mut self.progress {
+            progress.done += 1;
+            progress.placed += placed as usize;
+            hook(*progress);
+        }
+    }
+    
+    // find_spot - ask the placement strategy where the word should go
+    fn find_spot(&self, word: &str, rng: &mut dyn RngCore) -> Option<(usize, usize, Direction)> {
+        self.strategy.choose_spot(self, word, rng)
+    }
+    
+    // set_strategy - change how words are placed from here on
+    pub fn set_strategy(&mut self, strategy: Arc<dyn PlacementStrategy>) {
+        self.strategy = strategy;
+    }
+    
+    // width - number of columns in the grid
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    
+    // height - number of rows in the grid
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    
+    // cell - the letter at column x and row y, None off the edge of the grid
+    //        A letter is a str and not a char since it can carry combining accents, see letters
+    pub fn cell(&self, x: usize, y: usize) -> Option<&str> {
+        self.grid.get(y).and_then(|row| row.get(x)).map(String::as_str)
+    }
+    
+    // rows - the letters of each row from the top, for a renderer that lays out the grid itself
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = &str> + '_> + '_ {
+        self.grid.iter().map(|row| row.iter().map(String::as_str))
+    }
+    
+    // random_spot - randomly select x, y, and direction for the first 1 / RANDOM_SHARE of maxtries,
+    //               which finds a spot quickly for most words, then spend the rest of the tries going
+    //               through the positions in order from a random one, so a word with only a few spots
+    //               left still finds one before giving up
+    pub fn random_spot(&self, word: &str, rng: &mut dyn RngCore) -> Option<(usize, usize, Direction)> {
+        let mut dir = Direction::Right;
+        let random_tries = self.maxtries / RANDOM_SHARE;
+        let weighted = if self.dir_weights.is_empty() { None } else { WeightedIndex::new(self.dir_choices.iter().map(|d| self.direction_weight(*d))).ok() };
+        for _ in 0..random_tries {
+            let x = rng.gen_range(0, self.width);
+            let y = rng.gen_range(0, self.height);
+            match &weighted {
+                Some(weighted) => dir = self.dir_choices[weighted.sample(rng)],
+                None => if let Some(d) = self.dir_choices.choose(rng) { dir = *d },
+            }
+            if self.placement_valid(word, &x, &y, &dir) {
+                return Some((x, y, dir));
+            }
+        }
+        
+        let dirs = self.dir_choices.len();
+        let spots = self.width * self.height * dirs;
+        if spots == 0 {
+            return None;
+        }
+        // with enough tries left for every spot, the spots the word fits are worked out all at once
+        if self.maxtries - random_tries >= spots {
+            let candidates = self.candidates(word);
+            return match weighted {
+                Some(_) => candidates.choose_weighted(rng, |(_, _, d)| self.direction_weight(*d)).ok().or_else(|| candidates.choose(rng)).copied(),
+                None => candidates.choose(rng).copied(),
+            };
+        }
+        let start = rng.gen_range(0, spots);
+        for i in 0..spots.min(self.maxtries - random_tries) {
+            let spot = (start + i) % spots;
+            let (x, y, dir) = (spot / dirs % self.width, spot / dirs / self.width, self.dir_choices[spot % dirs]);
+            if self.placement_valid(word, &x, &y, &dir) {
+                return Some((x, y, dir));
+            }
+        }
+        None
+    }
+    
+    // densest_spot - the valid position that overlaps the most already placed letters, ties broken at random
+    pub fn densest_spot(&self, word: &str, rng: &mut dyn RngCore) -> Option<(usize, usize, Direction)> {
+        let mut candidates = self.candidates(word);
+        candidates.shuffle(rng);
+        candidates.into_iter().max_by_key(|(x, y, dir)| self.overlap_count(word, x, y, dir))
+    }
+    
+    // overlap_count - how many letters of the word would land on matching letters already in the grid
+    pub fn overlap_count(&self, word: &str, x: &usize, y: &usize, dir: &Direction) -> usize {
+        let (x_indeces, y_indeces) = self.get_indeces(word, x, y, dir);
+        let space = String::from(" ");
+        x_indeces.iter().zip(y_indeces.iter()).filter(|(xi, yi)| self.grid[**yi][**xi] != space).count()
+    }
+    
+    // candidates - every position and direction where the word could go right now
+    //              Rather than walking the word from every spot, the taken cells are grouped by their
+    //              letter and each one rules out the spots that would put a different letter of the
+    //              word on it, so only the spots left need the spacing and criss-cross checks
+    pub fn candidates(&self, word: &str) -> Vec<(usize, usize, Direction)> {
+        let letters = letters(word);
+        let dirs = self.dir_choices.len();
+        let spots = self.width * self.height * dirs;
+        self.tries.set(self.tries.get() + spots);
+        if letters.is_empty() {
+            return Vec::new();
+        }
+        
+        let mut taken: HashMap<&str, Vec<(usize, usize)>> = HashMap::new();
+        let mut unusable = Vec::new();
+        for (x, y, _) in self.iter_cells() {
+            if !self.usable(x, y) || self.reserved(x, y) {
+                unusable.push((x, y));
+            } else if self.grid[y][x] != " " {
+                taken.entry(self.grid[y][x].as_str()).or_default().push((x, y));
+            }
+        }
+        
+        // ruled_out[(y * width + x) * dirs + d] is set for a start that would put a letter of the
+        // word on a cell it can't go in
+        let mut ruled_out = vec![false; spots];
+        let mut rule_out = |cells: &[(usize, usize)], positions: &[usize]| {
+            for (d, dir) in self.dir_choices.iter().enumerate() {
+                let (x_inc, y_inc) = dir.incrementors();
+                for (x, y) in cells.iter() {
+                    for i in positions.iter() {
+                        let sx = *x as isize - x_inc as isize * *i as isize;
+                        let sy = *y as isize - y_inc as isize * *i as isize;
+                        if sx >= 0 && sy >= 0 && (sx as usize) < self.width && (sy as usize) < self.height {
+                            ruled_out[(sy as usize * self.width + sx as usize) * dirs + d] = true;
+                        }
+                    }
+                }
+            }
+        };
+        rule_out(&unusable, &(0..letters.len()).collect::<Vec<usize>>());
+        for (letter, cells) in taken.iter() {
+            let positions: Vec<usize> = (0..letters.len()).filter(|i| letters[*i] != *letter).collect();
+            rule_out(cells, &positions);
+        }
+        
+        let steps = letters.len() as isize - 1;
+        let checked = self.spacing.is_some() || self.crisscross;
+        let mut candidates = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                for (d, dir) in self.dir_choices.iter().enumerate() {
+                    let (x_inc, y_inc) = dir.incrementors();
+                    let (x_end, y_end) = (x as isize + x_inc as isize * steps, y as isize + y_inc as isize * steps);
+                    if ruled_out[(y * self.width + x) * dirs + d] || x_end < 0 || y_end < 0 || x_end >= self.width as isize || y_end >= self.height as isize {
+                        continue;
+                    }
+                    if checked {
+                        let cells: Vec<(usize, usize)> = (0..=steps).map(|i| ((x as isize + x_inc as isize * i) as usize, (y as isize + y_inc as isize * i) as usize)).collect();
+                        if !self.cells_fit(&letters, &cells) {
+                            continue;
+                        }
+                    }
+                    candidates.push((x, y, *dir));
+                }
+            }
+        }
+        candidates
+    }
+    
+    // place_all_backtracking - place every word by walking through all of the valid positions for each
+    //                          word in a random order, backing up to move earlier words when a later one
+    //                          has nowhere to go. Returns false if no layout was found within the step budget
+    pub fn place_all_backtracking(&mut self, words: &[String], rng: &mut dyn RngCore) -> bool {
+        // long words are the hardest to fit so they go first
+        let mut order: Vec<String> = words.iter().map(|w| self.case.apply(w)).collect();
+        order.sort_by_key(|w| std::cmp::Reverse(letters(w).len()));
+        
+        let mut steps = 0;
+        if self.backtrack(&order, 0, rng, &mut steps) {
+            let case = self.case;
+            self.entries.extend(words.iter().map(|w| case.apply(w)));
+            true
+        } else {
+            false
+        }
+    }
+    
+    // backtrack - the recursive search behind place_all_backtracking
+    fn backtrack(&mut self, words: &[String], i: usize, rng: &mut dyn RngCore, steps: &mut usize) -> bool {
+        if i == words.len() { return true; }
+        
+        let mut candidates = self.candidates(&words[i]);
+        candidates.shuffle(rng);
+        for (x, y, dir) in candidates {
+            *steps += 1;
+            if *steps > MAX_BACKTRACK_STEPS { return false; }
+            if steps.is_multiple_of(CANCEL_CHECK_STEPS) && self.cancel.as_ref().is_some_and(CancelToken::is_cancelled) { return false; }
+            
+            // remember what was under the word so it can be taken back out
+            let (x_indeces, y_indeces) = self.get_indeces(&words[i], &x, &y, &dir);
+            let previous: Vec<String> = x_indeces.iter().zip(y_indeces.iter()).map(|(xi, yi)| self.grid[*yi][*xi].clone()).collect();
+            
+            self.write_word(&words[i], &x, &y, &dir);
+            if self.backtrack(words, i + 1, rng, steps) {
+                return true;
+            }
+            
+            if let Some(placement) = self.placements.pop() {
+                self.emit(|| PlacementEvent::Removed { placement });
+            }
+            for ((xi, yi), cell) in x_indeces.iter().zip(y_indeces.iter()).zip(previous) {
+                self.grid[*yi][*xi] = cell;
+            }
+        }
+        false
+    }
+    
+    // write_word - put the letters of an already validated placement into the grid
+    fn write_word(&mut self, word: &str, x: &usize, y: &usize, dir: &Direction) {
+        self.write_placement(Placement { word: word.to_string(), x: *x, y: *y, dir: *dir, bend: None });
+    }
+    
+    // write_placement - write_word for a placement that may be bent
+    fn write_placement(&mut self, placement: Placement) {
+        for ((x, y), letter) in placement.cells().into_iter().zip(letters(&placement.word)) {
+            self.grid[y][x] = letter;
+        }
+        self.emit(|| PlacementEvent::Placed { placement: placement.clone() });
+        self.placements.push(placement);
+    }
+    
+    // density - fraction of the grid cells that currently hold a letter
+    pub fn density(&self) -> f32 {
+        let filled = self.iter_cells().filter(|c| c.2 != ' ').count();
+        filled as f32 / (self.width * self.height) as f32
+    }
+    
+    // iter_cells - every cell of the grid in row-major order as (x, y, letter)
+    pub fn iter_cells(&self) -> impl Iterator<Item = (usize, usize, char)> + '_ {
+        self.grid.iter().enumerate().flat_map(|(y, row)| {
+            row.iter().enumerate().map(move |(x, cell)| (x, y, cell.chars().next().unwrap_or(' ')))
+        })
+    }
+    
+    // densify - place additional copies of the entries until the target density or the attempt cap is reached
+    //           The copies are not added to the word list, they only make the grid busier
+    pub fn densify(&mut self, target: f32, max_attempts: usize, rng: &mut dyn RngCore) {
+        for _ in 0..max_attempts {
+            if self.density() >= target { break; }
+            let word = match self.entries.choose(rng) {
+                Some(w) => w.clone(),
+                None => break,
+            };
+            if let Some((x, y, dir)) = self.find_spot(&word, rng) {
+                self.write_word(&word, &x, &y, &dir);
+                self.stats.extra_placements += 1;
+            }
+        }
+    }
+    
+    // get_indeces - returns the Vec[x][y] for placement into the puzzle of each letter in the word
+    //               There is likely a more elegant way to do this
+    //               The caller is responsible for making sure the word stays inside the grid
+    pub fn get_indeces(&self, word: &str, x: &usize, y: &usize, dir: &Direction) -> (Vec<usize>, Vec<usize>) {
+        let (x_inc, y_inc) = dir.incrementors();
+        let count = letters(word).len();
+        let mut x_indeces: Vec<usize> = Vec::with_capacity(count);
+        let mut y_indeces: Vec<usize> = Vec::with_capacity(count);
+        
+        let mut xi = *x as isize;
+        let mut yi = *y as isize;
+        for _ in 0..count {
+            x_indeces.push(usize::try_from(xi).unwrap());
+            y_indeces.push(usize::try_from(yi).unwrap());
+            xi += x_inc as isize;
+            yi += y_inc as isize;
+        }
+        
+        (x_indeces, y_indeces)
+    }
+    
+    // placement_valid - returns true if the word fits at the given coordinates and direction with no collisions
+    //                   Lots of code duplication with get_indeces, likely a better way to do this.
+    pub fn placement_valid(&self, word: &str, x: &usize, y: &usize, dir: &Direction) -> bool {
+        self.tries.set(self.tries.get() + 1);
+        match self.rejection(word, x, y, dir) {
+            Some(reason) => {
+                self.emit(|| PlacementEvent::Rejected { word: word.to_string(), x: *x, y: *y, dir: *dir, reason });
+                false
+            },
+            None => true,
+        }
+    }
+    
+    // rejection - why the word doesn't fit at x, y going in the direction, None when it does
+    pub fn rejection(&self, word: &str, x: &usize, y: &usize, dir: &Direction) -> Option<Rejection> {
+        let (x_inc, y_inc) = dir.incrementors();
+        let letters = letters(word);
+        let steps = letters.len() as isize - 1;
+        let x_end = *x as isize + x_inc as isize * steps;
+        let y_end = *y as isize + y_inc as isize * steps;
+        
+        if *x < self.width && *y < self.height && x_end >= 0 && x_end < self.width as isize && y_end >= 0 && y_end < self.height as isize {
+            // the word fits, now make sure it doesn't collide
+            let cells: Vec<(usize, usize)> = (0..=steps).map(|i| ((*x as isize + x_inc as isize * i) as usize, (*y as isize + y_inc as isize * i) as usize)).collect();
+            self.cells_rejection(&letters, &cells)
+        } else {
+            Some(Rejection::OffGrid)
+        }
+    }
+    
+    // cells_fit - whether the letters can be written into the cells, which are inside the grid, without
+    //             colliding with another word or coming too close to one with spacing
+    fn cells_fit(&self, letters: &[String], cells: &[(usize, usize)]) -> bool {
+        self.cells_rejection(letters, cells).is_none()
+    }
+    
+    // cells_rejection - why the letters can't be written into the cells, None when they can
+    fn cells_rejection(&self, letters: &[String], cells: &[(usize, usize)]) -> Option<Rejection> {
+        let space = String::from(" ");
+        for (letter, (xi, yi)) in letters.iter().zip(cells.iter()) {
+            // as long as the grid contains " " or a matching letter there is no collision
+            if !self.usable(*xi, *yi) {
+                return Some(Rejection::Masked);
+            }
+            if self.reserved(*xi, *yi) {
+                return Some(Rejection::Reserved);
+            }
+            if !(self.grid[*yi][*xi] == *letter || self.grid[*yi][*xi] == space) {
+                return Some(Rejection::Collision);
+            }
+        }
+        
+        // with spacing the word can't share a cell, or come within spacing cells of another word,
+        // looking no further than the edges of the grid however big the spacing is
+        if let Some(spacing) = self.spacing {
+            for (xi, yi) in cells.iter() {
+                for ny in yi.saturating_sub(spacing)..=(yi.saturating_add(spacing)).min(self.height - 1) {
+                    for nx in xi.saturating_sub(spacing)..=(xi.saturating_add(spacing)).min(self.width - 1) {
+                        if (nx, ny) != (*xi, *yi) && cells.contains(&(nx, ny)) {
+                            continue;
+                        }
+                        if self.grid[ny][nx] != space {
+                            return Some(Rejection::TooClose);
+                        }
+                    }
+                }
+            }
+        }
+        
+        // a criss-cross word has to cross one that's already placed and can't touch the others
+        // anywhere else, so every run of letters across or down is a whole word
+        if self.crisscross && cells.len() > 1 {
+            let blank = |x: isize, y: isize| x < 0 || y < 0 || x >= self.width as isize || y >= self.height as isize || self.grid[y as usize][x as usize] == space;
+            let shared: Vec<bool> = cells.iter().map(|(x, y)| self.grid[*y][*x] != space).collect();
+            if (!self.placements.is_empty() && !shared.contains(&true)) || shared.windows(2).any(|pair| pair[0] && pair[1]) {
+                return Some(Rejection::CrissCross);
+            }
+            let (first, last) = (cells[0], cells[cells.len() - 1]);
+            let (dx, dy) = (cells[1].0 as isize - first.0 as isize, cells[1].1 as isize - first.1 as isize);
+            if !blank(first.0 as isize - dx, first.1 as isize - dy) || !blank(last.0 as isize + dx, last.1 as isize + dy) {
+                return Some(Rejection::CrissCross);
+            }
+            for ((x, y), shared) in cells.iter().zip(shared) {
+                let (x, y) = (*x as isize, *y as isize);
+                if !shared && (!blank(x + dy, y + dx) || !blank(x - dy, y - dx)) {
+                    return Some(Rejection::CrissCross);
+                }
+            }
+        }
+        None
+    }
+    
+    // crop - trim the rows and columns no word goes through off the edges of the grid, keeping the
+    //        placements in step, for a criss-cross that didn't need all of its grid
+    pub fn crop(&mut self) {
+        let cells: Vec<(usize, usize)> = self.placements.iter().flat_map(Placement::cells).collect();
+        let (left, right, top, bottom) = match (cells.iter().map(|c| c.0).min(), cells.iter().map(|c| c.0).max(), cells.iter().map(|c| c.1).min(), cells.iter().map(|c| c.1).max()) {
+            (Some(left), Some(right), Some(top), Some(bottom)) => (left, right, top, bottom),
+            _ => return,
+        };
+        self.grid = self.grid[top..=bottom].iter().map(|row| row[left..=right].to_vec()).collect();
+        if let Some(mask) = &mut self.mask {
+            *mask = mask[top..=bottom].iter().map(|row| row[left..=right].to_vec()).collect();
+        }
+        self.width = right - left + 1;
+        self.height = bottom - top + 1;
+        for p in self.placements.iter_mut() {
+            p.x -= left;
+            p.y -= top;
+        }
+    }
+    
+    // place_spangram - place the word so it reaches from one edge of the grid to the opposite one,
+    //                  in a straight line or turning a corner once, picking at random from every
+    //                  way it can, for the theme word of a Strands style puzzle
+    pub fn place_spangram(&mut self, word: &str, rng: &mut dyn RngCore) -> Result<(), WordfindError> {
+        let sanitized_word = self.case.apply(&sanitize(word, &self.keep_chars));
+        self.emit(|| PlacementEvent::Attempted { word: sanitized_word.clone() });
+        let letters = letters(&sanitized_word);
+        let (width, height) = (self.width as isize, self.height as isize);
+        
+        let mut shapes: Vec<(Direction, Option<(usize, Direction)>)> = self.dir_choices.iter().map(|dir| (*dir, None)).collect();
+        for dir in self.dir_choices.iter() {
+            let (x_inc, y_inc) = dir.incrementors();
+            for turn in self.dir_choices.iter().filter(|d| *d != dir && d.incrementors() != (-x_inc, -y_inc)) {
+                shapes.extend((1..letters.len().saturating_sub(1)).map(|at| (*dir, Some((at, *turn)))));
+            }
+        }
+        let mut spots = Vec::new();
+        for (y, x) in (0..self.height).flat_map(|y| (0..self.width).map(move |x| (y, x))) {
+            for (dir, bend) in shapes.iter() {
+                let placement = Placement { word: sanitized_word.clone(), x, y, dir: *dir, bend: *bend };
+                let path = placement.path();
+                if path.iter().any(|(x, y)| *x < 0 || *y < 0 || *x >= width || *y >= height) {
+                    continue;
+                }
+                let ((x1, y1), (x2, y2)) = (path[0], path[path.len() - 1]);
+                let spans = (x1.min(x2) == 0 && x1.max(x2) == width - 1) || (y1.min(y2) == 0 && y1.max(y2) == height - 1);
+                let cells: Vec<(usize, usize)> = path.iter().map(|(x, y)| (*x as usize, *y as usize)).collect();
+                if spans && self.cells_fit(&letters, &cells) {
+                    spots.push(placement);
+                }
+            }
+        }
+        
+        let result = match spots.choose(rng) {
+            Some(placement) => {
+                log_for(self.log_puzzle, Level::Debug, || format!("placed the spangram {} at row {} column {} going {:?}", sanitized_word, placement.y + 1, placement.x + 1, placement.dir));
+                self.entries.push(sanitized_word.clone());
+                self.write_placement(placement.clone());
+                self.spangram = Some(sanitized_word);
+                Ok(())
+            },
+            None => {
+                self.emit(|| PlacementEvent::Failed { word: sanitized_word.clone() });
+                Err(WordfindError::PlacementFailed { word: word.to_string(), tries: self.width * self.height * shapes.len() })
+            },
+        };
+        self.report_progress(result.is_ok());
+        result
+    }
+    
+    // spangram - the placement of the word place_spangram put edge to edge
+    pub fn spangram(&self) -> Option<&Placement> {
+        self.spangram.as_ref().and_then(|word| self.placements.iter().find(|p| p.word == *word))
+    }
+    
+    // bent_spot - a random placement for the word that turns once, the first leg going in one of the
+    //             allowed directions and the rest in another that isn't straight back, with the
+    //             corner somewhere in the middle of the word
+    pub fn bent_spot(&self, word: &str, rng: &mut dyn RngCore) -> Option<Placement> {
+        let letters = letters(word);
+        if letters.len() < 3 {
+            return None;
+        }
+        for _ in 1..self.maxtries {
+            self.tries.set(self.tries.get() + 1);
+            let dir = *self.dir_choices.choose(rng)?;
+            let (x_inc, y_inc) = dir.incrementors();
+            let turns: Vec<&Direction> = self.dir_choices.iter().filter(|d| **d != dir && d.incrementors() != (-x_inc, -y_inc)).collect();
+            let turn = match turns.choose(rng) {
+                Some(turn) => **turn,
+                None => return None,
+            };
+            let at = rng.gen_range(1, letters.len() - 1);
+            let placement = Placement { word: word.to_string(), x: rng.gen_range(0, self.width), y: rng.gen_range(0, self.height), dir, bend: Some((at, turn)) };
+            
+            let path = placement.path();
+            if path.iter().any(|(x, y)| *x < 0 || *y < 0 || *x >= self.width as isize || *y >= self.height as isize) {
+                continue;
+            }
+            let cells: Vec<(usize, usize)> = path.iter().map(|(x, y)| (*x as usize, *y as usize)).collect();
+            if self.cells_fit(&letters, &cells) {
+                return Some(placement);
+            }
+        }
+        None
+    }
+    
+    // render_text - the grid as rows of space separated letters followed by the word list
+    //               Blank cells that haven't been filled yet are shown as a '.'
+    //               With label set the columns get a header row and the rows are numbered
+    pub fn render_text(&self, label: bool) -> String {
+        self.text_layout(label, None)
+    }
+    
+    // render_text_answer - render_text with the letters of the placed words in uppercase and the
+    //                      filler shown in the given style, a '.' when the style leaves it blank
+    pub fn render_text_answer(&self, label: bool, style: AnswerStyle) -> String {
+        self.text_layout(label, Some(style))
+    }
+    
+    // answer_list - a line for each word with the column and row it starts at, counted from 1
+    //               like the labels, and the direction it reads in
+    //               A bent word also gets the cell it turns at and the direction after the turn
+    //
+    // GIRAFFE: (3, 12) DownRight
+    // ZEBRA: (1, 1) Right, (3, 1) Down
+    // The spangram has spangram on the end of its line
+    pub fn answer_list(&self) -> String {
+        let mut text = String::new();
+        for (entry, printed) in self.bank() {
+            // densify copies share the word, the first placement is the one in the word list
+            if let Some(p) = self.placements.iter().find(|p| p.word == *entry) {
+                text.push_str(&format!("{}: ({}, {}) {:?}", printed, p.x + 1, p.y + 1, p.dir));
+                if let Some((at, turn)) = p.bend {
+                    let (x, y) = p.cells()[at];
+                    text.push_str(&format!(", ({}, {}) {:?}", x + 1, y + 1, turn));
+                }
+                if self.spangram.as_ref() == Some(entry) {
+                    text.push_str(" spangram");
+                }
+                text.push('\n');
+            }
+        }
+        text
+    }
+    
+    // first_letters - a line for each word with the row and column of its first letter, counted from
+    //                 1 like the labels, for hints that don't give the whole answer away
+    //
+    // ZEBRA starts at row 4, column 17
+    pub fn first_letters(&self) -> String {
+        let mut text = String::new();
+        for (entry, printed) in self.bank() {
+            if let Some(p) = self.placements.iter().find(|p| p.word == *entry) {
+                text.push_str(&format!("{} starts at row {}, column {}\n", printed, p.y + 1, p.x + 1));
+            }
+        }
+        text
+    }
+    
+    // text_layout - the layout behind render_text and render_text_answer
+    fn text_layout(&self, label: bool, answer: Option<AnswerStyle>) -> String {
+        let width = self.grid[0].len();
+        let cell_width = if label { column_label(width - 1, width).len() } else { 1 };
+        let row_label_width = self.grid.len().to_string().len();
+        let grid = match answer {
+            Some(style) => self.answer_grid(style),
+            None => self.shown_grid(false),
+        };
+        let mut text = String::new();
+        
+        let heading = self.heading();
+        for line in heading.iter() {
+            text.push_str(line);
+            text.push('\n');
+        }
+        if !heading.is_empty() {
+            text.push('\n');
+        }
+        if label {
+            let header: Vec<String> = (0..width).map(|i| format!("{:>w$}", column_label(i, width), w = cell_width)).collect();
+            text.push_str(&format!("{:w$} {}\n", "", header.join(" "), w = row_label_width));
+        }
+        for (y, row) in grid.iter().enumerate() {
+            // cells outside a shaped grid are left blank
+            let cells: Vec<String> = row.iter().enumerate().map(|(x, c)| {
+                let shown = if !self.usable(x, y) { " " } else if c == " " { "." } else { c.as_str() };
+                format!("{:>w$}", shown, w = cell_width)
+            }).collect();
+            if label {
+                text.push_str(&format!("{:>w$} ", y + 1, w = row_label_width));
+            }
+            // a left to right mark after each letter stops bidi aware viewers from reversing the row
+            text.push_str(&cells.join(if self.rtl { "\u{200e} " } else { " " }));
+            text.push('\n');
+        }
+        
+        // text can't circle the hints so the puzzle lists them under the grid
+        if answer.is_none() && !self.revealed.is_empty() {
+            text.push_str(&format!("\nHints: {}\n", self.hint_list().join(", ")));
+        }
+        
+        // each section of the word bank after a blank line, under its heading, with the words
+        // padded out to line up in columns when the print style asks for more than one
+        let columns = self.print.columns(1);
+        for (heading, entries) in self.grouped_bank(answer.is_some()) {
+            let width = entries.iter().map(|e| e.chars().count()).max().unwrap_or(0);
+            let split = render::balanced_columns(&entries, columns);
+            let rows = (0..split.first().map_or(0, |c| c.len())).map(|row| {
+                let cells: Vec<String> = split.iter().filter_map(|column| column.get(row)).map(|e| format!("{:w$}", e, w = width)).collect();
+                cells.join("  ").trim_end().to_string()
+            });
+            text.push('\n');
+            for line in heading.map(|h| format!("{}:", h)).into_iter().chain(rows) {
+                if self.rtl {
+                    text.push('\u{200f}');
+                }
+                text.push_str(&line);
+                text.push('\n');
+            }
+        }
+        if let Some(id) = self.id_line() {
+            text.push_str(&format!("\n{}\n", id));
+        }
+        text
+    }
+    
+    // output - write the puzzle grid and words to a file in csv
+    pub fn output(&self, file_name: &str, config: &Config) -> Result<(), WordfindError> {
+        let mut file = fs::File::create(file_name)?;
+        self.write_csv(&mut file, config, false)?;
+        Ok(())
+    }
+    
+    // write_csv - the csv layout behind output, the leading blank columns and the
+    //             blank rows around the grid come from the config
+    //             With answer set only the letters of the placed words are written
+    fn write_csv<W: Write>(&self, file: &mut W, config: &Config, answer: bool) -> std::io::Result<()> {
+        let delimiter = config.delimiter.to_string();
+        let margin = delimiter.repeat(config.margin);
+        let pad_rows = "\n".repeat(config.pad_rows);
+        let quote = |text: &str| csv_quote(text, config.delimiter, config.quote_all);
+        
+        // title, subtitle, and instructions, with a blank row between them and the grid
+        let heading = self.heading();
+        for line in heading.iter() {
+            file.write_all(format!("{}{}\n", margin, quote(line)).as_bytes())?;
+        }
+        if !heading.is_empty() {
+            file.write_all(b"\n")?;
+        }
+        
+        // puzzle grid
+        file.write_all(pad_rows.as_bytes())?;
+        if config.label {
+            let width = self.grid[0].len();
+            let header: Vec<String> = (0..width).map(|i| quote(&column_label(i, width))).collect();
+            file.write_all(margin.as_bytes())?;
+            file.write_all(delimiter.as_bytes())?;
+            file.write_all(header.join(&delimiter).as_bytes())?;
+            file.write_all(b"\n")?;
+        }
+        let grid = if answer { self.answer_grid(config.answer_style) } else { self.shown_grid(false) };
+        for (y, v) in grid.iter().enumerate() {
+            file.write_all(margin.as_bytes())?;
+            if config.label {
+                file.write_all(format!("{}{}", quote(&(y + 1).to_string()), delimiter).as_bytes())?;
+            }
+            let cells: Vec<String> = v.iter().map(|c| quote(c)).collect();
+            file.write_all(cells.join(&delimiter).as_bytes())?;
+            file.write_all(b"\n")?;
+        }
+        file.write_all(pad_rows.as_bytes())?;
+        
+        // the hints are listed in a row under the puzzle grid
+        if !answer && !self.revealed.is_empty() {
+            let hints: Vec<String> = std::iter::once(String::from("Hints")).chain(self.hint_list()).map(|h| quote(&h)).collect();
+            file.write_all(format!("\n{}{}\n", margin, hints.join(&delimiter)).as_bytes())?;
+        }
+        
+        // search words, each one gets the margin in front of it so they line up under the grid
+        // and each section of the word bank gets a row with its heading before its words
+        file.write_all(b"\n\n\n")?;
+        // a numbered answer key has a legend instead, a row for each word with its number and the
+        // column and row it starts at
+        if answer && self.print.numbered_answers {
+            let legend = std::iter::once((String::from("Number"), String::from("Word"), String::from("Column"), String::from("Row")))
+                .chain(self.answer_numbers().into_iter().map(|(number, word, (x, y))| (number.to_string(), word, (x + 1).to_string(), (y + 1).to_string())));
+            for (number, word, column, row) in legend {
+                let fields = [quote(&number), quote(&word), quote(&column), quote(&row)];
+                file.write_all(format!("{}{}\n", margin, fields.join(&delimiter)).as_bytes())?;
+            }
+        } else {
+            for (heading, entries) in self.grouped_bank(answer) {
+                if let Some(heading) = heading {
+                    file.write_all(format!("{}{}\n", margin, quote(&heading)).as_bytes())?;
+                }
+                for row in entries.chunks(self.print.columns(config.words_per_row)) {
+                    for (i, entry) in row.iter().enumerate() {
+                        file.write_all(margin.as_bytes())?;
+                        if i > 0 && margin.is_empty() {
+                            file.write_all(delimiter.as_bytes())?;
+                        }
+                        file.write_all(quote(entry).as_bytes())?;
+                    }
+                    file.write_all(b"\n")?;
+                }
+            }
+        }
+        
+        // a comment row under everything else with what it takes to make the puzzle again
+        if let Some(metadata) = &self.metadata {
+            file.write_all(format!("\n{}{}\n", margin, quote(&format!("# {}, id {}", metadata.summary(), self.puzzle_id()))).as_bytes())?;
+        }
+        
+        Ok(())
+    }
+    
+    // answer_mask - for each cell of the grid, true when it holds a letter of a placed word
+    pub fn answer_mask(&self) -> Vec<Vec<bool>> {
+        let mut mask = vec![vec![false; self.width]; self.height];
+        for (x, y) in self.placements.iter().flat_map(|p| p.cells()) {
+            mask[y][x] = true;
+        }
+        mask
+    }
+    
+    // find_all - search the grid in all 8 directions and return every place the word can be found
+    //            The search ignores case, so the blocklist still catches a rude word in lowercase
+    pub fn find_all(&self, word: &str) -> Vec<Placement> {
+        let word = word.to_uppercase();
+        let letters = letters(&word);
+        let grid: Vec<Vec<String>> = self.grid.iter().map(|row| row.iter().map(|cell| cell.to_uppercase()).collect()).collect();
+        let width = self.width as isize;
+        let height = self.height as isize;
+        let mut found = Vec::new();
+        if letters.is_empty() { return found; }
+        
+        for (x, y, _) in self.iter_cells() {
+            for dir in Direction::ALL.iter() {
+                let (x_inc, y_inc) = dir.incrementors();
+                let mut xi = x as isize;
+                let mut yi = y as isize;
+                let mut matched = true;
+                for letter in letters.iter() {
+                    if xi < 0 || yi < 0 || xi >= width || yi >= height || grid[yi as usize][xi as usize] != *letter {
+                        matched = false;
+                        break;
+                    }
+                    xi += x_inc as isize;
+                    yi += y_inc as isize;
+                }
+                
+                if matched {
+                    found.push(Placement { word: word.clone(), x, y, dir: *dir, bend: None });
+                }
+            }
+        }
+        found
+    }
+    
+    // verify - make sure every entry in the word list is in the grid along the placement recorded
+    //          for it, and that the answer key shows the letter of the puzzle in every cell of a
+    //          word, a check to run before printing a puzzle that came from somewhere else
+    pub fn verify(&self) -> Result<(), WordfindError> {
+        for entry in self.entries.iter() {
+            // the letters are checked along where the word was put, which finds a bent word too
+            let letters = letters(entry);
+            let found = self.placements.iter().filter(|p| p.word == *entry).any(|p| {
+                let cells = p.cells();
+                cells.len() == letters.len() && cells.iter().zip(letters.iter()).all(|((x, y), letter)| {
+                    *x < self.width && *y < self.height && self.grid[*y][*x] == *letter
+                })
+            });
+            if !found {
+                return Err(WordfindError::WordNotFound { word: entry.clone() });
+            }
+        }
+        
+        let key = self.answer_grid(AnswerStyle::Blank);
+        for (y, row) in self.answer_mask().iter().enumerate() {
+            for x in (0..self.width).filter(|x| row[*x]) {
+                if key[y][x] != self.grid[y][x].to_uppercase() {
+                    return Err(WordfindError::AnswerKeyMismatch { x, y });
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    // difficulty_rating - how hard the puzzle is to solve, from the directions the words go in,
+    //                     how much they overlap, how much the filler looks like them, and how
+    //                     long they are, best called once the grid is filled
+    pub fn difficulty_rating(&self) -> DifficultyRating {
+        let words = self.placements.len();
+        if words == 0 {
+            return DifficultyRating { level: 1, ..DifficultyRating::default() };
+        }
+        
+        // right to left reads forwards in an rtl puzzle
+        let forwards = if self.rtl { -1 } else { 1 };
+        let backwards = self.placements.iter().filter(|p| {
+            let (x_inc, y_inc) = p.dir.incrementors();
+            x_inc == -forwards || (x_inc == 0 && y_inc < 0)
+        }).count();
+        let diagonal = self.placements.iter().filter(|p| p.dir.incrementors().0 != 0 && p.dir.incrementors().1 != 0).count();
+        
+        let cells: Vec<(usize, usize)> = self.placements.iter().flat_map(|p| p.cells()).collect();
+        let letters = cells.len();
+        let shared = cells.iter().filter(|cell| cells.iter().filter(|c| c == cell).count() > 1).count();
+        let overlap = shared as f32 / letters as f32;
+        
+        let word_letters: Vec<&String> = cells.iter().map(|(x, y)| &self.grid[*y][*x]).collect();
+        let filler: Vec<&String> = self.iter_cells().filter(|(x, y, c)| *c != ' ' && !cells.contains(&(*x, *y))).map(|(x, y, _)| &self.grid[y][x]).collect();
+        let filler_similarity = if filler.is_empty() { 0.0 } else { filler.iter().filter(|l| word_letters.contains(l)).count() as f32 / filler.len() as f32 };
+        let average_length = letters as f32 / words as f32;
+        
+        let rating = 0.3 * backwards as f32 / words as f32
+            + 0.2 * diagonal as f32 / words as f32
+            + 0.15 * overlap
+            + 0.2 * filler_similarity
+            + 0.15 * ((8.0 - average_length) / 5.0).clamp(0.0, 1.0);
+        let level = (1.0 + rating * 5.0).floor().clamp(1.0, 5.0) as u8;
+        DifficultyRating { level, rating, backwards, diagonal, overlap, filler_similarity, average_length }
+    }
+    
+    // score - how good the layout looks, higher is better
+    //         Used to pick the best of several candidate puzzles
+    pub fn score(&self, weights: &ScoreWeights) -> f32 {
+        if self.placements.is_empty() { return 0.0; }
+        
+        let overlap = self.crossings().len() as f32 / self.placements.len() as f32;
+        
+        let mut rows = vec![false; self.height];
+        let mut cols = vec![false; self.width];
+        for (x, y) in self.placements.iter().flat_map(|p| p.cells()) {
+            rows[y] = true;
+            cols[x] = true;
+        }
+        let used = |v: &Vec<bool>| v.iter().filter(|b| **b).count() as f32 / v.len() as f32;
+        let spread = (used(&rows) + used(&cols)) / 2.0;
+        
+        let mut dirs: Vec<Direction> = Vec::new();
+        for p in self.placements.iter() {
+            if !dirs.contains(&p.dir) { dirs.push(p.dir); }
+        }
+        let variety = dirs.len() as f32 / self.dir_choices.len() as f32;
+        
+        weights.overlap * overlap + weights.spread * spread + weights.variety * variety
+    }
+    
+    // reveal - pick the letters of each word to show the solver as hints, the fraction of its letters
+    //          rounded up so every word gets at least one, at random
+    //          A fraction of 0 takes the hints away
+    pub fn reveal(&mut self, fraction: f32, rng: &mut dyn RngCore) {
+        self.revealed = Vec::new();
+        if fraction <= 0.0 {
+            return;
+        }
+        for entry in self.entries.iter() {
+            // densify copies share the word, only the first placement gets hints
+            if let Some(p) = self.placements.iter().find(|p| p.word == *entry) {
+                let cells = p.cells();
+                let count = ((cells.len() as f32 * fraction).ceil() as usize).min(cells.len());
+                for cell in cells.choose_multiple(rng, count) {
+                    if !self.revealed.contains(cell) {
+                        self.revealed.push(*cell);
+                    }
+                }
+            }
+        }
+    }
+    
+    // revealed - the cells reveal picked as hints, in the order of the words
+    pub fn revealed(&self) -> &[(usize, usize)] {
+        &self.revealed
+    }
+    
+    // same_layout - whether the other puzzle has the same words in the same places, even when its
+    //               filler is different
+    pub fn same_layout(&self, other: &PuzzleGrid) -> bool {
+        let layout = |puzzle: &PuzzleGrid| -> Vec<(String, Vec<(usize, usize)>)> {
+            let mut layout: Vec<(String, Vec<(usize, usize)>)> = puzzle.placements.iter().map(|p| (p.word.clone(), p.cells())).collect();
+            layout.sort();
+            layout
+        };
+        (self.width, self.height) == (other.width, other.height) && layout(self) == layout(other)
+    }
+    
+    // puzzle_id - a fingerprint of the finished grid and the cells of each word, 16 hex digits that
+    //             come out the same for the same puzzle on any machine and from any version, for
+    //             checking that a print file, the json, and an answer key are of the same puzzle
+    //             It's a 64 bit FNV-1a hash, with the words in order so a puzzle read back from
+    //             a csv, which finds them again in word bank order, gets the same one
+    pub fn puzzle_id(&self) -> String {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut add = |text: &str| {
+            for byte in text.bytes().chain(std::iter::once(0)) {
+                hash = (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+            }
+        };
+        add(&format!("{} x {}", self.width, self.height));
+        for cell in self.grid.iter().flatten() {
+            add(cell);
+        }
+        let mut layout: Vec<(String, Vec<(usize, usize)>)> = self.placements.iter().map(|p| (p.word.clone(), p.cells())).collect();
+        layout.sort();
+        for (word, cells) in layout {
+            add(&format!("{} {:?}", word, cells));
+        }
+        format!("{:016x}", hash)
+    }
+    
+    // crossings - pairs of placed words that share a cell, along with the x, y of the shared cell
+    pub fn crossings(&self) -> Vec<(String, String, usize, usize)> {
+        let mut crossings = Vec::new();
+        for (i, a) in self.placements.iter().enumerate() {
+            let a_cells = a.cells();
+            for b in self.placements.iter().skip(i + 1) {
+                for (x, y) in b.cells() {
+                    if a_cells.contains(&(x, y)) {
+                        crossings.push((a.word.clone(), b.word.clone(), x, y));
+                    }
+                }
+            }
+        }
+        crossings
+    }
+    
+    // rotate90 - turn the grid a quarter turn clockwise, keeping the placements, the mask, and the
+    //            reserved and filler cells in step
+    //            The width and height trade places
+    pub fn rotate90(&mut self) {
+        let h = self.grid.len();
+        self.grid = rotated(&self.grid);
+        self.mask = self.mask.as_deref().map(rotated);
+        self.reserved = rotated(&self.reserved);
+        self.filled = self.filled.iter().map(|(x, y)| (h - 1 - y, *x)).collect();
+        std::mem::swap(&mut self.width, &mut self.height);
+        
+        for p in self.placements.iter_mut() {
+            let (x, y) = (p.x, p.y);
+            p.x = h - 1 - y;
+            p.y = x;
+            p.dir = p.dir.rotated90();
+            p.bend = p.bend.map(|(at, turn)| (at, turn.rotated90()));
+        }
+        self.revealed = self.revealed.iter().map(|(x, y)| (h - 1 - y, *x)).collect();
+    }
+    
+    // mirror_horizontal - flip the grid left to right, keeping the placements, the mask, and the
+    //                     reserved and filler cells in step
+    pub fn mirror_horizontal(&mut self) {
+        let w = self.grid[0].len();
+        for row in self.grid.iter_mut() {
+            row.reverse();
+        }
+        for row in self.mask.iter_mut().flatten().chain(self.reserved.iter_mut()) {
+            row.reverse();
+        }
+        self.filled = self.filled.iter().map(|(x, y)| (w - 1 - x, *y)).collect();
+        
+        for p in self.placements.iter_mut() {
+            p.x = w - 1 - p.x;
+            p.dir = p.dir.mirrored();
+            p.bend = p.bend.map(|(at, turn)| (at, turn.mirrored()));
+        }
+        self.revealed = self.revealed.iter().map(|(x, y)| (w - 1 - x, *y)).collect();
+    }
+    
+    // set_fill_chars - replace the letters used by fill_in, whitespace is ignored and letters are put
+    //                  in the puzzle's case, uppercase unless set_case says otherwise
+    //                  There must be at least one letter
+    pub fn set_fill_chars(&mut self, chars: &str) -> Result<(), WordfindError> {
+        let chars: Vec<char> = chars.chars().filter(|c| !c.is_whitespace()).flat_map(|c| self.case.fill_letters(c)).collect();
+        if chars.is_empty() {
+            return Err(WordfindError::NoFillLetters);
+        }
+        self.fill_chars = chars;
+        self.fill_weights = None;
+        Ok(())
+    }
+    
+    // set_case - the case words are placed in from here on, the fill letters are changed to match
+    pub fn set_case(&mut self, case: Case) {
+        self.case = case;
+        self.fill_chars = self.fill_chars.iter().map(|c| case.fill_letters(*c)[0]).collect();
+    }
+    
+    // set_keep_chars - punctuation to keep in the words placed from here on instead of leaving out
+    pub fn set_keep_chars(&mut self, chars: &str) {
+        self.keep_chars = chars.to_string();
+    }
+    
+    // set_fill_frequencies - replace the letters used by fill_in with the letters in the table, each one
+    //                        picked in proportion to its frequency
+    //                        Letters with a frequency of zero or less are left out
+    pub fn set_fill_frequencies(&mut self, table: &[(char, f32)]) -> Result<(), WordfindError> {
+        let table: Vec<(char, f32)> = table.iter().filter(|(_, f)| *f > 0.0).map(|(c, f)| (self.case.fill_letters(*c)[0], *f)).collect();
+        if table.is_empty() {
+            return Err(WordfindError::NoFillLetters);
+        }
+        self.fill_chars = table.iter().map(|(c, _)| *c).collect();
+        self.fill_weights = Some(table.iter().map(|(_, f)| *f).collect());
+        Ok(())
+    }
+    
+    // exclude_fill - remove letters from the set used by fill_in, there must be at least one left
+    pub fn exclude_fill(&mut self, exclude: &[char]) -> Result<(), WordfindError> {
+        let exclude: Vec<char> = exclude.iter().flat_map(|c| self.case.fill_letters(*c)).collect();
+        if let Some(weights) = &mut self.fill_weights {
+            let kept: Vec<f32> = self.fill_chars.iter().zip(weights.iter()).filter(|(c, _)| !exclude.contains(c)).map(|(_, w)| *w).collect();
+            *weights = kept;
+        }
+        self.fill_chars.retain(|c| !exclude.contains(c));
+        if self.fill_chars.is_empty() {
+            return Err(WordfindError::NoFillLetters);
+        }
+        Ok(())
+    }
+    
+    // fill_in - locate all blank grid entries and fill with a random letter
+    pub fn fill_in(&mut self, rng: &mut dyn RngCore) {
+        let weighted = self.fill_weights.as_ref().and_then(|w| WeightedIndex::new(w).ok());
+        let blanks: Vec<(usize, usize)> = self.iter_cells().filter(|(x, y, c)| *c == ' ' && self.usable(*x, *y)).map(|(x, y, _)| (x, y)).collect();
+        for (x, y) in blanks {
+            self.grid[y][x] = self.random_fill(weighted.as_ref(), rng);
+            self.filled.push((x, y));
+        }
+    }
+    
+    // clear_fill - blank out every cell that isn't a letter of a placed word, so the grid can be
+    //              filled again with the words where they are
+    pub fn clear_fill(&mut self) {
+        let mask = self.answer_mask();
+        for (x, y) in self.iter_cells().filter(|(x, y, _)| !mask[*y][*x] && self.usable(*x, *y)).map(|(x, y, _)| (x, y)).collect::<Vec<_>>() {
+            self.grid[y][x] = String::from(" ");
+        }
+        self.filled.clear();
+    }
+    
+    // refill - new random letters in every cell that isn't a letter of a placed word, for a puzzle
+    //          with a bad bit of filler that doesn't need a whole new layout
+    //          Decoys and a hidden message are filler too, fill puts them back from a config
+    pub fn refill(&mut self, rng: &mut dyn RngCore) {
+        self.clear_fill();
+        self.fill_in(rng);
+    }
+    
+    // random_fill - one of the fill letters, picked by weight when there are weights
+    //               With Case::Preserve it's lowercase half of the time
+    fn random_fill(&self, weighted: Option<&WeightedIndex<f32>>, rng: &mut dyn RngCore) -> String {
+        let idx = match weighted {
+            Some(weighted) => weighted.sample(rng),
+            None => rng.gen_range(0, self.fill_chars.len()),
+        };
+        let letter = self.fill_chars[idx];
+        if self.case == Case::Preserve && rng.gen() {
+            letter.to_lowercase().collect()
+        } else {
+            letter.to_string()
+        }
+    }
+    
+    // place_decoys - before the fill, write up to count misleading strings into the grid, either a
+    //                word from the list with one letter changed or the first few letters of one
+    //                Returns how many were placed, a decoy is skipped when there's no room for it or
+    //                when it would spell a word from the list with the letters it lands next to
+    pub fn place_decoys(&mut self, count: usize, rng: &mut dyn RngCore) -> usize {
+        let mut placed = 0;
+        for _ in 0..count {
+            let original: Vec<String> = match self.entries.choose(rng) {
+                Some(word) if letters(word).len() >= 3 => letters(word),
+                _ => continue,
+            };
+            
+            let decoy: String = if rng.gen() {
+                // near miss, swap one letter for a different fill letter
+                let mut changed = original.clone();
+                let i = rng.gen_range(0, changed.len());
+                let others: Vec<String> = self.fill_chars.iter().map(|c| c.to_string()).filter(|c| *c != changed[i]).collect();
+                match others.choose(rng) {
+                    Some(c) => changed[i] = c.clone(),
+                    None => continue,
+                }
+                changed.concat()
+            } else {
+                original[..rng.gen_range(2, original.len())].concat()
+            };
+            
+            // a decoy that spells a real word, forwards or backwards, would be a second answer
+            let reversed: String = letters(&decoy).into_iter().rev().collect();
+            if self.entries.iter().any(|w| *w == decoy || *w == reversed) { continue; }
+            
+            if let Some((x, y, dir)) = self.candidates(&decoy).choose(rng) {
+                if self.write_decoy_checked(&decoy, x, y, dir) {
+                    placed += 1;
+                }
+            }
+        }
+        self.stats.decoys += placed;
+        placed
+    }
+    
+    // write_decoy - write letters into the grid without recording them as a placement, so they stay
+    //               out of the answer key
+    fn write_decoy(&mut self, text: &str, x: &usize, y: &usize, dir: &Direction) {
+        let (x_indeces, y_indeces) = self.get_indeces(text, x, y, dir);
+        for ((xi, yi), letter) in x_indeces.iter().zip(y_indeces.iter()).zip(letters(text)) {
+            self.grid[*yi][*xi] = letter;
+        }
+    }
+    
+    // write_decoy_checked - write_decoy, taken back when it finishes another copy of a word from the
+    //                       list with the letters around it, a copy the fill can't re-roll since none
+    //                       of its cells are filler
+    //                       Returns whether the decoy was kept
+    fn write_decoy_checked(&mut self, text: &str, x: &usize, y: &usize, dir: &Direction) -> bool {
+        let copies = self.accidental_words().len();
+        let (x_indeces, y_indeces) = self.get_indeces(text, x, y, dir);
+        let cells: Vec<(usize, usize)> = x_indeces.into_iter().zip(y_indeces).collect();
+        let before: Vec<String> = cells.iter().map(|(x, y)| self.grid[*y][*x].clone()).collect();
+        self.write_decoy(text, x, y, dir);
+        if self.accidental_words().len() > copies {
+            for ((x, y), letter) in cells.into_iter().zip(before) {
+                self.grid[y][x] = letter;
+            }
+            return false;
+        }
+        true
+    }
+    
+    // accidental_words - every place a word from the list can be found in the grid other than where
+    //                    it was placed, usually spelled out by the random fill
+    pub fn accidental_words(&self) -> Vec<Placement> {
+        let mut placed: Vec<Vec<(usize, usize)>> = self.placements.iter().map(|p| p.cells()).collect();
+        for cells in placed.iter_mut() {
+            cells.sort_unstable();
+        }
+        
+        let mut words: Vec<&String> = self.entries.iter().collect();
+        words.sort();
+        words.dedup();
+        let mut found = Vec::new();
+        for word in words {
+            for p in self.find_all(word) {
+                // a palindrome reads the same both ways over the cells it was placed in
+                let mut cells = p.cells();
+                cells.sort_unstable();
+                if !placed.contains(&cells) {
+                    found.push(p);
+                }
+            }
+        }
+        found
+    }
+    
+    // blocked_words - every place a word from the blocklist can be read in the grid, in any direction
+    pub fn blocked_words(&self, blocklist: &[String]) -> Vec<Placement> {
+        blocklist.iter().flat_map(|word| self.find_all(word)).collect()
+    }
+    
+    // clear_accidental_words - re-roll the random fill letters in any accidental copy of a word
+    //                          until the only copies left are the placed ones
+    //                          Returns false when a copy is made up entirely of letters that weren't
+    //                          random fill, like crossing words or a hidden message, and can't be cleared
+    pub fn clear_accidental_words(&mut self, rng: &mut dyn RngCore) -> bool {
+        self.clear_unwanted_words(&[], rng)
+    }
+    
+    // clear_unwanted_words - clear_accidental_words that also re-rolls the fill under any word from
+    //                        the blocklist, both are cleared together so fixing one can't bring back the other
+    pub fn clear_unwanted_words(&mut self, blocklist: &[String], rng: &mut dyn RngCore) -> bool {
+        let weighted = self.fill_weights.as_ref().and_then(|w| WeightedIndex::new(w).ok());
+        for _ in 0..MAX_REROLLS {
+            let mut extras = self.accidental_words();
+            extras.extend(self.blocked_words(blocklist));
+            if extras.is_empty() {
+                return true;
+            }
+            
+            let mut stuck = true;
+            for p in extras.iter() {
+                let filler: Vec<(usize, usize)> = p.cells().into_iter().filter(|c| self.filled.contains(c)).collect();
+                if let Some((x, y)) = filler.choose(rng) {
+                    self.grid[*y][*x] = self.random_fill(weighted.as_ref(), rng);
+                    stuck = false;
+                }
+            }
+            if stuck {
+                return false;
+            }
+        }
+        self.accidental_words().is_empty() && self.blocked_words(blocklist).is_empty()
+    }
+    
+    // fill_in_message - fill the blank cells in reading order with the letters of the message, then
+    //                   fill whatever is left over with random letters
+    //                   Only the letters and digits of the message are used, in the puzzle's case
+    pub fn fill_in_message(&mut self, message: &str, rng: &mut dyn RngCore) -> Result<(), WordfindError> {
+        self.write_message(message)?;
+        self.fill_in(rng);
+        Ok(())
+    }
+    
+    // write_message - the first half of fill_in_message, the cells after the message are left blank
+    fn write_message(&mut self, message: &str) -> Result<(), WordfindError> {
+        let letters: Vec<String> = message.chars().filter(|c| c.is_alphanumeric()).map(|c| self.case.apply(&c.to_string())).collect();
+        let blanks: Vec<(usize, usize)> = self.iter_cells().filter(|(x, y, c)| *c == ' ' && self.usable(*x, *y)).map(|(x, y, _)| (x, y)).collect();
+        if letters.len() > blanks.len() {
+            return Err(WordfindError::MessageTooLong { letters: letters.len(), blanks: blanks.len() });
+        }
+        
+        for ((x, y), letter) in blanks.into_iter().zip(letters) {
+            self.grid[y][x] = letter;
+        }
+        Ok(())
+    }
+    
+    // fill_in_camouflage - a harder fill, the start of each word is copied next to where the real one
+    //                      begins and the rest of the blank cells get letters picked from the placed words
+    //
+    // A copy is always at least one letter short of its word so it can't be mistaken for the answer,
+    // it only goes in blank cells, never where the next letter of the word already follows it, and
+    // it's taken back if it spells a word with the letters around it anyway
+    pub fn fill_in_camouflage(&mut self, rng: &mut dyn RngCore) {
+        let placements = self.placements.clone();
+        for p in placements.iter() {
+            let letters = letters(&p.word);
+            if letters.len() < 3 { continue; }
+            let length = rng.gen_range(2, letters.len());
+            let prefix: String = letters[..length].concat();
+            
+            // every way the prefix fits in blank cells starting on a cell touching the first letter of
+            // the word, with the cell after it not going on with the word
+            let mut spots = Vec::new();
+            for (dx, dy) in Direction::ALL.iter().map(Direction::incrementors) {
+                let (x, y) = (p.x as isize + dx as isize, p.y as isize + dy as isize);
+                if x < 0 || y < 0 || x >= self.width as isize || y >= self.height as isize { continue; }
+                let (x, y) = (x as usize, y as usize);
+                for dir in self.dir_choices.iter().filter(|d| self.placement_valid(&prefix, &x, &y, d)) {
+                    let (x_indeces, y_indeces) = self.get_indeces(&prefix, &x, &y, dir);
+                    if x_indeces.iter().zip(y_indeces.iter()).any(|(xi, yi)| self.grid[*yi][*xi] != " ") { continue; }
+                    let (x_inc, y_inc) = dir.incrementors();
+                    let (nx, ny) = (x as isize + x_inc as isize * length as isize, y as isize + y_inc as isize * length as isize);
+                    let inside = nx >= 0 && ny >= 0 && nx < self.width as isize && ny < self.height as isize;
+                    if inside && self.grid[ny as usize][nx as usize].to_uppercase() == letters[length].to_uppercase() { continue; }
+                    spots.push((x, y, *dir));
+                }
+            }
+            if let Some((x, y, dir)) = spots.choose(rng) {
+                self.write_decoy_checked(&prefix, x, y, dir);
+            }
+        }
+        
+        // letters from the placed words, each one as likely as it is common in the word list
+        let pool: Vec<String> = self.placements.iter().flat_map(|p| letters(&p.word)).collect();
+        if pool.is_empty() {
+            return self.fill_in(rng);
+        }
+        let blanks: Vec<(usize, usize)> = self.iter_cells().filter(|(x, y, c)| *c == ' ' && self.usable(*x, *y)).map(|(x, y, _)| (x, y)).collect();
+        for (x, y) in blanks {
+            self.grid[y][x] = pool.choose(rng).unwrap().clone();
+            self.filled.push((x, y));
+        }
+    }
+}
+
+// Display for PuzzleGrid - a quick text view of the grid and word list for debugging
+//
+// Blank cells that haven't been filled yet are shown as a '.'
+impl fmt::Display for PuzzleGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render_text(false))
+    }
+}
+
+// Debug for PuzzleGrid - the grid with the letters of the placed words in brackets and where each
+//                        word went, to see the layout when a test fails
+impl fmt::Debug for PuzzleGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "PuzzleGrid {} x {}", self.width, self.height)?;
+        let mask = self.answer_mask();
+        for (y, row) in self.grid.iter().enumerate() {
+            let cells: String = row.iter().enumerate().map(|(x, cell)| {
+                let cell = if cell == " " { "." } else { cell };
+                if mask[y][x] { format!("[{}]", cell) } else { format!(" {} ", cell) }
+            }).collect();
+            writeln!(f, "{}", cells.trim_end())?;
+        }
+        for p in self.placements.iter() {
+            writeln!(f, "{} at ({}, {}) {:?}", p.word, p.x, p.y, p.dir)?;
+        }
+        Ok(())
+    }
+}
+
+// fill - fill in the blank cells of a generated puzzle the way the config asks for, the decoys
+//        go in first, then the hidden message, and the camouflage or random letters after them
+//        A second copy of a word or a blocklisted word the re-rolls can't clear starts the fill over,
+//        up to FILL_RETRIES times, and one that's still there after that is kept in
+//        PuzzleStats::uncleared for the Summary to warn about
+pub(crate) fn fill(puzzle: &mut PuzzleGrid, config: &Config, rng: &mut dyn RngCore) -> Result<(), WordfindError> {
+    // a criss-cross has no filler, the cells between the words are blacked out
+    if puzzle.crisscross {
+        return Ok(());
+    }
+    puzzle.emit(|| PlacementEvent::FillStarted);
+    let blocklist = config.blocklist.as_deref().unwrap_or(&[]);
+    let decoys = puzzle.stats.decoys;
+    for tries in 1..=FILL_RETRIES {
+        puzzle.place_decoys(config.decoys, rng);
+        if let Some(message) = &config.hidden_message {
+            puzzle.write_message(message)?;
+        }
+        if config.camouflage {
+            puzzle.fill_in_camouflage(rng);
+        } else {
+            puzzle.fill_in(rng);
+        }
+        if puzzle.clear_unwanted_words(blocklist, rng) {
+            return Ok(());
+        }
+        if tries < FILL_RETRIES {
+            puzzle.clear_fill();
+            puzzle.stats.decoys = decoys;
+        }
+    }
+    
+    // what's left is usually in the letters of the placed words, which no fill can change, so the
+    // last fill is kept, the placed words are still in the answer key
+    let mut words: Vec<String> = puzzle.accidental_words().into_iter().chain(puzzle.blocked_words(blocklist)).map(|p| p.word).collect();
+    words.sort();
+    words.dedup();
+    log(config, Level::Info, || format!("the fill couldn't get rid of {} spelled out in the letters of the words", words.join(", ")));
+    puzzle.stats.uncleared = words;
+    Ok(())
+}
+
+// rotated - the rows turned a quarter turn clockwise, the first row becomes the last column
+fn rotated<T: Clone>(rows: &[Vec<T>]) -> Vec<Vec<T>> {
+    let h = rows.len();
+    let w = rows.first().map_or(0, Vec::len);
+    (0..w).map(|x| (0..h).map(|y| rows[h - 1 - y][x].clone()).collect()).collect()
+}
+
+// blank_grid - an empty puzzle set up with the placement strategy and fill letters from the config
+fn blank_grid(config: &Config, alphabet: Alphabet, width: usize, height: usize, strategy: &Arc<dyn PlacementStrategy>) -> Result<PuzzleGrid, WordfindError> {
+    let mut puzzle = PuzzleGrid::new(width, height, config.maxtries, config.difficulty);
+    puzzle.set_strategy(strategy.clone());
+    puzzle.cancel = config.cancel.clone();
+    puzzle.log_puzzle = config.log_puzzle;
+    puzzle.events = config.events.clone();
+    puzzle.set_case(config.case);
+    puzzle.set_rtl(config.rtl);
+    if let Some(directions) = &config.directions {
+        puzzle.set_directions(directions)?;
+    }
+    puzzle.set_direction_weights(&config.direction_weights);
+    puzzle.set_spacing(config.spacing);
+    puzzle.set_bent(config.bent && !config.crisscross);
+    puzzle.set_crisscross(config.crisscross);
+    if let Some(mask) = &config.mask {
+        puzzle.set_mask(mask)?;
+    } else if let Some(shape) = config.shape {
+        puzzle.set_mask(&shape.mask(width, height))?;
+    } else if let Some(text) = &config.shape_text {
+        puzzle.set_mask(&letter_mask(text, width, height)?)?;
+    }
+    if let Some(chars) = &config.keep_chars {
+        puzzle.set_keep_chars(chars);
+    }
+    for (x, y, w, h) in config.reserved.iter() {
+        puzzle.reserve(*x, *y, *w, *h)?;
+    }
+    set_fill(&mut puzzle, config, alphabet)?;
+    Ok(puzzle)
+}
+
+// set_fill - the fill letters of the alphabet, or the ones from Config::fill_chars, weighted by
+//            Config::fill_frequencies and without Config::exclude_fill
+pub(crate) fn set_fill(puzzle: &mut PuzzleGrid, config: &Config, alphabet: Alphabet) -> Result<(), WordfindError> {
+    puzzle.set_fill_chars(config.fill_chars.as_deref().unwrap_or(alphabet.letters()))?;
+    if let Some(table) = &config.fill_frequencies {
+        puzzle.set_fill_frequencies(table)?;
+    }
+    puzzle.exclude_fill(&config.exclude_fill)
+}
+
+// csv_quote - a csv field, quoted when it holds the delimiter, a quote, or a line break, or when quote_all is set
+fn csv_quote(text: &str, delimiter: char, quote_all: bool) -> String {
+    if quote_all || text.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+// column_label - the header for column i of a grid that is width columns wide
+//                letters run out past 26 columns, so wide grids are numbered instead
+fn column_label(i: usize, width: usize) -> String {
+    if width <= 26 {
+        char::from(b'A' + i as u8).to_string()
+    } else {
+        (i + 1).to_string()
+    }
+}
+
+// letters - split a word into the letters that each take up one cell of the grid
+//           A letter is a character along with any combining accents or joiners that follow it,
+//           so "cafe" with a combining accent on the e is still 4 letters
+pub fn letters(word: &str) -> Vec<String> {
+    let mut letters: Vec<String> = Vec::new();
+    let mut join = false;
+    for c in word.chars() {
+        match letters.last_mut() {
+            Some(last) if join || is_combining(c) => last.push(c),
+            _ => letters.push(c.to_string()),
+        }
+        join = c == '\u{200d}';
+    }
+    letters
+}
+
+// sanitize - the word the way it's written into the grid, without the spaces, hyphens, and
+//            apostrophes, so "T-shirt" is placed as Tshirt before it's put in the puzzle's case
+//            Any of the punctuation in keep stays in, digits and other symbols are always kept
+pub fn sanitize(word: &str, keep: &str) -> String {
+    word.chars().filter(|c| !c.is_whitespace() && (!SEPARATORS.contains(*c) || keep.contains(*c))).collect()
+}
+
+// fold_diacritics - the text with the accents taken off its letters, "Élève" becomes "Eleve"
+//                   Letters that aren't an accented form of another letter, like ß or Æ, are kept
+pub fn fold_diacritics(text: &str) -> String {
+    const ACCENTED: &str = "ÀÁÂÃÄÅĀĂĄàáâãäåāăąÇĆĈĊČçćĉċčĎďÈÉÊËĒĔĖĘĚèéêëēĕėęěĜĞĠĢĝğġģĤĥÌÍÎÏĨĪĬĮİìíîïĩīĭįĴĵĶķĹĻĽĿŁĺļľŀłÑŃŅŇñńņňÒÓÔÕÖØŌŎŐòóôõöøōŏőŔŖŘŕŗřŚŜŞŠśŝşšŢŤţťÙÚÛÜŨŪŬŮŰŲùúûüũūŭůűųŴŵÝŸŶýÿŷŹŻŽźżž";
+    const PLAIN: &str = "AAAAAAAAAaaaaaaaaaCCCCCcccccDdEEEEEEEEEeeeeeeeeeGGGGggggHhIIIIIIIIIiiiiiiiiJjKkLLLLLlllllNNNNnnnnOOOOOOOOOoooooooooRRRrrrSSSSssssTTttUUUUUUUUUUuuuuuuuuuuWwYYYyyyZZZzzz";
+    text.chars().filter(|c| !is_combining(*c) || *c == '\u{200d}' || *c == '\u{200c}').map(|c| match ACCENTED.chars().position(|a| a == c) {
+        Some(i) => PLAIN.chars().nth(i).unwrap(),
+        None => c,
+    }).collect()
+}
+
+// is_combining - true for marks that attach to the character before them instead of standing alone
+fn is_combining(c: char) -> bool {
+    matches!(c as u32, 0x0300..=0x036f | 0x0483..=0x0489 | 0x0591..=0x05bd | 0x05bf | 0x05c1..=0x05c2 | 0x05c4..=0x05c5 | 0x05c7
+        | 0x0610..=0x061a | 0x064b..=0x065f | 0x0670 | 0x06d6..=0x06dc | 0x06df..=0x06e4 | 0x06e7..=0x06e8 | 0x06ea..=0x06ed
+        | 0x1ab0..=0x1aff | 0x1dc0..=0x1dff | 0x200c..=0x200d | 0x20d0..=0x20ff | 0xfe00..=0xfe0f | 0xfe20..=0xfe2f)
+}
+
+// roomier_size - the side of a square grid, at least as big as the one given, that the words fill
+//                about half of, for suggesting when they're crowded
+fn roomier_size(words: &[String], width: usize, height: usize) -> usize {
+    auto_size(words, 0.5).max(width.max(height) + 1)
+}
+
+// auto_size - the side of a square grid big enough for the longest word, where the letters of
+//             all of the words cover roughly the given fraction of the cells
+//             The density is kept between MIN_AUTO_DENSITY and 1, anything else, 0 or less
+//             included, is taken as the nearer of the two
+pub fn auto_size(words: &[String], density: f32) -> usize {
+    let density = if density > MIN_AUTO_DENSITY { density.min(1.0) } else { MIN_AUTO_DENSITY };
+    let longest = words.iter().map(|w| letters(w).len()).max().unwrap_or(0);
+    let letters: usize = words.iter().map(|w| letters(w).len()).sum();
+    let side = (letters as f32 / density).sqrt().ceil() as usize;
+    side.max(longest)
+}
+
+// placed_word - the word the way generate writes it into the grid
+fn placed_word(config: &Config, word: &str) -> String {
+    let keep = config.keep_chars.as_deref().unwrap_or("");
+    let plain = plain_arabic(word_text(word));
+    let sanitized = if config.fold_diacritics { sanitize(&fold_diacritics(&plain), keep) } else { sanitize(&plain, keep) };
+    config.case.apply(&sanitized)
+}
+
+// longest_fit - the most letters the word can have and still fit in the grid, the farthest a
+//               straight word reaches in the directions the puzzle allows, so a word as long as the
+//               grid is wide is too long when it can only go up and down, and around a corner for a
+//               bent word or the spangram
+fn longest_fit(config: &Config, word: &str, width: usize, height: usize) -> usize {
+    let spangram = config.spangram.as_ref().is_some_and(|s| placed_word(config, &plain_arabic(s)).to_uppercase() == word.to_uppercase());
+    if config.bent || spangram {
+        return width + height - 1;
+    }
+    let directions = match &config.directions {
+        _ if config.crisscross => vec![Direction::Right, Direction::Down],
+        Some(directions) => directions.clone(),
+        None => config.difficulty.directions(),
+    };
+    directions.iter().map(|dir| dir.reach(width, height)).max().unwrap_or_else(|| width.max(height))
+}
+
+// largest_size - the width and height of the grid the words have to fit in, as big as
+//                Config::grow_to lets it get
+fn largest_size(config: &Config) -> (usize, usize) {
+    match config.grow_to {
+        Some(max) => (config.width.max(max), config.height.max(max)),
+        None => (config.width, config.height),
+    }
+}
+
+// validate_words - check every word before placing any of them so all of the problems can be
+//                  fixed at once, blank entries are skipped but still count as a line
+//                  Words that are too long are only a problem when the grid can't grow to fit them, and
+//                  words listed twice only with Config::strict_duplicates
+//                  Words left out by the length or pattern filters aren't checked
+pub fn validate_words(config: &Config, words: &[String]) -> Vec<WordProblem> {
+    let (width, height) = largest_size(config);
+    // a dictionary can have many thousands of words, so the first line of each one is looked up
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut problems = Vec::new();
+    for (i, word) in words.iter().enumerate().filter(|(_, w)| is_word(w) && keep_word(config, w)) {
+        let line = i + 1;
+        let problem = |reason: String| WordProblem { file: None, line, word: word.clone(), reason };
+        let placed = placed_word(config, word);
+        if word.chars().any(|c| c.is_control() || c == '\u{fffd}') {
+            problems.push(problem(String::from("has a character that can't be printed, check the file's encoding")));
+        } else if placed.is_empty() {
+            // left out of the puzzle with a warning, see PuzzleGrid::no_letters
+        } else if !config.auto_size && letters(&placed).len() > longest_fit(config, &placed, width, height) {
+            let longest = longest_fit(config, &placed, width, height);
+            problems.push(problem(match longest < width.max(height) {
+                true => format!("is too long for a {} x {} puzzle, {} letters fit in the directions it allows", width, height, longest),
+                false => format!("is too long for a {} x {} puzzle", width, height),
+            }));
+        } else if let Some(pin) = split_pin(split_clue(word).0).1 {
+            // the grid size is only known here when it can't change
+            let fixed = !config.auto_size && config.grow_to.is_none();
+            match Pin::parse(pin) {
+                Err(reason) => problems.push(problem(reason)),
+                Ok(pin) if fixed => if let Err(reason) = pin.fits(letters(&placed).len(), width, height) { problems.push(problem(reason)) },
+                Ok(_) => (),
+            }
+        }
+        
+        if config.strict_duplicates && !placed.is_empty() {
+            match seen.get(&placed.to_uppercase()) {
+                Some(first) => problems.push(problem(format!("is already on line {}", first))),
+                None => { seen.insert(placed.to_uppercase(), line); },
+            }
+        }
+    }
+    problems
+}
+
+// split_clue - a line of the word list as the word and the clue after a |, "giraffe | the tallest animal"
+//              Lines without a clue, or with nothing after the |, are all word
+pub fn split_clue(line: &str) -> (&str, Option<&str>) {
+    match line.split_once('|') {
+        Some((word, clue)) if !clue.trim().is_empty() => (word.trim(), Some(clue.trim())),
+        Some((word, _)) => (word.trim(), None),
+        None => (line.trim(), None),
+    }
+}
+
+// split_pin - the word part of a line as the word and the pin after an @, "HALLOWEEN @ (0,0) Down",
+//             see Pin::parse
+pub fn split_pin(word: &str) -> (&str, Option<&str>) {
+    match word.split_once('@') {
+        Some((word, pin)) => (word.trim(), Some(pin.trim())),
+        None => (word.trim(), None),
+    }
+}
+
+// word_text - the word a line of the word list is for, without its clue or pin
+fn word_text(line: &str) -> &str {
+    split_pin(split_clue(line).0).0
+}
+
+// section_header - the name of the section a heading line of the word list starts, "[Animals]"
+pub fn section_header(line: &str) -> Option<&str> {
+    line.trim().strip_prefix('[').and_then(|name| name.strip_suffix(']')).map(str::trim)
+}
+
+// is_comment - true for a comment line of the word list, "# from the week 3 reading", which is
+//              left out
+fn is_comment(line: &str) -> bool {
+    line.trim().starts_with('#')
+}
+
+// is_word - true for the lines of the word list that aren't blank, a comment, or a section heading
+fn is_word(line: &str) -> bool {
+    !line.trim().is_empty() && !is_comment(line) && section_header(line).is_none()
+}
+
+// keep_word - true when the word passes the Config::min_length, Config::max_length, and
+//             Config::pattern filters
+fn keep_word(config: &Config, word: &str) -> bool {
+    let length = letters(&placed_word(config, word)).len();
+    let long_enough = length >= config.min_length && config.max_length.is_none_or(|max| length <= max);
+    let matches = match &config.pattern {
+        Some(pattern) => {
+            let pattern: Vec<char> = pattern.to_uppercase().chars().collect();
+            let word: Vec<char> = word_text(word).to_uppercase().chars().collect();
+            glob_match(&pattern, &word)
+        },
+        None => true,
+    };
+    long_enough && matches
+}
+
+// glob_match - true when the whole text matches the pattern, * matches any run of characters,
+//              ? any one character, and [abc] or [a-z] any one character in the brackets
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') => (0..=text.len()).any(|skip| glob_match(&pattern[1..], &text[skip..])),
+        Some('?') => !text.is_empty() && glob_match(&pattern[1..], &text[1..]),
+        Some('[') if pattern.contains(&']') => {
+            let end = pattern.iter().position(|c| *c == ']').unwrap();
+            let set = &pattern[1..end];
+            let in_set = |c: char| set.iter().enumerate().any(|(i, s)| match (set.get(i + 1), set.get(i + 2)) {
+                (Some('-'), Some(to)) => (*s..=*to).contains(&c),
+                _ => *s == c,
+            });
+            match text.first() {
+                Some(c) if in_set(*c) => glob_match(&pattern[end + 1..], &text[1..]),
+                _ => false,
+            }
+        },
+        Some(c) => text.first() == Some(c) && glob_match(&pattern[1..], &text[1..]),
+    }
+}
+
+// filter_words - the words to put in the puzzle, without blank entries and words left out by the
+//                Config::min_length, Config::max_length, and Config::pattern filters, cut down to
+//                Config::max_words
+//                Random words are picked with rng but kept in the order they were listed
+//                Section headings are kept where they are so the words stay in their sections
+pub fn filter_words(config: &Config, words: &[String], rng: &mut dyn RngCore) -> Vec<String> {
+    let kept: Vec<usize> = (0..words.len()).filter(|i| is_word(&words[*i]) && keep_word(config, &words[*i])).collect();
+    let picked: Vec<usize> = match config.max_words {
+        Some(max) if max < kept.len() && config.random_words => {
+            let mut picked = rand::seq::index::sample(rng, kept.len(), max).into_vec();
+            picked.sort_unstable();
+            picked.into_iter().map(|i| kept[i]).collect()
+        },
+        Some(max) => kept.into_iter().take(max).collect(),
+        None => kept,
+    };
+    words.iter().enumerate()
+        .filter(|(i, w)| picked.binary_search(i).is_ok() || section_header(w).is_some())
+        .map(|(_, w)| w.clone())
+        .collect()
+}
+
+// generate - creates the PuzzleGrid and places the words, leaving the blank spaces for fill_in
+//
+// Every fresh grid gets its own rng seeded from the one passed in, so a seeded run
+// is reproducible even when the grid has to be regenerated
+// Any rng will do, tests can pass in one of the mock rngs to make placement predictable
+pub fn generate(config: &Config, words: &[String], rng: &mut dyn RngCore) -> Result<PuzzleGrid, WordfindError> {
+    if words.iter().all(|w| section_header(w).is_some()) {
+        return Err(WordfindError::NoWords);
+    }
+    
+    // sizing the grid for the density is auto_size aiming for it
+    let sized;
+    let config = match config.target_density.filter(|_| config.density_mode == DensityMode::Size) {
+        Some(target) => {
+            sized = Config { auto_size: true, auto_density: target, ..config.clone() };
+            &sized
+        },
+        None => config,
+    };
+    
+    // a shaped grid can't grow, it's always the size of its mask
+    if let Some(max) = config.grow_to.filter(|_| config.mask.is_none()) {
+        return generate_growing(config, words, max, rng);
+    }
+    
+    // the words under a heading line, "[Animals]", are grouped under it in the word bank, the ones
+    // before the first heading aren't in a section
+    let mut heading = None;
+    let mut listed: Vec<(&String, Option<&str>)> = Vec::new();
+    for line in words.iter() {
+        match section_header(line) {
+            Some(name) => heading = Some(name).filter(|name| !name.is_empty()),
+            None if is_word(line) => listed.push((line, heading)),
+            None => {},
+        }
+    }
+    
+    // a line can have a clue to print in place of the word, "giraffe | the tallest animal", and
+    // arabic typed in presentation forms is turned back into plain letters, one per cell
+    // and one can be pinned to a start cell or direction, "HALLOWEEN @ (0,0) Down"
+    let clues: Vec<Option<&str>> = listed.iter().map(|(w, _)| split_clue(w).1).collect();
+    let plain: Vec<String> = listed.iter().map(|(w, _)| plain_arabic(word_text(w))).collect();
+    let mut pins = Vec::new();
+    for (line, word) in listed.iter().map(|(w, _)| w).zip(plain.iter()) {
+        pins.push(match split_pin(split_clue(line).0).1 {
+            Some(pin) => Some(Pin::parse(pin).map_err(|reason| WordfindError::InvalidWord { word: word.clone(), reason: format!("it {}", reason) })?),
+            None => None,
+        });
+    }
+    let words = &plain[..];
+    
+    // the grid gets the words without their punctuation, or their accents when folding, and the
+    // word bank prints them the way they were typed
+    // the same word twice, even in a different case or with different punctuation, would be placed
+    // twice and printed twice in the word bank, so only the first one is kept
+    let mut original: Vec<String> = Vec::new();
+    let mut sanitized: Vec<String> = Vec::new();
+    let mut duplicates = Vec::new();
+    let mut no_letters = Vec::new();
+    let mut kept_clues = Vec::new();
+    let mut sections = Vec::new();
+    let mut pinned: Vec<(String, Pin)> = Vec::new();
+    for (((word, clue), (_, section)), pin) in words.iter().zip(clues).zip(listed).zip(pins) {
+        let placed = placed_word(config, word);
+        if placed.is_empty() {
+            no_letters.push(word.clone());
+        } else if sanitized.iter().any(|w| w.to_uppercase() == placed.to_uppercase()) {
+            if config.strict_duplicates {
+                return Err(WordfindError::InvalidWord { word: word.clone(), reason: String::from("it is in the word list more than once") });
+            }
+            duplicates.push(word.clone());
+        } else {
+            if let Some(clue) = clue {
+                kept_clues.push((placed.clone(), clue.to_string()));
+            }
+            if let Some(section) = section {
+                sections.push((placed.clone(), section.to_string()));
+            }
+            if let Some(pin) = pin {
+                pinned.push((placed.clone(), pin));
+            }
+            original.push(word.clone());
+            sanitized.push(placed);
+        }
+    }
+    let words = &sanitized[..];
+    if words.is_empty() {
+        return Err(WordfindError::NoWords);
+    }
+    
+    let (width, height) = if let Some(mask) = &config.mask {
+        (mask.first().map_or(0, Vec::len), mask.len())
+    } else if config.auto_size {
+        let size = auto_size(words, config.auto_density);
+        (size, size)
+    } else {
+        (config.width, config.height)
+    };
+    
+    if width == 0 || height == 0 {
+        return Err(WordfindError::InvalidSize { width, height });
+    }
+    
+    // validate that the words all fit along the longer side of the grid
+    for (word, typed) in words.iter().zip(original.iter()) {
+        if letters(word).len() > longest_fit(config, word, width, height) {
+            return Err(WordfindError::WordTooLong { word: typed.clone(), width, height });
+        }
+    }
+    
+    // and that there's room for all of their letters, an over-full list would only use up maxtries
+    // before failing on whichever word happened to be last
+    let total: usize = words.iter().map(|w| letters(w).len()).sum();
+    let cells = config.mask.as_ref().map_or(width * height, |mask| mask.iter().flatten().filter(|c| **c).count());
+    let fill = total as f32 / cells.max(1) as f32;
+    if fill > OVERFULL && !config.skip_failed {
+        return Err(WordfindError::TooManyLetters { letters: total, cells, size: roomier_size(words, width, height) });
+    }
+    if fill > CROWDED {
+        log(config, Level::Info, || format!("the words have {} letters for {} cells, so they may take a lot of tries to fit, a {} x {} grid would give them more room", total, cells, roomier_size(words, width, height), roomier_size(words, width, height)));
+    }
+    
+    let alphabet = config.alphabet.unwrap_or_else(|| Alphabet::detect(words));
+    let strategy: Arc<dyn PlacementStrategy> = match &config.strategy {
+        Some(strategy) => strategy.clone(),
+        None if config.crisscross => Arc::new(CrissCrossPlacement),
+        None if config.dense || config.min_crossings > 0 => Arc::new(OverlapPlacement),
+        None if config.balanced || config.symmetric => Arc::new(BalancedPlacement { symmetric: config.symmetric }),
+        None => Arc::new(RandomPlacement),
+    };
+    
+    // place all of the words in the puzzle, the pinned ones first so the rest go around them,
+    // starting over with a fresh grid if too many fail
+    // the spangram goes in before anything else, it needs the most room
+    let spangram = match &config.spangram {
+        Some(spangram) => {
+            let placed = placed_word(config, &plain_arabic(spangram));
+            let found = words.iter().find(|w| w.to_uppercase() == placed.to_uppercase());
+            Some(found.ok_or_else(|| WordfindError::InvalidWord { word: spangram.clone(), reason: String::from("it's the spangram but isn't in the word list") })?.clone())
+        },
+        None => None,
+    };
+    // the words that have to go along an edge are pinned there
+    for edge in config.edge_words.iter() {
+        let placed = placed_word(config, &plain_arabic(edge));
+        let found = words.iter().find(|w| w.to_uppercase() == placed.to_uppercase());
+        let found = found.ok_or_else(|| WordfindError::InvalidWord { word: edge.clone(), reason: String::from("it has to go along an edge but isn't in the word list") })?;
+        if spangram.as_ref() == Some(found) {
+            return Err(WordfindError::InvalidWord { word: edge.clone(), reason: String::from("it's the spangram, which reaches across the grid instead") });
+        }
+        match pinned.iter_mut().find(|(p, _)| p == found) {
+            Some((_, pin)) => pin.edge = true,
+            None => pinned.push((found.clone(), Pin { start: None, direction: None, edge: true })),
+        }
+    }
+    let free: Vec<String> = words.iter().filter(|w| !pinned.iter().any(|(p, _)| p == *w) && spangram.as_ref() != Some(*w)).cloned().collect();
+    let mut attempt = 0;
+    let mut tries = 0;
+    let (mut puzzle, mut grid_rng) = loop {
+        if let Some(cancel) = &config.cancel {
+            cancel.check()?;
+        }
+        let mut grid_rng = StdRng::seed_from_u64(rng.gen());
+        let mut puzzle = blank_grid(config, alphabet, width, height, &strategy)?;
+        puzzle.tries.set(tries);
+        let progress = Progress { done: 0, total: words.len(), placed: 0, attempt: attempt + 1, puzzle: 1, puzzles: 1 };
+        puzzle.progress = config.progress.clone().map(|hook| (hook, progress));
+        puzzle.emit(|| PlacementEvent::GridStarted { attempt: attempt + 1 });
+        let mut failed: Vec<String> = spangram.iter().filter(|word| puzzle.place_spangram(word, &mut grid_rng).is_err()).cloned().collect();
+        failed.extend(puzzle.place_all_pinned(&pinned, &mut grid_rng));
+        failed.extend(strategy.place_all(&mut puzzle, &free, &mut grid_rng));
+        puzzle.failed = failed;
+        
+        // the strategy boxed some words out, search for a layout that fits them all
+        if !puzzle.failed.is_empty() && config.backtrack {
+            log(config, Level::Info, || format!("{} didn't fit, searching for a layout that fits every word", puzzle.failed.join(", ")));
+            let mut search = blank_grid(config, alphabet, width, height, &strategy)?;
+            search.tries.set(puzzle.tries.get());
+            if spangram.iter().all(|word| search.place_spangram(word, &mut grid_rng).is_ok()) && search.place_all_pinned(&pinned, &mut grid_rng).is_empty() && search.place_all_backtracking(&free, &mut grid_rng) {
+                log(config, Level::Info, || String::from("the search fit every word"));
+                if let Some(hook) = &config.progress {
+                    hook(Progress { done: words.len(), placed: words.len(), ..progress });
+                }
+                puzzle = search;
+            } else {
+                log(config, Level::Info, || String::from("the search couldn't fit every word either"));
+                puzzle.tries.set(search.tries.get());
+            }
+        }
+        tries = puzzle.tries.get();
+        puzzle.progress = None;
+        if !pinned.is_empty() || spangram.is_some() || config.crisscross {
+            puzzle.entries.sort_by_key(|entry| words.iter().position(|w| w == entry));
+        }
+        if let Some(cancel) = &config.cancel {
+            cancel.check()?;
+        }
+        
+        // a word that doesn't cross enough of the others is as bad as one that didn't fit
+        if let Some(word) = puzzle.lonely_words(config.min_crossings).first() {
+            if attempt >= config.max_regens.max(CROSSING_REGENS) {
+                return Err(WordfindError::TooFewCrossings { word: word.clone(), crossings: config.min_crossings });
+            }
+            attempt += 1;
+            log(config, Level::Info, || format!("{} crosses fewer than {} other words, starting over with a new grid, attempt {}", word, config.min_crossings, attempt + 1));
+            continue;
+        }
+        
+        // skipping failed words is fine as long as something made it into the puzzle
+        let failed_fraction = puzzle.failed.len() as f32 / words.len() as f32;
+        // a criss-cross that boxed a word out usually fits it on another go
+        let too_many = if config.skip_failed { puzzle.entries.is_empty() } else { failed_fraction > config.max_failed_fraction };
+        let regens = if config.crisscross { config.max_regens.max(CROSSING_REGENS) } else { config.max_regens };
+        match puzzle.failed.first() {
+            Some(word) if too_many => {
+                if attempt >= regens {
+                    return Err(WordfindError::PlacementFailed { word: word.clone(), tries: config.maxtries });
+                }
+                attempt += 1;
+                log(config, Level::Info, || format!("{} of {} words didn't fit, starting over with a new grid, attempt {} of {}", puzzle.failed.len(), words.len(), attempt + 1, regens + 1));
+            },
+            Some(_) => {
+                log(config, Level::Info, || format!("left out {}", puzzle.failed.join(", ")));
+                break (puzzle, grid_rng);
+            },
+            None => break (puzzle, grid_rng),
+        }
+    };
+    
+    // move the words around for a better looking layout, a criss-cross already has the layout it
+    // needs and its words can't move on their own without coming apart
+    if let Some(objective) = config.optimize.filter(|_| !config.crisscross) {
+        let before = (puzzle.grid.clone(), puzzle.placements.clone(), puzzle.measure(objective));
+        let after = puzzle.optimize_words(objective, OPTIMIZE_ROUNDS, &free, &mut grid_rng);
+        if puzzle.lonely_words(config.min_crossings).is_empty() {
+            log(config, Level::Info, || format!("moved the words for {:?}, from {:.0}% to {:.0}%", objective, before.2 * 100.0, after * 100.0));
+        } else {
+            puzzle.grid = before.0;
+            puzzle.placements = before.1;
+            log(config, Level::Info, || format!("kept the layout as it was, the one best for {:?} has words that cross fewer than {} others", objective, config.min_crossings));
+        }
+    }
+    
+    // make the grid busier if it came out too sparse, a criss-cross only needs the part of the grid
+    // its words reach
+    if config.crisscross {
+        puzzle.crop();
+    } else if let Some(target) = config.target_density {
+        match config.density_mode {
+            DensityMode::Copies => puzzle.densify(target, MAX_DENSITY_ATTEMPTS, &mut grid_rng),
+            DensityMode::Pool => {
+                let mut pool: Vec<String> = config.density_pool.iter().filter(|w| is_word(w)).map(|w| placed_word(config, &plain_arabic(word_text(w))))
+                    .filter(|w| !w.is_empty() && !words.iter().any(|placed| placed.to_uppercase() == w.to_uppercase()))
+                    .collect();
+                pool.shuffle(&mut grid_rng);
+                for word in pool {
+                    if puzzle.density() >= target {
+                        break;
+                    }
+                    if !puzzle.entries.iter().any(|e| e.to_uppercase() == word.to_uppercase()) && letters(&word).len() <= width.max(height) && puzzle.place(&word, &mut grid_rng).is_ok() {
+                        log(config, Level::Debug, || format!("added {} from the pool to cover more of the grid", word));
+                    }
+                }
+            },
+            DensityMode::Warn | DensityMode::Size => {},
+        }
+        if puzzle.density() < target {
+            log(config, Level::Info, || format!("the words cover {:.0}% of the grid, short of the {:.0}% asked for", puzzle.density() * 100.0, target * 100.0));
+        }
+    }
+    puzzle.stats.density = puzzle.density();
+    puzzle.stats.directions = Direction::ALL.iter()
+        .map(|dir| (*dir, puzzle.placements.iter().filter(|p| p.dir == *dir).count()))
+        .filter(|(_, count)| *count > 0)
+        .collect();
+    let cells: Vec<(usize, usize)> = puzzle.placements.iter().flat_map(Placement::cells).collect();
+    puzzle.stats.overlaps = puzzle.iter_cells().filter(|(x, y, _)| cells.iter().filter(|c| **c == (*x, *y)).count() > 1).count();
+    puzzle.stats.words = puzzle.entries.len();
+    puzzle.stats.tries = puzzle.tries.get();
+    
+    // safety net against placement bugs
+    if config.verify {
+        puzzle.verify()?;
+    }
+    puzzle.stats.regens = attempt;
+    puzzle.duplicates = duplicates;
+    puzzle.no_letters = no_letters;
+    puzzle.title = config.title.clone();
+    puzzle.subtitle = config.subtitle.clone();
+    puzzle.instructions = config.instructions.clone();
+    puzzle.spellings = words.iter().cloned().zip(original.iter().map(|w| config.case.apply(w))).filter(|(placed, printed)| placed != printed).collect();
+    puzzle.clues = kept_clues;
+    puzzle.sections = sections;
+    
+    // a criss-cross word bank is grouped by length, shortest first, the solver works out where
+    // each word goes by counting the boxes
+    let mut word_order = config.bank_order();
+    if config.crisscross && puzzle.sections.is_empty() {
+        let mut by_length: Vec<&String> = puzzle.entries.iter().collect();
+        by_length.sort_by_key(|w| letters(w).len());
+        puzzle.sections = by_length.into_iter().map(|w| (w.clone(), format!("{} Letters", letters(w).len()))).collect();
+        if word_order == WordOrder::Original {
+            word_order = WordOrder::Length;
+        }
+    }
+    puzzle.set_word_order(word_order, &mut grid_rng);
+    puzzle.set_bank_style(config.bank_style);
+    puzzle.set_print_style(config.print_style());
+    if config.bank_style == BankStyle::Scrambled {
+        puzzle.scramble_bank(&mut grid_rng);
+    }
+    puzzle.reveal(config.reveal, &mut grid_rng);
+    
+    Ok(puzzle)
+}
+
+// generate_growing - generate, adding 2 to the width and height each time the words don't fit,
+//                    until the longer side would pass max
+fn generate_growing(config: &Config, words: &[String], max: usize, rng: &mut dyn RngCore) -> Result<PuzzleGrid, WordfindError> {
+    let mut grown = config.clone();
+    grown.grow_to = None;
+    if grown.auto_size {
+        let placed: Vec<String> = words.iter().filter(|w| section_header(w).is_none()).map(|w| placed_word(config, w)).collect();
+        grown.width = auto_size(&placed, grown.auto_density);
+        grown.height = grown.width;
+        grown.auto_size = false;
+    }
+    
+    loop {
+        match generate(&grown, words, rng) {
+            Err(WordfindError::PlacementFailed { .. }) | Err(WordfindError::WordTooLong { .. }) | Err(WordfindError::TooManyLetters { .. }) if grown.width.max(grown.height) + 2 <= max => {
+                grown.width += 2;
+                grown.height += 2;
+                log(config, Level::Info, || format!("the words didn't fit, growing the grid to {} x {}", grown.width, grown.height));
+            },
+            result => return result,
+        }
+    }
+}
+
+// generate_best - generates Config::candidates puzzles and keeps the one with the highest score
+pub fn generate_best(config: &Config, words: &[String], rng: &mut dyn RngCore) -> Result<PuzzleGrid, WordfindError> {
+    let mut best = generate(config, words, rng)?;
+    let mut best_score = best.score(&config.score_weights);
+    for _ in 1..config.candidates {
+        let puzzle = generate(config, words, rng)?;
+        let score = puzzle.score(&config.score_weights);
+        if score > best_score {
+            best = puzzle;
+            best_score = score;
+        }
+    }
+    Ok(best)
+}
+
+// Summary - what happened during a run, for the caller to report
+//
+#[derive(Debug,Clone,Default)]
+pub struct Summary {
+    // words that could not be placed and were left out of the puzzle
+    pub skipped: Vec<String>,
+    // words that were left out because they were already in the list
+    pub duplicates: Vec<String>,
+    // lines of the word list that were left out because they had no letters
+    pub no_letters: Vec<String>,
+    // the files that were written, in the order they were written
+    pub files: Vec<PathBuf>,
+    // puzzles made, the words placed in all of them, and the grids tried to get there
+    pub puzzles: usize,
+    pub placed: usize,
+    pub attempts: usize,
+    // the difficulty level of each puzzle made, 1 to 5, see PuzzleGrid::difficulty_rating
+    pub levels: Vec<u8>,
+    // the width and height of each puzzle made
+    pub sizes: Vec<(usize, usize)>,
+    // fraction of the cells of each puzzle made that the words go through
+    pub fill: Vec<f32>,
+    // seed the run started from, pass it to Config::seed to make the same puzzles again
+    pub seed: Option<u64>,
+    // how long the run took, from reading the words to writing the last file
+    pub elapsed: Duration,
+    // how the words went into each puzzle made
+    pub stats: Vec<PuzzleStats>,
+    // the PuzzleGrid::puzzle_id of each puzzle made, printed on its pages to match them up later
+    pub ids: Vec<String>,
+    // with DensityMode::Warn, the puzzles whose words cover less of the grid than
+    // Config::target_density, by their number from 1 and how much they cover
+    pub sparse: Vec<(usize, f32)>,
+    // the puzzles that still spell out a word a second time or a blocklisted word after the fill
+    // was started over, by their number from 1 and the words, see PuzzleStats::uncleared
+    pub uncleared: Vec<(usize, Vec<String>)>,
+    // each puzzle made as text followed by its answer key from render_ansi, with Config::show
+    pub shown: Vec<String>,
+}
+
+impl Summary {
+    // add - count a finished puzzle in the totals, and keep it to show with Config::show
+    fn add(&mut self, puzzle: &PuzzleGrid, config: &Config) {
+        self.puzzles += 1;
+        self.placed += puzzle.entries.len();
+        self.attempts += puzzle.stats.regens + 1;
+        self.levels.push(puzzle.difficulty_rating().level);
+        self.sizes.push((puzzle.width, puzzle.height));
+        let cells = puzzle.iter_cells().filter(|(x, y, _)| puzzle.usable(*x, *y)).count();
+        let covered = puzzle.answer_mask().iter().flatten().filter(|c| **c).count();
+        self.fill.push(covered as f32 / cells.max(1) as f32);
+        if let Some(target) = config.target_density.filter(|_| config.density_mode == DensityMode::Warn) {
+            if puzzle.stats.density < target {
+                self.sparse.push((self.puzzles, puzzle.stats.density));
+            }
+        }
+        if !puzzle.stats.uncleared.is_empty() {
+            self.uncleared.push((self.puzzles, puzzle.stats.uncleared.clone()));
+        }
+        self.seed = self.seed.or(puzzle.metadata.as_ref().map(|m| m.seed));
+        self.stats.push(puzzle.stats.clone());
+        self.ids.push(puzzle.puzzle_id());
+        if config.show {
+            self.shown.push(format!("{}\n{}", puzzle.render_text(config.label), puzzle.render_ansi()));
+        }
+    }
+}
+
+// run - the main runner. Reads the words file and hands off to run_with_words
+//       Blank lines are passed along so the word list problems have the right line numbers
+//       With more than one words file each file is checked on its own and its words go in a
+//       section of the word bank named after the file, the same word in two files is used once
+//       A theme takes the place of the words file, with theme::THEME_WORDS of the words that fit
+//       picked at random unless Config::max_words says how many
+//       With Config::languages there's a puzzle for each language instead, see make_languages
+pub fn run(mut config: Config) -> Result<Summary, WordfindError> {
+    let started = Instant::now();
+    start_timeout(&mut config);
+    if !config.languages.is_empty() {
+        let summary = run_languages(&config)?;
+        return Ok(Summary { elapsed: started.elapsed(), ..summary });
+    }
+    let words = checked_words(&mut config)?;
+    let summary = run_valid_words(config, &words)?;
+    Ok(Summary { elapsed: started.elapsed(), ..summary })
+}
+
+// checked_words - the words run puts in the puzzle, from the theme or the words files, after
+//                 validate_words has found nothing wrong with them
+//                 A theme sets Config::max_words when it isn't set
+fn checked_words(config: &mut Config) -> Result<Vec<String>, WordfindError> {
+    if let Some(theme) = config.theme {
+        // the theme words too long for the grid in the directions it allows are left out instead
+        // of being problems, measured the way validate_words measures them
+        let (width, height) = largest_size(config);
+        let words: Vec<String> = theme.words().iter()
+            .filter(|w| config.auto_size || letters(&placed_word(config, w)).len() <= longest_fit(config, &placed_word(config, w), width, height))
+            .map(|w| w.to_string())
+            .collect();
+        if config.max_words.is_none() {
+            config.max_words = Some(theme::THEME_WORDS);
+            config.random_words = true;
+        }
+        check_words(config, &words)?;
+        return Ok(words);
+    }
+    
+    if config.more_wordsfiles.is_empty() {
+        let words = read_word_list(config, &config.wordsfile)?;
+        check_words(config, &words)?;
+        return Ok(words);
+    }
+    
+    let mut words = Vec::new();
+    let mut problems = Vec::new();
+    for file in std::iter::once(&config.wordsfile).chain(config.more_wordsfiles.iter()) {
+        let lines = read_word_list(config, file)?;
+        problems.extend(validate_words(config, &lines).into_iter().map(|p| WordProblem { file: Some(file.clone()), ..p }));
+        let name = Path::new(file).file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| file.clone());
+        words.push(format!("[{}]", name));
+        words.extend(lines);
+    }
+    if !problems.is_empty() {
+        return Err(WordfindError::InvalidWordList { problems });
+    }
+    Ok(words)
+}
+
+// FitReport - what dry_run found out about a word list, without writing anything
+//
+#[derive(Debug,Clone,Default)]
+pub struct FitReport {
+    // words that would go in the puzzle, after the filters and without the duplicates
+    pub words: usize,
+    // words that would only be used once because they're already in the list
+    pub duplicates: Vec<String>,
+    // lines that would be left out because they have no letters
+    pub no_letters: Vec<String>,
+    // size of the grid, the one the trial puzzle grew to with Config::grow_to
+    pub width: usize,
+    pub height: usize,
+    // fraction of the grid the letters of the words would cover, before any of them overlap
+    pub coverage: f32,
+    // words the trial puzzle left out with Config::skip_failed
+    pub skipped: Vec<String>,
+    // why the trial puzzle couldn't be made, the words are likely to fit when there isn't one
+    pub failure: Option<String>,
+    // a square grid size that would give the words more room, when they cover more than CROWDED
+    // of the grid
+    pub roomier_size: Option<usize>,
+}
+
+// dry_run - check the word list the way run does and make a trial puzzle from it to see whether
+//           the words are likely to fit, without writing any files
+//           A problem with the word list is an error like it is for run, anything that stopped
+//           the trial puzzle goes in FitReport::failure
+pub fn dry_run(mut config: Config) -> Result<FitReport, WordfindError> {
+    start_timeout(&mut config);
+    let words = checked_words(&mut config)?;
+    let mut rng = match config.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let words = WordList::from(words).filter(&config, &mut rng);
+    
+    let stats = words.stats(&config);
+    let placed = words.placed(&config);
+    let mut report = FitReport { words: stats.words, duplicates: stats.duplicates, no_letters: stats.no_letters, ..FitReport::default() };
+    let (width, height) = if config.auto_size {
+        let size = auto_size(&placed, config.auto_density);
+        (size, size)
+    } else {
+        (config.width, config.height)
+    };
+    
+    let (width, height) = match make_puzzle(&config, &words, &mut rng) {
+        Ok(puzzle) => {
+            report.skipped = puzzle.failed.clone();
+            (puzzle.width, puzzle.height)
+        },
+        Err(err) => {
+            report.failure = Some(err.to_string());
+            (width, height)
+        },
+    };
+    let cells = (width * height).max(1) as f32;
+    report.coverage = placed.iter().map(|w| letters(w).len()).sum::<usize>() as f32 / cells;
+    if report.coverage > CROWDED {
+        report.roomier_size = Some(roomier_size(&placed, width, height));
+    }
+    report.width = width;
+    report.height = height;
+    Ok(report)
+}
+
+// check_words - validate_words, with the problems as an error
+//               A grid with no room is an InvalidSize error first, rather than every word being
+//               too long for it
+pub(crate) fn check_words(config: &Config, words: &[String]) -> Result<(), WordfindError> {
+    if !config.auto_size && config.mask.is_none() && (config.width == 0 || config.height == 0) {
+        return Err(WordfindError::InvalidSize { width: config.width, height: config.height });
+    }
+    let problems = validate_words(config, words);
+    if !problems.is_empty() {
+        return Err(WordfindError::InvalidWordList { problems });
+    }
+    Ok(())
+}
+
+// start_timeout - start the clock on Config::timeout, by giving the cancel token a deadline
+pub(crate) fn start_timeout(config: &mut Config) {
+    if let Some(timeout) = config.timeout {
+        config.cancel = Some(config.cancel.take().unwrap_or_default().with_timeout(timeout));
+    }
+}
+
+// read_words - the trimmed lines of a words file, a file of "-" is read from stdin and an http or
+//              https url is downloaded with the http feature
+fn read_words(file: &str) -> Result<Vec<String>, WordfindError> {
+    let text = if file == "-" {
+        std::io::read_to_string(std::io::stdin())?
+    } else if file.starts_with("http://") || file.starts_with("https://") {
+        download(file)?
+    } else {
+        fs::read_to_string(file)?
+    };
+    Ok(WordList::from_text(&text).into_lines())
+}
+
+// read_word_list - the lines of a words file, or with Config::column the words of a csv or tsv
+//                  file as lines, see table_words
+fn read_word_list(config: &Config, file: &str) -> Result<Vec<String>, WordfindError> {
+    let lines = read_words(file)?;
+    match &config.column {
+        Some(column) => table_words(&lines, column, config.clue_column.as_deref(), config.delimiter),
+        None => Ok(lines),
+    }
+}
+
+// table_words - the words of a spreadsheet export as word list lines, the field under the column
+//               heading on each row with the field under the clue heading after a |, "giraffe |
+//               the tallest animal"
+//               The first line holds the headings, matched without regard to case, and a column
+//               can also be a number from 1. The fields are separated by tabs when the headings
+//               are, and by the delimiter otherwise
+//               The headings and rows without a word become blank lines, so the line numbers of
+//               the word list problems are still the lines of the file
+fn table_words(lines: &[String], column: &str, clue_column: Option<&str>, delimiter: char) -> Result<Vec<String>, WordfindError> {
+    let heading = match lines.iter().position(|l| !l.is_empty()) {
+        Some(heading) => heading,
+        None => return Ok(Vec::new()),
+    };
+    let delimiter = if lines[heading].contains('\t') { '\t' } else { delimiter };
+    let headings = solve::csv_row(&lines[heading], delimiter);
+    let find = |name: &str| match headings.iter().position(|h| h.eq_ignore_ascii_case(name)) {
+        Some(i) => Ok(i),
+        None => name.parse::<usize>().ok().filter(|n| (1..=headings.len()).contains(n)).map(|n| n - 1)
+            .ok_or_else(|| WordfindError::Usage(format!("the words file has no column {}, its columns are {}", name, headings.join(", ")))),
+    };
+    let word = find(column)?;
+    let clue = clue_column.map(find).transpose()?;
+    
+    Ok(lines.iter().enumerate().map(|(i, line)| {
+        if i <= heading {
+            return String::new();
+        }
+        let fields = solve::csv_row(line, delimiter);
+        let field = |i: usize| fields.get(i).map(String::as_str).unwrap_or("");
+        match clue.map(field).filter(|c| !c.is_empty()) {
+            Some(clue) if !field(word).is_empty() => format!("{} | {}", field(word), clue),
+            _ => field(word).to_string(),
+        }
+    }).collect())
+}
+
+#[cfg(feature = "http")]
+use fetch::download;
+
+// download - word list urls need the http feature
+#[cfg(not(feature = "http"))]
+fn download(_url: &str) -> Result<String, WordfindError> {
+    Err(WordfindError::MissingFeature { feature: String::from("http") })
+}
+
+// serve - the http service needs the serve feature
+#[cfg(not(feature = "serve"))]
+pub fn serve(_address: &str) -> Result<(), WordfindError> {
+    Err(WordfindError::MissingFeature { feature: String::from("serve") })
+}
+
+// run_with_words - Creates the PuzzleGrid, places the given words and outputs results
+//                  The words are checked with validate_words first and then cut down by filter_words
+pub fn run_with_words(mut config: Config, words: &[String]) -> Result<Summary, WordfindError> {
+    let started = Instant::now();
+    start_timeout(&mut config);
+    check_words(&config, words)?;
+    let summary = run_valid_words(config, words)?;
+    Ok(Summary { elapsed: started.elapsed(), ..summary })
+}
+
+// make_from_text - a filled in puzzle from the words one to a line, the way a words file has them,
+//                  made with the seed, for the callers that hand the words over as text
+//                  A theme in the config takes the place of the words
+#[cfg(any(feature = "wasm", feature = "ffi", feature = "serve"))]
+fn make_from_text(mut config: Config, words: &str, seed: u64) -> Result<PuzzleGrid, WordfindError> {
+    config.seed = Some(seed);
+    let words: Vec<String> = if config.theme.is_some() {
+        checked_words(&mut config)?
+    } else {
+        let words = WordList::from_text(words);
+        words.validate(&config)?;
+        words.into_lines()
+    };
+    let mut rng = StdRng::seed_from_u64(seed);
+    let words = filter_words(&config, &words, &mut rng);
+    make_puzzle(&config, &words, &mut rng)
+}
+
+// run_valid_words - run_with_words for words that have already been through validate_words
+fn run_valid_words(config: Config, words: &[String]) -> Result<Summary, WordfindError> {
+    let seed = run_seed(&config);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let words = filter_words(&config, words, &mut rng);
+    if config.double {
+        return run_double(&config, &words, seed, &mut rng);
+    }
+    let mut puzzles = if config.count > 1 {
+        make_batch(&config, config.count, &mut rng, |config, _, rng| make_puzzle(config, &words, rng))?
+    } else {
+        vec![make_puzzle(&config, &words, &mut rng)?]
+    };
+    for (i, puzzle) in puzzles.iter_mut().enumerate() {
+        let number = if config.count > 1 { Some(i + 1) } else { None };
+        puzzle.metadata = Some(Metadata::new(puzzle, seed, number));
+    }
+    
+    let mut summary = Summary::default();
+    for (i, puzzle) in puzzles.iter().enumerate() {
+        // output the finished puzzle and the answer key
+        let files = if config.count > 1 {
+            render::write_files(puzzle, &numbered_config(&config, i + 1))?
+        } else {
+            render::write_files(puzzle, &config)?
+        };
+        summary.files.extend(files);
+        summary.add(puzzle, &config);
+        summary.skipped.extend(puzzle.failed.iter().filter(|w| !summary.skipped.contains(w)).cloned().collect::<Vec<String>>());
+        summary.duplicates = puzzle.duplicates.clone();
+        summary.no_letters = puzzle.no_letters.clone();
+    }
+    Ok(summary)
+}
+
+// run_double - the two puzzles of Config::double, made from the same words with the words in
+//              different places and written into one file
+fn run_double(config: &Config, words: &[String], seed: u64, rng: &mut StdRng) -> Result<Summary, WordfindError> {
+    let mut puzzles = make_batch(config, 2, rng, |config, _, rng| make_puzzle(config, words, rng))?;
+    
+    // the second layout is drawn again with new seeds until its words are somewhere else, and only
+    // when they never are do the words fit the grid just the one way
+    let mut tries = 1;
+    while puzzles[0].same_layout(&puzzles[1]) {
+        if tries == MAX_REPEATS {
+            return Err(WordfindError::SameLayout);
+        }
+        log(config, Level::Info, || String::from("puzzle B came out the same as puzzle A, making it again"));
+        puzzles[1] = make_puzzle(&batch_config(config, 1, 2), words, &mut StdRng::seed_from_u64(rng.gen()))?;
+        tries += 1;
+    }
+    
+    // the subtitles tell the two apart
+    let mut summary = Summary::default();
+    for (puzzle, name) in puzzles.iter_mut().zip(["Puzzle A", "Puzzle B"].iter()) {
+        let subtitle = match puzzle.subtitle() {
+            Some(subtitle) => format!("{} - {}", subtitle, name),
+            None => name.to_string(),
+        };
+        puzzle.set_subtitle(Some(subtitle));
+        puzzle.metadata = Some(Metadata::new(puzzle, seed, None));
+        summary.add(puzzle, config);
+        summary.skipped.extend(puzzle.failed.iter().filter(|w| !summary.skipped.contains(w)).cloned().collect::<Vec<String>>());
+        summary.duplicates = puzzle.duplicates.clone();
+        summary.no_letters = puzzle.no_letters.clone();
+    }
+    summary.files = render::write_double(&puzzles, config)?;
+    Ok(summary)
+}
+
+// run_languages - the puzzles of make_languages written to files named for their language,
+//                 puzzle_es.csv and answer_key_es.csv
+fn run_languages(config: &Config) -> Result<Summary, WordfindError> {
+    let mut summary = Summary::default();
+    for (code, puzzle) in make_languages(config)? {
+        summary.files.extend(render::write_files(&puzzle, &suffixed_config(config, &code))?);
+        summary.add(&puzzle, config);
+        summary.skipped.extend(puzzle.failed.iter().filter(|w| !summary.skipped.contains(w)).cloned().collect::<Vec<String>>());
+        summary.duplicates.extend(puzzle.duplicates.iter().cloned());
+        summary.no_letters.extend(puzzle.no_letters.iter().cloned());
+    }
+    Ok(summary)
+}
+
+// make_languages - a puzzle for each of Config::languages, for a bilingual class to work on the
+//                  same words side by side
+//                  Every list is checked before any puzzle is made, then each one is made from the
+//                  same seed in a grid the size of the first, with each word put where the word on
+//                  the same line of the first list went when it fits there, so the lists should be
+//                  translations of each other in the same order
+//                  Each puzzle is filled with the letters of its own words, see language_fill
+fn make_languages(config: &Config) -> Result<Vec<(String, PuzzleGrid)>, WordfindError> {
+    let mut lists = Vec::new();
+    let mut problems = Vec::new();
+    for (_, file) in config.languages.iter() {
+        let lines = read_word_list(config, file)?;
+        problems.extend(validate_words(config, &lines).into_iter().map(|p| WordProblem { file: Some(file.clone()), ..p }));
+        lists.push(lines);
+    }
+    if !problems.is_empty() {
+        return Err(WordfindError::InvalidWordList { problems });
+    }
+    
+    let seed = run_seed(config);
+    let mut made: Vec<(String, Vec<String>, PuzzleGrid)> = Vec::new();
+    for ((code, _), lines) in config.languages.iter().zip(lists) {
+        let mut language = config.clone();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let words = filter_words(&language, &lines, &mut rng);
+        if language.fill_chars.is_none() {
+            language.fill_chars = language_fill(&language, &words);
+        }
+        if let Some((_, first_words, first)) = made.first() {
+            language.width = first.width;
+            language.height = first.height;
+            language.auto_size = false;
+            language.grow_to = None;
+            if language.strategy.is_none() && !language.crisscross {
+                let spots = first_words.iter().filter(|w| is_word(w)).zip(words.iter().filter(|w| is_word(w))).filter_map(|(first_word, word)| {
+                    let placed = placed_word(config, &plain_arabic(word_text(first_word))).to_uppercase();
+                    let spot = first.placements.iter().find(|p| p.bend.is_none() && p.word.to_uppercase() == placed)?;
+                    Some((placed_word(config, &plain_arabic(word_text(word))).to_uppercase(), (spot.x, spot.y, spot.dir)))
+                }).collect();
+                language.strategy = Some(Arc::new(MatchedPlacement { spots }));
+            }
+        }
+        let mut puzzle = make_puzzle(&language, &words, &mut rng)?;
+        puzzle.metadata = Some(Metadata::new(&puzzle, seed, None));
+        made.push((code.clone(), words, puzzle));
+    }
+    Ok(made.into_iter().map(|(code, _, puzzle)| (code, puzzle)).collect())
+}
+
+// language_fill - the fill letters for a puzzle of the words, the letters of their alphabet and any
+//                 others the words use, like the Ñ of spanish, so those don't stand out from the fill
+//                 None when the alphabet has all of them, or when the accents are folded away
+fn language_fill(config: &Config, words: &[String]) -> Option<String> {
+    if config.fold_diacritics {
+        return None;
+    }
+    let placed: Vec<String> = words.iter().filter(|w| is_word(w)).map(|w| placed_word(config, &plain_arabic(word_text(w))).to_uppercase()).collect();
+    let alphabet = config.alphabet.unwrap_or_else(|| Alphabet::detect(&placed));
+    let mut fill = alphabet.letters().to_string();
+    for c in placed.iter().flat_map(|w| w.chars()) {
+        if c.is_alphabetic() && !fill.contains(c) {
+            fill.push(c);
+        }
+    }
+    if fill.len() == alphabet.letters().len() { None } else { Some(fill) }
+}
+
+// run_seed - Config::seed, or a random seed when it isn't set, kept in the metadata of the puzzles
+//            so they can be made again
+fn run_seed(config: &Config) -> u64 {
+    config.seed.unwrap_or_else(|| rand::thread_rng().gen())
+}
+
+// make_puzzle - generate_best, and then fill the empty grid spaces with the hidden message and
+//               random letters, tuned to Config::level when it's set
+fn make_puzzle(config: &Config, words: &[String], rng: &mut dyn RngCore) -> Result<PuzzleGrid, WordfindError> {
+    if let Some(level) = config.level {
+        return make_leveled(config, words, level, rng);
+    }
+    let mut puzzle = generate_best(config, words, rng)?;
+    fill(&mut puzzle, config, rng)?;
+    Ok(puzzle)
+}
+
+// make_leveled - make puzzles until one's difficulty_rating comes out at the level, a step harder
+//                each time one comes out too easy and a step easier each time one comes out too
+//                hard, see level_step, so the level means the same puzzle whether the list is
+//                short or long. After LEVEL_TRIES puzzles the one closest to the level is kept
+fn make_leveled(config: &Config, words: &[String], level: u8, rng: &mut dyn RngCore) -> Result<PuzzleGrid, WordfindError> {
+    let level = level.clamp(1, 5);
+    // the middle of the ratings that round to the level
+    let middle = (level as f32 - 0.5) / 5.0;
+    let mut step = (level as usize - 1) * (LEVEL_STEPS - 1) / 4;
+    let mut closest: Option<(f32, PuzzleGrid)> = None;
+    for _ in 0..LEVEL_TRIES {
+        let mut tuned = config.clone();
+        tuned.level = None;
+        level_step(&mut tuned, step);
+        let puzzle = make_puzzle(&tuned, words, rng)?;
+        let rating = puzzle.difficulty_rating();
+        if rating.level == level {
+            return Ok(puzzle);
+        }
+        step = if rating.level < level { (step + 1).min(LEVEL_STEPS - 1) } else { step.saturating_sub(1) };
+        let distance = (rating.rating - middle).abs();
+        if closest.as_ref().is_none_or(|(best, _)| distance < *best) {
+            closest = Some((distance, puzzle));
+        }
+    }
+    match closest {
+        Some((_, puzzle)) => {
+            log(config, Level::Info, || format!("no puzzle came out at level {} in {} tries, using one at level {}", level, LEVEL_TRIES, puzzle.difficulty_rating().level));
+            Ok(puzzle)
+        },
+        None => make_puzzle(&Config { level: None, ..config.clone() }, words, rng),
+    }
+}
+
+// level_step - set the config up for a step of make_leveled, from 0 for the easiest puzzles to
+//              LEVEL_STEPS - 1 for the hardest, the directions of easy, medium, and then hard, then
+//              more backwards diagonals, a camouflage fill, the words packed together, then only
+//              backwards words with decoys, and last only the backwards diagonals
+fn level_step(config: &mut Config, step: usize) {
+    config.difficulty = match step {
+        0 => Difficulty::Easy,
+        1 => Difficulty::Medium,
+        _ => Difficulty::Hard,
+    };
+    config.directions = Some(match step {
+        6 => BACKWARDS.to_vec(),
+        7.. => BACKWARDS[..2].to_vec(),
+        _ => config.difficulty.directions(),
+    });
+    config.direction_weights = if step >= 3 { BACKWARDS_WEIGHTS.to_vec() } else { Vec::new() };
+    config.camouflage = step >= 4;
+    config.dense = step >= 5;
+    if step >= 6 {
+        config.decoys = config.decoys.max(EXPERT_DECOYS);
+    }
+}
+
+// make_batch - count puzzles from make, each made with its own rng seeded from rng so a seeded batch
+//              comes out the same however many Config::threads make them at once
+//              make gets a copy of config whose progress reports and log messages say which puzzle
+//              they're for
+//              A puzzle that comes out the same as an earlier one, or only differs in its filler, is
+//              made again with a new seed
+fn make_batch<F>(config: &Config, count: usize, rng: &mut dyn RngCore, make: F) -> Result<Vec<PuzzleGrid>, WordfindError>
+where
+    F: Fn(&Config, usize, &mut StdRng) -> Result<PuzzleGrid, WordfindError> + Sync,
+{
+    let make = |i: usize, rng: &mut StdRng| make(&batch_config(config, i, count), i, rng);
+    let seeds: Vec<u64> = (0..count).map(|_| rng.gen()).collect();
+    let made = parallel_map(&seeds, config.threads, |i, seed| make(i, &mut StdRng::seed_from_u64(*seed)));
+    let mut puzzles: Vec<PuzzleGrid> = Vec::new();
+    for (i, puzzle) in made.into_iter().enumerate() {
+        let mut puzzle = puzzle?;
+        let mut tries = 1;
+        while puzzles.iter().any(|p| p.grid == puzzle.grid) && tries < MAX_REPEATS {
+            log(config, Level::Info, || format!("puzzle {} came out the same as an earlier one, making it again", i + 1));
+            puzzle = make(i, &mut StdRng::seed_from_u64(rng.gen()))?;
+            tries += 1;
+        }
+        puzzles.push(puzzle);
+    }
+    Ok(puzzles)
+}
+
+// batch_config - a copy of config for puzzle i of count, whose progress reports and log messages
+//                say which puzzle they're for
+fn batch_config(config: &Config, i: usize, count: usize) -> Config {
+    let mut config = config.clone();
+    if let Some(hook) = config.progress.take() {
+        config.progress = Some(Arc::new(move |progress| hook(Progress { puzzle: i + 1, puzzles: count, ..progress })));
+    }
+    config.log_puzzle = Some(i + 1);
+    config
+}
+
+// parallel_map - f applied to each item and its index on up to threads threads, one for each core
+//                when threads is 0, with the results in the order of the items
+fn parallel_map<T, R, F>(items: &[T], threads: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(usize, &T) -> R + Sync,
+{
+    let threads = match threads {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }.clamp(1, items.len().max(1));
+    
+    // each thread takes the next item nobody has started on until they're all done
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, R)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads).map(|_| scope.spawn(|| {
+            let mut done = Vec::new();
+            loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= items.len() {
+                    return done;
+                }
+                done.push((i, f(i, &items[i])));
+            }
+        })).collect();
+        workers.into_iter().flat_map(|worker| worker.join().expect("a puzzle thread panicked")).collect()
+    });
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+// run_book - a puzzle book, a puzzle for each word file in the Config::wordsfile directory, or
+//            listed in it when it's a manifest file, all written to one html or pdf document
+//            The contents come first, then a page for each puzzle titled from its file name, and
+//            then the answer keys, Config::title is the title of the book
+pub fn run_book(mut config: Config) -> Result<Summary, WordfindError> {
+    let started = Instant::now();
+    start_timeout(&mut config);
+    let files = book_files(&config.wordsfile)?;
+    if files.is_empty() {
+        return Err(WordfindError::NoWords);
+    }
+    
+    // every file is checked before any puzzle is made
+    let mut lists = Vec::new();
+    let mut problems = Vec::new();
+    for file in files.iter() {
+        let words = read_word_list(&config, &file.to_string_lossy())?;
+        problems.extend(validate_words(&config, &words).into_iter().map(|p| WordProblem { file: Some(file.to_string_lossy().to_string()), ..p }));
+        lists.push(words);
+    }
+    if !problems.is_empty() {
+        return Err(WordfindError::InvalidWordList { problems });
+    }
+    
+    let seed = run_seed(&config);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut puzzles = make_batch(&config, files.len(), &mut rng, |config, i, rng| {
+        let words = filter_words(config, &lists[i], rng);
+        let mut puzzle = make_puzzle(config, &words, rng)?;
+        puzzle.set_title(Some(book_title(&files[i])));
+        Ok(puzzle)
+    })?;
+    for (i, puzzle) in puzzles.iter_mut().enumerate() {
+        puzzle.metadata = Some(Metadata::new(puzzle, seed, Some(i + 1)));
+    }
+    let mut summary = Summary::default();
+    for puzzle in puzzles.iter() {
+        summary.add(puzzle, &config);
+        summary.skipped.extend(puzzle.failed.iter().cloned());
+        summary.duplicates.extend(puzzle.duplicates.iter().cloned());
+        summary.no_letters.extend(puzzle.no_letters.iter().cloned());
+    }
+    summary.files = render::write_book(&puzzles, &config)?;
+    summary.elapsed = started.elapsed();
+    Ok(summary)
+}
+
+// book_files - the word files of a book, the .txt files in a directory in name order or the files
+//              listed in a manifest, one per line relative to the manifest with # comments
+fn book_files(path: &str) -> Result<Vec<PathBuf>, WordfindError> {
+    let path = Path::new(path);
+    if path.is_dir() {
+        let mut files: Vec<PathBuf> = fs::read_dir(path)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|file| file.is_file() && file.extension().is_some_and(|e| e == "txt"))
+            .collect();
+        files.sort();
+        return Ok(files);
+    }
+    let base = path.parent().unwrap_or_else(|| Path::new(""));
+    Ok(fs::read_to_string(path)?.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| base.join(line))
+        .collect())
+}
+
+// book_title - the title of a book's puzzle from its word file, farm_animals.txt is Farm animals
+fn book_title(file: &Path) -> String {
+    let stem = file.file_stem().map(|s| s.to_string_lossy().replace(['_', '-'], " ")).unwrap_or_default();
+    let mut chars = stem.trim().chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::from("Word Find"),
+    }
+}
+
+// numbered_config - the config for one puzzle of a Config::count batch, with the number before the
+//                   extension of each file name, puzzle_01.csv
+fn numbered_config(config: &Config, number: usize) -> Config {
+    let digits = config.count.to_string().len().max(2);
+    suffixed_config(config, &format!("{:0w$}", number, w = digits))
+}
+
+// suffixed_config - the config with the suffix before the extension of each file name, for the
+//                   puzzles of a batch or a set of languages
+fn suffixed_config(config: &Config, suffix: &str) -> Config {
+    let numbered = |name: &str| match name.rfind('.').filter(|dot| !name[*dot..].contains('/')) {
+        Some(dot) => format!("{}_{}{}", &name[..dot], suffix, &name[dot..]),
+        None => format!("{}_{}", name, suffix),
+    };
+    let extension = render::output_extension(config);
+    let mut numbered_config = config.clone();
+    numbered_config.output = match config.output.as_deref() {
+        Some("-") => Some(String::from("-")),
+        output => Some(numbered(output.unwrap_or(&format!("puzzle.{}", extension)))),
+    };
+    numbered_config.answer_output = Some(numbered(&render::answer_output(config, &extension)));
+    numbered_config.answer_list = config.answer_list.as_deref().map(numbered);
+    numbered_config.hint_file = config.hint_file.as_deref().map(numbered);
+    numbered_config
+}
+
+// not really exhaustively tested... just needed to check a few pieces along the way
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    
+    fn test_rng() -> StdRng {
+        StdRng::seed_from_u64(1234)
+    }
+    
+    #[test]
+    fn dir_incrementors_work() {
+        let mut dir = Direction::Right;
+        assert_eq!(dir.incrementors(), (1, 0));
+        dir = Direction::UpRight;
+        assert_eq!(dir.incrementors(), (1, -1));
+    }
+    
+    #[test]
+    fn indeces(){
+        let puzzle = PuzzleGrid::new(20, 20, 10000, Difficulty::Hard);
+        let x: usize = 10;
+        let y: usize = 10;
+        let dir = Direction::DownRight;
+        let (x_indeces, y_indeces) = puzzle.get_indeces("Thanks", &x, &y, &dir);
+        assert_eq!(x_indeces, [10, 11, 12, 13, 14, 15]);
+        assert_eq!(y_indeces, [10, 11, 12, 13, 14, 15]);
+    }
+    
+    #[test]
+    fn density_target() {
+        let config = Config { width: 10, height: 10, target_density: Some(0.4), ..Config::default() };
+        let words: Vec<String> = ["Hello", "Thanks", "Goodbye"].iter().map(|w| w.to_string()).collect();
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        assert_eq!(puzzle.entries.len(), 3);
+        assert!(puzzle.stats.density >= 0.4);
+        assert!(puzzle.stats.extra_placements > 0);
+        
+        // words from the pool go in the word bank
+        let pool: Vec<String> = ["cat", "dog", "owl", "emu", "yak", "hello", "giraffe", "zebra", "lion", "tiger", "bear", "otter"].iter().map(|w| w.to_string()).collect();
+        let config = Config { density_mode: DensityMode::Pool, density_pool: pool, ..config };
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        assert!(puzzle.stats.density >= 0.4);
+        assert!(puzzle.entries.len() > 3 && puzzle.entries.len() < 15);
+        assert_eq!(puzzle.entries.iter().filter(|e| *e == "HELLO").count(), 1);
+        assert!(puzzle.word_bank().len() == puzzle.entries.len());
+        
+        let config = Config { density_mode: DensityMode::Size, ..config };
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        assert_eq!(puzzle.entries.len(), 3);
+        assert!(puzzle.width < 10);
+        
+        let config = Config { density_mode: DensityMode::Warn, ..config };
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        assert_eq!((puzzle.entries.len(), puzzle.stats.extra_placements), (3, 0));
+        let mut summary = Summary::default();
+        summary.add(&puzzle, &config);
+        assert_eq!(summary.sparse, [(1, puzzle.stats.density)]);
+    }
+    
+    #[test]
+    fn placement_stats() {
+        let words: Vec<String> = ["cat", "dog", "owl", "emu", "yak"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 8, height: 8, ..Config::default() };
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        let stats = puzzle.stats();
+        assert_eq!(stats.words, 5);
+        assert_eq!(stats.directions.iter().map(|(_, count)| count).sum::<usize>(), 5);
+        assert!(stats.tries >= 5 && stats.tries_per_word() >= 1.0);
+        assert_eq!(stats.density, (15 - stats.overlaps) as f32 / 64.0);
+        assert_eq!(PuzzleStats::default().tries_per_word(), 0.0);
+    }
+    
+    #[test]
+    fn display_lines() {
+        let mut puzzle = PuzzleGrid::new(5, 5, 10000, Difficulty::Medium);
+        let mut rng = test_rng();
+        puzzle.place("abc", &mut rng).unwrap();
+        puzzle.place("de", &mut rng).unwrap();
+        let text = format!("{}", puzzle);
+        assert_eq!(text.lines().count(), 5 + 1 + 2);
+        assert!(text.lines().next().unwrap().len() == 9);
+        assert!(text.contains('.'));
+        
+        let mut puzzle = PuzzleGrid::new(4, 2, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &1, &0, &Direction::Right);
+        assert_eq!(format!("{:?}", puzzle), "PuzzleGrid 4 x 2\n . [C][A][T]\n .  .  .  .\nCAT at (1, 0) Right\n");
+    }
+    
+    #[test]
+    fn regenerate_on_failure() {
+        // six letter words in a 6 x 6 grid occasionally box each other out
+        let words: Vec<String> = ["ABCDEF", "GHIJKL", "MNOPQR", "STUVWX", "YZABCD"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 6, height: 6, maxtries: 1000, max_regens: 10, backtrack: false, ..Config::default() };
+        let mut rng = test_rng();
+        for _ in 0..50 {
+            let puzzle = generate(&config, &words, &mut rng).unwrap();
+            assert_eq!(puzzle.entries.len(), 5);
+            assert!(puzzle.failed.is_empty());
+        }
+    }
+    
+    #[test]
+    fn cell_iterator() {
+        let mut puzzle = PuzzleGrid::new(4, 4, 10000, Difficulty::Medium);
+        let mut rng = test_rng();
+        puzzle.place("abcd", &mut rng).unwrap();
+        puzzle.fill_in(&mut rng);
+        let cells: Vec<(usize, usize, char)> = puzzle.iter_cells().collect();
+        assert_eq!(cells.len(), 16);
+        assert_eq!((cells[0].0, cells[0].1), (0, 0));
+        assert_eq!((cells[1].0, cells[1].1), (1, 0));
+        assert_eq!((cells[4].0, cells[4].1), (0, 1));
+        assert!(cells.iter().all(|c| c.2.is_ascii_uppercase()));
+        
+        let rows: Vec<Vec<&str>> = puzzle.rows().map(|row| row.collect()).collect();
+        assert_eq!(rows.len(), 4);
+        assert!(rows.iter().all(|row| row.len() == 4));
+        assert_eq!(puzzle.cell(1, 2), Some(rows[2][1]));
+        assert_eq!(puzzle.cell(4, 0), None);
+        let mut combining = PuzzleGrid::new(4, 4, 10000, Difficulty::Medium);
+        combining.write_word("cafe\u{301}", &0, &0, &Direction::Right);
+        assert_eq!(combining.cell(3, 0), Some("e\u{301}"));
+    }
+    
+    #[test]
+    fn excluded_fill_letters() {
+        let mut puzzle = PuzzleGrid::new(100, 100, 10000, Difficulty::Medium);
+        let mut rng = test_rng();
+        puzzle.exclude_fill(&['q', 'Z']).unwrap();
+        puzzle.fill_in(&mut rng);
+        assert!(puzzle.iter_cells().all(|c| c.2 != 'Q' && c.2 != 'Z'));
+        
+        let all: Vec<char> = FILL_CHARS.chars().collect();
+        assert!(puzzle.exclude_fill(&all).is_err());
+    }
+    
+    #[test]
+    fn weighted_fill() {
+        let mut puzzle = PuzzleGrid::new(100, 100, 10000, Difficulty::Medium);
+        puzzle.set_fill_frequencies(&ENGLISH_FREQUENCIES).unwrap();
+        puzzle.exclude_fill(&['t']).unwrap();
+        puzzle.fill_in(&mut test_rng());
+        let count = |letter: char| puzzle.iter_cells().filter(|c| c.2 == letter).count();
+        assert_eq!(count('T'), 0);
+        assert!(count('E') > 10 * count('X'));
+        assert!(count('E') > 10 * count('Z'));
+        
+        assert!(puzzle.set_fill_frequencies(&[('a', 0.0)]).is_err());
+        puzzle.set_fill_frequencies(&[('a', 1.0), ('b', 0.0)]).unwrap();
+        assert_eq!(puzzle.fill_chars, vec!['A']);
+    }
+    
+    #[test]
+    fn custom_fill_letters() {
+        let mut puzzle = PuzzleGrid::new(30, 30, 10000, Difficulty::Medium);
+        puzzle.set_fill_chars("ae iou").unwrap();
+        puzzle.fill_in(&mut test_rng());
+        assert!(puzzle.iter_cells().all(|c| "AEIOU".contains(c.2)));
+        assert!(matches!(puzzle.set_fill_chars(" "), Err(WordfindError::NoFillLetters)));
+        
+        let config = Config { width: 10, height: 10, fill_chars: Some(String::from("XY")), exclude_fill: vec!['y'], ..Config::default() };
+        let words = vec![String::from("Hello")];
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        puzzle.fill_in(&mut test_rng());
+        assert_eq!(puzzle.iter_cells().filter(|c| c.2 == 'X').count(), 95);
+    }
+    
+    #[test]
+    fn rotate_directions() {
+        assert_eq!(Direction::DownRight.rotated90(), Direction::DownLeft);
+        assert_eq!(Direction::Right.rotated90(), Direction::Down);
+        assert_eq!(Direction::UpRight.mirrored(), Direction::UpLeft);
+    }
+    
+    fn placements_match(puzzle: &PuzzleGrid) -> bool {
+        puzzle.placements.iter().all(|p| {
+            p.cells().iter().zip(p.word.chars()).all(|((x, y), c)| puzzle.grid[*y][*x] == c.to_string())
+        })
+    }
+    
+    #[test]
+    fn rotate_and_mirror() {
+        let mut puzzle = PuzzleGrid::new(10, 10, 10000, Difficulty::Hard);
+        let mut rng = test_rng();
+        for word in ["Hello", "Thanks", "Goodbye", "How", "Are", "You"].iter() {
+            puzzle.place(word, &mut rng).unwrap();
+        }
+        assert_eq!(puzzle.placements.len(), 6);
+        assert!(placements_match(&puzzle));
+        puzzle.rotate90();
+        assert!(placements_match(&puzzle));
+        puzzle.mirror_horizontal();
+        assert!(placements_match(&puzzle));
+    }
+    
+    #[test]
+    fn run_in_memory() {
+        let dir = std::env::temp_dir().join("wordfindgen_run_in_memory");
+        let _ = fs::remove_dir_all(&dir);
+        let words: Vec<String> = ["Hello", "How", "Are", "You"].iter().map(|w| w.to_string()).collect();
+        run_with_words(Config { output_dir: Some(dir.to_string_lossy().to_string()), ..Config::default() }, &words).unwrap();
+        let answers = fs::read_to_string(dir.join("answer_key.csv")).unwrap();
+        let puzzle = fs::read_to_string(dir.join("puzzle.csv")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(puzzle.lines().count(), answers.lines().count());
+        assert!(answers.contains(",,,HELLO,,,HOW"));
+        assert!(!puzzle.lines().next().unwrap().contains(' '));
+    }
+    
+    #[test]
+    fn piped_output() {
+        let dir = std::env::temp_dir().join("wordfindgen_piped_output");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let words = dir.join("w.txt");
+        fs::write(&words, "cat\ndog\n").unwrap();
+        let command = format!("wordfindgen {} --seed 1 --size 4 --hard --format text --output - --output-dir {}", words.display(), dir.display());
+        let config = || match Command::from_args(command.split(' ').map(String::from)).unwrap() {
+            Command::Generate(config) => *config,
+            _ => panic!("expected a generate command"),
+        };
+        
+        // piping it twice works, there's no answer key left behind to stop the second run
+        assert!(run(config()).unwrap().files.is_empty());
+        assert!(run(config()).unwrap().files.is_empty());
+        assert!(!dir.join("answer_key.txt").exists());
+        
+        // unless it's asked for
+        let summary = run(Config { answer_output: Some(String::from("key.txt")), ..config() }).unwrap();
+        assert_eq!(summary.files, vec![dir.join("key.txt")]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn output_paths() {
+        let dir = std::env::temp_dir().join("wordfindgen_output_paths");
+        let _ = fs::remove_dir_all(&dir);
+        let words: Vec<String> = ["Hello", "How", "Are", "You"].iter().map(|w| w.to_string()).collect();
+        let config = Config { output_dir: Some(dir.to_string_lossy().to_string()), output: Some(String::from("week1.csv")), answer_output: Some(String::from("week1_key.csv")), ..Config::default() };
+        let summary = run_with_words(config, &words).unwrap();
+        assert_eq!(summary.files, vec![dir.join("week1_key.csv"), dir.join("week1.csv")]);
+        assert!(summary.files.iter().all(|f| f.exists()));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn several_formats() {
+        let dir = std::env::temp_dir().join("wordfindgen_several_formats");
+        let _ = fs::remove_dir_all(&dir);
+        let words: Vec<String> = ["cat", "dog", "owl"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 6, height: 6, format: Format::Csv, more_formats: vec![Format::Json, Format::Text], output: Some(String::from("week1.csv")), answer_list: Some(String::from("answers.txt")), output_dir: Some(dir.to_string_lossy().to_string()), ..Config::default() };
+        let summary = run_with_words(config, &words).unwrap();
+        let names: Vec<String> = summary.files.iter().map(|f| f.file_name().unwrap().to_string_lossy().into_owned()).collect();
+        assert_eq!(names, ["answer_key.csv", "answers.txt", "week1.csv", "week1.json", "answer_key.txt", "week1.txt"]);
+        
+        // the formats are all the same puzzle
+        let puzzle = PuzzleGrid::from_json(&fs::read_to_string(dir.join("week1.json")).unwrap()).unwrap();
+        let text = fs::read_to_string(dir.join("week1.txt")).unwrap();
+        assert!(puzzle.grid().iter().all(|row| text.contains(&row.join(" "))));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn combined_answer_key() {
+        let dir = std::env::temp_dir().join("wordfindgen_combined_answer_key");
+        let _ = fs::remove_dir_all(&dir);
+        let words: Vec<String> = ["cat", "dog"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 5, height: 5, seed: Some(4), format: Format::Text, combined: true, output_dir: Some(dir.to_string_lossy().to_string()), ..Config::default() };
+        run_with_words(Config { combined: false, ..config.clone() }, &words).unwrap();
+        let puzzle = fs::read_to_string(dir.join("puzzle.txt")).unwrap();
+        let answers = fs::read_to_string(dir.join("answer_key.txt")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        let summary = run_with_words(config.clone(), &words).unwrap();
+        assert_eq!(summary.files, vec![dir.join("puzzle.txt")]);
+        
+        // each line is the puzzle's with the answer key's after it
+        let text = fs::read_to_string(&summary.files[0]).unwrap();
+        assert_eq!(text.lines().count(), puzzle.lines().count().max(answers.lines().count()));
+        assert!(text.lines().zip(puzzle.lines()).all(|(both, alone)| both.starts_with(alone.trim_end())));
+        assert!(text.lines().zip(answers.lines()).all(|(both, key)| both.ends_with(key.trim_end())));
+        
+        let summary = run_with_words(Config { format: Format::Latex, force: true, ..config }, &words).unwrap();
+        assert_eq!(summary.files.len(), 1);
+        assert_eq!(fs::read_to_string(&summary.files[0]).unwrap().matches("\\newpage").count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn several_word_files() {
+        let dir = std::env::temp_dir().join("wordfindgen_several_files");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("animals.txt"), "cat\ndog\n").unwrap();
+        fs::write(dir.join("colors.txt"), "red\n\nDog\ntan\n").unwrap();
+        let file = |name: &str| dir.join(name).to_string_lossy().to_string();
+        let config = Config { wordsfile: file("animals.txt"), more_wordsfiles: vec![file("colors.txt")], width: 6, height: 6, format: Format::Text, output_dir: Some(file("out")), answer_key: false, ..Config::default() };
+        let summary = run(config.clone()).unwrap();
+        assert_eq!(summary.duplicates, ["Dog"]);
+        let text = fs::read_to_string(dir.join("out").join("puzzle.txt")).unwrap();
+        assert!(text.ends_with(&format!("\nanimals:\nCAT\nDOG\n\ncolors:\nRED\nTAN\n\nPuzzle ID {}\n", summary.ids[0])));
+        
+        fs::write(dir.join("colors.txt"), "red\n\nturquoise\n").unwrap();
+        let err = run(config).unwrap_err();
+        assert!(err.to_string().ends_with(&format!("\n  {} line 3: turquoise is too long for a 6 x 6 puzzle", file("colors.txt"))));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn theme_words() {
+        let dir = std::env::temp_dir().join("wordfindgen_theme_words");
+        let _ = fs::remove_dir_all(&dir);
+        let config = Config { theme: Some(Theme::Colors), seed: Some(3), format: Format::Json, output_dir: Some(dir.to_string_lossy().to_string()), answer_key: false, ..Config::default() };
+        run(config).unwrap();
+        let json = fs::read_to_string(dir.join("puzzle.json")).unwrap();
+        let words = &json[json.find("\"words\":[").unwrap()..json.find("],\"sections\"").unwrap()];
+        assert_eq!(words.matches(',').count() + 1, theme::THEME_WORDS);
+        fs::remove_dir_all(&dir).unwrap();
+        
+        // only the words that fit the directions are kept
+        let mut config = Config { theme: Some(Theme::Colors), width: 12, height: 4, directions: Some(vec![Direction::Down, Direction::DownRight]), ..Config::default() };
+        let words = checked_words(&mut config).unwrap();
+        assert!(!words.is_empty() && words.iter().all(|w| letters(w).len() <= 4));
+    }
+    
+    #[test]
+    fn spreadsheet_words() {
+        let lines: Vec<String> = ["Unit,Word,Definition", "1,giraffe,\"tall, with spots\"", "1,,", "2,owl,"].iter().map(|l| l.to_string()).collect();
+        assert_eq!(table_words(&lines, "word", Some("Definition"), ',').unwrap(), ["", "giraffe | tall, with spots", "", "owl"]);
+        assert_eq!(table_words(&lines, "2", None, ',').unwrap()[3], "owl");
+        assert!(table_words(&lines, "term", None, ',').is_err());
+        let tabs: Vec<String> = ["word\tclue", "cat\tsays meow"].iter().map(|l| l.to_string()).collect();
+        assert_eq!(table_words(&tabs, "1", Some("clue"), ',').unwrap(), ["", "cat | says meow"]);
+        
+        let file = std::env::temp_dir().join("wordfindgen_spreadsheet_words.csv");
+        fs::write(&file, lines.join("\n")).unwrap();
+        let mut config = Config { wordsfile: file.to_string_lossy().to_string(), column: Some(String::from("Word")), clue_column: Some(String::from("definition")), ..Config::default() };
+        let words = checked_words(&mut config).unwrap();
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        assert_eq!(puzzle.puzzle_bank(), ["tall, with spots", "OWL"]);
+        
+        // a word too long for the grid is reported on its line of the file
+        fs::write(&file, "word,definition\nhippopotamus,\n").unwrap();
+        config.width = 5;
+        config.height = 5;
+        match checked_words(&mut config) {
+            Err(WordfindError::InvalidWordList { problems }) => assert_eq!(problems[0].line, 2),
+            _ => panic!("expected the long word to be reported"),
+        }
+        fs::remove_file(&file).unwrap();
+    }
+    
+    #[test]
+    fn metadata_recorded() {
+        let dir = std::env::temp_dir().join("wordfindgen_metadata_recorded");
+        let _ = fs::remove_dir_all(&dir);
+        let words: Vec<String> = ["cat", "dog", "owl"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 6, height: 6, seed: Some(42), difficulty: Difficulty::Easy, output_dir: Some(dir.to_string_lossy().to_string()), ..Config::default() };
+        let summary = run_with_words(config.clone(), &words).unwrap();
+        assert_eq!((summary.seed, summary.sizes.clone()), (Some(42), vec![(6, 6)]));
+        assert_eq!(summary.fill, [9.0 / 36.0]);
+        assert_eq!(summary.files.len(), 2);
+        let csv = fs::read_to_string(dir.join("puzzle.csv")).unwrap();
+        let comment = csv.lines().last().unwrap();
+        assert!(comment.starts_with(&format!(",,,\"# wordfindgen {}, seed 42, 6 x 6, directions Right Down, made ", env!("CARGO_PKG_VERSION"))));
+        
+        run_with_words(Config { format: Format::Json, count: 2, ..config.clone() }, &words).unwrap();
+        let json = fs::read_to_string(dir.join("puzzle_02.json")).unwrap();
+        assert!(json.contains("\"seed\":42,\"puzzle\":2,\"width\":6,\"height\":6,\"directions\":[\"Right\",\"Down\"]"));
+        
+        // the answer key is named after a puzzle name with tokens, so the next run has files of its own
+        let templated = Config { output: Some(String::from("{seed}.csv")), ..config.clone() };
+        run_with_words(templated.clone(), &words).unwrap();
+        let summary = run_with_words(Config { seed: Some(43), ..templated }, &words).unwrap();
+        assert_eq!(summary.files, [dir.join("43_answer_key.csv"), dir.join("43.csv")]);
+        assert!(dir.join("42_answer_key.csv").exists());
+        fs::remove_dir_all(&dir).unwrap();
+        
+        let metadata = Metadata { version: String::from("1.0.0"), seed: 7, puzzle: None, width: 4, height: 4, directions: vec![Direction::Right], created: 1709285400 };
+        assert_eq!(metadata.timestamp(), "2024-03-01T09:30:00Z");
+        assert_eq!(render::file_name("{title}_{seed}_{date}.csv", Some(&metadata), "Zoo Animals!"), "zoo_animals_7_2024-03-01.csv");
+        assert_eq!(render::file_name("puzzle.csv", Some(&metadata), "Zoo"), "puzzle.csv");
+        assert_eq!(Metadata { created: 0, ..metadata }.timestamp(), "1970-01-01T00:00:00Z");
+    }
+    
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let words: Vec<String> = ["cat", "dog", "owl"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 6, height: 6, title: Some(String::from("Pets")), directions: Some(vec![Direction::Right, Direction::Down]), ..Config::default() };
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        fill(&mut puzzle, &config, &mut test_rng()).unwrap();
+        
+        let stored: PuzzleGrid = serde_json::from_str(&serde_json::to_string(&puzzle).unwrap()).unwrap();
+        assert_eq!(stored.render_json(), puzzle.render_json());
+        assert_eq!(stored.placements(), puzzle.placements());
+        
+        // the hooks and the strategy are code, so they're left out, and so are the options not given
+        let stored: Config = serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!((stored.width, stored.title, stored.directions), (6, config.title.clone(), config.directions.clone()));
+        let partial: Config = serde_json::from_str("{\"width\": 12, \"difficulty\": \"Hard\"}").unwrap();
+        assert_eq!((partial.width, partial.height, partial.difficulty), (12, 20, Difficulty::Hard));
+    }
+    
+    #[test]
+    fn puzzle_ids() {
+        let dir = std::env::temp_dir().join("wordfindgen_puzzle_ids");
+        let _ = fs::remove_dir_all(&dir);
+        let words: Vec<String> = ["cat", "dog", "owl"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 6, height: 6, seed: Some(42), format: Format::Text, output_dir: Some(dir.to_string_lossy().to_string()), ..Config::default() };
+        let summary = run_with_words(config.clone(), &words).unwrap();
+        let id = summary.ids[0].clone();
+        assert_eq!(id.len(), 16);
+        // the puzzle and its answer key both end with it
+        for file in summary.files.iter() {
+            assert!(fs::read_to_string(file).unwrap().ends_with(&format!("\nPuzzle ID {}\n", id)));
+        }
+        
+        // the same puzzle as json has the same id, and keeps it when it's read back
+        run_with_words(Config { format: Format::Json, title: Some(String::from("Pets")), ..config }, &words).unwrap();
+        let json = fs::read_to_string(dir.join("puzzle.json")).unwrap();
+        assert!(json.contains(&format!("\"id\":\"{}\"", id)));
+        let mut puzzle = PuzzleGrid::from_json(&json).unwrap();
+        assert_eq!(puzzle.puzzle_id(), id);
+        puzzle.grid[0][0] = String::from("?");
+        assert_ne!(puzzle.puzzle_id(), id);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn numbered_puzzles() {
+        let dir = std::env::temp_dir().join("wordfindgen_numbered_puzzles");
+        let _ = fs::remove_dir_all(&dir);
+        let words: Vec<String> = ["cat", "dog", "owl"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 5, height: 5, count: 3, seed: Some(9), output_dir: Some(dir.to_string_lossy().to_string()), answer_list: Some(String::from("answers")), ..Config::default() };
+        let summary = run_with_words(config, &words).unwrap();
+        assert_eq!(summary.files.len(), 9);
+        assert_eq!(summary.files[..3], [dir.join("answer_key_01.csv"), dir.join("answers_01"), dir.join("puzzle_01.csv")]);
+        let puzzles: Vec<String> = (1..=3).map(|n| fs::read_to_string(dir.join(format!("puzzle_0{}.csv", n))).unwrap()).collect();
+        assert!(puzzles[0] != puzzles[1] && puzzles[1] != puzzles[2] && puzzles[0] != puzzles[2]);
+        fs::remove_dir_all(&dir).unwrap();
+        
+        let config = Config { count: 120, output: Some(String::from("out/week.1.txt")), ..Config::default() };
+        assert_eq!(numbered_config(&config, 7).output.as_deref(), Some("out/week.1_007.txt"));
+        assert_eq!(numbered_config(&config, 7).answer_output.as_deref(), Some("answer_key_007.csv"));
+    }
+    
+    #[test]
+    fn double_puzzles() {
+        let dir = std::env::temp_dir().join("wordfindgen_double_puzzles");
+        let _ = fs::remove_dir_all(&dir);
+        let words: Vec<String> = ["cat", "dog", "owl", "emu"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 6, height: 6, seed: Some(3), double: true, format: Format::Text, output_dir: Some(dir.to_string_lossy().to_string()), ..Config::default() };
+        let summary = run_with_words(config.clone(), &words).unwrap();
+        assert_eq!(summary.puzzles, 2);
+        assert_eq!(summary.files, [dir.join("answer_key.txt"), dir.join("puzzle.txt")]);
+        let text = fs::read_to_string(dir.join("puzzle.txt")).unwrap();
+        assert!(text.lines().next().unwrap().starts_with("Puzzle A") && text.lines().next().unwrap().ends_with("Puzzle B"));
+        
+        // everything but text is a page for each puzzle
+        let summary = run_with_words(Config { format: Format::Csv, ..config.clone() }, &words).unwrap();
+        assert_eq!(summary.files, [dir.join("puzzle.html")]);
+        assert_eq!(fs::read_to_string(dir.join("puzzle.html")).unwrap().matches("<div class=\"page\">").count(), 4);
+        fs::remove_dir_all(&dir).unwrap();
+        
+        // a word that fills the grid only goes in one way
+        let config = Config { width: 3, height: 1, difficulty: Difficulty::Easy, output_dir: None, ..config };
+        assert!(matches!(run_with_words(config.clone(), &words[..1]), Err(WordfindError::SameLayout)));
+        
+        // with room to move and going backwards too it has four, and the second puzzle is drawn again
+        // until it's another one
+        let dir = std::env::temp_dir().join("wordfindgen_double_puzzles_redrawn");
+        let config = Config { width: 4, difficulty: Difficulty::Hard, output_dir: Some(dir.to_string_lossy().to_string()), force: true, ..config };
+        for seed in 0..8 {
+            assert_eq!(run_with_words(Config { seed: Some(seed), ..config.clone() }, &words[..1]).unwrap().puzzles, 2);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn language_puzzles() {
+        let dir = std::env::temp_dir().join("wordfindgen_language_puzzles");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let (en, es) = (dir.join("animals_en.txt"), dir.join("animals_es.txt"));
+        fs::write(&en, "tiger\nlion\nowl\n").unwrap();
+        fs::write(&es, "tigre\nleón\nbúho\n").unwrap();
+        let languages = vec![(String::from("en"), en.to_string_lossy().to_string()), (String::from("es"), es.to_string_lossy().to_string())];
+        let config = Config { seed: Some(4), auto_size: true, languages, output_dir: Some(dir.to_string_lossy().to_string()), ..Config::default() };
+        
+        let puzzles = make_languages(&config).unwrap();
+        let (english, spanish) = (&puzzles[0].1, &puzzles[1].1);
+        assert_eq!((puzzles[0].0.as_str(), puzzles[1].0.as_str()), ("en", "es"));
+        assert_eq!((english.width, english.height), (spanish.width, spanish.height));
+        let spot = |puzzle: &PuzzleGrid, word: &str| puzzle.placements().iter().find(|p| p.word == word).map(|p| (p.x, p.y, p.dir));
+        assert_eq!(spot(english, "TIGER"), spot(spanish, "TIGRE"));
+        assert!(spanish.grid().iter().flatten().all(|c| c.chars().all(|c| c.is_ascii_uppercase() || "ÓÚ".contains(c))));
+        assert_eq!(language_fill(&Config::default(), &[String::from("ñandú")]), Some(String::from("ABCDEFGHIJKLMNOPQRSTUVWXYZÑÚ")));
+        assert_eq!(language_fill(&Config::default(), &[String::from("owl")]), None);
+        
+        let summary = run(config).unwrap();
+        assert_eq!(summary.puzzles, 2);
+        assert!(["puzzle_en.csv", "answer_key_en.csv", "puzzle_es.csv", "answer_key_es.csv"].iter().all(|f| summary.files.contains(&dir.join(f))));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn batch_threads() {
+        let words: Vec<String> = ["cat", "dog", "owl", "emu"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 6, height: 6, ..Config::default() };
+        let batch = |threads: usize| {
+            let config = Config { threads, ..config.clone() };
+            make_batch(&config, 8, &mut test_rng(), |config, _, rng| make_puzzle(config, &words, rng)).unwrap()
+        };
+        let one: Vec<Vec<Vec<String>>> = batch(1).into_iter().map(|p| p.grid).collect();
+        let four: Vec<Vec<Vec<String>>> = batch(4).into_iter().map(|p| p.grid).collect();
+        assert_eq!(one, four);
+        assert!(one.iter().enumerate().all(|(i, grid)| !one[..i].contains(grid)));
+        assert_eq!(parallel_map(&[1, 2, 3], 0, |i, n| i * 10 + n), [1, 12, 23]);
+    }
+    
+    #[test]
+    fn progress_reports() {
+        let words: Vec<String> = ["cat", "dog", "owl"].iter().map(|w| w.to_string()).collect();
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let seen = reports.clone();
+        let config = Config { width: 6, height: 6, progress: Some(Arc::new(move |p| seen.lock().unwrap().push(p))), ..Config::default() };
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        let last = Progress { done: 3, total: 3, placed: 3, attempt: 1, puzzle: 1, puzzles: 1 };
+        assert_eq!(reports.lock().unwrap().len(), 3);
+        assert_eq!(reports.lock().unwrap().last(), Some(&last));
+        assert!(puzzle.progress.is_none());
+        
+        reports.lock().unwrap().clear();
+        make_batch(&config, 2, &mut test_rng(), |config, _, rng| make_puzzle(config, &words, rng)).unwrap();
+        let mut puzzles: Vec<(usize, usize)> = reports.lock().unwrap().iter().map(|p| (p.puzzle, p.puzzles)).collect();
+        puzzles.sort();
+        puzzles.dedup();
+        assert_eq!(puzzles, [(1, 2), (2, 2)]);
+    }
+    
+    #[test]
+    fn difficulty_presets() {
+        let words: Vec<String> = ["cat", "dog", "owl", "emu", "yak"].iter().map(|w| w.to_string()).collect();
+        let mut config = Config { width: 8, height: 8, ..Config::default() };
+        Difficulty::Easy.apply(&mut config);
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        assert!(puzzle.placements().iter().all(|p| p.dir == Direction::Right || p.dir == Direction::Down));
+        
+        Difficulty::Expert.apply(&mut config);
+        assert_eq!(config.difficulty.directions().len(), 8);
+        assert!(config.dense && config.camouflage);
+        assert_eq!((config.decoys, config.bank_style), (EXPERT_DECOYS, BankStyle::Hidden));
+        Difficulty::Medium.apply(&mut config);
+        assert!(!config.dense && !config.camouflage);
+        assert_eq!((config.decoys, config.bank_style), (0, BankStyle::Words));
+        assert_eq!(Difficulty::from_name("Expert"), Some(Difficulty::Expert));
+    }
+    
+    #[test]
+    fn chosen_directions() {
+        let words: Vec<String> = ["cat", "dog", "owl", "emu", "yak"].iter().map(|w| w.to_string()).collect();
+        let diagonals = [Direction::UpRight, Direction::UpLeft, Direction::DownRight, Direction::DownLeft];
+        let config = Config { width: 8, height: 8, rtl: true, directions: Some(diagonals.to_vec()), ..Config::default() };
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        assert!(puzzle.placements().iter().all(|p| diagonals.contains(&p.dir)));
+        
+        let config = Config { directions: Some(Vec::new()), ..config };
+        assert!(matches!(generate(&config, &words, &mut test_rng()), Err(WordfindError::NoDirections)));
+        
+        // a direction weighted far above the others takes nearly every word
+        let mut puzzle = PuzzleGrid::new(20, 20, 10000, Difficulty::Hard);
+        puzzle.set_direction_weights(&[(Direction::Down, 50.0), (Direction::Left, 0.0)]);
+        let mut rng = test_rng();
+        let spots: Vec<Direction> = (0..200).filter_map(|_| puzzle.random_spot("CAT", &mut rng)).map(|s| s.2).collect();
+        assert!(spots.iter().filter(|d| **d == Direction::Down).count() > 150);
+        assert!(!spots.contains(&Direction::Left));
+        assert_eq!(Direction::from_name("Down-Right"), Some(Direction::DownRight));
+        assert_eq!(Direction::from_name("sideways"), None);
+    }
+    
+    // TestLog - keeps the log messages about the words of log_messages, the other tests run at the
+    //           same time and log their own words
+    struct TestLog(Mutex<Vec<(Level, String)>>);
+    
+    impl log::Log for TestLog {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+        
+        fn log(&self, record: &log::Record) {
+            let message = record.args().to_string();
+            if ["QUOKKA", "NUMBAT", "DINGO"].iter().any(|word| message.contains(word)) {
+                self.0.lock().unwrap().push((record.level(), message));
+            }
+        }
+        
+        fn flush(&self) {}
+    }
+    
+    static TEST_LOG: TestLog = TestLog(Mutex::new(Vec::new()));
+    
+    #[test]
+    fn log_messages() {
+        log::set_logger(&TEST_LOG).unwrap();
+        log::set_max_level(log::LevelFilter::Debug);
+        let words: Vec<String> = ["quokka", "numbat", "dingo"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 7, height: 7, ..Config::default() };
+        generate(&config, &words, &mut test_rng()).unwrap();
+        assert_eq!(TEST_LOG.0.lock().unwrap().len(), 3);
+        assert!(TEST_LOG.0.lock().unwrap().iter().all(|(level, message)| *level == Level::Debug && message.starts_with("placed ")));
+        
+        TEST_LOG.0.lock().unwrap().clear();
+        make_batch(&config, 2, &mut test_rng(), |config, _, rng| make_puzzle(config, &words, rng)).unwrap();
+        assert!(TEST_LOG.0.lock().unwrap().iter().any(|(_, message)| message.starts_with("puzzle 2: placed ")));
+    }
+    
+    #[test]
+    fn placement_events() {
+        let words: Vec<String> = ["cat", "dog", "owl"].iter().map(|w| w.to_string()).collect();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let seen = events.clone();
+        let config = Config { width: 4, height: 4, events: Some(Arc::new(move |event: &PlacementEvent| seen.lock().unwrap().push(event.clone()))), ..Config::default() };
+        let puzzle = make_puzzle(&config, &words, &mut test_rng()).unwrap();
+        let events = events.lock().unwrap();
+        assert_eq!(events[0], PlacementEvent::GridStarted { attempt: 1 });
+        assert_eq!(events.last(), Some(&PlacementEvent::FillStarted));
+        let placed: Vec<&Placement> = events.iter().filter_map(|e| match e {
+            PlacementEvent::Placed { placement } => Some(placement),
+            _ => None,
+        }).collect();
+        assert_eq!(placed, puzzle.placements().iter().collect::<Vec<&Placement>>());
+        assert_eq!(events.iter().filter(|e| matches!(e, PlacementEvent::Attempted { .. })).count(), 3);
+        for event in events.iter() {
+            if let PlacementEvent::Rejected { word, x, y, dir, reason } = event {
+                assert!(*reason == Rejection::OffGrid || *reason == Rejection::Collision);
+                assert!(!(word == "CAT" && (*x, *y, *dir) == (puzzle.placements()[0].x, puzzle.placements()[0].y, puzzle.placements()[0].dir)));
+            }
+        }
+        
+        let mut grid = PuzzleGrid::new(4, 1, 10000, Difficulty::Medium);
+        grid.write_word("CAT", &0, &0, &Direction::Right);
+        assert_eq!(grid.rejection("DOG", &2, &0, &Direction::Right), Some(Rejection::OffGrid));
+        assert_eq!(grid.rejection("AX", &1, &0, &Direction::Right), Some(Rejection::Collision));
+        assert_eq!(grid.rejection("TO", &2, &0, &Direction::Right), None);
+    }
+    
+    #[test]
+    fn dry_runs() {
+        let dir = std::env::temp_dir().join("wordfindgen_dry_run");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("words.txt"), "cat\ndog\nCat\nowl\nemu\n").unwrap();
+        let config = Config { wordsfile: dir.join("words.txt").to_string_lossy().to_string(), width: 6, height: 6, output_dir: Some(dir.join("out").to_string_lossy().to_string()), seed: Some(1), ..Config::default() };
+        let report = dry_run(config.clone()).unwrap();
+        assert_eq!((report.words, report.width, report.height), (4, 6, 6));
+        assert_eq!(report.duplicates, ["Cat"]);
+        assert_eq!(report.coverage, 12.0 / 36.0);
+        assert_eq!(report.failure, None);
+        assert!(!dir.join("out").exists());
+        
+        let report = dry_run(Config { width: 4, height: 4, backtrack: false, maxtries: 1, ..config.clone() }).unwrap();
+        assert_eq!(report.roomier_size, None);
+        let report = dry_run(Config { width: 3, height: 3, backtrack: false, maxtries: 5, ..config.clone() }).unwrap();
+        assert_eq!(report.roomier_size, Some(5));
+        assert_eq!(report.failure.as_deref(), Some("the words have 12 letters, too many for the 9 cells of the grid, try --size 5 or --auto-size"));
+        assert!(matches!(dry_run(Config { width: 2, height: 2, ..config }), Err(WordfindError::InvalidWordList { .. })));
+    }
+    
+    #[test]
+    fn cancelled() {
+        let words: Vec<String> = ["cat", "dog", "owl"].iter().map(|w| w.to_string()).collect();
+        let cancel = CancelToken::new();
+        let config = Config { width: 6, height: 6, cancel: Some(cancel.clone()), ..Config::default() };
+        assert!(generate(&config, &words, &mut test_rng()).is_ok());
+        cancel.clone().cancel();
+        assert!(matches!(generate(&config, &words, &mut test_rng()), Err(WordfindError::Cancelled)));
+        
+        let mut config = Config { width: 6, height: 6, timeout: Some(Duration::from_secs(0)), ..Config::default() };
+        start_timeout(&mut config);
+        match generate(&config, &words, &mut test_rng()) {
+            Err(err @ WordfindError::TimedOut { .. }) => assert!(err.to_string().starts_with("gave up after 0 seconds")),
+            _ => panic!("expected the puzzle to time out"),
+        }
+        config.cancel = None;
+        assert!(matches!(run_with_words(config, &words), Err(WordfindError::TimedOut { .. })));
+    }
+    
+    #[test]
+    fn puzzle_book() {
+        let dir = std::env::temp_dir().join("wordfindgen_puzzle_book");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("farm_animals.txt"), "cow\npig\n").unwrap();
+        fs::write(dir.join("colors.txt"), "red\ntan\n").unwrap();
+        fs::write(dir.join("notes.md"), "not a word list\n").unwrap();
+        let out = dir.join("out");
+        let config = Config { wordsfile: dir.to_string_lossy().to_string(), width: 6, height: 6, format: Format::Html, output_dir: Some(out.to_string_lossy().to_string()), ..Config::default() };
+        let summary = run_book(config.clone()).unwrap();
+        assert_eq!(summary.files, [out.join("book.html")]);
+        let html = fs::read_to_string(out.join("book.html")).unwrap();
+        assert!(html.contains("<li><a href=\"#puzzle-1\">Colors</a> <span>2</span></li>\n<li><a href=\"#puzzle-2\">Farm animals</a> <span>3</span></li>"));
+        assert!(html.contains("start on page 4"));
+        assert_eq!(html.matches("class=\"page\"").count(), 5);
+        assert!(html.contains("<h1 id=\"answer-2\">Farm animals - Answer Key</h1>"));
+        
+        fs::write(dir.join("book.list"), "# this week\nfarm_animals.txt\n").unwrap();
+        let config = Config { wordsfile: dir.join("book.list").to_string_lossy().to_string(), ..config };
+        assert!(matches!(run_book(config.clone()), Err(WordfindError::FileExists { .. })));
+        run_book(Config { force: true, ..config }).unwrap();
+        let html = fs::read_to_string(out.join("book.html")).unwrap();
+        assert_eq!(html.matches("class=\"page\"").count(), 3);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn hidden_message() {
+        let mut puzzle = PuzzleGrid::new(4, 2, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &1, &0, &Direction::Right);
+        puzzle.fill_in_message("hi, you!", &mut rand::rngs::mock::StepRng::new(0, 0)).unwrap();
+        assert_eq!(puzzle.grid().concat().concat(), "HCATIYOU");
+        
+        let mut puzzle = PuzzleGrid::new(4, 2, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &1, &0, &Direction::Right);
+        puzzle.fill_in_message("ok", &mut rand::rngs::mock::StepRng::new(0, 0)).unwrap();
+        assert_eq!(puzzle.grid().concat().concat(), "OCATKAAA");
+        
+        let mut puzzle = PuzzleGrid::new(4, 2, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &1, &0, &Direction::Right);
+        match puzzle.fill_in_message("too long", &mut test_rng()) {
+            Err(WordfindError::MessageTooLong { letters, blanks }) => assert_eq!((letters, blanks), (7, 5)),
+            _ => panic!("expected the message to be too long"),
+        }
+    }
+    
+    #[test]
+    fn accidental_copies() {
+        // with only C, A, T, and S to fill with, copies of CAT show up all over the place
+        let mut puzzle = PuzzleGrid::new(8, 8, 10000, Difficulty::Hard);
+        puzzle.write_word("CAT", &0, &0, &Direction::Right);
+        puzzle.write_word("SAT", &7, &7, &Direction::Up);
+        puzzle.entries = vec![String::from("CAT"), String::from("SAT")];
+        puzzle.set_fill_chars("CATS").unwrap();
+        let mut rng = test_rng();
+        puzzle.fill_in(&mut rng);
+        assert!(!puzzle.accidental_words().is_empty());
+        assert!(puzzle.clear_accidental_words(&mut rng));
+        assert!(puzzle.accidental_words().is_empty());
+        assert_eq!(puzzle.find_all("CAT").len(), 1);
+        
+        // palindromes are found forwards and backwards over the same cells
+        let mut puzzle = PuzzleGrid::new(3, 1, 10000, Difficulty::Medium);
+        puzzle.write_word("EYE", &0, &0, &Direction::Right);
+        puzzle.entries.push(String::from("EYE"));
+        assert!(puzzle.accidental_words().is_empty());
+        
+        // placed letters that spell a word again can't be fixed by the fill
+        let mut puzzle = PuzzleGrid::new(4, 2, 10000, Difficulty::Medium);
+        puzzle.write_word("AB", &0, &0, &Direction::Right);
+        puzzle.write_word("AB", &0, &1, &Direction::Right);
+        puzzle.entries.push(String::from("AB"));
+        puzzle.placements.pop();
+        puzzle.fill_in(&mut rng);
+        assert!(!puzzle.clear_accidental_words(&mut rng));
+        
+        // a word inside another placed word stays whatever the fill does, fill keeps the puzzle after
+        // starting the fill over and the summary warns about it
+        let mut puzzle = PuzzleGrid::new(5, 2, 10000, Difficulty::Medium);
+        puzzle.write_word("CATS", &0, &0, &Direction::Right);
+        puzzle.write_word("CAT", &0, &1, &Direction::Right);
+        puzzle.entries = vec![String::from("CATS"), String::from("CAT")];
+        let config = Config::default();
+        fill(&mut puzzle, &config, &mut rng).unwrap();
+        assert!(puzzle.iter_cells().all(|c| c.2 != ' '));
+        assert_eq!(puzzle.stats().uncleared, ["CAT"]);
+        let mut summary = Summary::default();
+        summary.add(&puzzle, &config);
+        assert_eq!(summary.uncleared, [(1, vec![String::from("CAT")])]);
+    }
+    
+    #[test]
+    fn blocklist_scan() {
+        let blocklist = vec![String::from("ab"), String::from("BA")];
+        let mut puzzle = PuzzleGrid::new(10, 10, 10000, Difficulty::Hard);
+        puzzle.write_word("CAT", &0, &0, &Direction::Right);
+        puzzle.entries.push(String::from("CAT"));
+        puzzle.set_fill_chars("AB").unwrap();
+        let mut rng = test_rng();
+        puzzle.fill_in(&mut rng);
+        assert!(!puzzle.blocked_words(&blocklist).is_empty());
+        
+        // A and B can't sit next to each other anywhere, adding Z gives the re-rolls a way out
+        puzzle.set_fill_chars("ABZ").unwrap();
+        assert!(puzzle.clear_unwanted_words(&blocklist, &mut rng));
+        assert!(puzzle.blocked_words(&blocklist).is_empty());
+        assert_eq!(puzzle.find_all("CAT").len(), 1);
+        
+        let config = Config { width: 10, height: 10, blocklist: Some(DEFAULT_BLOCKLIST.iter().map(|w| w.to_string()).collect()), ..Config::default() };
+        let words = vec![String::from("Giraffe")];
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        fill(&mut puzzle, &config, &mut test_rng()).unwrap();
+        assert!(puzzle.blocked_words(config.blocklist.as_ref().unwrap()).is_empty());
+        assert!(puzzle.stats().uncleared.is_empty());
+        
+        // a blocklisted word in the letters of a placed one can't be cleared, it's reported instead
+        let config = Config { blocklist: Some(vec![String::from("raf")]), ..config };
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        fill(&mut puzzle, &config, &mut test_rng()).unwrap();
+        assert_eq!(puzzle.stats().uncleared, ["RAF"]);
+    }
+    
+    #[test]
+    fn near_miss_decoys() {
+        let mut puzzle = PuzzleGrid::new(12, 12, 10000, Difficulty::Medium);
+        let mut rng = test_rng();
+        puzzle.place("GIRAFFE", &mut rng).unwrap();
+        puzzle.place("ZEBRA", &mut rng).unwrap();
+        let before = puzzle.iter_cells().filter(|c| c.2 != ' ').count();
+        assert_eq!(puzzle.place_decoys(6, &mut rng), 6);
+        assert_eq!(puzzle.stats().decoys, 6);
+        assert!(puzzle.iter_cells().filter(|c| c.2 != ' ').count() > before);
+        
+        // the decoys never make a second copy of a word and stay out of the answer key
+        puzzle.fill_in(&mut rng);
+        assert_eq!(puzzle.placements().len(), 2);
+        assert_eq!(puzzle.find_all("GIRAFFE").len(), 1);
+        assert_eq!(puzzle.find_all("ZEBRA").len(), 1);
+        
+        // words too short to make a decoy from are skipped
+        let mut puzzle = PuzzleGrid::new(5, 5, 10000, Difficulty::Medium);
+        puzzle.place("OX", &mut rng).unwrap();
+        assert_eq!(puzzle.place_decoys(3, &mut rng), 0);
+    }
+    
+    #[test]
+    fn camouflage_fill() {
+        let mut puzzle = PuzzleGrid::new(8, 8, 10000, Difficulty::Medium);
+        puzzle.write_word("ZEBRA", &2, &3, &Direction::Right);
+        puzzle.write_word("OX", &0, &0, &Direction::Down);
+        puzzle.fill_in_camouflage(&mut test_rng());
+        assert!(puzzle.iter_cells().all(|c| "ZEBRAOX".contains(c.2)));
+        assert_eq!(puzzle.find_all("ZEBRA").len(), 1);
+        
+        // a prefix of ZEBRA starts next to the real Z
+        let around: Vec<(usize, usize)> = Direction::ALL.iter().map(|d| d.incrementors()).map(|(dx, dy)| ((2 + dx) as usize, (3 + dy) as usize)).collect();
+        let decoy = Direction::ALL.iter().any(|d| around.iter().any(|(x, y)| puzzle.placement_valid("ZE", x, y, d) && (*x, *y) != (2, 3)));
+        assert!(decoy);
+    }
+    
+    #[test]
+    fn decoys_finish_no_copies() {
+        // a decoy or a camouflage prefix that spells out a word with the letters around it would be
+        // an extra answer the fill can't re-roll, any copy left is made of the placed words alone
+        let words: Vec<String> = ["horse", "bee", "cat", "dog", "otter", "eagle", "mouse", "tiger"].iter().map(|w| w.to_string()).collect();
+        for seed in 0..40 {
+            let config = Config { width: 9, height: 9, decoys: 10, camouflage: seed % 2 == 0, ..Config::default() };
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut puzzle = generate(&config, &words, &mut rng).unwrap();
+            fill(&mut puzzle, &config, &mut rng).unwrap();
+            let mask = puzzle.answer_mask();
+            assert!(puzzle.accidental_words().iter().all(|p| p.cells().iter().all(|(x, y)| mask[*y][*x])), "seed {}\n{:?}", seed, puzzle);
+        }
+    }
+    
+    #[test]
+    fn unicode_words() {
+        assert_eq!(letters("niño"), vec!["n", "i", "ñ", "o"]);
+        assert_eq!(letters("cafe\u{301}"), vec!["c", "a", "f", "e\u{301}"]);
+        
+        // accented words fit by letter count, not byte length, and are uppercased properly
+        let config = Config { width: 4, height: 4, ..Config::default() };
+        let words = vec![String::from("café"), String::from("niño"), String::from("élan")];
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        puzzle.fill_in(&mut test_rng());
+        assert_eq!(puzzle.entries(), ["CAFÉ", "NIÑO", "ÉLAN"]);
+        for word in puzzle.entries() {
+            assert_eq!(puzzle.find_all(word).len(), 1);
+        }
+        
+        let mut puzzle = PuzzleGrid::new(4, 1, 10000, Difficulty::Medium);
+        puzzle.place("cafe\u{301}", &mut test_rng()).unwrap();
+        assert_eq!(puzzle.grid()[0][3], "E\u{301}");
+        assert_eq!(puzzle.placements()[0].cells().len(), 4);
+    }
+    
+    #[test]
+    fn folded_accents() {
+        assert_eq!(fold_diacritics("Élève"), "Eleve");
+        assert_eq!(fold_diacritics("nin\u{303}o straße"), "nino straße");
+        
+        let config = Config { width: 8, height: 8, fold_diacritics: true, ..Config::default() };
+        let words = vec![String::from("élève"), String::from("garçon"), String::from("livre")];
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        puzzle.fill_in(&mut test_rng());
+        assert_eq!(puzzle.entries(), ["ELEVE", "GARCON", "LIVRE"]);
+        assert_eq!(puzzle.word_bank(), ["ÉLÈVE", "GARÇON", "LIVRE"]);
+        assert_eq!(puzzle.find_all("GARCON").len(), 1);
+        assert!(puzzle.render_text(false).ends_with("\nÉLÈVE\nGARÇON\nLIVRE\n"));
+        assert!(puzzle.answer_list().starts_with("ÉLÈVE: "));
+    }
+    
+    #[test]
+    fn punctuated_entries() {
+        assert_eq!(sanitize("rock 'n' roll", ""), "rocknroll");
+        assert_eq!(sanitize("T-shirt", ""), "Tshirt");
+        assert_eq!(sanitize("x-2.5", "-"), "x-2.5");
+        
+        let config = Config { width: 8, height: 8, ..Config::default() };
+        let words = vec![String::from("New York"), String::from("T-shirt"), String::from("hat")];
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        puzzle.fill_in(&mut test_rng());
+        assert_eq!(puzzle.entries(), ["NEWYORK", "TSHIRT", "HAT"]);
+        assert_eq!(puzzle.word_bank(), ["NEW YORK", "T-SHIRT", "HAT"]);
+        assert_eq!(puzzle.find_all("NEWYORK").len(), 1);
+    }
+    
+    #[test]
+    fn word_bank_order() {
+        let words: Vec<String> = ["zebra", "Élan", "ox", "cat"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 6, height: 6, fold_diacritics: true, word_order: WordOrder::Alphabetical, ..Config::default() };
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        assert_eq!(puzzle.entries(), ["ZEBRA", "ELAN", "OX", "CAT"]);
+        assert_eq!(puzzle.word_bank(), ["CAT", "ÉLAN", "OX", "ZEBRA"]);
+        assert!(puzzle.answer_list().starts_with("CAT: "));
+        
+        puzzle.set_word_order(WordOrder::Length, &mut test_rng());
+        assert_eq!(puzzle.word_bank(), ["OX", "CAT", "ÉLAN", "ZEBRA"]);
+        puzzle.set_word_order(WordOrder::Shuffled, &mut test_rng());
+        let mut shuffled = puzzle.word_bank();
+        shuffled.sort();
+        assert_eq!(shuffled, ["CAT", "OX", "ZEBRA", "ÉLAN"]);
+        puzzle.set_word_order(WordOrder::Original, &mut test_rng());
+        assert_eq!(puzzle.word_bank(), ["ZEBRA", "ÉLAN", "OX", "CAT"]);
+    }
+    
+    #[test]
+    fn hidden_word_bank() {
+        let words: Vec<String> = ["cat", "dog"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 6, height: 6, bank_style: BankStyle::Hidden, ..Config::default() };
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        puzzle.fill_in(&mut test_rng());
+        assert!(puzzle.puzzle_bank().is_empty());
+        assert!(puzzle.render_text(false).ends_with("\n\n"));
+        assert!(puzzle.render_text_answer(false, AnswerStyle::Blank).ends_with("\nCAT\nDOG\n"));
+        
+        let mut csv = Vec::new();
+        puzzle.write_csv(&mut csv, &config, false).unwrap();
+        assert!(!String::from_utf8(csv).unwrap().contains("CAT"));
+        
+        puzzle.set_bank_style(BankStyle::Count);
+        assert_eq!(puzzle.puzzle_bank(), ["2 words to find"]);
+        assert_eq!(puzzle.word_bank(), ["CAT", "DOG"]);
+    }
+    
+    #[test]
+    fn scrambled_word_bank() {
+        let words: Vec<String> = ["cat", "New York", "aaa"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 8, height: 8, bank_style: BankStyle::Scrambled, ..Config::default() };
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        puzzle.fill_in(&mut test_rng());
+        let bank = puzzle.puzzle_bank();
+        assert_eq!(puzzle.word_bank(), ["CAT", "NEW YORK", "AAA"]);
+        for (scrambled, word) in bank.iter().zip(puzzle.word_bank()) {
+            let mut a: Vec<char> = scrambled.chars().collect();
+            let mut b: Vec<char> = word.chars().collect();
+            a.sort_unstable();
+            b.sort_unstable();
+            assert_eq!(a, b);
+        }
+        assert_ne!(bank[0], "CAT");
+        assert_eq!(bank[1].find(' '), Some(3));
+        assert_eq!(bank[2], "AAA");
+        assert!(puzzle.render_text_answer(false, AnswerStyle::Blank).ends_with("\nCAT\nNEW YORK\nAAA\n"));
+    }
+    
+    #[test]
+    fn interlocked() {
+        let words: Vec<String> = ["cat", "toad", "owl", "emu", "yak", "lamb", "mole"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 8, height: 8, min_crossings: 1, ..Config::default() };
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        assert!(puzzle.crossing_counts().iter().all(|n| *n >= 1));
+        
+        let words: Vec<String> = ["cat", "dog"].iter().map(|w| w.to_string()).collect();
+        match generate(&config, &words, &mut test_rng()) {
+            Err(WordfindError::TooFewCrossings { crossings, .. }) => assert_eq!(crossings, 1),
+            _ => panic!("expected cat and dog not to cross"),
+        }
+    }
+    
+    #[test]
+    fn difficulty_ratings() {
+        let words: Vec<String> = ["cat", "dog", "owl", "emu", "yak"].iter().map(|w| w.to_string()).collect();
+        let mut config = Config { width: 8, height: 8, ..Config::default() };
+        Difficulty::Easy.apply(&mut config);
+        let easy = make_puzzle(&config, &words, &mut test_rng()).unwrap().difficulty_rating();
+        assert_eq!((easy.backwards, easy.diagonal), (0, 0));
+        Difficulty::Expert.apply(&mut config);
+        let expert = make_puzzle(&config, &words, &mut test_rng()).unwrap().difficulty_rating();
+        assert!(expert.rating > easy.rating);
+        assert!(expert.level >= easy.level);
+        assert!((1..=5).contains(&easy.level) && (1..=5).contains(&expert.level));
+        assert_eq!(PuzzleGrid::new(4, 4, 10, Difficulty::Medium).difficulty_rating().level, 1);
+    }
+    
+    #[test]
+    fn leveled_puzzles() {
+        let short: Vec<String> = ["cat", "dog", "owl", "emu", "yak", "elk", "ant", "bee"].iter().map(|w| w.to_string()).collect();
+        let long: Vec<String> = ["elephant", "giraffe", "kangaroo", "crocodile", "flamingo", "hedgehog", "squirrel", "tortoise", "penguin", "dolphin", "leopard", "antelope"].iter().map(|w| w.to_string()).collect();
+        for (words, size) in [(&short, 8), (&long, 15)] {
+            for level in 2..=4 {
+                let config = Config { width: size, height: size, level: Some(level), ..Config::default() };
+                assert_eq!(make_puzzle(&config, words, &mut test_rng()).unwrap().difficulty_rating().level, level);
+            }
+        }
+        
+        let mut config = Config::default();
+        level_step(&mut config, 0);
+        assert_eq!((config.directions.as_ref().map(Vec::len), config.dense), (Some(2), false));
+        level_step(&mut config, LEVEL_STEPS - 1);
+        assert!(config.dense && config.camouflage && config.decoys == EXPERT_DECOYS);
+        assert_eq!(config.directions, Some(vec![Direction::UpLeft, Direction::DownLeft]));
+    }
+    
+    #[test]
+    fn shaped_grid() {
+        let words: Vec<String> = ["cat", "dog", "owl", "emu"].iter().map(|w| w.to_string()).collect();
+        let mask: Vec<Vec<bool>> = (0..6).map(|y| (0..6).map(|x| x + y >= 5).collect()).collect();
+        let config = Config { mask: Some(mask.clone()), hidden_message: Some(String::from("hi")), ..Config::default() };
+        let puzzle = make_puzzle(&config, &words, &mut test_rng()).unwrap();
+        assert_eq!((puzzle.width(), puzzle.height()), (6, 6));
+        for (x, y, c) in puzzle.iter_cells() {
+            assert_eq!(c != ' ', mask[y][x]);
+        }
+        assert!(puzzle.answer_grid(AnswerStyle::Dots)[0][0] == " ");
+        
+        let mut puzzle = PuzzleGrid::new(4, 4, 10, Difficulty::Medium);
+        assert!(matches!(puzzle.set_mask(&mask), Err(WordfindError::InvalidMask { .. })));
+        assert!(puzzle.set_mask(&vec![vec![false; 4]; 4]).is_err());
+    }
+    
+    #[test]
+    fn reserved_areas() {
+        let words: Vec<String> = ["cat", "dog", "owl", "emu", "yak"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 7, height: 7, reserved: vec![(0, 0, 4, 3), (6, 6, 5, 5)], ..Config::default() };
+        let puzzle = make_puzzle(&config, &words, &mut test_rng()).unwrap();
+        for (x, y) in puzzle.placements().iter().flat_map(Placement::cells) {
+            assert!(!puzzle.reserved(x, y));
+            assert!(!(x < 4 && y < 3) && (x, y) != (6, 6));
+        }
+        // the reserved cells still get filler
+        assert!(puzzle.iter_cells().all(|(_, _, c)| c != ' '));
+        assert!(puzzle.reserved(3, 2) && !puzzle.reserved(4, 2));
+        
+        // the reserved corner and the filler turn and flip with the grid
+        let mut puzzle = make_puzzle(&Config { width: 7, height: 5, reserved: vec![(0, 0, 4, 3)], ..config }, &words, &mut test_rng()).unwrap();
+        let filler: Vec<String> = puzzle.filled.iter().map(|(x, y)| puzzle.grid[*y][*x].clone()).collect();
+        puzzle.rotate90();
+        assert!(puzzle.reserved(4, 0) && puzzle.reserved(2, 3) && !puzzle.reserved(1, 0) && !puzzle.reserved(4, 4));
+        puzzle.mirror_horizontal();
+        assert!(puzzle.reserved(0, 0) && puzzle.reserved(2, 3) && !puzzle.reserved(3, 0));
+        assert_eq!(puzzle.filled.iter().map(|(x, y)| puzzle.grid[*y][*x].clone()).collect::<Vec<String>>(), filler);
+        assert!(puzzle.placements().iter().flat_map(Placement::cells).all(|(x, y)| !puzzle.reserved(x, y)));
+        
+        let mut puzzle = PuzzleGrid::new(4, 4, 10, Difficulty::Medium);
+        puzzle.reserve(0, 0, 4, 1).unwrap();
+        assert_eq!(puzzle.rejection("CAT", &0, &0, &Direction::Right), Some(Rejection::Reserved));
+        assert!(matches!(puzzle.reserve(4, 0, 1, 1), Err(WordfindError::InvalidMask { .. })));
+    }
+    
+    #[test]
+    fn bent_words() {
+        let words: Vec<String> = ["zebra", "camel", "otter", "ox"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 8, height: 8, bent: true, verify: true, ..Config::default() };
+        let puzzle = make_puzzle(&config, &words, &mut test_rng()).unwrap();
+        let bent: Vec<&Placement> = puzzle.placements().iter().filter(|p| p.bend.is_some()).collect();
+        assert_eq!(bent.len(), 3);
+        for p in bent {
+            let (at, turn) = p.bend.unwrap();
+            let cells = p.cells();
+            assert_eq!(cells.iter().map(|(x, y)| puzzle.grid[*y][*x].clone()).collect::<String>(), p.word);
+            assert!(at > 0 && at < cells.len() - 1 && turn != p.dir);
+            assert!(puzzle.answer_list().contains(&format!("{}: ({}, {}) {:?}, ({}, {}) {:?}\n", p.word, p.x + 1, p.y + 1, p.dir, cells[at].0 + 1, cells[at].1 + 1, turn)));
+        }
+        assert!(puzzle.placements().iter().any(|p| p.word == "OX" && p.bend.is_none()));
+    }
+    
+    #[test]
+    fn spangrams() {
+        let words: Vec<String> = ["cat", "elephant", "owl", "emu"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 7, height: 7, spangram: Some(String::from("Elephant")), ..Config::default() };
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        let spangram = puzzle.spangram().unwrap();
+        let cells = spangram.cells();
+        let ((x1, y1), (x2, y2)) = (cells[0], cells[cells.len() - 1]);
+        assert_eq!(spangram.word, "ELEPHANT");
+        assert!((x1.min(x2), x1.max(x2)) == (0, 6) || (y1.min(y2), y1.max(y2)) == (0, 6));
+        assert_eq!(puzzle.render_json().matches("\"spangram\":true").count(), 1);
+        assert!(puzzle.answer_list().lines().any(|line| line.starts_with("ELEPHANT") && line.ends_with(" spangram")));
+        assert_eq!(puzzle.entries, ["CAT", "ELEPHANT", "OWL", "EMU"]);
+        
+        let config = Config { spangram: Some(String::from("zebra")), ..config };
+        assert!(matches!(generate(&config, &words[..1], &mut test_rng()), Err(WordfindError::InvalidWord { .. })));
+    }
+    
+    #[test]
+    fn crisscross() {
+        let words: Vec<String> = ["cat", "tiger", "rabbit", "horse", "otter", "goat"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 15, height: 15, crisscross: true, ..Config::default() };
+        let puzzle = make_puzzle(&config, &words, &mut test_rng()).unwrap();
+        assert_eq!(puzzle.entries().len(), 6);
+        assert!(puzzle.placements().iter().all(|p| p.dir == Direction::Right || p.dir == Direction::Down));
+        assert!(puzzle.crossing_counts().iter().all(|crossings| *crossings > 0));
+        
+        // the grid is cropped to the words, and every run of two or more letters is one of them
+        let grid = puzzle.shown_grid(true);
+        assert!(grid[0].iter().any(|c| c != BLOCK) && grid.iter().any(|row| row[0] != BLOCK));
+        let columns: Vec<String> = (0..puzzle.width()).map(|x| grid.iter().map(|row| row[x].clone()).collect()).collect();
+        for line in grid.iter().map(|row| row.concat()).chain(columns) {
+            for run in line.split(BLOCK).filter(|run| run.len() > 1) {
+                assert!(puzzle.entries().iter().any(|w| w == run), "{} isn't a word", run);
+            }
+        }
+        
+        // the puzzle shows empty boxes and blocks, and the word bank is grouped by length
+        assert!(puzzle.shown_grid(false).iter().flatten().all(|c| c == " " || c == BLOCK));
+        assert!(puzzle.render_text(false).contains('#'));
+        let bank = puzzle.grouped_bank(false);
+        assert_eq!(bank[0], (Some(String::from("3 Letters")), vec![String::from("CAT")]));
+        assert_eq!(bank.last().unwrap().0.as_deref(), Some("6 Letters"));
+    }
+    
+    #[test]
+    fn revealed_hints() {
+        let words: Vec<String> = ["cat", "giraffe", "owl"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 8, height: 8, reveal: 0.5, ..Config::default() };
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        
+        // half of each word rounded up, and only cells of the words
+        assert_eq!(puzzle.revealed().len(), 2 + 4 + 2);
+        let cells: Vec<(usize, usize)> = puzzle.placements().iter().flat_map(Placement::cells).collect();
+        assert!(puzzle.revealed().iter().all(|cell| cells.contains(cell)));
+        let (x, y) = puzzle.revealed()[0];
+        let text = puzzle.render_text(false);
+        assert!(text.contains(&format!("\nHints: {} ({}, {}), ", puzzle.grid()[y][x], x + 1, y + 1)));
+        assert!(!puzzle.render_text_answer(false, AnswerStyle::Blank).contains("Hints"));
+        assert_eq!(puzzle.render_svg(false).matches("<circle ").count(), 8);
+        
+        puzzle.reveal(0.0, &mut test_rng());
+        assert!(puzzle.revealed().is_empty());
+        assert!(!puzzle.render_text(false).contains("Hints"));
+    }
+    
+    #[test]
+    fn kept_apart() {
+        let words: Vec<String> = ["cat", "cow", "owl", "emu", "yak", "toad"].iter().map(|w| w.to_string()).collect();
+        for spacing in 0..2 {
+            let config = Config { width: 10, height: 10, dense: true, spacing: Some(spacing), ..Config::default() };
+            let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+            let cells: Vec<Vec<(usize, usize)>> = puzzle.placements().iter().map(Placement::cells).collect();
+            for (i, word) in cells.iter().enumerate() {
+                for other in cells[i + 1..].iter() {
+                    let apart = |(x, y): &(usize, usize), (ox, oy): &(usize, usize)| x.abs_diff(*ox).max(y.abs_diff(*oy)) > spacing;
+                    assert!(word.iter().all(|a| other.iter().all(|b| apart(a, b))));
+                }
+            }
+        }
+        
+        let mut puzzle = PuzzleGrid::new(3, 3, 10000, Difficulty::Medium);
+        puzzle.set_spacing(Some(1));
+        puzzle.write_word("CAT", &0, &0, &Direction::Right);
+        assert!(!puzzle.placement_valid("OX", &0, &1, &Direction::Right));
+        assert!(puzzle.placement_valid("OX", &0, &2, &Direction::Right));
+        
+        // a spacing far bigger than the grid only looks as far as its edges
+        puzzle.set_spacing(Some(usize::MAX));
+        assert_eq!(puzzle.rejection("OX", &0, &2, &Direction::Right), Some(Rejection::TooClose));
+        let mut puzzle = PuzzleGrid::new(3, 3, 10000, Difficulty::Medium);
+        puzzle.set_spacing(Some(usize::MAX));
+        assert!(puzzle.placement_valid("CAT", &0, &0, &Direction::Right));
+    }
+    
+    #[test]
+    fn pinned_words() {
+        assert_eq!(split_pin("HALLOWEEN @ (0,0) Down"), ("HALLOWEEN", Some("(0,0) Down")));
+        assert_eq!(Pin::parse("(0, 2) down-right"), Ok(Pin { start: Some((0, 2)), direction: Some(Direction::DownRight), edge: false }));
+        assert_eq!(Pin::parse("left"), Ok(Pin { start: None, direction: Some(Direction::Left), edge: false }));
+        assert_eq!(Pin::parse("(0,3) Edge up"), Ok(Pin { start: Some((0, 3)), direction: Some(Direction::Up), edge: true }));
+        assert!(Pin::parse("(1,x)").is_err());
+        assert!(Pin::parse("").is_err());
+        
+        let words: Vec<String> = ["cat", "Halloween @ (0,0) Down | spooky night", "owl @ left", "bat"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 10, height: 10, ..Config::default() };
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        assert_eq!(puzzle.entries(), ["CAT", "HALLOWEEN", "OWL", "BAT"]);
+        let halloween = puzzle.placements().iter().find(|p| p.word == "HALLOWEEN").unwrap();
+        assert_eq!((halloween.x, halloween.y, halloween.dir), (0, 0, Direction::Down));
+        assert_eq!(puzzle.placements().iter().find(|p| p.word == "OWL").unwrap().dir, Direction::Left);
+        assert_eq!(puzzle.puzzle_bank()[1], "spooky night");
+        
+        let words: Vec<String> = ["ghost @ (8,0) right", "owl @ sideways", "bat @ (2,2)"].iter().map(|w| w.to_string()).collect();
+        let problems = validate_words(&config, &words);
+        assert_eq!(problems.iter().map(|p| p.reason.as_str()).collect::<Vec<&str>>(), ["runs off the edge of a 10 x 10 puzzle going Right", "has an unknown direction, sideways"]);
+    }
+    
+    #[test]
+    fn edge_to_edge_words() {
+        // a word as long as the grid fits in every row and column, and the diagonals corner to corner
+        let puzzle = PuzzleGrid::new(5, 5, 10000, Difficulty::Hard);
+        let valid = puzzle.iter_cells().flat_map(|(x, y, _)| Direction::ALL.iter().map(move |dir| (x, y, *dir))).filter(|(x, y, dir)| puzzle.placement_valid("HELLO", x, y, dir)).count();
+        assert_eq!((valid, puzzle.candidates("HELLO").len()), (24, 24));
+        let puzzle = PuzzleGrid::new(7, 4, 10000, Difficulty::Hard);
+        assert_eq!(puzzle.candidates("GIRAFFE").len(), 8);
+        assert!(puzzle.candidates("GIRAFFE").iter().all(|(_, _, dir)| dir.straight()));
+        
+        // and is only too long when the directions can't reach across
+        let words: Vec<String> = ["strawberry", "fig"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 10, height: 5, difficulty: Difficulty::Hard, ..Config::default() };
+        assert!(validate_words(&config, &words).is_empty());
+        assert_eq!(generate(&config, &words, &mut test_rng()).unwrap().entries().len(), 2);
+        let config = Config { directions: Some(vec![Direction::Down, Direction::Up]), ..config };
+        assert_eq!(validate_words(&config, &words)[0].reason, "is too long for a 10 x 5 puzzle, 5 letters fit in the directions it allows");
+        assert!(matches!(generate(&config, &words, &mut test_rng()), Err(WordfindError::WordTooLong { .. })));
+    }
+    
+    #[test]
+    fn edge_words() {
+        let words: Vec<String> = ["cat", "ocean @ edge", "dog", "strawberry @ edge"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 10, height: 10, edge_words: vec![String::from("Dog")], ..Config::default() };
+        for seed in 0..5 {
+            let puzzle = generate(&config, &words, &mut StdRng::seed_from_u64(seed)).unwrap();
+            assert_eq!(puzzle.entries(), ["CAT", "OCEAN", "DOG", "STRAWBERRY"]);
+            for word in ["OCEAN", "DOG", "STRAWBERRY"].iter() {
+                let placement = puzzle.placements().iter().find(|p| p.word == *word).unwrap();
+                assert!(puzzle.along_edge(placement.x, placement.y, placement.dir));
+            }
+        }
+        
+        let config = Config { edge_words: vec![String::from("zebra")], ..config };
+        assert!(matches!(generate(&config, &words, &mut test_rng()), Err(WordfindError::InvalidWord { .. })));
+        let words: Vec<String> = ["ocean @ edge down-right", "otter @ (4,4) edge", "owl @ (0,4) edge"].iter().map(|w| w.to_string()).collect();
+        let problems = validate_words(&config, &words);
+        assert_eq!(problems.iter().map(|p| p.reason.as_str()).collect::<Vec<&str>>(), ["can't lie along an edge going DownRight", "starts away from the edges of a 10 x 10 puzzle"]);
+    }
+    
+    #[test]
+    fn clue_word_bank() {
+        assert_eq!(split_clue("giraffe | the tallest animal"), ("giraffe", Some("the tallest animal")));
+        assert_eq!(split_clue("giraffe |"), ("giraffe", None));
+        assert_eq!(split_clue(" giraffe "), ("giraffe", None));
+        
+        let words: Vec<String> = ["giraffe | the tallest animal", "owl", "cat|says meow"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 8, height: 8, ..Config::default() };
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        puzzle.fill_in(&mut test_rng());
+        assert_eq!(puzzle.entries(), &["GIRAFFE", "OWL", "CAT"]);
+        assert_eq!(puzzle.puzzle_bank(), ["the tallest animal", "OWL", "says meow"]);
+        assert_eq!(puzzle.answer_bank(), ["the tallest animal: GIRAFFE", "OWL", "says meow: CAT"]);
+        assert!(puzzle.render_text_answer(false, AnswerStyle::Blank).ends_with("\nthe tallest animal: GIRAFFE\nOWL\nsays meow: CAT\n"));
+    }
+    
+    #[test]
+    fn word_bank_sections() {
+        let words: Vec<String> = ["owl", "[Pets]", "cat", "# the farm ones", "dog", "[ Farm ]", "cow", "[Pets]", "rat"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 6, height: 6, ..Config::default() };
+        assert!(validate_words(&config, &words).is_empty());
+        let filtered = filter_words(&Config { max_words: Some(2), ..config.clone() }, &words, &mut test_rng());
+        assert_eq!(filtered, ["owl", "[Pets]", "cat", "[ Farm ]", "[Pets]"]);
+        
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        puzzle.fill_in(&mut test_rng());
+        assert_eq!(puzzle.entries(), &["OWL", "CAT", "DOG", "COW", "RAT"]);
+        assert_eq!(puzzle.section("DOG"), Some("Pets"));
+        assert_eq!(puzzle.section("OWL"), None);
+        let bank = puzzle.grouped_bank(false);
+        assert_eq!(bank[0], (None, vec![String::from("OWL")]));
+        assert_eq!(bank[1], (Some(String::from("Pets")), vec![String::from("CAT"), String::from("DOG"), String::from("RAT")]));
+        assert_eq!(bank[2], (Some(String::from("Farm")), vec![String::from("COW")]));
+        assert!(puzzle.render_text_answer(false, AnswerStyle::Blank).ends_with("\nOWL\n\nPets:\nCAT\nDOG\nRAT\n\nFarm:\nCOW\n"));
+        assert!(puzzle.render_markdown(false).ends_with("- OWL\n\n### Pets\n\n- CAT\n- DOG\n- RAT\n\n### Farm\n\n- COW\n"));
+        assert!(puzzle.render_html(false).contains("<h3>Farm</h3>\n<ul class=\"words\">\n<li>COW</li>\n</ul>"));
+        assert!(puzzle.render_json().contains("\"sections\":[{\"heading\":\"Pets\",\"words\":[\"CAT\",\"DOG\",\"RAT\"]},{\"heading\":\"Farm\",\"words\":[\"COW\"]}]"));
+        
+        let only_headings: Vec<String> = vec![String::from("[Pets]"), String::from("# Pets")];
+        assert!(matches!(generate(&config, &only_headings, &mut test_rng()), Err(WordfindError::NoWords)));
+    }
+    
+    #[test]
+    fn duplicate_words() {
+        let words: Vec<String> = ["Cat", "dog", "CAT", "Do-g", "owl"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 6, height: 6, ..Config::default() };
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        assert_eq!(puzzle.entries(), ["CAT", "DOG", "OWL"]);
+        assert_eq!(puzzle.duplicates(), ["CAT", "Do-g"]);
+        
+        let config = Config { strict_duplicates: true, ..config };
+        assert!(matches!(generate(&config, &words, &mut test_rng()), Err(WordfindError::InvalidWord { word, .. }) if word == "CAT"));
+    }
+    
+    #[test]
+    fn numeric_entries() {
+        let config = Config { width: 8, height: 8, keep_chars: Some(String::from("-")), ..Config::default() };
+        let words = vec![String::from("3.14159"), String::from("-42"), String::from("1729")];
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        puzzle.fill_in(&mut test_rng());
+        assert_eq!(puzzle.entries(), ["3.14159", "-42", "1729"]);
+        assert_eq!(puzzle.find_all("-42").len(), 1);
+        let mask = puzzle.answer_mask();
+        assert!(puzzle.iter_cells().filter(|(x, y, _)| !mask[*y][*x]).all(|c| c.2.is_ascii_digit()));
+    }
+    
+    #[test]
+    fn letter_case() {
+        let words = vec![String::from("Cat"), String::from("dog")];
+        let config = Config { width: 6, height: 6, case: Case::Lower, ..Config::default() };
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        puzzle.fill_in(&mut test_rng());
+        assert_eq!(puzzle.entries(), ["cat", "dog"]);
+        assert!(puzzle.iter_cells().all(|c| c.2.is_ascii_lowercase()));
+        assert_eq!(puzzle.find_all("CAT").len(), 1);
+        
+        let config = Config { width: 6, height: 6, case: Case::Preserve, ..Config::default() };
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        puzzle.fill_in(&mut test_rng());
+        assert_eq!(puzzle.word_bank(), ["Cat", "dog"]);
+        assert!(puzzle.iter_cells().any(|c| c.2.is_ascii_lowercase()));
+        assert!(puzzle.iter_cells().any(|c| c.2.is_ascii_uppercase()));
+        let cells = puzzle.placements()[0].cells();
+        assert_eq!(cells.iter().map(|(x, y)| puzzle.grid()[*y][*x].as_str()).collect::<String>(), "Cat");
+    }
+    
+    #[test]
+    fn script_filler() {
+        let config = Config { width: 6, height: 6, ..Config::default() };
+        let words = vec![String::from("γάτα"), String::from("σκύλος")];
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        puzzle.fill_in(&mut test_rng());
+        assert_eq!(puzzle.entries(), ["ΓΆΤΑ", "ΣΚΎΛΟΣ"]);
+        let mask = puzzle.answer_mask();
+        assert!(puzzle.iter_cells().filter(|(x, y, _)| !mask[*y][*x]).all(|c| Alphabet::Greek.letters().contains(c.2)));
+        
+        let config = Config { width: 6, height: 6, alphabet: Some(Alphabet::Cyrillic), ..Config::default() };
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        puzzle.fill_in(&mut test_rng());
+        assert!(puzzle.iter_cells().filter(|(x, y, _)| !mask[*y][*x]).any(|c| Alphabet::Cyrillic.letters().contains(c.2)));
+    }
+    
+    #[test]
+    fn right_to_left() {
+        let mut puzzle = PuzzleGrid::new(6, 6, 10000, Difficulty::Medium);
+        puzzle.set_rtl(true);
+        assert!(puzzle.rtl());
+        assert!(puzzle.dir_choices.contains(&Direction::Left));
+        assert!(!puzzle.dir_choices.contains(&Direction::Right));
+        puzzle.set_rtl(true);
+        assert!(puzzle.dir_choices.contains(&Direction::Left));
+        
+        let config = Config { width: 5, height: 5, rtl: true, ..Config::default() };
+        let words = vec![String::from("שלום"), String::from("\u{fedf}\u{fe8e}")];
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        assert_eq!(puzzle.entries()[1], "لا");
+        assert!(puzzle.placements().iter().all(|p| p.dir != Direction::Right));
+        let text = puzzle.render_text(false);
+        assert!(text.lines().next().unwrap().contains("\u{200e} "));
+        assert!(text.ends_with("\u{200f}שלום\n\u{200f}لا\n"));
+    }
+    
+    #[test]
+    fn coordinate_answers() {
+        let mut puzzle = PuzzleGrid::new(5, 5, 10000, Difficulty::Hard);
+        puzzle.write_word("CAT", &2, &0, &Direction::DownRight);
+        puzzle.write_word("DOG", &4, &4, &Direction::Left);
+        puzzle.write_word("CAT", &0, &4, &Direction::Up);
+        puzzle.entries = vec![String::from("DOG"), String::from("CAT"), String::from("EMU")];
+        assert_eq!(puzzle.answer_list(), "DOG: (5, 5) Left\nCAT: (3, 1) DownRight\n");
+        assert_eq!(puzzle.first_letters(), "DOG starts at row 5, column 5\nCAT starts at row 1, column 3\n");
+    }
+    
+    #[test]
+    fn crossed_words() {
+        let mut puzzle = PuzzleGrid::new(5, 5, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &0, &1, &Direction::Right);
+        puzzle.write_word("MAP", &1, &0, &Direction::Down);
+        puzzle.write_word("DOG", &0, &4, &Direction::Right);
+        assert_eq!(puzzle.crossings(), vec![(String::from("CAT"), String::from("MAP"), 1, 1)]);
+    }
+    
+    #[test]
+    fn csv_margin() {
+        let mut puzzle = PuzzleGrid::new(4, 4, 10000, Difficulty::Medium);
+        let mut rng = test_rng();
+        puzzle.place("abc", &mut rng).unwrap();
+        puzzle.fill_in(&mut rng);
+        let config = Config { margin: 5, pad_rows: 2, ..Config::default() };
+        let mut out = Vec::new();
+        puzzle.write_csv(&mut out, &config, false).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "");
+        assert_eq!(lines[1], "");
+        for row in lines[2..6].iter() {
+            assert!(row.starts_with(",,,,,"));
+            assert!(!row[5..].starts_with(','));
+        }
+        assert_eq!(lines[6], "");
+        
+        let config = Config { margin: 1, delimiter: ';', words_per_row: 3, quote_all: true, ..Config::default() };
+        puzzle.entries = vec![String::from("ABC"), String::from("DE"), String::from("F;G")];
+        let mut out = Vec::new();
+        puzzle.write_csv(&mut out, &config, false).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.lines().next().unwrap().starts_with(";\""));
+        assert_eq!(text.lines().last().unwrap(), ";\"ABC\";\"DE\";\"F;G\"");
+        assert_eq!(csv_quote("F;G", ';', false), "\"F;G\"");
+        assert_eq!(csv_quote("say \"hi\"", ',', false), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_quote("ABC", ',', false), "ABC");
+        
+        // a numbered answer key lists where each word starts in place of the word bank
+        let mut puzzle = PuzzleGrid::new(4, 3, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &0, &1, &Direction::Right);
+        puzzle.write_word("DOG", &3, &0, &Direction::Down);
+        puzzle.entries = vec![String::from("CAT"), String::from("DOG")];
+        puzzle.set_print_style(PrintStyle { numbered_answers: true, ..PrintStyle::default() });
+        let mut out = Vec::new();
+        puzzle.write_csv(&mut out, &Config { margin: 1, ..Config::default() }, true).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.ends_with("\n,Number,Word,Column,Row\n,1,CAT,1,2\n,2,DOG,4,1\n"));
+    }
+    
+    #[test]
+    fn labeled_text() {
+        let puzzle = PuzzleGrid::new(3, 3, 10000, Difficulty::Medium);
+        let text = puzzle.render_text(true);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0].trim(), "A B C");
+        assert_eq!(lines[1], "1 . . .");
+        assert_eq!(lines[2], "2 . . .");
+        assert_eq!(lines[3], "3 . . .");
+        
+        let wide = PuzzleGrid::new(30, 30, 10000, Difficulty::Medium);
+        let text = wide.render_text(true);
+        assert!(text.lines().next().unwrap().trim().starts_with("1  2  3"));
+        assert!(text.lines().next().unwrap().ends_with("30"));
+    }
+    
+    #[test]
+    fn text_answer_key() {
+        let mut puzzle = PuzzleGrid::new(4, 2, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &1, &0, &Direction::Right);
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        assert_eq!(puzzle.render_text(false).lines().next().unwrap(), "A C A T");
+        let answer = puzzle.render_text_answer(false, AnswerStyle::Blank);
+        let lines: Vec<&str> = answer.lines().collect();
+        assert_eq!(lines[0], ". C A T");
+        assert_eq!(lines[1], ". . . .");
+        
+        // the same grid with the filler kept in lowercase
+        let answer = puzzle.render_text_answer(false, AnswerStyle::Lowercase);
+        assert_eq!(answer.lines().take(2).collect::<Vec<&str>>(), vec!["a C A T", "a a a a"]);
+        let grid = puzzle.answer_grid(AnswerStyle::Dots);
+        assert_eq!(grid[0], vec![".", "C", "A", "T"]);
+    }
+    
+    #[test]
+    fn bank_columns() {
+        assert_eq!(render::balanced_columns(&[1, 2, 3, 4], 3), vec![&[1, 2][..], &[3], &[4]]);
+        assert_eq!(render::balanced_columns(&[1, 2], 3), vec![&[1][..], &[2]]);
+        
+        let mut puzzle = PuzzleGrid::new(4, 2, 10000, Difficulty::Medium);
+        puzzle.entries.extend(["CAT", "DOG", "HORSE", "OWL"].iter().map(|w| w.to_string()));
+        puzzle.set_print_style(PrintStyle { bank_columns: Some(3), ..PrintStyle::default() });
+        assert!(puzzle.render_text(false).ends_with("\nCAT    HORSE  OWL\nDOG\n"));
+        puzzle.set_print_style(PrintStyle::default());
+        assert!(puzzle.render_text(false).ends_with("\nCAT\nDOG\nHORSE\nOWL\n"));
+    }
+    
+    #[test]
+    fn empty_word_list() {
+        let path = std::env::temp_dir().join("wordfindgen_empty_words.txt");
+        fs::write(&path, "\n   \n").unwrap();
+        let config = Config { wordsfile: path.to_string_lossy().to_string(), ..Config::default() };
+        let err = run(config).unwrap_err();
+        assert_eq!(err.to_string(), "no words to place");
+        fs::remove_file(&path).unwrap();
+    }
+    
+    #[test]
+    fn word_list_problems() {
+        let words: Vec<String> = ["Cat", "", "Hippopotamus", "--", "cat", "D\u{fffd}g", "Ox"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 10, height: 10, strict_duplicates: true, ..Config::default() };
+        let problems = validate_words(&config, &words);
+        assert_eq!(problems.iter().map(|p| p.line).collect::<Vec<usize>>(), vec![3, 5, 6]);
+        assert_eq!(problems[0].to_string(), "line 3: Hippopotamus is too long for a 10 x 10 puzzle");
+        assert_eq!(problems[1].reason, "is already on line 1");
+        
+        let err = run_with_words(config.clone(), &words).unwrap_err();
+        assert!(err.to_string().starts_with("the word list has 3 problems\n  line 3: Hippopotamus"));
+        assert!(err.to_json().contains("{\"line\":5,\"word\":\"cat\",\"reason\":\"is already on line 1\"}"));
+        assert_eq!(err.kind().exit_code(), 3);
+        let err = run_with_words(Config { width: 0, height: 0, ..config.clone() }, &words).unwrap_err();
+        assert!(matches!(err, WordfindError::InvalidSize { width: 0, height: 0 }));
+        assert_eq!(err.kind().exit_code(), 2);
+        assert_eq!(WordfindError::FileExists { path: PathBuf::from("puzzle.csv") }.kind().exit_code(), 5);
+        let err = WordfindError::PlacementFailed { word: String::from("EMU"), tries: 5 };
+        assert_eq!(err.to_json(), "{\"error\":\"EMU could not be placed in the puzzle\",\"kind\":\"placement\",\"word\":\"EMU\",\"reason\":\"could not be placed in 5 tries\"}");
+        
+        let config = Config { grow_to: Some(12), ..config };
+        assert_eq!(validate_words(&config, &words).len(), 2);
+        let config = Config { strict_duplicates: false, ..config };
+        assert_eq!(validate_words(&config, &words).len(), 1);
+    }
+    
+    #[test]
+    fn word_file_lines() {
+        let path = std::env::temp_dir().join("wordfindgen_word_file_lines.txt");
+        fs::write(&path, "\u{feff}# pets from week 3\r\nCat\r\n  \r\n[Birds]\r\n  owl  \r\n--\r\n").unwrap();
+        let words = read_words(&path.to_string_lossy()).unwrap();
+        assert_eq!(words, ["# pets from week 3", "Cat", "", "[Birds]", "owl", "--"]);
+        assert!(validate_words(&Config::default(), &words).is_empty());
+        assert_eq!(filter_words(&Config::default(), &words, &mut test_rng()), ["Cat", "[Birds]", "owl", "--"]);
+        
+        let config = Config { width: 6, height: 6, ..Config::default() };
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        assert_eq!(puzzle.entries(), ["CAT", "OWL"]);
+        assert_eq!(puzzle.no_letters(), ["--"]);
+        assert_eq!(puzzle.sections, [(String::from("OWL"), String::from("Birds"))]);
+        fs::remove_file(&path).unwrap();
+    }
+    
+    #[test]
+    fn word_filters() {
+        let words: Vec<String> = ["cat", "", "giraffe", "ox", "camel", "zebra", "cow"].iter().map(|w| w.to_string()).collect();
+        let config = Config { min_length: 3, ..Config::default() };
+        assert_eq!(filter_words(&config, &words, &mut test_rng()), ["cat", "giraffe", "camel", "zebra", "cow"]);
+        let config = Config { min_length: 3, max_length: Some(5), ..Config::default() };
+        assert_eq!(filter_words(&config, &words, &mut test_rng()), ["cat", "camel", "zebra", "cow"]);
+        
+        let config = Config { pattern: Some(String::from("C*")), max_words: Some(2), ..Config::default() };
+        assert_eq!(filter_words(&config, &words, &mut test_rng()), ["cat", "camel"]);
+        let config = Config { pattern: Some(String::from("[a-c]?[mw]*")), ..Config::default() };
+        assert_eq!(filter_words(&config, &words, &mut test_rng()), ["camel", "cow"]);
+        
+        let config = Config { max_words: Some(3), random_words: true, ..Config::default() };
+        let picked = filter_words(&config, &words, &mut test_rng());
+        assert_eq!(picked.len(), 3);
+        let positions: Vec<usize> = picked.iter().map(|p| words.iter().position(|w| w == p).unwrap()).collect();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]));
+        
+        // a word that's filtered out isn't checked
+        let config = Config { width: 5, height: 5, pattern: Some(String::from("c*")), ..Config::default() };
+        assert!(validate_words(&config, &words).is_empty());
+    }
+    
+    #[test]
+    fn verify_puzzle() {
+        let config = Config { width: 10, height: 10, difficulty: Difficulty::Hard, verify: true, ..Config::default() };
+        let words: Vec<String> = ["Hello", "Thanks", "Goodbye"].iter().map(|w| w.to_string()).collect();
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        assert!(puzzle.verify().is_ok());
+        assert_eq!(puzzle.find_all("goodbye").len(), 1);
+        
+        let (x, y) = puzzle.placements[0].cells()[0];
+        puzzle.grid[y][x] = String::from(" ");
+        assert!(puzzle.verify().is_err());
+        
+        // a word has to be where its placement says, not just somewhere in the grid
+        let mut puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        let (x, y) = puzzle.placements[0].cells()[0];
+        puzzle.placements[0].x = (x + 1) % 10;
+        puzzle.placements[0].y = (y + 1) % 10;
+        assert!(matches!(puzzle.verify(), Err(WordfindError::WordNotFound { .. })));
+    }
+    
+    #[test]
+    fn custom_size() {
+        let words: Vec<String> = ["Hello", "Thanks"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 30, height: 30, ..Config::default() };
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        assert_eq!(puzzle.iter_cells().count(), 900);
+        
+        let config = Config { width: 5, height: 5, ..Config::default() };
+        let err = generate(&config, &words, &mut test_rng()).err().unwrap();
+        assert_eq!(err.to_string(), "Thanks is too long to fit in a 5 x 5 puzzle");
+        
+        let config = Config { width: 0, height: 10, ..Config::default() };
+        assert!(generate(&config, &words, &mut test_rng()).is_err());
+    }
+    
+    #[test]
+    fn seeded_puzzles_repeat() {
+        let words: Vec<String> = ["Hello", "Thanks", "Goodbye", "How", "Are", "You"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 10, height: 10, difficulty: Difficulty::Hard, ..Config::default() };
+        let make = |seed: u64| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut puzzle = generate(&config, &words, &mut rng).unwrap();
+            puzzle.fill_in(&mut rng);
+            puzzle.grid
+        };
+        assert_eq!(make(7), make(7));
+        assert_ne!(make(7), make(8));
+    }
+    
+    #[test]
+    fn rectangular_grid() {
+        let words: Vec<String> = ["Hello", "Thanks", "Goodbye", "Rectangle"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 12, height: 4, verify: true, ..Config::default() };
+        let mut rng = test_rng();
+        let mut puzzle = generate(&config, &words, &mut rng).unwrap();
+        assert_eq!(puzzle.grid.len(), 4);
+        assert!(puzzle.grid.iter().all(|row| row.len() == 12));
+        assert!(placements_match(&puzzle));
+        
+        puzzle.fill_in(&mut rng);
+        assert_eq!(puzzle.render_text(false).lines().next().unwrap().len(), 23);
+        puzzle.rotate90();
+        assert_eq!(puzzle.grid.len(), 12);
+        assert!(placements_match(&puzzle));
+        
+        // a mask turns with the grid, cut off corners of a 7 x 4 grid end up on the other sides
+        let words: Vec<String> = ["Cat", "Dog"].iter().map(|w| w.to_string()).collect();
+        let mask: Vec<Vec<bool>> = (0..4).map(|y| (0..7).map(|x| (x, y) != (0, 0) && (x, y) != (6, 3)).collect()).collect();
+        let config = Config { width: 7, height: 4, mask: Some(mask), ..Config::default() };
+        let mut puzzle = generate(&config, &words, &mut rng).unwrap();
+        puzzle.fill_in(&mut rng);
+        puzzle.rotate90();
+        assert!(!puzzle.usable(3, 0) && !puzzle.usable(0, 6) && puzzle.usable(0, 0));
+        assert!(format!("{}", puzzle).lines().count() >= 7);
+        puzzle.mirror_horizontal();
+        assert!(!puzzle.usable(0, 0) && !puzzle.usable(3, 6) && puzzle.usable(3, 0));
+        assert!(placements_match(&puzzle));
+        assert!(format!("{}", puzzle).lines().count() >= 7);
+        
+        let config = Config { width: 12, height: 4, ..Config::default() };
+        let words = vec![String::from("Thirteenchars")];
+        assert!(generate(&config, &words, &mut rng).is_err());
+    }
+    
+    #[test]
+    fn giant_grid() {
+        let words: Vec<String> = ["Hello", "Thanks", "Goodbye"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 150, height: 150, difficulty: Difficulty::Hard, verify: true, ..Config::default() };
+        let mut rng = test_rng();
+        let mut puzzle = generate(&config, &words, &mut rng).unwrap();
+        puzzle.fill_in(&mut rng);
+        assert_eq!(puzzle.iter_cells().count(), 150 * 150);
+        let last = puzzle.iter_cells().last().unwrap();
+        assert_eq!((last.0, last.1), (149, 149));
+    }
+    
+    #[test]
+    fn words_reach_every_edge() {
+        // a word as long as the grid is wide only fits flush against both edges
+        let mut rng = test_rng();
+        for dir in Direction::ALL.iter() {
+            let mut puzzle = PuzzleGrid::new(5, 5, 10000, Difficulty::Hard);
+            puzzle.dir_choices = vec![*dir];
+            puzzle.place("abcde", &mut rng).unwrap();
+            assert!(placements_match(&puzzle));
+        }
+    }
+    
+    #[test]
+    fn auto_sized_grid() {
+        let words: Vec<String> = ["Supercalifragilistic", "Hi"].iter().map(|w| w.to_string()).collect();
+        assert_eq!(auto_size(&words, 0.5), 20);
+        let words: Vec<String> = ["Hello", "Thanks", "Goodbye", "How", "Are", "You"].iter().map(|w| w.to_string()).collect();
+        assert_eq!(auto_size(&words, 0.5), 8);
+        // a density of 0 or less can't make the grid endless
+        assert_eq!(auto_size(&words, 0.0), auto_size(&words, MIN_AUTO_DENSITY));
+        assert_eq!(auto_size(&words, -1.0), auto_size(&words, MIN_AUTO_DENSITY));
+        assert_eq!(auto_size(&words, 4.0), auto_size(&words, 1.0));
+        
+        let config = Config { width: 3, height: 3, auto_size: true, auto_density: 0.3, ..Config::default() };
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        assert_eq!(puzzle.grid.len(), 10);
+    }
+    
+    #[test]
+    fn last_spot_found() {
+        // the only room left is the bottom row, 1 of the 50 spots, the in order tries find it
+        for seed in 0..20 {
+            let mut puzzle = PuzzleGrid::new(5, 5, 68, Difficulty::Easy);
+            for (y, word) in ["ABCDE", "FGHIJ", "KLMNO", "PQRST"].iter().enumerate() {
+                puzzle.write_word(word, &0, &y, &Direction::Right);
+            }
+            assert_eq!(puzzle.random_spot("VWXYZ", &mut StdRng::seed_from_u64(seed)), Some((0, 4, Direction::Right)));
+        }
+        let puzzle = PuzzleGrid::new(5, 5, 68, Difficulty::Easy);
+        assert!(puzzle.random_spot("TOOLONG", &mut test_rng()).is_none());
+    }
+    
+    #[test]
+    fn candidates_match_placement_valid() {
+        let mut puzzle = PuzzleGrid::new(7, 6, 100, Difficulty::Hard);
+        let mask: Vec<Vec<bool>> = (0..6).map(|y| (0..7).map(|x| !(x == 3 && y == 2)).collect()).collect();
+        puzzle.set_mask(&mask).unwrap();
+        puzzle.write_word("CAT", &0, &0, &Direction::Right);
+        puzzle.write_word("TOE", &2, &0, &Direction::Down);
+        puzzle.write_word("ANT", &6, &5, &Direction::UpLeft);
+        let check = |puzzle: &PuzzleGrid| {
+            for word in ["ACE", "TEA", "ONCE", "AT", "TOOLONGWORD"].iter() {
+                let mut expected = Vec::new();
+                for y in 0..puzzle.height {
+                    for x in 0..puzzle.width {
+                        for dir in puzzle.dir_choices.iter() {
+                            if puzzle.placement_valid(word, &x, &y, dir) {
+                                expected.push((x, y, *dir));
+                            }
+                        }
+                    }
+                }
+                assert_eq!(puzzle.candidates(word), expected);
+            }
+        };
+        check(&puzzle);
+        puzzle.set_spacing(Some(1));
+        check(&puzzle);
+        puzzle.set_spacing(None);
+        puzzle.set_crisscross(true);
+        check(&puzzle);
+    }
+    
+    #[test]
+    fn backtracking_fills_tight_grid() {
+        // five words that only fit as the five rows or five columns of the grid
+        let words: Vec<String> = ["ABCDE", "FGHIJ", "KLMNO", "PQRST", "UVWXY"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 5, height: 5, maxtries: 10, verify: true, ..Config::default() };
+        let mut rng = test_rng();
+        for _ in 0..5 {
+            let puzzle = generate(&config, &words, &mut rng).unwrap();
+            assert_eq!(puzzle.entries, ["ABCDE", "FGHIJ", "KLMNO", "PQRST", "UVWXY"]);
+            assert!(placements_match(&puzzle));
+            assert_eq!(puzzle.density(), 1.0);
+        }
+        
+        let config = Config { backtrack: false, ..config };
+        assert!(generate(&config, &words, &mut rng).is_err());
+    }
+    
+    #[test]
+    fn scoring() {
+        let mut puzzle = PuzzleGrid::new(5, 5, 10000, Difficulty::Medium);
+        assert_eq!(puzzle.score(&ScoreWeights::default()), 0.0);
+        puzzle.write_word("CAT", &0, &1, &Direction::Right);
+        puzzle.write_word("MAP", &1, &0, &Direction::Down);
+        let overlap_only = ScoreWeights { overlap: 1.0, spread: 0.0, variety: 0.0 };
+        assert_eq!(puzzle.score(&overlap_only), 0.5);
+        let spread_only = ScoreWeights { overlap: 0.0, spread: 1.0, variety: 0.0 };
+        assert_eq!(puzzle.score(&spread_only), 0.6);
+        let variety_only = ScoreWeights { overlap: 0.0, spread: 0.0, variety: 1.0 };
+        assert_eq!(puzzle.score(&variety_only), 0.4);
+        
+        let words: Vec<String> = ["Hello", "Thanks", "Goodbye", "How", "Are", "You"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 10, height: 10, candidates: 5, ..Config::default() };
+        let single = generate(&config, &words, &mut test_rng()).unwrap();
+        let best = generate_best(&config, &words, &mut test_rng()).unwrap();
+        assert!(best.score(&config.score_weights) >= single.score(&config.score_weights));
+    }
+    
+    #[test]
+    fn dense_placement_overlaps() {
+        let mut puzzle = PuzzleGrid::new(10, 10, 10000, Difficulty::Medium);
+        puzzle.set_strategy(Arc::new(OverlapPlacement));
+        let mut rng = test_rng();
+        puzzle.place("Thanks", &mut rng).unwrap();
+        puzzle.place("Hats", &mut rng).unwrap();
+        assert!(!puzzle.crossings().is_empty());
+        assert!(placements_match(&puzzle));
+    }
+    
+    #[test]
+    fn optimized_layout() {
+        let words: Vec<String> = ["Thanks", "Hats", "Shark", "Stack", "Knots @ (0,0) Right"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 10, height: 10, verify: true, ..Config::default() };
+        let plain = generate(&config, &words, &mut StdRng::seed_from_u64(5)).unwrap();
+        let config = Config { optimize: Some(Objective::Overlaps), ..config };
+        let puzzle = generate(&config, &words, &mut StdRng::seed_from_u64(5)).unwrap();
+        assert!(puzzle.measure(Objective::Overlaps) >= plain.measure(Objective::Overlaps));
+        assert!(placements_match(&puzzle));
+        assert_eq!(puzzle.stats().tries, plain.stats().tries);
+        let knots = puzzle.placements().iter().find(|p| p.word == "KNOTS").unwrap();
+        assert_eq!((knots.x, knots.y, knots.dir), (0, 0, Direction::Right));
+    }
+    
+    #[test]
+    fn library_accessors() {
+        let words: Vec<String> = ["Hello", "Thanks"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 8, height: 6, ..Config::default() };
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut puzzle = generate(&config, &words, &mut rng).unwrap();
+        puzzle.fill_in(&mut rng);
+        assert_eq!(puzzle.grid().len(), 6);
+        assert_eq!(puzzle.entries(), ["HELLO", "THANKS"]);
+        assert_eq!(puzzle.placements().len(), 2);
+        assert!(puzzle.failed().is_empty());
+        assert!(puzzle.stats().density > 0.0);
+    }
+    
+    #[test]
+    fn injected_rng() {
+        // the step rng always returns 0, so every spot and every fill letter is the first choice
+        let mut rng = rand::rngs::mock::StepRng::new(0, 0);
+        let mut puzzle = PuzzleGrid::new(4, 4, 100, Difficulty::Medium);
+        puzzle.place("abc", &mut rng).unwrap();
+        assert_eq!((puzzle.placements[0].x, puzzle.placements[0].y, puzzle.placements[0].dir), (0, 0, Direction::Right));
+        puzzle.fill_in(&mut rng);
+        assert_eq!(puzzle.grid[0].join(""), "ABCA");
+        assert!(puzzle.grid[1..].iter().flatten().all(|c| c == "A"));
+    }
+    
+    #[test]
+    fn typed_errors() {
+        let mut rng = test_rng();
+        let words = vec![String::from("- -")];
+        assert!(matches!(generate(&Config::default(), &words, &mut rng), Err(WordfindError::NoWords)));
+        
+        let words: Vec<String> = ["ABCDE", "FGHIJ", "KLMNO"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 5, height: 2, maxtries: 50, ..Config::default() };
+        assert!(matches!(generate(&config, &words, &mut rng), Err(WordfindError::TooManyLetters { letters: 15, cells: 10, size: 6 })));
+        let config = Config { skip_failed: true, ..config };
+        assert!(generate(&config, &words, &mut rng).is_ok());
+        
+        let words: Vec<String> = ["ABCDE", "FGHIJ", "KLMNO", "PQ"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 5, height: 3, maxtries: 50, ..Config::default() };
+        match generate(&config, &words, &mut rng) {
+            Err(WordfindError::PlacementFailed { word, tries }) => {
+                assert_eq!(tries, 50);
+                assert!(words.contains(&word));
+            },
+            _ => panic!("expected a placement failure"),
+        }
+        
+        let config = Config { wordsfile: String::from("no/such/words.txt"), ..Config::default() };
+        assert!(matches!(run(config), Err(WordfindError::Io(_))));
+    }
+    
+    #[test]
+    fn skip_failed_words() {
+        let words: Vec<String> = ["ABCDE", "FGHIJ", "KLMNO", "PQ"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 5, height: 2, maxtries: 50, skip_failed: true, ..Config::default() };
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        assert!(!puzzle.failed().is_empty());
+        assert_eq!(puzzle.entries().len() + puzzle.failed().len(), 4);
+        assert!(puzzle.failed().iter().all(|w| !puzzle.entries().contains(&w.to_uppercase())));
+    }
+    
+    #[test]
+    fn grow_until_it_fits() {
+        let words: Vec<String> = ["ABCDEFG", "HIJKLMN", "OPQRSTU", "VWXYZAB", "CDEFGHI", "JKLMNOP", "QRSTUVW", "XYZABCD"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 5, height: 5, maxtries: 200, grow_to: Some(15), backtrack: false, ..Config::default() };
+        let puzzle = generate(&config, &words, &mut test_rng()).unwrap();
+        assert!(puzzle.width() > 7 && puzzle.width() <= 15);
+        assert_eq!(puzzle.width() % 2, 1);
+        assert_eq!(puzzle.entries().len(), 8);
+        
+        let config = Config { grow_to: Some(6), ..config };
+        assert!(matches!(generate(&config, &words, &mut test_rng()), Err(WordfindError::WordTooLong { .. })));
+    }
+}
@@ -0,0 +1,510 @@
+use std::convert::TryFrom;
+use std::iter::Peekable;
+use std::str::Chars;
+use std::time::Instant;
+use crate::solve::{csv_fields, find_grid};
+use crate::{fill, fold_diacritics, letters, read_words, render, sanitize, set_fill, Alphabet, Config, Difficulty, Direction, Metadata, Placement, PuzzleGrid, SeedableRng, StdRng, Summary, WordOrder, WordfindError};
+
+// Json - a json value, just enough json to read back the puzzles render_json writes
+//        Numbers are kept as their text so a seed doesn't lose digits on the way through an f64
+//
+#[derive(Debug,Clone,PartialEq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+// What Json::get finds for a key that isn't there
+const NULL: Json = Json::Null;
+
+impl Json {
+    // parse - the json value of the whole text
+    pub(crate) fn parse(text: &str) -> Result<Json, WordfindError> {
+        let mut chars = text.chars().peekable();
+        let value = json_value(&mut chars);
+        skip_space(&mut chars);
+        match value {
+            Some(value) if chars.peek().is_none() => Ok(value),
+            _ => Err(invalid("it isn't json")),
+        }
+    }
+    
+    // get - the value of a key of an object, Null when it isn't there or this isn't an object
+    pub(crate) fn get(&self, key: &str) -> &Json {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v).unwrap_or(&NULL),
+            _ => &NULL,
+        }
+    }
+    
+    // items - the values of an array, none when this isn't an array
+    pub(crate) fn items(&self) -> &[Json] {
+        match self {
+            Json::Array(items) => items,
+            _ => &[],
+        }
+    }
+    
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::Str(text) => Some(text),
+            _ => None,
+        }
+    }
+    
+    pub(crate) fn as_u64(&self) -> Option<u64> {
+        match self {
+            Json::Number(number) => number.parse().ok(),
+            _ => None,
+        }
+    }
+    
+    pub(crate) fn as_usize(&self) -> Option<usize> {
+        self.as_u64().and_then(|n| usize::try_from(n).ok())
+    }
+}
+
+// json_value - the next value in the text, None when it isn't one
+fn json_value(chars: &mut Peekable<Chars>) -> Option<Json> {
+    skip_space(chars);
+    match *chars.peek()? {
+        '{' => {
+            chars.next();
+            let mut fields = Vec::new();
+            skip_space(chars);
+            if chars.peek() == Some(&'}') {
+                chars.next();
+                return Some(Json::Object(fields));
+            }
+            loop {
+                skip_space(chars);
+                if chars.next()? != '"' {
+                    return None;
+                }
+                let key = json_string(chars)?;
+                skip_space(chars);
+                if chars.next()? != ':' {
+                    return None;
+                }
+                fields.push((key, json_value(chars)?));
+                skip_space(chars);
+                match chars.next()? {
+                    ',' => continue,
+                    '}' => return Some(Json::Object(fields)),
+                    _ => return None,
+                }
+            }
+        },
+        '[' => {
+            chars.next();
+            let mut items = Vec::new();
+            skip_space(chars);
+            if chars.peek() == Some(&']') {
+                chars.next();
+                return Some(Json::Array(items));
+            }
+            loop {
+                items.push(json_value(chars)?);
+                skip_space(chars);
+                match chars.next()? {
+                    ',' => continue,
+                    ']' => return Some(Json::Array(items)),
+                    _ => return None,
+                }
+            }
+        },
+        '"' => {
+            chars.next();
+            json_string(chars).map(Json::Str)
+        },
+        _ => {
+            let mut word = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || "+-.".contains(*c)) {
+                word.push(c);
+            }
+            match word.as_str() {
+                "null" => Some(Json::Null),
+                "true" => Some(Json::Bool(true)),
+                "false" => Some(Json::Bool(false)),
+                _ if word.parse::<f64>().is_ok() => Some(Json::Number(word)),
+                _ => None,
+            }
+        },
+    }
+}
+
+// json_string - the rest of a json string whose opening quote has been read, with the escapes
+//               turned back into the characters, None when it doesn't end
+fn json_string(chars: &mut Peekable<Chars>) -> Option<String> {
+    let mut text = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(text),
+            '\\' => match chars.next()? {
+                'n' => text.push('\n'),
+                't' => text.push('\t'),
+                'r' => text.push('\r'),
+                'u' => {
+                    let code: String = chars.by_ref().take(4).collect();
+                    text.extend(u32::from_str_radix(&code, 16).ok().and_then(char::from_u32));
+                },
+                escaped => text.push(escaped),
+            },
+            c => text.push(c),
+        }
+    }
+}
+
+fn skip_space(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+// json_rows - the rows of the grid array of a json puzzle, split into letters
+pub(crate) fn json_rows(json: &Json) -> Result<Vec<Vec<String>>, WordfindError> {
+    let rows: Vec<Vec<String>> = json.get("grid").items().iter().filter_map(Json::as_str).map(letters).collect();
+    let width = rows.first().map(Vec::len).unwrap_or(0);
+    if width == 0 {
+        return Err(invalid("the json has no grid array"));
+    }
+    if rows.iter().any(|row| row.len() != width) {
+        return Err(invalid("the rows of the json grid aren't all the same length"));
+    }
+    Ok(rows)
+}
+
+// invalid - the error for a puzzle file that can't be read back
+fn invalid(reason: &str) -> WordfindError {
+    WordfindError::InvalidGrid { reason: reason.to_string() }
+}
+
+impl PuzzleGrid {
+    // from_json - a puzzle render_json wrote, read back with the words where they were placed, so it
+    //             can be written out again in another format without placing them again
+    //             The headings, word bank and its sections, hints, and metadata come back too
+    pub fn from_json(text: &str) -> Result<PuzzleGrid, WordfindError> {
+        let json = Json::parse(text)?;
+        let mut puzzle = PuzzleGrid::loaded(json_rows(&json)?);
+        puzzle.title = json.get("title").as_str().map(String::from);
+        puzzle.subtitle = json.get("subtitle").as_str().map(String::from);
+        puzzle.instructions = json.get("instructions").as_str().map(String::from);
+        
+        for place in json.get("placements").items() {
+            let word = place.get("word").as_str().ok_or_else(|| invalid("a placement has no word"))?;
+            let cells: Vec<(usize, usize)> = place.get("cells").items().iter()
+                .filter_map(|cell| Some((cell.items().first()?.as_usize()?, cell.items().get(1)?.as_usize()?)))
+                .collect();
+            let placement = placement_along(word, &cells).filter(|_| cells.iter().all(|(x, y)| *x < puzzle.width && *y < puzzle.height))
+                .ok_or_else(|| invalid(&format!("the cells of {} don't make a path through the grid", word)))?;
+            if place.get("spangram") == &Json::Bool(true) {
+                puzzle.spangram = Some(word.to_string());
+            }
+            puzzle.placements.push(placement);
+        }
+        let words: Vec<String> = json.get("words").items().iter().filter_map(Json::as_str).map(String::from).collect();
+        puzzle.load_bank(&words);
+        
+        // the sections list the printed words, the puzzle keeps the entries they're for
+        let bank = puzzle.bank().into_iter().map(|(entry, printed)| (entry.clone(), printed)).collect::<Vec<_>>();
+        for section in json.get("sections").items() {
+            let heading = section.get("heading").as_str().unwrap_or_default();
+            for word in section.get("words").items().iter().filter_map(Json::as_str) {
+                if let Some((entry, _)) = bank.iter().find(|(_, printed)| printed == word) {
+                    puzzle.sections.push((entry.clone(), heading.to_string()));
+                }
+            }
+        }
+        puzzle.revealed = json.get("revealed").items().iter()
+            .filter_map(|cell| Some((cell.items().first()?.as_usize()?, cell.items().get(1)?.as_usize()?)))
+            .filter(|(x, y)| *x < puzzle.width && *y < puzzle.height)
+            .collect();
+        
+        let metadata = json.get("metadata");
+        if let (Some(version), Some(seed)) = (metadata.get("version").as_str(), metadata.get("seed").as_u64()) {
+            let directions: Vec<Direction> = metadata.get("directions").items().iter().filter_map(|d| Direction::from_name(d.as_str()?)).collect();
+            let created = metadata.get("created").as_str().and_then(parse_timestamp).unwrap_or(0);
+            puzzle.metadata = Some(Metadata {
+                version: version.to_string(), seed, puzzle: metadata.get("puzzle").as_usize(),
+                width: metadata.get("width").as_usize().unwrap_or(puzzle.width), height: metadata.get("height").as_usize().unwrap_or(puzzle.height),
+                directions, created,
+            });
+        }
+        Ok(puzzle)
+    }
+    
+    // from_csv - a puzzle written as csv, read back by finding the grid, the headings above it, and
+    //            the word bank and metadata row under it, each word is found in the grid again
+    //            A csv doesn't say where the words are, so a word of the bank that can't be found in
+    //            a straight line, like a bent word, is taken for a section heading
+    pub fn from_csv(text: &str, delimiter: char) -> Result<PuzzleGrid, WordfindError> {
+        let (start, rows) = find_grid(text, delimiter).ok_or_else(|| invalid("there's no grid of letters in it"))?;
+        let height = rows.len();
+        let mut puzzle = PuzzleGrid::loaded(rows);
+        let lines: Vec<Vec<String>> = text.lines().map(|line| csv_fields(line, delimiter)).collect();
+        
+        // the title, subtitle, and instructions are the lines above the grid, in that order
+        let mut heading = lines[..start].iter().filter_map(|fields| fields.first().cloned());
+        puzzle.title = heading.next();
+        puzzle.subtitle = heading.next();
+        puzzle.instructions = heading.next();
+        
+        let mut words = Vec::new();
+        let mut section: Option<String> = None;
+        for fields in lines[start + height..].iter() {
+            match fields.first().map(String::as_str) {
+                Some("Hints") => puzzle.revealed = fields[1..].iter().filter_map(|hint| parse_hint(hint)).collect(),
+                Some(comment) if comment.starts_with("# ") => puzzle.metadata = parse_summary(&comment[2..]),
+                _ => for field in fields.iter() {
+                    let key = sanitize(field, "");
+                    match puzzle.find_all(&key).first() {
+                        Some(found) => {
+                            let entry: String = found.cells().iter().map(|(x, y)| puzzle.grid[*y][*x].as_str()).collect();
+                            if let Some(heading) = &section {
+                                puzzle.sections.push((entry.clone(), heading.clone()));
+                            }
+                            puzzle.placements.push(Placement { word: entry, ..found.clone() });
+                            words.push(field.clone());
+                        },
+                        None => section = Some(field.clone()),
+                    }
+                },
+            }
+        }
+        puzzle.load_bank(&words);
+        Ok(puzzle)
+    }
+    
+    // loaded - a puzzle for the rows of a grid read back from a file, words are added to it with
+    //          placements and load_bank
+    fn loaded(rows: Vec<Vec<String>>) -> PuzzleGrid {
+        let mut puzzle = PuzzleGrid::new(rows[0].len(), rows.len(), 0, Difficulty::Medium);
+        puzzle.grid = rows;
+        puzzle
+    }
+    
+    // load_bank - set the entries from the placements and the word bank from the printed words in
+    //             it, each printed word goes with the entry it's spelled like, the rest are paired
+    //             up in order
+    fn load_bank(&mut self, printed: &[String]) {
+        let key = |word: &str| fold_diacritics(&sanitize(word, "")).to_uppercase();
+        self.entries = Vec::new();
+        for placement in self.placements.iter() {
+            if !self.entries.contains(&placement.word) {
+                self.entries.push(placement.word.clone());
+            }
+        }
+        
+        let mut paired: Vec<Option<usize>> = printed.iter().map(|_| None).collect();
+        for (i, word) in printed.iter().enumerate() {
+            paired[i] = (0..self.entries.len()).find(|e| !paired.contains(&Some(*e)) && key(&self.entries[*e]) == key(word));
+        }
+        for i in 0..printed.len() {
+            if paired[i].is_none() {
+                paired[i] = (0..self.entries.len()).find(|e| !paired.contains(&Some(*e)));
+            }
+        }
+        self.bank_order = paired.iter().flatten().cloned().collect();
+        self.spellings = printed.iter().zip(paired.iter())
+            .filter_map(|(word, entry)| entry.map(|e| (self.entries[e].clone(), word.clone())))
+            .filter(|(entry, word)| entry != word)
+            .collect();
+    }
+}
+
+// convert - read back the puzzle in file, json or csv, and write it out again in Config::format
+//           with its answer key, the words stay where they were
+//           Config::title, subtitle, and instructions replace the puzzle's own when they're set,
+//           Config::refill gives it new filler from Config::seed, and Config::verify checks the
+//           puzzle before it's written
+pub fn convert(config: Config, file: &str) -> Result<Summary, WordfindError> {
+    let started = Instant::now();
+    let text = read_words(file)?.join("\n");
+    let mut puzzle = if file.ends_with(".json") || text.starts_with('{') {
+        PuzzleGrid::from_json(&text)?
+    } else {
+        PuzzleGrid::from_csv(&text, config.delimiter)?
+    };
+    if config.title.is_some() {
+        puzzle.title = config.title.clone();
+    }
+    if config.subtitle.is_some() {
+        puzzle.subtitle = config.subtitle.clone();
+    }
+    if config.instructions.is_some() {
+        puzzle.instructions = config.instructions.clone();
+    }
+    if config.refill {
+        refill(&mut puzzle, &config)?;
+    }
+    puzzle.bank_style = config.bank_style;
+    puzzle.print = config.print_style();
+    if config.bank_order() != WordOrder::Original {
+        let seed = puzzle.metadata.as_ref().map_or(0, |m| m.seed);
+        puzzle.set_word_order(config.bank_order(), &mut StdRng::seed_from_u64(seed));
+    }
+    if config.verify {
+        puzzle.verify()?;
+    }
+    
+    let mut summary = Summary { files: render::write_files(&puzzle, &config)?, ..Summary::default() };
+    summary.add(&puzzle, &config);
+    summary.elapsed = started.elapsed();
+    Ok(summary)
+}
+
+// refill - new filler for a puzzle read back from a file, in the letters of the alphabet of its words
+//          unless the config picks others, with the decoys, hidden message, and blocklist of the
+//          config the way a new puzzle gets them
+fn refill(puzzle: &mut PuzzleGrid, config: &Config) -> Result<(), WordfindError> {
+    puzzle.clear_fill();
+    set_fill(puzzle, config, config.alphabet.unwrap_or_else(|| Alphabet::detect(&puzzle.entries)))?;
+    let seed = config.seed.unwrap_or_else(rand::random);
+    fill(puzzle, config, &mut StdRng::seed_from_u64(seed))
+}
+
+// placement_along - the placement of a word covering the cells, which go in a straight line or turn
+//                   one corner, None when they don't make a path the word fits
+fn placement_along(word: &str, cells: &[(usize, usize)]) -> Option<Placement> {
+    let step = |i: usize| {
+        let ((x1, y1), (x2, y2)) = (cells[i], cells[i + 1]);
+        Direction::from_incrementors((x2 as isize - x1 as isize) as i8, (y2 as isize - y1 as isize) as i8)
+    };
+    let (x, y) = *cells.first()?;
+    let dir = if cells.len() > 1 { step(0)? } else { Direction::Right };
+    let mut placement = Placement { word: word.to_string(), x, y, dir, bend: None };
+    if let Some(at) = (1..cells.len().saturating_sub(1)).find(|i| step(*i) != Some(dir)) {
+        placement.bend = Some((at, step(at)?));
+    }
+    if placement.cells() == cells {
+        Some(placement)
+    } else {
+        None
+    }
+}
+
+// parse_hint - the cell of a hint from the hints row of a csv, "C (1, 3)" is column 1 of row 3
+fn parse_hint(hint: &str) -> Option<(usize, usize)> {
+    let (x, y) = hint.split_once('(')?.1.strip_suffix(')')?.split_once(',')?;
+    Some((x.trim().parse::<usize>().ok()?.checked_sub(1)?, y.trim().parse::<usize>().ok()?.checked_sub(1)?))
+}
+
+// parse_summary - the metadata in the comment row of a csv, the other way from Metadata::summary
+//
+// wordfindgen 0.1.0, seed 42, puzzle 2, 6 x 6, directions Right Down, made 2024-03-01T09:30:00Z
+fn parse_summary(summary: &str) -> Option<Metadata> {
+    let mut metadata = Metadata { version: String::new(), seed: 0, puzzle: None, width: 0, height: 0, directions: Vec::new(), created: 0 };
+    for part in summary.split(", ").map(str::trim) {
+        let (name, value) = part.split_once(' ')?;
+        match name {
+            "wordfindgen" => metadata.version = value.to_string(),
+            "seed" => metadata.seed = value.parse().ok()?,
+            "puzzle" => metadata.puzzle = Some(value.parse().ok()?),
+            "directions" => metadata.directions = value.split_whitespace().filter_map(Direction::from_name).collect(),
+            "made" => metadata.created = parse_timestamp(value)?,
+            // the puzzle id is worked out from the grid again
+            "id" => {},
+            _ => {
+                let (width, height) = part.split_once(" x ")?;
+                metadata.width = width.parse().ok()?;
+                metadata.height = height.parse().ok()?;
+            },
+        }
+    }
+    Some(metadata).filter(|m| !m.version.is_empty())
+}
+
+// parse_timestamp - seconds since 1970 for an ISO 8601 date and time, the other way from
+//                   Metadata::timestamp
+fn parse_timestamp(text: &str) -> Option<u64> {
+    let (date, time) = text.strip_suffix('Z')?.split_once('T')?;
+    let date: Vec<i64> = date.split('-').map(|n| n.parse().ok()).collect::<Option<_>>()?;
+    let time: Vec<u64> = time.split(':').map(|n| n.parse().ok()).collect::<Option<_>>()?;
+    if date.len() != 3 || time.len() != 3 {
+        return None;
+    }
+    
+    // a calendar date to days since 1970, from Howard Hinnant's days_from_civil
+    let (month, day) = (date[1], date[2]);
+    let year = if month <= 2 { date[0] - 1 } else { date[0] };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = u64::try_from(era * 146097 + doe - 719468).ok()?;
+    Some(days * 86400 + time[0] * 3600 + time[1] * 60 + time[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fill, generate, AnswerStyle, Config, Format, WordOrder};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use std::fs;
+    
+    #[test]
+    fn json_values() {
+        let json = Json::parse("{\"a\": [1, -2.5, true, null], \"b\": \"x\\\"y\\u00e9\", \"c\": {}}").unwrap();
+        assert_eq!(json.get("a").items(), [Json::Number(String::from("1")), Json::Number(String::from("-2.5")), Json::Bool(true), Json::Null]);
+        assert_eq!(json.get("b").as_str(), Some("x\"yé"));
+        assert_eq!(json.get("c"), &Json::Object(Vec::new()));
+        assert_eq!(json.get("d"), &Json::Null);
+        assert!(Json::parse("{\"a\": 1").is_err());
+        assert!(Json::parse("[1] 2").is_err());
+        assert_eq!(parse_timestamp("2024-03-01T09:30:00Z"), Some(1709285400));
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(0));
+    }
+    
+    #[test]
+    fn puzzles_read_back() {
+        let words: Vec<String> = ["T-shirt", "[Pets]", "Cat", "dog", "owl"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 8, height: 8, difficulty: Difficulty::Hard, word_order: WordOrder::Alphabetical, reveal: 0.3, title: Some(String::from("Clothes")), ..Config::default() };
+        let mut rng = StdRng::seed_from_u64(1234);
+        let mut puzzle = generate(&config, &words, &mut rng).unwrap();
+        fill(&mut puzzle, &config, &mut rng).unwrap();
+        puzzle.metadata = Some(Metadata { version: String::from("1.0.0"), seed: u64::MAX, puzzle: Some(2), width: 8, height: 8, directions: vec![Direction::Right, Direction::UpLeft], created: 1709285400 });
+        
+        let json = PuzzleGrid::from_json(&puzzle.render_json()).unwrap();
+        assert_eq!(json.render_json(), puzzle.render_json());
+        assert_eq!(json.answer_list(), puzzle.answer_list());
+        
+        let mut text = Vec::new();
+        puzzle.write_csv(&mut text, &config, false).unwrap();
+        let csv = PuzzleGrid::from_csv(&String::from_utf8(text.clone()).unwrap(), ',').unwrap();
+        assert_eq!(csv.grid(), puzzle.grid());
+        assert_eq!(csv.answer_mask(), puzzle.answer_mask());
+        assert_eq!((csv.title(), csv.metadata()), (Some("Clothes"), puzzle.metadata()));
+        assert_eq!(csv.revealed(), puzzle.revealed());
+        assert_eq!(csv.render_markdown(true), puzzle.render_markdown(true));
+        
+        assert!(PuzzleGrid::from_json("{\"grid\":[]}").is_err());
+        
+        // converting the json to csv writes the same csv
+        let dir = std::env::temp_dir().join("wordfindgen_puzzles_read_back");
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("old.json");
+        fs::write(&file, puzzle.render_json()).unwrap();
+        let summary = convert(Config { output_dir: Some(dir.to_string_lossy().to_string()), ..config }, &file.to_string_lossy()).unwrap();
+        assert_eq!((summary.files.len(), summary.placed), (2, 4));
+        assert_eq!(fs::read_to_string(dir.join("puzzle.csv")).unwrap().into_bytes(), text);
+        
+        // new filler around the same words
+        let config = Config { output_dir: Some(dir.to_string_lossy().to_string()), format: Format::Json, output: Some(String::from("new.json")), refill: true, seed: Some(7), ..Config::default() };
+        convert(config, &file.to_string_lossy()).unwrap();
+        let refilled = PuzzleGrid::from_json(&fs::read_to_string(dir.join("new.json")).unwrap()).unwrap();
+        assert_eq!(refilled.answer_list(), puzzle.answer_list());
+        assert_eq!(refilled.answer_grid(AnswerStyle::Blank), puzzle.answer_grid(AnswerStyle::Blank));
+        assert_ne!(refilled.grid(), puzzle.grid());
+        assert!(refilled.verify().is_ok());
+        fs::remove_dir_all(&dir).unwrap();
+        
+        let mut again = PuzzleGrid::from_json(&puzzle.render_json()).unwrap();
+        again.refill(&mut StdRng::seed_from_u64(8));
+        assert_eq!(again.answer_grid(AnswerStyle::Blank), puzzle.answer_grid(AnswerStyle::Blank));
+        assert!(again.grid().iter().flatten().all(|c| c != " "));
+        assert!(PuzzleGrid::from_csv("Just a title\n", ',').is_err());
+    }
+}
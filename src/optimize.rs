@@ -0,0 +1,205 @@
+use rand::{Rng, RngCore};
+use rand::seq::SliceRandom;
+use crate::{letters, CancelToken, Placement, PuzzleGrid, CANCEL_CHECK_STEPS};
+
+// Rounds of moving a word the optimizer gets from --optimize
+pub const OPTIMIZE_ROUNDS: usize = 2000;
+
+// How much worse a move can make the layout and still be kept at the start of the optimizer, the
+// allowance falls to nothing by the last round so it ends up climbing to the nearest best layout
+const START_TEMPERATURE: f32 = 0.05;
+
+// Objective - what PuzzleGrid::optimize moves the words around to get more of, each measured from
+//             0 to 1
+//
+#[derive(Debug,Clone,Copy,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Objective {
+    // the fraction of the word letters that share a cell with another word, for a tightly woven grid
+    Overlaps,
+    // how close the middle of the words is to the middle of the grid, for a puzzle that doesn't
+    // lean to one side
+    Centered,
+    // the fraction of the word cells with another word cell where the grid turned halfway round
+    // puts them, for the balanced look of a crossword
+    Symmetric,
+}
+
+impl Objective {
+    // from_name - the objective for a name given on the command line
+    pub fn from_name(name: &str) -> Option<Objective> {
+        match name.to_ascii_lowercase().as_str() {
+            "overlaps" | "overlap" => Some(Objective::Overlaps),
+            "centered" | "centred" | "center" | "centre" => Some(Objective::Centered),
+            "symmetric" | "symmetry" => Some(Objective::Symmetric),
+            _ => None,
+        }
+    }
+}
+
+impl PuzzleGrid {
+    // measure - how well the layout meets the objective, from 0 to 1
+    pub fn measure(&self, objective: Objective) -> f32 {
+        self.measure_with(objective, &[])
+    }
+    
+    // measure_with - measure with the cells of a word that isn't placed yet added to the layout
+    fn measure_with(&self, objective: Objective, extra: &[(usize, usize)]) -> f32 {
+        let mut covered = vec![vec![0; self.width]; self.height];
+        for (x, y) in self.placements.iter().flat_map(Placement::cells).chain(extra.iter().copied()) {
+            covered[y][x] += 1;
+        }
+        let cells: Vec<(usize, usize)> = (0..self.height).flat_map(|y| (0..self.width).map(move |x| (x, y))).filter(|(x, y)| covered[*y][*x] > 0).collect();
+        if cells.is_empty() {
+            return 0.0;
+        }
+        
+        match objective {
+            Objective::Overlaps => {
+                let letters: usize = covered.iter().flatten().sum();
+                let shared: usize = covered.iter().flatten().filter(|c| **c > 1).sum();
+                shared as f32 / letters as f32
+            },
+            Objective::Centered => {
+                let (middle_x, middle_y) = ((self.width - 1) as f32 / 2.0, (self.height - 1) as f32 / 2.0);
+                let farthest = middle_x.hypot(middle_y);
+                if farthest == 0.0 {
+                    return 1.0;
+                }
+                let x = cells.iter().map(|c| c.0 as f32).sum::<f32>() / cells.len() as f32;
+                let y = cells.iter().map(|c| c.1 as f32).sum::<f32>() / cells.len() as f32;
+                1.0 - (x - middle_x).hypot(y - middle_y) / farthest
+            },
+            Objective::Symmetric => {
+                let matched = cells.iter().filter(|(x, y)| covered[self.height - 1 - y][self.width - 1 - x] > 0).count();
+                matched as f32 / cells.len() as f32
+            },
+        }
+    }
+    
+    // balance - how evenly the layout would sit in the grid with a word in the cells, the words
+    //           centered and reaching across as many of the rows and columns as they can, and with
+    //           symmetric also looking the same turned halfway round
+    pub(crate) fn balance(&self, cells: &[(usize, usize)], symmetric: bool) -> f32 {
+        let mut rows = vec![false; self.height];
+        let mut columns = vec![false; self.width];
+        for (x, y) in self.placements.iter().flat_map(Placement::cells).chain(cells.iter().copied()) {
+            rows[y] = true;
+            columns[x] = true;
+        }
+        let spread = (rows.iter().filter(|r| **r).count() as f32 / self.height as f32 + columns.iter().filter(|c| **c).count() as f32 / self.width as f32) / 2.0;
+        let mirrored = if symmetric { self.measure_with(Objective::Symmetric, cells) } else { 0.0 };
+        self.measure_with(Objective::Centered, cells) + spread + mirrored
+    }
+    
+    // optimize - move the placed words around the grid for rounds, one at a time, to make the
+    //            layout meet the objective better, and return how well it meets it at the end
+    //            This is simulated annealing, a move that makes the layout a little worse is kept
+    //            now and then early on so it doesn't get stuck, and the best layout seen is the one
+    //            left in the grid. Only straight words move, and it has to be done before fill_in
+    pub fn optimize(&mut self, objective: Objective, rounds: usize, rng: &mut dyn RngCore) -> f32 {
+        let spangram = self.spangram.clone();
+        let movable: Vec<String> = self.placements.iter().map(|p| p.word.clone()).filter(|w| spangram.as_ref() != Some(w)).collect();
+        self.optimize_words(objective, rounds, &movable, rng)
+    }
+    
+    // optimize_words - optimize moving only the words in the list, so pinned words stay put
+    pub(crate) fn optimize_words(&mut self, objective: Objective, rounds: usize, words: &[String], rng: &mut dyn RngCore) -> f32 {
+        let movable: Vec<usize> = (0..self.placements.len())
+            .filter(|i| self.placements[*i].bend.is_none() && words.iter().any(|w| w.to_uppercase() == self.placements[*i].word.to_uppercase()))
+            .collect();
+        let mut score = self.measure(objective);
+        if movable.is_empty() {
+            return score;
+        }
+        
+        // the spots tried here aren't the ones that went into placing the words
+        let tries = self.tries.get();
+        let mut best = (score, self.grid.clone(), self.placements.clone());
+        for round in 0..rounds {
+            if round.is_multiple_of(CANCEL_CHECK_STEPS) && self.cancel.as_ref().is_some_and(CancelToken::is_cancelled) {
+                break;
+            }
+            let i = movable[rng.gen_range(0, movable.len())];
+            let (grid, placement) = (self.grid.clone(), self.placements[i].clone());
+            self.lift(i);
+            let (x, y, dir) = match self.candidates(&placement.word).choose(rng) {
+                Some(spot) => *spot,
+                None => {
+                    self.grid = grid;
+                    continue;
+                },
+            };
+            let moved = Placement { x, y, dir, ..placement.clone() };
+            for ((cx, cy), letter) in moved.cells().into_iter().zip(letters(&moved.word)) {
+                self.grid[cy][cx] = letter;
+            }
+            self.placements[i] = moved;
+            
+            let temperature = START_TEMPERATURE * (1.0 - round as f32 / rounds as f32);
+            let measured = self.measure(objective);
+            if measured >= score || rng.gen::<f32>() < ((measured - score) / temperature).exp() {
+                score = measured;
+                if score > best.0 {
+                    best = (score, self.grid.clone(), self.placements.clone());
+                }
+            } else {
+                self.grid = grid;
+                self.placements[i] = placement;
+            }
+        }
+        
+        self.tries.set(tries);
+        self.grid = best.1;
+        self.placements = best.2;
+        best.0
+    }
+    
+    // lift - take the letters of a placement out of the grid, leaving the cells it shares with
+    //        another word
+    fn lift(&mut self, i: usize) {
+        let others: Vec<(usize, usize)> = self.placements.iter().enumerate().filter(|(j, _)| *j != i).flat_map(|(_, p)| p.cells()).collect();
+        for (x, y) in self.placements[i].cells() {
+            if !others.contains(&(x, y)) {
+                self.grid[y][x] = String::from(" ");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use crate::{Difficulty, Direction};
+    
+    #[test]
+    fn layout_optimized() {
+        let mut puzzle = PuzzleGrid::new(9, 9, 10000, Difficulty::Hard);
+        puzzle.write_word("CAT", &0, &0, &Direction::Right);
+        puzzle.write_word("TOE", &8, &3, &Direction::Down);
+        puzzle.write_word("ACE", &0, &8, &Direction::Right);
+        assert_eq!(puzzle.measure(Objective::Overlaps), 0.0);
+        assert_eq!(puzzle.measure(Objective::Symmetric), 0.0);
+        
+        let mut rng = StdRng::seed_from_u64(3);
+        let overlaps = puzzle.optimize(Objective::Overlaps, 500, &mut rng);
+        assert!(overlaps > 0.0);
+        assert_eq!(overlaps, puzzle.measure(Objective::Overlaps));
+        assert_eq!(puzzle.placements().len(), 3);
+        assert!(puzzle.verify().is_ok());
+        
+        let centered = puzzle.optimize(Objective::Centered, 500, &mut rng);
+        assert!(centered > 0.9);
+        assert!(puzzle.verify().is_ok());
+        
+        // the pinned word stays where it was
+        let cat = (puzzle.placements()[0].x, puzzle.placements()[0].y);
+        puzzle.optimize_words(Objective::Symmetric, 500, &[String::from("TOE"), String::from("ACE")], &mut rng);
+        assert_eq!((puzzle.placements()[0].x, puzzle.placements()[0].y), cat);
+        
+        assert_eq!(Objective::from_name("Centre"), Some(Objective::Centered));
+        assert_eq!(Objective::from_name("pretty"), None);
+    }
+}
@@ -0,0 +1,198 @@
+use std::io::{BufRead, Write};
+use std::time::{Duration, Instant};
+use crate::{checked_words, filter_words, letters, make_puzzle, run_seed, start_timeout, Config, Metadata, Placement, PuzzleGrid, StdRng, SeedableRng, WordfindError};
+
+// PlayReport - how a game of play went
+//
+#[derive(Debug,Clone,Default)]
+pub struct PlayReport {
+    // the words that were claimed, as they're printed in the word bank, in the order they were found
+    pub found: Vec<String>,
+    // the words that were still left when the game ended
+    pub missed: Vec<String>,
+    // how long the game took
+    pub elapsed: Duration,
+    // seed the puzzle was made from, to play the same one again with --seed
+    pub seed: u64,
+}
+
+// play - make a puzzle the way run does and play it on the terminal instead of writing it out
+//        The grid is printed with its columns and rows numbered and a word is claimed by typing
+//        the cells it starts and ends at, column and row, like "3 1 3 6"
+//        An empty line shows the grid again and q gives up, the answers are printed either way
+pub fn play<R: BufRead, W: Write>(mut config: Config, input: R, output: W) -> Result<PlayReport, WordfindError> {
+    start_timeout(&mut config);
+    let words = checked_words(&mut config)?;
+    let seed = run_seed(&config);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let words = filter_words(&config, &words, &mut rng);
+    let mut puzzle = make_puzzle(&config, &words, &mut rng)?;
+    puzzle.metadata = Some(Metadata::new(&puzzle, seed, None));
+    let report = play_puzzle(&puzzle, input, output)?;
+    Ok(PlayReport { seed, ..report })
+}
+
+// play_puzzle - the game for a puzzle that's already been made
+pub(crate) fn play_puzzle<R: BufRead, W: Write>(puzzle: &PuzzleGrid, input: R, mut output: W) -> Result<PlayReport, WordfindError> {
+    let started = Instant::now();
+    // each word of the word bank with where it was placed, densify copies share the word so the
+    // first placement is the one in the word list
+    let words: Vec<(String, &Placement)> = puzzle.bank().into_iter()
+        .filter_map(|(entry, printed)| puzzle.placements.iter().find(|p| p.word == *entry).map(|p| (printed, p)))
+        .collect();
+    let mut found: Vec<usize> = Vec::new();
+    
+    write!(output, "{}", board(puzzle, &words, &found))?;
+    writeln!(output, "Type the column and row of the first and last letters of a word, like 3 1 3 6, or q to give up")?;
+    let mut lines = input.lines();
+    while found.len() < words.len() {
+        write!(output, "> ")?;
+        output.flush()?;
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            write!(output, "{}", board(puzzle, &words, &found))?;
+            continue;
+        }
+        if line.eq_ignore_ascii_case("q") || line.eq_ignore_ascii_case("quit") {
+            break;
+        }
+        let numbers: Vec<usize> = line.split(|c: char| !c.is_ascii_digit()).filter(|n| !n.is_empty()).filter_map(|n| n.parse().ok()).collect();
+        let (from, to) = match numbers.as_slice() {
+            [x1, y1, x2, y2] if [x1, x2].iter().all(|x| (1..=puzzle.width).contains(*x)) && [y1, y2].iter().all(|y| (1..=puzzle.height).contains(*y)) => ((x1 - 1, y1 - 1), (x2 - 1, y2 - 1)),
+            [_, _, _, _] => {
+                writeln!(output, "The columns go from 1 to {} and the rows from 1 to {}", puzzle.width, puzzle.height)?;
+                continue;
+            },
+            _ => {
+                writeln!(output, "Type four numbers, the column and row of the first letter and then of the last")?;
+                continue;
+            },
+        };
+        match claim(puzzle, &words, &found, from, to) {
+            Some(i) => {
+                found.push(i);
+                writeln!(output, "Found {}, {} of {} at {}", words[i].0, found.len(), words.len(), clock(started.elapsed()))?;
+                write!(output, "{}", board(puzzle, &words, &found))?;
+            },
+            None => writeln!(output, "There's no word from the list there")?,
+        }
+    }
+    
+    let elapsed = started.elapsed();
+    let missed: Vec<usize> = (0..words.len()).filter(|i| !found.contains(i)).collect();
+    if missed.is_empty() {
+        writeln!(output, "You found all {} words in {}", words.len(), clock(elapsed))?;
+    } else {
+        writeln!(output, "You found {} of {} words in {}, the rest were", found.len(), words.len(), clock(elapsed))?;
+        for &i in &missed {
+            let place = words[i].1;
+            writeln!(output, "{}: ({}, {}) {:?}", words[i].0, place.x + 1, place.y + 1, place.dir)?;
+        }
+    }
+    Ok(PlayReport {
+        found: found.iter().map(|&i| words[i].0.clone()).collect(),
+        missed: missed.iter().map(|&i| words[i].0.clone()).collect(),
+        elapsed,
+        seed: 0,
+    })
+}
+
+// claim - the word that hasn't been found yet that starts and ends at the two cells, from either end
+//         A straight line that spells out one of the words somewhere it wasn't placed counts too
+fn claim(puzzle: &PuzzleGrid, words: &[(String, &Placement)], found: &[usize], from: (usize, usize), to: (usize, usize)) -> Option<usize> {
+    let left: Vec<usize> = (0..words.len()).filter(|i| !found.contains(i)).collect();
+    let ends = |p: &Placement| {
+        let cells = p.cells();
+        (cells[0], cells[cells.len() - 1])
+    };
+    if let Some(&i) = left.iter().find(|&&i| ends(words[i].1) == (from, to) || ends(words[i].1) == (to, from)) {
+        return Some(i);
+    }
+    
+    let (dx, dy) = (to.0 as isize - from.0 as isize, to.1 as isize - from.1 as isize);
+    if dx != 0 && dy != 0 && dx.abs() != dy.abs() {
+        return None;
+    }
+    let steps = dx.abs().max(dy.abs());
+    let text: String = (0..=steps).map(|s| {
+        let x = from.0 as isize + dx.signum() * s;
+        let y = from.1 as isize + dy.signum() * s;
+        puzzle.grid[y as usize][x as usize].to_uppercase()
+    }).collect();
+    let reversed: String = letters(&text).into_iter().rev().collect();
+    left.into_iter().find(|&i| {
+        let word = words[i].1.word.to_uppercase();
+        word == text || word == reversed
+    })
+}
+
+// board - the grid with its columns and rows numbered and the letters of the found words in
+//         brackets, followed by the words still to find
+fn board(puzzle: &PuzzleGrid, words: &[(String, &Placement)], found: &[usize]) -> String {
+    let claimed: Vec<(usize, usize)> = found.iter().flat_map(|&i| words[i].1.cells()).collect();
+    let mut text = String::from("   ");
+    for x in 1..=puzzle.width {
+        text.push_str(&format!("{:^3}", x));
+    }
+    text.push('\n');
+    for (y, row) in puzzle.grid.iter().enumerate() {
+        text.push_str(&format!("{:>3}", y + 1));
+        for (x, cell) in row.iter().enumerate() {
+            if claimed.contains(&(x, y)) {
+                text.push_str(&format!("[{}]", cell));
+            } else {
+                text.push_str(&format!(" {} ", cell));
+            }
+        }
+        text.push('\n');
+    }
+    let left: Vec<&str> = (0..words.len()).filter(|i| !found.contains(i)).map(|i| words[i].0.as_str()).collect();
+    text.push_str(&format!("Words left: {}\n", left.join(", ")));
+    text
+}
+
+// clock - the time as minutes and seconds, 1:05
+fn clock(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Difficulty, Direction};
+    
+    fn pets() -> PuzzleGrid {
+        let mut puzzle = PuzzleGrid::new(4, 3, 10000, Difficulty::Hard);
+        puzzle.write_word("CAT", &1, &0, &Direction::Right);
+        puzzle.write_word("DOG", &2, &2, &Direction::Left);
+        puzzle.entries.extend(vec![String::from("CAT"), String::from("DOG")]);
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        puzzle
+    }
+    
+    #[test]
+    fn words_claimed() {
+        let puzzle = pets();
+        let mut output = Vec::new();
+        let report = play_puzzle(&puzzle, "2 1 2 3\n9 1 9 2\ncat\n\n4 1 2 1\n1 3 3 3\n".as_bytes(), &mut output).unwrap();
+        assert_eq!(report.found, vec![String::from("CAT"), String::from("DOG")]);
+        assert!(report.missed.is_empty());
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("There's no word from the list there"));
+        assert!(output.contains("The columns go from 1 to 4 and the rows from 1 to 3"));
+        assert!(output.contains("Type four numbers"));
+        assert!(output.contains("Found CAT, 1 of 2 at 0:00"));
+        assert!(output.contains("  1 A [C][A][T]\n"));
+        assert!(output.contains("You found all 2 words in 0:00"));
+        
+        let mut output = Vec::new();
+        let report = play_puzzle(&puzzle, "q\n".as_bytes(), &mut output).unwrap();
+        assert_eq!(report.missed, vec![String::from("CAT"), String::from("DOG")]);
+        assert!(String::from_utf8(output).unwrap().contains("DOG: (3, 3) Left\n"));
+    }
+}
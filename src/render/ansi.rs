@@ -0,0 +1,71 @@
+use crate::PuzzleGrid;
+use super::BLOCK;
+
+// The colors the words of the answer key cycle through, red, green, yellow, blue, magenta, and cyan
+// and then the bright ones
+const WORD_COLORS: [u8; 12] = [31, 32, 33, 34, 35, 36, 91, 92, 93, 94, 95, 96];
+
+impl PuzzleGrid {
+    // render_ansi - the answer key for a terminal, rows of space separated letters with each placed
+    //               word in a color of its own and the filler dimmed, followed by the word bank with
+    //               each word in its color
+    //               A cell two words share takes the color of the one later in the word bank
+    pub fn render_ansi(&self) -> String {
+        let bank = self.bank();
+        let mut colors: Vec<Vec<Option<u8>>> = vec![vec![None; self.width]; self.height];
+        for (i, (entry, _)) in bank.iter().enumerate() {
+            for place in self.placements.iter().filter(|p| p.word == **entry) {
+                for (x, y) in place.cells() {
+                    colors[y][x] = Some(WORD_COLORS[i % WORD_COLORS.len()]);
+                }
+            }
+        }
+        
+        let mut text = String::new();
+        let heading = self.heading();
+        for line in heading.iter() {
+            text.push_str(line);
+            text.push('\n');
+        }
+        if !heading.is_empty() {
+            text.push('\n');
+        }
+        for (y, row) in self.shown_grid(true).iter().enumerate() {
+            let cells: Vec<String> = row.iter().enumerate().map(|(x, cell)| match colors[y][x] {
+                _ if !self.usable(x, y) => String::from(" "),
+                Some(color) => format!("\x1b[1;{}m{}\x1b[0m", color, cell.to_uppercase()),
+                None if cell == " " => String::from("."),
+                None if cell == BLOCK => cell.clone(),
+                None => format!("\x1b[2m{}\x1b[0m", cell),
+            }).collect();
+            text.push_str(&cells.join(" "));
+            text.push('\n');
+        }
+        text.push('\n');
+        for (i, (_, printed)) in bank.iter().enumerate() {
+            text.push_str(&format!("\x1b[1;{}m{}\x1b[0m\n", WORD_COLORS[i % WORD_COLORS.len()], printed));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Difficulty, Direction, PuzzleGrid};
+    
+    #[test]
+    fn colored_answers() {
+        let mut puzzle = PuzzleGrid::new(3, 2, 10000, Difficulty::Medium);
+        puzzle.write_word("AT", &1, &0, &Direction::Down);
+        puzzle.write_word("IT", &0, &1, &Direction::Right);
+        puzzle.entries.extend(vec![String::from("AT"), String::from("IT")]);
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        
+        let text = puzzle.render_ansi();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "\x1b[2mA\x1b[0m \x1b[1;31mA\x1b[0m \x1b[2mA\x1b[0m");
+        // the T is shared, IT comes later in the word bank so it gets its color
+        assert_eq!(lines[1], "\x1b[1;32mI\x1b[0m \x1b[1;32mT\x1b[0m \x1b[2mA\x1b[0m");
+        assert_eq!(&lines[3..], ["\x1b[1;31mAT\x1b[0m", "\x1b[1;32mIT\x1b[0m"]);
+    }
+}
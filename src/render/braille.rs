@@ -0,0 +1,156 @@
+use crate::{fold_diacritics, PuzzleGrid, WordfindError};
+
+// North American braille ascii, the character for each of the 64 braille cells in the order of
+// the unicode braille patterns, the bits of the index are dots 1 to 6
+const BRAILLE_ASCII: &str = " A1B'K2L@CIF/MSP\"E3H9O6R^DJG>NTQ,*5<-U8V.%[$+X!&;:4\\0Z7(_?W]#Y)=";
+
+// The cells on a line and the lines on a page of an embosser
+const BRAILLE_LINE: usize = 40;
+const BRAILLE_PAGE: usize = 25;
+
+// The full cell that stands in for a filler letter on the answer key, so the columns still line up
+const FULL_CELL: char = '=';
+
+impl PuzzleGrid {
+    // render_brf - the puzzle as a brf file for an embosser, in uncontracted braille with no capital
+    //              signs, the title and then the grid with a blank cell between the letters and the
+    //              word bank a word to a line under it, in pages of 25 lines of 40 cells
+    //              With answer set the filler letters are full cells so only the words stand out
+    //              The grid can be at most BRAILLE_LINE / 2 letters across, see check_braille
+    pub fn render_brf(&self, answer: bool) -> String {
+        let mut lines: Vec<String> = Vec::new();
+        let title = if answer { self.answer_title() } else { self.display_title().to_string() };
+        lines.extend(wrap(&braille_text(&title)));
+        for line in [self.subtitle(), self.instructions()].iter().flatten() {
+            lines.extend(wrap(&braille_text(line)));
+        }
+        lines.push(String::new());
+        
+        let mask = self.answer_mask();
+        for (y, row) in self.shown_grid(answer).iter().enumerate() {
+            let cells: Vec<String> = row.iter().enumerate().map(|(x, cell)| match cell.as_str() {
+                _ if !self.usable(x, y) => String::from(" "),
+                _ if answer && !mask[y][x] && !self.blocked(x, y) => FULL_CELL.to_string(),
+                " " => String::from(" "),
+                cell => braille_text(cell),
+            }).collect();
+            lines.push(cells.join(" ").trim_end().to_string());
+        }
+        
+        lines.push(String::new());
+        for entry in self.printed_bank(answer) {
+            lines.extend(wrap(&braille_text(&entry)));
+        }
+        if let Some(id) = self.id_line() {
+            lines.push(String::new());
+            lines.extend(wrap(&braille_text(&id)));
+        }
+        
+        // a form feed starts each new page
+        let pages: Vec<String> = lines.chunks(BRAILLE_PAGE).map(|page| page.join("\n")).collect();
+        format!("{}\n", pages.join("\n\x0c"))
+    }
+    
+    // render_braille - render_brf with the unicode braille patterns, for reading on screen or with
+    //                  a braille display
+    pub fn render_braille(&self, answer: bool) -> String {
+        self.render_brf(answer).chars().map(|c| match BRAILLE_ASCII.find(c) {
+            Some(dots) => char::from_u32(0x2800 + dots as u32).unwrap_or(c),
+            None => c,
+        }).collect()
+    }
+    
+    // check_braille - whether the grid fits on the line of an embosser with the blank cells between
+    //                 the letters
+    pub(crate) fn check_braille(&self) -> Result<(), WordfindError> {
+        if self.width * 2 - 1 > BRAILLE_LINE {
+            return Err(WordfindError::Usage(format!("a braille line holds {} cells, so the grid can be at most {} letters across", BRAILLE_LINE, BRAILLE_LINE / 2)));
+        }
+        Ok(())
+    }
+}
+
+// braille_text - text in uncontracted braille ascii, the letters with no capital signs, digits
+//                after a number sign with a letter sign before a letter a to j that follows them,
+//                and accented letters without their accents
+fn braille_text(text: &str) -> String {
+    let mut brf = String::new();
+    let mut number = false;
+    for c in fold_diacritics(text).chars() {
+        let c = c.to_ascii_uppercase();
+        match c {
+            '0'..='9' => {
+                if !number {
+                    brf.push('#');
+                    number = true;
+                }
+                brf.push(b"JABCDEFGHI"[c as usize - '0' as usize] as char);
+                continue;
+            },
+            'A'..='J' if number => {
+                brf.push(';');
+                brf.push(c);
+            },
+            'A'..='Z' => brf.push(c),
+            '.' => brf.push('4'),
+            ',' => brf.push('1'),
+            '\'' => brf.push('\''),
+            '-' => brf.push('-'),
+            '?' => brf.push('8'),
+            '!' => brf.push('6'),
+            ':' => brf.push('3'),
+            ';' => brf.push('2'),
+            _ => brf.push(' '),
+        }
+        number = false;
+    }
+    brf
+}
+
+// wrap - the line split at spaces into lines that fit the embosser, a word longer than a line is
+//        split where the line ends
+fn wrap(line: &str) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    for word in line.split(' ').filter(|w| !w.is_empty()) {
+        let last = lines.last_mut().unwrap();
+        if !last.is_empty() && last.len() + 1 + word.len() <= BRAILLE_LINE {
+            last.push(' ');
+            last.push_str(word);
+            continue;
+        }
+        if !last.is_empty() {
+            lines.push(String::new());
+        }
+        let chars: Vec<char> = word.chars().collect();
+        for (i, chunk) in chars.chunks(BRAILLE_LINE).enumerate() {
+            if i > 0 {
+                lines.push(String::new());
+            }
+            lines.last_mut().unwrap().extend(chunk);
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Difficulty, Direction};
+    
+    #[test]
+    fn braille_output() {
+        assert_eq!(braille_text("Week 12a, Café!"), "WEEK #AB;A1 CAFE6");
+        assert_eq!(wrap(&"ABCDEFGHIJ ".repeat(5)), vec!["ABCDEFGHIJ ABCDEFGHIJ ABCDEFGHIJ", "ABCDEFGHIJ ABCDEFGHIJ"]);
+        
+        let mut puzzle = PuzzleGrid::new(4, 3, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &0, &1, &Direction::Right);
+        puzzle.entries.push(String::from("CAT"));
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        puzzle.set_title(Some(String::from("Pets")));
+        assert_eq!(puzzle.render_brf(false), "PETS\n\nA A A A\nC A T A\nA A A A\n\nCAT\n");
+        assert_eq!(puzzle.render_brf(true), "PETS - ANSWER KEY\n\n= = = =\nC A T =\n= = = =\n\nCAT\n");
+        assert!(puzzle.render_braille(false).starts_with("⠏⠑⠞⠎\n\n⠁⠀⠁⠀⠁⠀⠁\n⠉⠀⠁⠀⠞⠀⠁\n"));
+        assert!(puzzle.check_braille().is_ok());
+        assert!(PuzzleGrid::new(21, 3, 10000, Difficulty::Medium).check_braille().is_err());
+    }
+}
@@ -0,0 +1,427 @@
+use crate::{BankStyle, PuzzleGrid};
+use super::{escape_json, escape_xml, PrintStyle, LETTER_FRACTION};
+
+// Each page is a block the browser breaks after when printing, the grid cells are fixed size squares
+const STYLE: &str = "\
+body { font-family: Helvetica, Arial, sans-serif; }
+.page { page-break-after: always; margin: 0 auto; max-width: 8in; }
+h1, h2, p.instructions { text-align: center; }
+table.grid { border-collapse: collapse; margin: 0 auto; }
+table.grid td { border: 1px solid #000; width: 1.6em; height: 1.6em; text-align: center; vertical-align: middle; font-size: 1.2em; }
+table.grid td.placed { background: #ccc; }
+table.grid sup { font-size: 0.5em; vertical-align: top; }
+table.grid td.outside { border: none; }
+table.grid td.block { background: #000; }
+p.puzzle-id { text-align: right; font-size: 0.7em; color: #555; }
+table.grid span.start { font-weight: bold; text-decoration: underline; }
+table.grid span.hint { display: inline-block; width: 1.3em; line-height: 1.3em; border: 2px solid #000; border-radius: 50%; font-weight: bold; }
+ul.words { columns: 3; list-style: none; padding: 0; margin: 1.5em auto; }
+h3 { margin-bottom: 0; }
+ol.contents { max-width: 5in; margin: 2em auto; line-height: 2; }
+ol.contents span { float: right; }
+@media print { .page:last-child { page-break-after: auto; } }
+";
+
+// Extra styles for high contrast, bold black on white with the answers white on black
+const HIGH_CONTRAST_STYLE: &str = "\
+body { color: #000; background: #fff; }
+table.grid td { border: 2px solid #000; font-weight: bold; }
+table.grid td.placed { background: #000; color: #fff; }
+ul.words { font-weight: bold; }
+";
+
+// Extra styles for the playable page, the cells under the mouse and the words that have been found
+const PLAY_STYLE: &str = "\
+table.grid td { cursor: pointer; user-select: none; }
+table.grid td.selecting { background: #ffe08a; }
+table.grid td.found { background: #9fd69f; }
+ul.words li.found { text-decoration: line-through; color: #777; }
+";
+
+// Drag from the first letter of a word to the last to select it, a straight line selection is
+// checked forwards and backwards against the placements in the embedded solution, and one that
+// isn't straight against the ends of the bent words
+const PLAY_SCRIPT: &str = "\
+const solution = JSON.parse(document.getElementById('solution').textContent);
+const grid = document.querySelector('table.grid');
+let start = null;
+function pos(td) { return { x: +td.dataset.x, y: +td.dataset.y }; }
+function cell(x, y) { return grid.querySelector('td[data-x=\"' + x + '\"][data-y=\"' + y + '\"]'); }
+function line(a, b) {
+  const dx = Math.sign(b.x - a.x), dy = Math.sign(b.y - a.y);
+  const n = Math.max(Math.abs(b.x - a.x), Math.abs(b.y - a.y));
+  if (b.x - a.x !== dx * n || b.y - a.y !== dy * n) return null;
+  const path = [];
+  for (let i = 0; i <= n; i++) path.push([a.x + dx * i, a.y + dy * i]);
+  return path;
+}
+function same(a, b) { return a.length === b.length && a.every((c, i) => c[0] === b[i][0] && c[1] === b[i][1]); }
+function clear() { grid.querySelectorAll('td.selecting').forEach(td => td.classList.remove('selecting')); }
+function ends(p, a, b) {
+  const first = p.cells[0], last = p.cells[p.cells.length - 1];
+  return (first[0] === a.x && first[1] === a.y && last[0] === b.x && last[1] === b.y) || (last[0] === a.x && last[1] === a.y && first[0] === b.x && first[1] === b.y);
+}
+function check(path, a, b) {
+  const reversed = path ? path.slice().reverse() : null;
+  const hit = solution.placements.find(p => path ? same(p.cells, path) || same(p.cells, reversed) : ends(p, a, b));
+  if (!hit) return;
+  hit.cells.forEach(([x, y]) => cell(x, y).classList.add('found'));
+  document.querySelectorAll('ul.words li').forEach(li => { if (li.dataset.word === hit.word) li.classList.add('found'); });
+}
+grid.addEventListener('mousedown', e => {
+  const td = e.target.closest('td');
+  if (td) { start = pos(td); td.classList.add('selecting'); e.preventDefault(); }
+});
+grid.addEventListener('mouseover', e => {
+  const td = e.target.closest('td');
+  if (!start || !td) return;
+  clear();
+  (line(start, pos(td)) || [[start.x, start.y]]).forEach(([x, y]) => cell(x, y).classList.add('selecting'));
+});
+document.addEventListener('mouseup', e => {
+  const td = e.target.closest ? e.target.closest('table.grid td') : null;
+  clear();
+  if (start && td) check(line(start, pos(td)), start, pos(td));
+  start = null;
+});
+";
+
+impl PuzzleGrid {
+    // render_html - a standalone html page with the grid as a table and the word list under it
+    //               With answer_key set a second page follows with the letters of the placed words shaded
+    pub fn render_html(&self, answer_key: bool) -> String {
+        let mut html = format!("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n", escape_xml(self.display_title()));
+        html.push_str(STYLE);
+        html.push_str(&print_css(&self.print));
+        html.push_str("</style>\n</head>\n<body>\n");
+        html.push_str(&self.html_page(&self.html_heading(), None));
+        if answer_key {
+            let heading = format!("<h1>{}</h1>\n", escape_xml(&self.answer_title()));
+            html.push_str(&self.html_page(&heading, Some(&self.answer_mask())));
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+    
+    // render_html_interactive - an html page that can be played in the browser, words are selected
+    //                           by dragging across them and checked against the solution embedded in
+    //                           the page
+    pub fn render_html_interactive(&self) -> String {
+        let placements: Vec<String> = self.placements.iter().map(|p| {
+            let cells: Vec<String> = p.cells().iter().map(|(x, y)| format!("[{},{}]", x, y)).collect();
+            format!("{{\"word\":{},\"cells\":[{}]}}", escape_json(&p.word), cells.join(","))
+        }).collect();
+        
+        let mut html = format!("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n", escape_xml(self.display_title()));
+        html.push_str(STYLE);
+        html.push_str(&print_css(&self.print));
+        html.push_str(PLAY_STYLE);
+        html.push_str(&format!("</style>\n</head>\n<body>\n<div class=\"page\"{}>\n", self.html_dir()));
+        html.push_str(&self.html_heading());
+        html.push_str("<table class=\"grid\" dir=\"ltr\">\n");
+        for (y, row) in self.shown_grid(false).iter().enumerate() {
+            html.push_str("<tr>");
+            for (x, cell) in row.iter().enumerate() {
+                if !self.usable(x, y) {
+                    html.push_str("<td class=\"outside\"></td>");
+                    continue;
+                }
+                if self.blocked(x, y) {
+                    html.push_str("<td class=\"block\"></td>");
+                    continue;
+                }
+                html.push_str(&format!("<td data-x=\"{}\" data-y=\"{}\">{}</td>", x, y, self.html_cell(x, y, cell, false)));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n");
+        if matches!(self.bank_style, BankStyle::Words | BankStyle::Scrambled) {
+            for (heading, lines) in self.group_lines(self.puzzle_bank()) {
+                html.push_str(&html_bank_heading(heading.as_deref()));
+                for (entry, shown) in lines {
+                    html.push_str(&format!("<li data-word=\"{}\">{}</li>\n", escape_xml(entry), escape_xml(&shown)));
+                }
+                html.push_str("</ul>\n");
+            }
+        } else {
+            html.push_str("<ul class=\"words\">\n");
+            for line in self.puzzle_bank() {
+                html.push_str(&format!("<li>{}</li>\n", escape_xml(&line)));
+            }
+            html.push_str("</ul>\n");
+        }
+        html.push_str(&self.html_id());
+        html.push_str("</div>\n");
+        html.push_str(&format!("<script type=\"application/json\" id=\"solution\">{{\"placements\":[{}]}}</script>\n", placements.join(",")));
+        html.push_str("<script>\n");
+        html.push_str(PLAY_SCRIPT);
+        html.push_str("</script>\n</body>\n</html>\n");
+        html
+    }
+    
+    // html_heading - the title, subtitle, and instructions that go above the puzzle
+    fn html_heading(&self) -> String {
+        let mut html = format!("<h1>{}</h1>\n", escape_xml(self.display_title()));
+        if let Some(subtitle) = self.subtitle() {
+            html.push_str(&format!("<h2>{}</h2>\n", escape_xml(subtitle)));
+        }
+        if let Some(instructions) = self.instructions() {
+            html.push_str(&format!("<p class=\"instructions\">{}</p>\n", escape_xml(instructions)));
+        }
+        html
+    }
+    
+    // html_cell - the letter in a cell of the grid, circled when it's a hint on the puzzle and
+    //             bolded when it's the first letter of a word with PrintStyle::first_letters
+    fn html_cell(&self, x: usize, y: usize, letter: &str, answer: bool) -> String {
+        if !answer && self.revealed.contains(&(x, y)) {
+            format!("<span class=\"hint\">{}</span>", escape_xml(letter))
+        } else if self.bold_starts(answer).contains(&(x, y)) {
+            format!("<span class=\"start\">{}</span>", escape_xml(letter))
+        } else {
+            escape_xml(letter)
+        }
+    }
+    
+    // html_dir - the direction attribute for a page of a right to left puzzle, the grid itself is
+    //            always laid out left to right so the columns match the placements
+    fn html_dir(&self) -> &'static str {
+        if self.rtl() { " dir=\"rtl\"" } else { "" }
+    }
+    
+    // html_page - one printed page under the given heading html, shade marks the cells to highlight
+    fn html_page(&self, heading: &str, shade: Option<&[Vec<bool>]>) -> String {
+        let mut html = format!("<div class=\"page\"{}>\n{}<table class=\"grid\" dir=\"ltr\">\n", self.html_dir(), heading);
+        let numbers = if shade.is_some() { self.start_numbers() } else { vec![vec![String::new(); self.width]; self.height] };
+        for (y, row) in self.shown_grid(shade.is_some()).iter().enumerate() {
+            html.push_str("<tr>");
+            for (x, cell) in row.iter().enumerate() {
+                match shade {
+                    _ if !self.usable(x, y) => html.push_str("<td class=\"outside\"></td>"),
+                    _ if self.blocked(x, y) => html.push_str("<td class=\"block\"></td>"),
+                    Some(mask) if mask[y][x] => html.push_str(&format!("<td class=\"placed\">{}{}</td>", sup(&numbers[y][x]), escape_xml(cell))),
+                    _ => html.push_str(&format!("<td>{}</td>", self.html_cell(x, y, cell, shade.is_some()))),
+                }
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n");
+        for (heading, entries) in self.grouped_bank(shade.is_some()) {
+            html.push_str(&html_bank_heading(heading.as_deref()));
+            for entry in entries.iter() {
+                html.push_str(&format!("<li>{}</li>\n", escape_xml(entry)));
+            }
+            html.push_str("</ul>\n");
+        }
+        html.push_str(&self.html_id());
+        html.push_str("</div>\n");
+        html
+    }
+    
+    // html_id - the puzzle id at the foot of a page, when the puzzle has one
+    fn html_id(&self) -> String {
+        self.id_line().map(|id| format!("<p class=\"puzzle-id\">{}</p>\n", id)).unwrap_or_default()
+    }
+}
+
+// render_html_book - the puzzles of a book as one html page, a contents page listing the puzzles
+//                    and their page numbers, a page for each puzzle, and then the answer keys
+pub(crate) fn render_html_book(title: &str, puzzles: &[PuzzleGrid]) -> String {
+    let mut html = format!("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n", escape_xml(title));
+    html.push_str(STYLE);
+    html.push_str(&print_css(&puzzles.first().map(|p| p.print.clone()).unwrap_or_default()));
+    html.push_str("</style>\n</head>\n<body>\n");
+    
+    // the contents are page 1, so the puzzles start on page 2 and the answer keys follow them
+    html.push_str(&format!("<div class=\"page\">\n<h1>{}</h1>\n<ol class=\"contents\">\n", escape_xml(title)));
+    for (i, puzzle) in puzzles.iter().enumerate() {
+        html.push_str(&format!("<li><a href=\"#puzzle-{}\">{}</a> <span>{}</span></li>\n", i + 1, escape_xml(puzzle.display_title()), i + 2));
+    }
+    html.push_str(&format!("</ol>\n<p class=\"instructions\"><a href=\"#answer-1\">Answer keys</a> start on page {}</p>\n</div>\n", puzzles.len() + 2));
+    
+    for (i, puzzle) in puzzles.iter().enumerate() {
+        let heading = format!("<a id=\"puzzle-{}\"></a>{}", i + 1, puzzle.html_heading());
+        html.push_str(&puzzle.html_page(&heading, None));
+    }
+    for (i, puzzle) in puzzles.iter().enumerate() {
+        let heading = format!("<h1 id=\"answer-{}\">{}</h1>\n", i + 1, escape_xml(&puzzle.answer_title()));
+        html.push_str(&puzzle.html_page(&heading, Some(&puzzle.answer_mask())));
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+// render_html_pages - the puzzles as one html page with a printed page for each, followed by a page
+//                     for each answer key with answer_key set, for the two puzzles of a double
+pub(crate) fn render_html_pages(puzzles: &[PuzzleGrid], answer_key: bool) -> String {
+    let title = puzzles.first().map_or("Word Find", |p| p.display_title());
+    let mut html = format!("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n", escape_xml(title));
+    html.push_str(STYLE);
+    html.push_str(&print_css(&puzzles.first().map(|p| p.print.clone()).unwrap_or_default()));
+    html.push_str("</style>\n</head>\n<body>\n");
+    for puzzle in puzzles.iter() {
+        html.push_str(&puzzle.html_page(&puzzle.html_heading(), None));
+    }
+    if answer_key {
+        for puzzle in puzzles.iter() {
+            let heading = format!("<h1>{}</h1>\n<h2>{}</h2>\n", escape_xml(&puzzle.answer_title()), escape_xml(puzzle.subtitle().unwrap_or("")));
+            html.push_str(&puzzle.html_page(&heading, Some(&puzzle.answer_mask())));
+        }
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+// print_css - the styles for the print style, after STYLE so they win, nothing for the default one
+//             Sized letters keep each page from being split when it's printed
+fn print_css(style: &PrintStyle) -> String {
+    let mut css = String::new();
+    if let Some(font) = &style.font {
+        let font: String = font.chars().filter(|c| c.is_alphanumeric() || " ,-'\"".contains(*c)).collect();
+        css.push_str(&format!("body {{ font-family: {}; }}\n", font));
+    }
+    if let Some(size) = style.letter_size {
+        let cell = size / LETTER_FRACTION;
+        css.push_str(&format!("table.grid td {{ width: {:.1}pt; height: {:.1}pt; font-size: {:.1}pt; }}\n", cell, cell, size));
+        css.push_str(&format!("ul.words {{ font-size: {:.1}pt; }}\n", style.bank_font()));
+        css.push_str(".page { break-inside: avoid; }\n");
+    }
+    if let Some(columns) = style.bank_columns {
+        css.push_str(&format!("ul.words {{ columns: {}; }}\n", columns.max(1)));
+    }
+    if style.high_contrast {
+        css.push_str(HIGH_CONTRAST_STYLE);
+    }
+    css
+}
+
+// html_bank_heading - the start of the list for one section of the word bank, under its heading
+fn html_bank_heading(heading: Option<&str>) -> String {
+    match heading {
+        Some(heading) => format!("<h3>{}</h3>\n<ul class=\"words\">\n", escape_xml(heading)),
+        None => String::from("<ul class=\"words\">\n"),
+    }
+}
+
+// sup - the numbers of the words that start in a cell as a superscript, nothing when there are none
+fn sup(numbers: &str) -> String {
+    if numbers.is_empty() { String::new() } else { format!("<sup>{}</sup>", numbers) }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Difficulty, Direction, PuzzleGrid};
+    
+    #[test]
+    fn shaped_html() {
+        let mut puzzle = PuzzleGrid::new(3, 3, 10000, Difficulty::Medium);
+        puzzle.set_mask(&crate::letter_mask("T", 3, 3).unwrap()).unwrap();
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        let html = puzzle.render_html(true);
+        assert_eq!(html.matches("<td class=\"outside\"></td>").count(), 12);
+        assert_eq!(html.matches("<td>A</td>").count(), 6);
+    }
+    
+    #[test]
+    fn first_letters() {
+        let mut puzzle = PuzzleGrid::new(4, 3, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &0, &1, &Direction::Right);
+        puzzle.write_word("DOG", &3, &0, &Direction::Down);
+        puzzle.entries.extend(vec![String::from("CAT"), String::from("DOG")]);
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        assert!(!puzzle.render_html(false).contains("class=\"start\""));
+        
+        puzzle.set_print_style(crate::PrintStyle { first_letters: true, ..crate::PrintStyle::default() });
+        let html = puzzle.render_html(true);
+        let puzzle_page = &html[..html.find("Answer Key").unwrap()];
+        assert!(puzzle_page.contains("<td><span class=\"start\">C</span></td><td>A</td>"));
+        assert_eq!(puzzle_page.matches("<span class=\"start\">").count(), 2);
+        // the answer key shows the whole words
+        assert!(!html[html.find("Answer Key").unwrap()..].contains("<span class=\"start\">"));
+    }
+    
+    #[test]
+    fn numbered_answers() {
+        let mut puzzle = PuzzleGrid::new(4, 3, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &0, &1, &Direction::Right);
+        puzzle.write_word("DOG", &3, &0, &Direction::Down);
+        puzzle.entries.extend(vec![String::from("CAT"), String::from("DOG")]);
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        puzzle.set_print_style(crate::PrintStyle { numbered_answers: true, ..crate::PrintStyle::default() });
+        
+        let html = puzzle.render_html(true);
+        assert!(html.contains("<td class=\"placed\"><sup>1</sup>C</td>"));
+        assert!(html.contains("<td class=\"placed\"><sup>2</sup>D</td>"));
+        assert!(html.contains("<li>1. CAT</li>\n<li>2. DOG</li>"));
+        // the puzzle page has neither
+        let puzzle_page = &html[..html.find("Answer Key").unwrap()];
+        assert!(!puzzle_page.contains("<sup>") && puzzle_page.contains("<li>CAT</li>"));
+    }
+    
+    #[test]
+    fn html_pages() {
+        let mut puzzle = PuzzleGrid::new(4, 3, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &0, &1, &Direction::Right);
+        puzzle.entries.push(String::from("CAT"));
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        
+        let html = puzzle.render_html(true);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert_eq!(html.matches("<tr>").count(), 6);
+        assert_eq!(html.matches("<td class=\"placed\">").count(), 3);
+        assert!(html.contains("<li>CAT</li>"));
+        
+        let html = puzzle.render_html(false);
+        assert_eq!(html.matches("class=\"page\"").count(), 1);
+        assert!(html.contains("<h1>Word Find</h1>"));
+        
+        puzzle.set_title(Some(String::from("Pets & Animals")));
+        puzzle.set_instructions(Some(String::from("Find the animals")));
+        let html = puzzle.render_html(true);
+        assert!(html.contains("<title>Pets &amp; Animals</title>"));
+        assert!(html.contains("<h1>Pets &amp; Animals</h1>\n<p class=\"instructions\">Find the animals</p>"));
+        assert!(html.contains("<h1>Pets &amp; Animals - Answer Key</h1>"));
+        assert_eq!(html.matches("Find the animals").count(), 1);
+        assert!(html.contains("<div class=\"page\">\n"));
+        
+        puzzle.set_rtl(true);
+        assert!(puzzle.render_html(false).contains("<div class=\"page\" dir=\"rtl\">\n<h1>Pets &amp; Animals</h1>"));
+    }
+    
+    #[test]
+    fn large_print_html() {
+        let mut puzzle = PuzzleGrid::new(10, 10, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &0, &1, &Direction::Right);
+        puzzle.entries.push(String::from("CAT"));
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        assert!(!puzzle.render_html(true).contains("pt;"));
+        
+        puzzle.set_print_style(crate::PrintStyle { font: Some(String::from("Georgia, serif</style>")), ..crate::PrintStyle::large_print() });
+        let html = puzzle.render_html(true);
+        assert!(html.contains("body { font-family: Georgia, serifstyle; }\n"));
+        assert!(html.contains("table.grid td { width: 40.0pt; height: 40.0pt; font-size: 24.0pt; }\nul.words { font-size: 18.0pt; }\n"));
+        assert!(html.contains("table.grid td.placed { background: #000; color: #fff; }"));
+        assert!(puzzle.check_page().is_ok());
+        
+        // 12 cells of 40 points is 480, 13 is more than the 504 between the margins
+        let mut wide = PuzzleGrid::new(13, 5, 10000, Difficulty::Medium);
+        wide.set_print_style(crate::PrintStyle::large_print());
+        assert!(matches!(wide.check_page(), Err(crate::WordfindError::TooBigToPrint { width: 13, height: 5, .. })));
+        wide.set_print_style(crate::PrintStyle { letter_size: Some(18.0), ..crate::PrintStyle::default() });
+        assert!(wide.check_page().is_ok());
+    }
+    
+    #[test]
+    fn playable_html() {
+        let mut puzzle = PuzzleGrid::new(4, 3, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &0, &1, &Direction::Right);
+        puzzle.entries.push(String::from("CAT"));
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        
+        let html = puzzle.render_html_interactive();
+        assert!(html.contains("{\"placements\":[{\"word\":\"CAT\",\"cells\":[[0,1],[1,1],[2,1]]}]}"));
+        assert!(html.contains("<td data-x=\"3\" data-y=\"2\">A</td>"));
+        assert!(html.contains("<li data-word=\"CAT\">CAT</li>"));
+        assert!(html.contains("addEventListener('mouseup'"));
+    }
+}
@@ -0,0 +1,62 @@
+use crate::PuzzleGrid;
+use super::escape_json;
+
+impl PuzzleGrid {
+    // render_ipuz - the puzzle in the ipuz interchange format as a wordsearch, so it can be opened
+    //               in puzzle apps that read ipuz
+    //
+    // The solution maps each word to the cells it covers, ipuz counts columns and rows from 1
+    // The subtitle goes in the notes and the instructions in the intro, both are left out when unset,
+    // and the puzzle_id is the uniqueid
+    pub fn render_ipuz(&self) -> String {
+        let rows: Vec<String> = self.grid.iter().map(|row| {
+            let cells: Vec<String> = row.iter().map(|c| escape_json(c)).collect();
+            format!("[{}]", cells.join(","))
+        }).collect();
+        
+        // densify copies share the word, the first placement of each entry is the one in the solution
+        let mut solution = Vec::new();
+        for entry in self.entries.iter() {
+            if let Some(p) = self.placements.iter().find(|p| p.word == *entry) {
+                let cells: Vec<String> = p.cells().iter().map(|(x, y)| format!("[{},{}]", x + 1, y + 1)).collect();
+                solution.push(format!("{}:{{\"cells\":[{}]}}", escape_json(entry), cells.join(",")));
+            }
+        }
+        
+        let mut heading = format!("\"uniqueid\":\"{}\",\"title\":{}", self.puzzle_id(), escape_json(self.display_title()));
+        if let Some(subtitle) = self.subtitle() {
+            heading.push_str(&format!(",\"notes\":{}", escape_json(subtitle)));
+        }
+        if let Some(instructions) = self.instructions() {
+            heading.push_str(&format!(",\"intro\":{}", escape_json(instructions)));
+        }
+        
+        format!("{{\"version\":\"http://ipuz.org/v2\",\"kind\":[\"http://ipuz.org/wordsearch#1\"],{},\
+                 \"dimensions\":{{\"width\":{},\"height\":{}}},\"puzzle\":[{}],\"solution\":{{{}}},\
+                 \"showanswers\":\"After\",\"zigzag\":false,\"retrace\":false}}\n",
+                heading, self.width, self.height, rows.join(","), solution.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Difficulty, Direction, PuzzleGrid};
+    
+    #[test]
+    fn ipuz_wordsearch() {
+        let mut puzzle = PuzzleGrid::new(3, 2, 10000, Difficulty::Medium);
+        puzzle.write_word("AT", &1, &0, &Direction::Down);
+        puzzle.entries.push(String::from("AT"));
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        let ipuz = puzzle.render_ipuz();
+        assert!(ipuz.contains("\"kind\":[\"http://ipuz.org/wordsearch#1\"]"));
+        assert!(ipuz.contains("\"dimensions\":{\"width\":3,\"height\":2}"));
+        assert!(ipuz.contains("\"puzzle\":[[\"A\",\"A\",\"A\"],[\"A\",\"T\",\"A\"]]"));
+        assert!(ipuz.contains("\"solution\":{\"AT\":{\"cells\":[[2,1],[2,2]]}}"));
+        assert!(ipuz.contains("\"title\":\"Word Find\",\"dimensions\""));
+        
+        puzzle.set_title(Some(String::from("Pets")));
+        puzzle.set_instructions(Some(String::from("Find the \"pets\"")));
+        assert!(puzzle.render_ipuz().contains("\"title\":\"Pets\",\"intro\":\"Find the \\\"pets\\\"\",\"dimensions\""));
+    }
+}
@@ -0,0 +1,61 @@
+use crate::PuzzleGrid;
+use super::escape_json;
+
+impl PuzzleGrid {
+    // render_json - the puzzle as json, the grid as an array of row strings, the word list, the
+    //               section headings of the word list with their words, and where each word was
+    //               placed with the path of cells it covers and whether it's the spangram, the
+    //               difficulty level from difficulty_rating, and the cells revealed as hints
+    //               The puzzle_id and then the metadata the puzzle was made with come last, the
+    //               metadata null when it wasn't set
+    //               The title, subtitle, and instructions are null when the puzzle doesn't have them
+    //
+    // {"title":"Pets","subtitle":null,"instructions":null,"width":4,"height":1,"grid":["CATS"],"words":["CAT"],
+    //  "sections":[{"heading":"Cats","words":["CAT"]}],
+    //  "placements":[{"word":"CAT","x":0,"y":0,"direction":"Right","cells":[[0,0],[1,0],[2,0]],"spangram":false}],
+    //  "difficulty":{"level":2,"rating":0.25},"revealed":[[1,0]],"id":"4f1c0e9a27b3d865",
+    //  "metadata":{"version":"0.1.0","seed":42,"puzzle":null,"width":4,"height":1,"directions":["Right","Down"],
+    //  "created":"2024-03-01T09:30:00Z"}}
+    pub fn render_json(&self) -> String {
+        let rows: Vec<String> = self.grid.iter().map(|row| escape_json(&row.concat())).collect();
+        let words: Vec<String> = self.word_bank().iter().map(|w| escape_json(w)).collect();
+        let sections: Vec<String> = self.group_lines(self.word_bank()).into_iter().filter_map(|(heading, lines)| {
+            let words: Vec<String> = lines.iter().map(|(_, w)| escape_json(w)).collect();
+            heading.map(|h| format!("{{\"heading\":{},\"words\":[{}]}}", escape_json(&h), words.join(",")))
+        }).collect();
+        let placements: Vec<String> = self.placements.iter().map(|p| {
+            let cells: Vec<String> = p.cells().iter().map(|(x, y)| format!("[{},{}]", x, y)).collect();
+            let spangram = self.spangram.as_ref() == Some(&p.word);
+            format!("{{\"word\":{},\"x\":{},\"y\":{},\"direction\":\"{:?}\",\"cells\":[{}],\"spangram\":{}}}", escape_json(&p.word), p.x, p.y, p.dir, cells.join(","), spangram)
+        }).collect();
+        
+        let difficulty = self.difficulty_rating();
+        let revealed: Vec<String> = self.revealed.iter().map(|(x, y)| format!("[{},{}]", x, y)).collect();
+        let metadata = self.metadata().map(|m| {
+            let directions: Vec<String> = m.directions.iter().map(|d| format!("\"{:?}\"", d)).collect();
+            let puzzle = m.puzzle.map(|n| n.to_string()).unwrap_or_else(|| String::from("null"));
+            format!("{{\"version\":{},\"seed\":{},\"puzzle\":{},\"width\":{},\"height\":{},\"directions\":[{}],\"created\":\"{}\"}}",
+                    escape_json(&m.version), m.seed, puzzle, m.width, m.height, directions.join(","), m.timestamp())
+        }).unwrap_or_else(|| String::from("null"));
+        let text = |t: Option<&str>| t.map(escape_json).unwrap_or_else(|| String::from("null"));
+        
+        format!("{{\"title\":{},\"subtitle\":{},\"instructions\":{},\"width\":{},\"height\":{},\"grid\":[{}],\"words\":[{}],\"sections\":[{}],\"placements\":[{}],\"difficulty\":{{\"level\":{},\"rating\":{:.2}}},\"revealed\":[{}],\"id\":\"{}\",\"metadata\":{}}}\n",
+                text(self.title()), text(self.subtitle()), text(self.instructions()), self.width, self.height, rows.join(","), words.join(","), sections.join(","), placements.join(","), difficulty.level, difficulty.rating, revealed.join(","), self.puzzle_id(), metadata)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Difficulty, Direction, PuzzleGrid};
+    
+    #[test]
+    fn json_output() {
+        let mut puzzle = PuzzleGrid::new(4, 2, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &1, &1, &Direction::Right);
+        puzzle.entries.push(String::from("CAT"));
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        puzzle.set_title(Some(String::from("Pets")));
+        assert_eq!(puzzle.render_json(), "{\"title\":\"Pets\",\"subtitle\":null,\"instructions\":null,\"width\":4,\"height\":2,\"grid\":[\"AAAA\",\"ACAT\"],\"words\":[\"CAT\"],\
+            \"sections\":[],\"placements\":[{\"word\":\"CAT\",\"x\":1,\"y\":1,\"direction\":\"Right\",\"cells\":[[1,1],[2,1],[3,1]],\"spangram\":false}],\"difficulty\":{\"level\":2,\"rating\":0.35},\"revealed\":[],\"id\":\"7fa96f41f7e680ca\",\"metadata\":null}\n");
+    }
+}
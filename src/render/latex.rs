@@ -0,0 +1,110 @@
+use crate::PuzzleGrid;
+
+impl PuzzleGrid {
+    // render_latex - a latex fragment with the grid as a tabular and the word bank in three columns,
+    //                or as many as the print style sets, meant to be pulled into a larger document with \input
+    //                With answer set the cells of the placed words are shaded
+    //                The title, subtitle, and instructions are centered above the grid when set
+    //
+    // The document needs \usepackage{multicol} and \usepackage[table]{xcolor}
+    pub fn render_latex(&self, answer: bool) -> String {
+        let mask = self.answer_mask();
+        let mut tex = String::from("% wordfindgen puzzle, needs \\usepackage{multicol} and \\usepackage[table]{xcolor}\n");
+        tex.push_str("\\begin{center}\n");
+        if let Some(title) = self.title() {
+            let title = if answer { self.answer_title() } else { String::from(title) };
+            tex.push_str(&format!("{{\\Large\\bfseries {}}}\\\\[0.5em]\n", escape_latex(&title)));
+        }
+        if let Some(subtitle) = self.subtitle() {
+            tex.push_str(&format!("{{\\large {}}}\\\\[0.5em]\n", escape_latex(subtitle)));
+        }
+        if let Some(instructions) = self.instructions() {
+            tex.push_str(&format!("{}\\\\[1em]\n", escape_latex(instructions)));
+        }
+        tex.push_str("\\renewcommand{\\arraystretch}{1.4}\n");
+        tex.push_str(&format!("\\begin{{tabular}}{{|{}}}\n\\hline\n", "c|".repeat(self.width)));
+        let starts = self.bold_starts(answer);
+        for (y, row) in self.shown_grid(answer).iter().enumerate() {
+            let cells: Vec<String> = row.iter().enumerate().map(|(x, letter)| {
+                if self.blocked(x, y) {
+                    String::from("\\cellcolor{black}")
+                } else if !answer && self.revealed.contains(&(x, y)) {
+                    format!("\\textcircled{{{}}}", escape_latex(letter))
+                } else if starts.contains(&(x, y)) {
+                    format!("\\textbf{{{}}}", escape_latex(letter))
+                } else if answer && mask[y][x] {
+                    format!("\\cellcolor{{lightgray}}{}", escape_latex(letter))
+                } else {
+                    escape_latex(letter)
+                }
+            }).collect();
+            tex.push_str(&cells.join(" & "));
+            tex.push_str(" \\\\ \\hline\n");
+        }
+        tex.push_str("\\end{tabular}\n\\end{center}\n\n");
+        
+        let columns = self.print.columns(3);
+        if columns > 1 {
+            tex.push_str(&format!("\\begin{{multicols}}{{{}}}\n", columns));
+        }
+        tex.push_str("\\noindent\n");
+        let words: Vec<String> = self.grouped_bank(answer).into_iter().flat_map(|(heading, entries)| {
+            let heading = heading.map(|h| format!("\\textbf{{{}}}", escape_latex(&h)));
+            heading.into_iter().chain(entries.iter().map(|w| escape_latex(w)).collect::<Vec<String>>())
+        }).collect();
+        tex.push_str(&words.join(" \\\\\n"));
+        tex.push('\n');
+        if columns > 1 {
+            tex.push_str("\\end{multicols}\n");
+        }
+        if let Some(id) = self.id_line() {
+            tex.push_str(&format!("\n\\hfill{{\\footnotesize {}}}\n", id));
+        }
+        tex
+    }
+}
+
+// escape_latex - make text safe to typeset, the characters latex treats specially are escaped
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            },
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Difficulty, Direction};
+    
+    #[test]
+    fn latex_tabular() {
+        let mut puzzle = PuzzleGrid::new(3, 2, 10000, Difficulty::Medium);
+        puzzle.write_word("AT", &1, &0, &Direction::Down);
+        puzzle.entries.push(String::from("AT"));
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        
+        let tex = puzzle.render_latex(false);
+        assert!(tex.contains("\\begin{tabular}{|c|c|c|}"));
+        assert!(tex.contains("A & T & A \\\\ \\hline"));
+        assert!(!tex.contains("\\cellcolor"));
+        
+        let tex = puzzle.render_latex(true);
+        assert!(tex.contains("A & \\cellcolor{lightgray}T & A \\\\ \\hline"));
+        assert_eq!(escape_latex("R&D_100%"), "R\\&D\\_100\\%");
+        
+        puzzle.set_title(Some(String::from("Pets")));
+        assert!(puzzle.render_latex(false).contains("{\\Large\\bfseries Pets}\\\\[0.5em]\n"));
+        assert!(puzzle.render_latex(true).contains("{\\Large\\bfseries Pets - Answer Key}"));
+    }
+}
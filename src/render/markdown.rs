@@ -0,0 +1,102 @@
+use crate::{column_label, PuzzleGrid};
+use super::BLOCK;
+
+impl PuzzleGrid {
+    // render_markdown - the grid as a markdown table with the word list under it
+    //                   With answer_key set the answer key follows in a collapsed <details> section
+    //                   The subtitle and instructions go between the title and the table
+    pub fn render_markdown(&self, answer_key: bool) -> String {
+        let mut md = format!("# {}\n\n", self.display_title());
+        if let Some(subtitle) = self.subtitle() {
+            md.push_str(&format!("*{}*\n\n", subtitle));
+        }
+        if let Some(instructions) = self.instructions() {
+            md.push_str(&format!("{}\n\n", instructions));
+        }
+        md.push_str(&self.markdown_table(None));
+        md.push_str("\n## Words\n");
+        md.push_str(&self.markdown_bank(false));
+        
+        if answer_key {
+            md.push_str("\n<details>\n<summary>Answer key</summary>\n\n");
+            md.push_str(&self.markdown_table(Some(&self.answer_mask())));
+            
+            // the words weren't listed with the puzzle, so they go with the answers
+            if self.puzzle_bank() != self.answer_bank() {
+                md.push_str(&self.markdown_bank(true));
+            }
+            md.push_str("\n</details>\n");
+        }
+        if let Some(id) = self.id_line() {
+            md.push_str(&format!("\n<sub>{}</sub>\n", id));
+        }
+        md
+    }
+    
+    // markdown_bank - the word bank as a list, each section under its heading, answer picks the
+    //                 word bank for the answer key
+    fn markdown_bank(&self, answer: bool) -> String {
+        let mut md = String::new();
+        for (heading, entries) in self.grouped_bank(answer) {
+            if let Some(heading) = heading {
+                md.push_str(&format!("\n### {}\n", heading));
+            }
+            md.push('\n');
+            for entry in entries.iter() {
+                md.push_str(&format!("- {}\n", entry));
+            }
+        }
+        md
+    }
+    
+    // markdown_table - the grid with the column labels as the header row
+    //                  Cells outside of mask are left empty so only the placed words show, and
+    //                  without a mask the hints and the first letters of PrintStyle::first_letters
+    //                  are in bold
+    fn markdown_table(&self, mask: Option<&[Vec<bool>]>) -> String {
+        let header: Vec<String> = (0..self.width).map(|i| column_label(i, self.width)).collect();
+        let mut table = format!("| {} |\n|{}\n", header.join(" | "), ":-:|".repeat(self.width));
+        let starts = self.bold_starts(mask.is_some());
+        for (y, row) in self.shown_grid(mask.is_some()).iter().enumerate() {
+            let cells: Vec<String> = row.iter().enumerate().map(|(x, letter)| match mask {
+                _ if self.blocked(x, y) => BLOCK.to_string(),
+                Some(mask) if !mask[y][x] => String::from(" "),
+                None if self.revealed.contains(&(x, y)) || starts.contains(&(x, y)) => format!("**{}**", letter),
+                _ => letter.clone(),
+            }).collect();
+            table.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BankStyle, Difficulty, Direction, PuzzleGrid};
+    
+    #[test]
+    fn markdown_tables() {
+        let mut puzzle = PuzzleGrid::new(3, 2, 10000, Difficulty::Medium);
+        puzzle.write_word("AT", &1, &0, &Direction::Down);
+        puzzle.entries.push(String::from("AT"));
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        
+        let md = puzzle.render_markdown(true);
+        let lines: Vec<&str> = md.lines().collect();
+        assert_eq!(lines[2], "| A | B | C |");
+        assert_eq!(lines[3], "|:-:|:-:|:-:|");
+        assert_eq!(lines[5], "| A | T | A |");
+        assert!(md.contains("- AT\n"));
+        assert!(md.contains("<summary>Answer key</summary>\n\n| A | B | C |\n|:-:|:-:|:-:|\n|   | A |   |\n|   | T |   |\n"));
+        assert!(!puzzle.render_markdown(false).contains("<details>"));
+        
+        puzzle.set_title(Some(String::from("Pets")));
+        puzzle.set_subtitle(Some(String::from("Level 1")));
+        assert!(puzzle.render_markdown(false).starts_with("# Pets\n\n*Level 1*\n\n| A | B | C |\n"));
+        
+        puzzle.set_bank_style(BankStyle::Count);
+        let md = puzzle.render_markdown(true);
+        assert!(md.contains("## Words\n\n- 1 word to find\n"));
+        assert!(md.ends_with("|   | T |   |\n\n- AT\n\n</details>\n"));
+    }
+}
@@ -0,0 +1,849 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use rand::RngCore;
+use rand::seq::SliceRandom;
+use std::time::{SystemTime, UNIX_EPOCH};
+use super::{letters, utc_timestamp, Config, Metadata, PuzzleGrid, WordfindError};
+
+mod ansi;
+mod braille;
+mod template;
+mod html;
+mod ipuz;
+mod json;
+mod latex;
+mod markdown;
+#[cfg(feature = "pdf")]
+mod pdf;
+#[cfg(feature = "png")]
+mod png;
+mod svg;
+mod xlsx;
+
+// A section heading of the word bank and its lines, each with the entry it's for
+type BankGroup<'a> = (Option<String>, Vec<(&'a String, String)>);
+
+// What a criss-cross shows in a cell no word goes through, the formats that draw the grid black it out
+pub const BLOCK: &str = "#";
+
+// Spaces between the puzzles of a double laid out side by side
+const SIDE_BY_SIDE_GAP: &str = "        ";
+
+// Upper bound on the shuffles tried to get a scrambled word that doesn't spell the word
+const MAX_SCRAMBLES: usize = 20;
+
+// Format - the kinds of file a puzzle can be written as
+//
+#[derive(Debug,Clone,Copy,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Format {
+    // comma separated rows for a spreadsheet, the answer key goes in a second file
+    Csv,
+    // a print ready page for the puzzle and a second page for the answer key, needs the pdf feature
+    Pdf,
+    // a standalone web page that prints with the answer key on its own page
+    Html,
+    // a web page that can be played in the browser by dragging across the words
+    HtmlInteractive,
+    // vector drawings that scale to any size, the answer key goes in a second file
+    Svg,
+    // images for slides and documents, the answer key goes in a second file, needs the png feature
+    Png,
+    // fixed width rows of letters with the word bank under them, the answer key goes in a second file
+    Text,
+    // the grid, word list, and placements for programs to read
+    Json,
+    // the open ipuz puzzle format, read by puzzle apps and editors
+    Ipuz,
+    // an excel workbook with the grid already formatted for printing, the answer key is a second sheet
+    Xlsx,
+    // a fragment to \input into a latex document, the answer key goes in a second file
+    Latex,
+    // a markdown table for wikis and readmes, the answer key is a collapsed section at the end
+    Markdown,
+    // braille ascii for an embosser, the answer key goes in a second file
+    Brf,
+    // the braille as unicode braille patterns for the screen, the answer key goes in a second file
+    Braille,
+}
+
+impl Format {
+    // from_name - the format for a name given on the command line
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name.to_ascii_lowercase().as_str() {
+            "csv" => Some(Format::Csv),
+            "pdf" => Some(Format::Pdf),
+            "html" => Some(Format::Html),
+            "html-interactive" => Some(Format::HtmlInteractive),
+            "svg" => Some(Format::Svg),
+            "png" => Some(Format::Png),
+            "text" | "txt" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "ipuz" => Some(Format::Ipuz),
+            "xlsx" => Some(Format::Xlsx),
+            "latex" | "tex" => Some(Format::Latex),
+            "md" | "markdown" => Some(Format::Markdown),
+            "brf" => Some(Format::Brf),
+            "braille" => Some(Format::Braille),
+            _ => None,
+        }
+    }
+    
+    // extension - the file extension for the format, without the dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Pdf => "pdf",
+            Format::Html | Format::HtmlInteractive => "html",
+            Format::Svg => "svg",
+            Format::Png => "png",
+            Format::Text => "txt",
+            Format::Json => "json",
+            Format::Ipuz => "ipuz",
+            Format::Xlsx => "xlsx",
+            Format::Latex => "tex",
+            Format::Markdown => "md",
+            Format::Brf => "brf",
+            Format::Braille => "txt",
+        }
+    }
+}
+
+// AnswerStyle - how the csv and text answer keys show the filler letters around the placed words
+//
+#[derive(Debug,Clone,Copy,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AnswerStyle {
+    // only the placed words, the filler is left out
+    Blank,
+    // the filler in lowercase so the answer key lines up with the puzzle letter for letter
+    Lowercase,
+    // a dot in place of every filler letter
+    Dots,
+}
+
+impl AnswerStyle {
+    // from_name - the style for a name given on the command line
+    pub fn from_name(name: &str) -> Option<AnswerStyle> {
+        match name.to_ascii_lowercase().as_str() {
+            "blank" => Some(AnswerStyle::Blank),
+            "lowercase" => Some(AnswerStyle::Lowercase),
+            "dots" => Some(AnswerStyle::Dots),
+            _ => None,
+        }
+    }
+}
+
+// WordOrder - the order the word bank is printed in
+//
+#[derive(Debug,Clone,Copy,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WordOrder {
+    // the order of the word list
+    Original,
+    // a to z, the way published puzzles list them
+    Alphabetical,
+    // a random order so the list gives no hints about where the words are
+    Shuffled,
+    // shortest to longest, a to z within each length
+    Length,
+}
+
+impl WordOrder {
+    // from_name - the order for a name given on the command line
+    pub fn from_name(name: &str) -> Option<WordOrder> {
+        match name.to_ascii_lowercase().as_str() {
+            "original" => Some(WordOrder::Original),
+            "alpha" | "alphabetical" => Some(WordOrder::Alphabetical),
+            "shuffled" | "shuffle" => Some(WordOrder::Shuffled),
+            "length" => Some(WordOrder::Length),
+            _ => None,
+        }
+    }
+}
+
+// BankStyle - what the puzzle shows in place of the word bank, the answer key always lists the words
+//
+#[derive(Debug,Clone,Copy,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BankStyle {
+    // the words to find
+    Words,
+    // nothing, the expert variant where the solver doesn't know what to look for
+    Hidden,
+    // only how many words there are to find
+    Count,
+    // each word with its letters scrambled, the solver unscrambles it before looking for it
+    Scrambled,
+}
+
+impl BankStyle {
+    // from_name - the style for a name given on the command line
+    pub fn from_name(name: &str) -> Option<BankStyle> {
+        match name.to_ascii_lowercase().as_str() {
+            "words" => Some(BankStyle::Words),
+            "hidden" | "none" => Some(BankStyle::Hidden),
+            "count" => Some(BankStyle::Count),
+            "scrambled" | "anagram" => Some(BankStyle::Scrambled),
+            _ => None,
+        }
+    }
+}
+
+// ImageOptions - sizes in pixels for image output
+//
+#[derive(Debug,Clone,Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImageOptions {
+    // width and height of each grid cell
+    pub cell_size: usize,
+    // blank border around the grid and word bank
+    pub margin: usize,
+    // height of the letters in the grid, rounded down to a multiple of 7
+    pub font_size: usize,
+}
+
+impl Default for ImageOptions {
+    fn default() -> ImageOptions {
+        ImageOptions { cell_size: 40, margin: 20, font_size: 28 }
+    }
+}
+
+// A US letter page in points, with a 3/4 inch margin all around, for the pdf pages and for checking
+// that a large print puzzle fits on one
+pub(crate) const PAGE_WIDTH: f32 = 612.0;
+pub(crate) const PAGE_HEIGHT: f32 = 792.0;
+pub(crate) const PAGE_MARGIN: f32 = 54.0;
+
+// Size of the word bank in points when the letters aren't set by PrintStyle::letter_size
+pub(crate) const BANK_FONT: f32 = 12.0;
+
+// Height of the letters in the grid for --large-print, in points
+pub const LARGE_PRINT_SIZE: f32 = 24.0;
+
+// The grid letters take up this much of the height of their cell
+pub(crate) const LETTER_FRACTION: f32 = 0.6;
+
+// PrintStyle - the look of the pdf, html, and svg output, for solvers who need bigger or clearer
+//              letters than the defaults, and how many columns the word bank takes in every format
+//
+#[derive(Debug,Clone,Default,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrintStyle {
+    // height of the grid letters in points with the cells sized to fit them and the word bank
+    // three quarters of it, None sizes the grid to fill the page
+    pub letter_size: Option<f32>,
+    // the font, a css font family for html and svg, for pdf one with serif or times in its name
+    // is Times and one with mono or courier is Courier, anything else is Helvetica
+    pub font: Option<String>,
+    // bold black letters and heavy borders, with the answer key in white on black in place of the
+    // light shading
+    pub high_contrast: bool,
+    // columns of the word bank, each filled before the next with none more than a word longer than
+    // another, None for the format's own, three for pdf, png, svg, xlsx, html, and latex, one for
+    // text, and Config::words_per_row a row for csv, markdown and braille are always one
+    pub bank_columns: Option<usize>,
+    // number the words of the answer key, the number at the cell each word starts at and before
+    // it in the word bank, a superscript for html and pdf and a legend with the cells for csv
+    pub numbered_answers: bool,
+    // bold the first letter of every word on the puzzle, a hint short of circling letters with
+    // Config::reveal, for pdf, png, svg, html, latex, markdown, xlsx, and templates
+    pub first_letters: bool,
+}
+
+impl PrintStyle {
+    // large_print - the style for low vision solvers, 24 point letters in high contrast
+    pub fn large_print() -> PrintStyle {
+        PrintStyle { letter_size: Some(LARGE_PRINT_SIZE), font: None, high_contrast: true, bank_columns: None, numbered_answers: false, first_letters: false }
+    }
+    
+    // columns - the columns of the word bank, or the format's own number of them
+    pub(crate) fn columns(&self, default: usize) -> usize {
+        self.bank_columns.unwrap_or(default).max(1)
+    }
+    
+    // bank_font - the size of the word bank in points
+    pub(crate) fn bank_font(&self) -> f32 {
+        self.letter_size.map_or(BANK_FONT, |size| (size * 0.75).max(BANK_FONT))
+    }
+}
+
+// balanced_columns - the lines of a word bank split into columns, filled one at a time, with the
+//                    first ones a line longer when the lines don't come out even
+pub(crate) fn balanced_columns<T>(lines: &[T], columns: usize) -> Vec<&[T]> {
+    let columns = columns.max(1);
+    let (rows, extra) = (lines.len() / columns, lines.len() % columns);
+    let mut split = Vec::new();
+    let mut rest = lines;
+    for column in 0..columns {
+        let (lines, after) = rest.split_at(rows + (column < extra) as usize);
+        if !lines.is_empty() {
+            split.push(lines);
+        }
+        rest = after;
+    }
+    split
+}
+
+impl PuzzleGrid {
+    // check_page - with PrintStyle::letter_size set, that the puzzle and its word bank fit on one
+    //              printed page with letters that size, the pages are never split
+    pub(crate) fn check_page(&self) -> Result<(), WordfindError> {
+        let size = match self.print.letter_size {
+            Some(size) => size,
+            None => return Ok(()),
+        };
+        let cell = size / LETTER_FRACTION;
+        let bank_line = self.print.bank_font() * 4.0 / 3.0;
+        let bank_rows = self.printed_bank(false).len().max(self.printed_bank(true).len()).div_ceil(self.print.columns(3));
+        let heading: f32 = [Some(24.0), self.subtitle().map(|_| 16.0), self.instructions().map(|_| self.print.bank_font())].iter().flatten().map(|s| s * 1.4).sum();
+        let height = heading + bank_line + cell * self.height as f32 + bank_line * (bank_rows + 1) as f32;
+        if cell * self.width as f32 > PAGE_WIDTH - 2.0 * PAGE_MARGIN || height > PAGE_HEIGHT - 2.0 * PAGE_MARGIN {
+            return Err(WordfindError::TooBigToPrint { width: self.width, height: self.height, letter_size: size });
+        }
+        Ok(())
+    }
+    
+    // check_pdf_text - that every letter of the grid, the word bank, and the headings is in the
+    //                  Latin-1 letters of the pdf's built in font, the others would print as '?'
+    pub(crate) fn check_pdf_text(&self) -> Result<(), WordfindError> {
+        let bank = self.printed_bank(false);
+        let headings = [self.title(), self.subtitle(), self.instructions()];
+        let text = self.grid.iter().flatten().chain(bank.iter()).map(String::as_str).chain(headings.iter().flatten().copied());
+        match text.flat_map(str::chars).find(|c| !matches!(c, ' '..='~' | '\u{a0}'..='\u{ff}')) {
+            Some(letter) => Err(WordfindError::NotInPdfFont { letter }),
+            None => Ok(()),
+        }
+    }
+    
+    // display_title - the title to put on the puzzle, a generic one when it doesn't have one
+    fn display_title(&self) -> &str {
+        self.title().unwrap_or("Word Find")
+    }
+    
+    // shown_grid - the grid the way the puzzle prints it, or the answer key with answer set, a
+    //              criss-cross puzzle has an empty box for each letter of the words that isn't a
+    //              hint and both have BLOCK in the cells between them
+    pub fn shown_grid(&self, answer: bool) -> Vec<Vec<String>> {
+        self.grid.iter().enumerate().map(|(y, row)| {
+            row.iter().enumerate().map(|(x, cell)| match cell {
+                _ if self.blocked(x, y) => String::from(BLOCK),
+                _ if self.hides_letter(x, y, answer) => String::from(" "),
+                _ => cell.clone(),
+            }).collect()
+        }).collect()
+    }
+    
+    // hides_letter - whether the puzzle, or the answer key with answer set, leaves the letter of the
+    //                cell out for the solver to write in, the letters of a criss-cross that aren't hints
+    fn hides_letter(&self, x: usize, y: usize, answer: bool) -> bool {
+        self.crisscross && !answer && !self.revealed.contains(&(x, y))
+    }
+    
+    // id_line - the puzzle_id as it's printed at the foot of the puzzle and the answer key, for a
+    //           puzzle with metadata, the ones the program made or read back
+    pub(crate) fn id_line(&self) -> Option<String> {
+        self.metadata.as_ref().map(|_| format!("Puzzle ID {}", self.puzzle_id()))
+    }
+    
+    // hint_list - the revealed letters with the column and row they're in counted from 1 like the
+    //             labels, for the formats that can't circle them in the grid, "C (1, 3)"
+    pub fn hint_list(&self) -> Vec<String> {
+        self.revealed.iter().map(|(x, y)| format!("{} ({}, {})", self.grid[*y][*x], x + 1, y + 1)).collect()
+    }
+    
+    // answer_grid - the filled in grid with the letters of the placed words in uppercase and the
+    //               filler shown the way the style asks for, cells left out of a shaped grid stay blank
+    //               and the cells between the words of a criss-cross are BLOCK
+    pub fn answer_grid(&self, style: AnswerStyle) -> Vec<Vec<String>> {
+        let mask = self.answer_mask();
+        self.grid.iter().zip(mask.iter()).enumerate().map(|(y, (row, placed))| {
+            row.iter().zip(placed.iter()).enumerate().map(|(x, (cell, placed))| match (placed, style) {
+                (true, _) => cell.to_uppercase(),
+                (false, _) if self.blocked(x, y) => String::from(BLOCK),
+                (false, _) if cell == " " => cell.clone(),
+                (false, AnswerStyle::Blank) => String::from(" "),
+                (false, AnswerStyle::Lowercase) => cell.to_lowercase(),
+                (false, AnswerStyle::Dots) => String::from("."),
+            }).collect()
+        }).collect()
+    }
+    
+    // puzzle_bank - the lines printed under the puzzle in place of the word bank, in the bank style
+    pub fn puzzle_bank(&self) -> Vec<String> {
+        match self.bank_style {
+            BankStyle::Words => self.bank().into_iter().map(|(entry, printed)| match self.clue(entry) {
+                Some(clue) => clue.to_string(),
+                None => printed,
+            }).collect(),
+            BankStyle::Hidden => Vec::new(),
+            BankStyle::Count if self.entries.len() == 1 => vec![String::from("1 word to find")],
+            BankStyle::Count => vec![format!("{} words to find", self.entries.len())],
+            BankStyle::Scrambled => self.word_bank().into_iter().map(|printed| match self.scrambles.iter().find(|(word, _)| *word == printed) {
+                Some((_, scrambled)) => scrambled.clone(),
+                None => printed,
+            }).collect(),
+        }
+    }
+    
+    // scramble_bank - mix up the letters of each word in the word bank for BankStyle::Scrambled
+    //                 Spaces and punctuation stay where they are, and a word only comes out the same
+    //                 when every ordering of its letters spells it, like AAA
+    pub fn scramble_bank(&mut self, rng: &mut dyn RngCore) {
+        self.scrambles = self.word_bank().into_iter().map(|printed| {
+            let letters = letters(&printed);
+            let slots: Vec<usize> = (0..letters.len()).filter(|i| letters[*i].chars().next().is_some_and(char::is_alphanumeric)).collect();
+            let mut scrambled = letters.clone();
+            for _ in 0..MAX_SCRAMBLES {
+                let mut mixed: Vec<&String> = slots.iter().map(|i| &letters[*i]).collect();
+                mixed.shuffle(rng);
+                for (i, letter) in slots.iter().zip(mixed) {
+                    scrambled[*i] = letter.clone();
+                }
+                if scrambled != letters { break; }
+            }
+            (printed, scrambled.concat())
+        }).collect();
+    }
+    
+    // answer_bank - the word bank for the answer key, a word that has a clue follows its clue
+    //
+    // the tallest animal: GIRAFFE
+    pub fn answer_bank(&self) -> Vec<String> {
+        self.bank().into_iter().enumerate().map(|(i, (entry, printed))| {
+            let line = match self.clue(entry) {
+                Some(clue) => format!("{}: {}", clue, printed),
+                None => printed,
+            };
+            if self.print.numbered_answers { format!("{}. {}", i + 1, line) } else { line }
+        }).collect()
+    }
+    
+    // answer_numbers - the number of each placed word of the word bank for
+    //                  PrintStyle::numbered_answers, counted from 1 in word bank order, with the word
+    //                  as it's printed and the cell it starts at
+    pub(crate) fn answer_numbers(&self) -> Vec<(usize, String, (usize, usize))> {
+        self.bank().into_iter().enumerate().filter_map(|(i, (entry, printed))| {
+            // densify copies share the word, the first placement is the one in the word list
+            self.placements.iter().find(|p| p.word == *entry).map(|p| (i + 1, printed, (p.x, p.y)))
+        }).collect()
+    }
+    
+    // start_numbers - the numbers of the words that start in each cell, "1,4" when two do, or
+    //                 nothing for every cell without PrintStyle::numbered_answers
+    pub(crate) fn start_numbers(&self) -> Vec<Vec<String>> {
+        let mut numbers = vec![vec![String::new(); self.width]; self.height];
+        for (number, _, (x, y)) in self.answer_numbers().into_iter().filter(|_| self.print.numbered_answers) {
+            if !numbers[y][x].is_empty() {
+                numbers[y][x].push(',');
+            }
+            numbers[y][x].push_str(&number.to_string());
+        }
+        numbers
+    }
+    
+    // bold_starts - the cells the words start at, bolded on the puzzle with
+    //               PrintStyle::first_letters, and none on the answer key or without it
+    pub(crate) fn bold_starts(&self, answer: bool) -> Vec<(usize, usize)> {
+        if answer || !self.print.first_letters {
+            return Vec::new();
+        }
+        // densify copies share the word, only the first placement is bolded
+        self.entries.iter().filter_map(|entry| self.placements.iter().find(|p| p.word == *entry)).map(|p| (p.x, p.y)).collect()
+    }
+    
+    // clue - the clue printed in place of the entry in the word bank, if it has one
+    pub fn clue(&self, entry: &str) -> Option<&str> {
+        self.clues.iter().find(|(placed, _)| placed == entry).map(|(_, clue)| clue.as_str())
+    }
+    
+    // section - the heading of the word list section the entry was listed under, if it was in one
+    pub fn section(&self, entry: &str) -> Option<&str> {
+        self.sections.iter().find(|(placed, _)| placed == entry).map(|(_, heading)| heading.as_str())
+    }
+    
+    // grouped_bank - the word bank for the puzzle, or for the answer key with answer set, split up
+    //                under the section headings of the word list in the order they were listed
+    //                Words that weren't in a section come first with no heading, and a word bank
+    //                that isn't a line per word, like BankStyle::Count, is all in one group
+    pub fn grouped_bank(&self, answer: bool) -> Vec<(Option<String>, Vec<String>)> {
+        let lines = if answer { self.answer_bank() } else { self.puzzle_bank() };
+        if self.sections.is_empty() || lines.len() != self.entries.len() {
+            return vec![(None, lines)];
+        }
+        self.group_lines(lines).into_iter()
+            .map(|(heading, lines)| (heading, lines.into_iter().map(|(_, line)| line).collect()))
+            .collect()
+    }
+    
+    // group_lines - a word bank with a line per entry, in word bank order, grouped by section with
+    //               the entry each line is for
+    pub(crate) fn group_lines(&self, lines: Vec<String>) -> Vec<BankGroup<'_>> {
+        let bank = self.bank();
+        let mut groups: Vec<BankGroup> = Vec::new();
+        for heading in std::iter::once(None).chain(self.sections.iter().map(|(_, h)| Some(h.as_str()))) {
+            if groups.iter().any(|(h, _)| h.as_deref() == heading) {
+                continue;
+            }
+            let group: Vec<(&String, String)> = bank.iter().zip(lines.iter())
+                .filter(|((entry, _), _)| self.section(entry) == heading)
+                .map(|((entry, _), line)| (*entry, line.clone()))
+                .collect();
+            if !group.is_empty() {
+                groups.push((heading.map(String::from), group));
+            }
+        }
+        groups
+    }
+    
+    // printed_bank - the word bank for the puzzle, or for the answer key with answer set, as lines
+    //                with each section heading on a line of its own before its words, "Animals:"
+    pub(crate) fn printed_bank(&self, answer: bool) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (heading, words) in self.grouped_bank(answer) {
+            if let Some(heading) = heading {
+                lines.push(format!("{}:", heading));
+            }
+            lines.extend(words);
+        }
+        lines
+    }
+    
+    // answer_title - the heading for the answer key
+    fn answer_title(&self) -> String {
+        match self.title() {
+            Some(title) => format!("{} - Answer Key", title),
+            None => String::from("Answer Key"),
+        }
+    }
+}
+
+// write_files - write the filled in puzzle and its answer key in the configured format and each of
+//               Config::more_formats, and the answer list when one was asked for, and return the
+//               paths of the files that were written
+//               Every format is rendered before anything is written, so a puzzle too big for one
+//               of them doesn't leave the others behind
+//               An output of "-" sends the puzzle to stdout, the answer key still goes to its file
+pub(crate) fn write_files(puzzle: &PuzzleGrid, config: &Config) -> Result<Vec<PathBuf>, WordfindError> {
+    let configs: Vec<Config> = std::iter::once(config.clone()).chain(config.more_formats.iter().map(|format| format_config(config, *format))).collect();
+    let rendered = configs.iter().map(|config| render_files(puzzle, config)).collect::<Result<Vec<_>, _>>()?;
+    let mut written = Vec::new();
+    for (i, (config, (contents, answer))) in configs.iter().zip(rendered).enumerate() {
+        let mut lists = Vec::new();
+        if let Some(name) = config.answer_list.as_ref().filter(|_| i == 0) {
+            lists.push((name, puzzle.answer_list()));
+        }
+        if let Some(name) = config.hint_file.as_ref().filter(|_| i == 0) {
+            lists.push((name, puzzle.first_letters()));
+        }
+        written.extend(write_output(puzzle, config, &output_extension(config), contents, answer, lists)?);
+    }
+    Ok(written)
+}
+
+// format_config - the config for writing the puzzle in one of Config::more_formats, with the
+//                 extension of the output names given changed to the format's, puzzle_01.csv is
+//                 puzzle_01.pdf
+fn format_config(config: &Config, format: Format) -> Config {
+    let from = format!(".{}", output_extension(config));
+    let to = format!(".{}", format.extension());
+    let renamed = |name: &String| match name.strip_suffix(&from) {
+        Some(stem) => format!("{}{}", stem, to),
+        None => format!("{}{}", name, to),
+    };
+    Config { format, more_formats: Vec::new(), output: config.output.as_ref().map(renamed), answer_output: config.answer_output.as_ref().map(renamed), ..config.clone() }
+}
+
+// output_extension - the extension of the files written for the config, the template's own when
+//                    there is one and the format's when there isn't
+pub(crate) fn output_extension(config: &Config) -> String {
+    match &config.template {
+        Some(template) => Path::new(template).extension().map(|e| e.to_string_lossy().into_owned()).unwrap_or_else(|| String::from("txt")),
+        None => config.format.extension().to_string(),
+    }
+}
+
+// render_files - the puzzle in Config::format, and its answer key for the formats that put it in a
+//                file of its own unless Config::combined puts it in with the puzzle
+pub(crate) fn render_files(puzzle: &PuzzleGrid, config: &Config) -> Result<(Vec<u8>, Option<Vec<u8>>), WordfindError> {
+    let (contents, answer) = separate_files(puzzle, config)?;
+    Ok(match answer {
+        Some(answer) if config.combined && config.answer_key => (combined_file(puzzle, config, contents, answer), None),
+        answer => (contents, answer),
+    })
+}
+
+// combined_file - the puzzle and its answer key in one file for Config::combined, a smaller answer
+//                 key beside the puzzle for svg and png, beside it for text, under it for csv and
+//                 templates, and on the page after it for latex and braille
+fn combined_file(puzzle: &PuzzleGrid, config: &Config, contents: Vec<u8>, answer: Vec<u8>) -> Vec<u8> {
+    match config.format {
+        _ if config.template.is_some() => [contents, answer].concat(),
+        Format::Text => side_by_side(&[String::from_utf8_lossy(&contents).into_owned(), String::from_utf8_lossy(&answer).into_owned()]).into_bytes(),
+        Format::Latex => [contents, b"\\newpage\n".to_vec(), answer].concat(),
+        // a form feed starts the answer key on a new embosser page
+        Format::Brf | Format::Braille => [contents, b"\x0c".to_vec(), answer].concat(),
+        Format::Svg => puzzle.render_svg_combined().into_bytes(),
+        #[cfg(feature = "png")]
+        Format::Png => puzzle.render_png_combined(&config.image),
+        _ => [contents, b"\n".to_vec(), answer].concat(),
+    }
+}
+
+// separate_files - the puzzle and, for the formats that keep it in a file of its own, the answer key
+fn separate_files(puzzle: &PuzzleGrid, config: &Config) -> Result<(Vec<u8>, Option<Vec<u8>>), WordfindError> {
+    let csv = |answer: bool| -> io::Result<Vec<u8>> {
+        let mut out = Vec::new();
+        puzzle.write_csv(&mut out, config, answer)?;
+        Ok(out)
+    };
+    
+    if let Some(template) = &config.template {
+        let template = fs::read_to_string(template)?;
+        return Ok((puzzle.render_template(&template, false)?.into_bytes(), Some(puzzle.render_template(&template, true)?.into_bytes())));
+    }
+    
+    match config.format {
+        Format::Pdf => {
+            puzzle.check_page()?;
+            puzzle.check_pdf_text()?;
+        },
+        Format::Html => puzzle.check_page()?,
+        Format::Brf | Format::Braille => puzzle.check_braille()?,
+        _ => {},
+    }
+    
+    // formats that put the answer key in the same file as the puzzle have no separate answer key
+    Ok(match config.format {
+        Format::Csv => (csv(false)?, Some(csv(true)?)),
+        Format::Text => (puzzle.render_text(config.label).into_bytes(), Some(puzzle.render_text_answer(config.label, config.answer_style).into_bytes())),
+        #[cfg(feature = "pdf")]
+        Format::Pdf => (puzzle.render_pdf(config.answer_key), None),
+        #[cfg(not(feature = "pdf"))]
+        Format::Pdf => return Err(WordfindError::MissingFeature { feature: String::from("pdf") }),
+        Format::Html => (puzzle.render_html(config.answer_key).into_bytes(), None),
+        Format::HtmlInteractive => (puzzle.render_html_interactive().into_bytes(), None),
+        Format::Json => (puzzle.render_json().into_bytes(), None),
+        Format::Ipuz => (puzzle.render_ipuz().into_bytes(), None),
+        Format::Xlsx => (puzzle.render_xlsx(config.answer_key), None),
+        Format::Latex => (puzzle.render_latex(false).into_bytes(), Some(puzzle.render_latex(true).into_bytes())),
+        Format::Markdown => (puzzle.render_markdown(config.answer_key).into_bytes(), None),
+        Format::Brf => (puzzle.render_brf(false).into_bytes(), Some(puzzle.render_brf(true).into_bytes())),
+        Format::Braille => (puzzle.render_braille(false).into_bytes(), Some(puzzle.render_braille(true).into_bytes())),
+        Format::Svg => (puzzle.render_svg(false).into_bytes(), Some(puzzle.render_svg(true).into_bytes())),
+        #[cfg(feature = "png")]
+        Format::Png => (puzzle.render_png(false, &config.image), Some(puzzle.render_png(true, &config.image))),
+        #[cfg(not(feature = "png"))]
+        Format::Png => return Err(WordfindError::MissingFeature { feature: String::from("png") }),
+    })
+}
+
+// write_double - write the two puzzles of Config::double into one file, side by side for text and
+//                a page each for pdf, with their answer keys in the answer key file or on pages
+//                after them, and html for every other format
+pub(crate) fn write_double(puzzles: &[PuzzleGrid], config: &Config) -> Result<Vec<PathBuf>, WordfindError> {
+    for puzzle in puzzles.iter().filter(|_| config.format != Format::Text) {
+        puzzle.check_page()?;
+        if config.format == Format::Pdf {
+            puzzle.check_pdf_text()?;
+        }
+    }
+    let (contents, answer, extension): (Vec<u8>, Option<Vec<u8>>, &str) = match config.format {
+        Format::Text => {
+            let puzzle: Vec<String> = puzzles.iter().map(|p| p.render_text(config.label)).collect();
+            let answer: Vec<String> = puzzles.iter().map(|p| p.render_text_answer(config.label, config.answer_style)).collect();
+            (side_by_side(&puzzle).into_bytes(), Some(side_by_side(&answer).into_bytes()), "txt")
+        },
+        #[cfg(feature = "pdf")]
+        Format::Pdf => (pdf::render_pdf_pages(puzzles, config.answer_key), None, "pdf"),
+        #[cfg(not(feature = "pdf"))]
+        Format::Pdf => return Err(WordfindError::MissingFeature { feature: String::from("pdf") }),
+        _ => (html::render_html_pages(puzzles, config.answer_key).into_bytes(), None, "html"),
+    };
+    write_output(&puzzles[0], config, extension, contents, answer, Vec::new())
+}
+
+// write_output - write the puzzle, its answer key when there is one and Config::answer_key is set,
+//                and the text files in lists, like the answer list, to their files in
+//                Config::output_dir, and return the paths of the files that were written
+//                The tokens in the file names are filled in from the puzzle, and nothing is written
+//                when one of the files is already there unless Config::force is set
+//                A puzzle piped to stdout only writes its answer key with Config::answer_output,
+//                so running it again doesn't stop on the answer key the last run left behind
+fn write_output(puzzle: &PuzzleGrid, config: &Config, extension: &str, contents: Vec<u8>, answer: Option<Vec<u8>>, lists: Vec<(&String, String)>) -> Result<Vec<PathBuf>, WordfindError> {
+    let dir = match &config.output_dir {
+        Some(dir) => {
+            fs::create_dir_all(dir)?;
+            PathBuf::from(dir)
+        },
+        None => PathBuf::new(),
+    };
+    let name = |name: &str| dir.join(file_name(name, puzzle.metadata.as_ref(), puzzle.display_title()));
+    let output = config.output.clone().unwrap_or_else(|| format!("puzzle.{}", extension));
+    let answer_output = name(&answer_output(config, extension));
+    
+    let to_stdout = output == "-";
+    let mut files = Vec::new();
+    if let (true, Some(answer)) = (config.answer_key && (!to_stdout || config.answer_output.is_some()), answer) {
+        files.push((answer_output, answer));
+    }
+    for (list, text) in lists {
+        files.push((name(list), text.into_bytes()));
+    }
+    if !to_stdout {
+        files.push((name(&output), contents.clone()));
+    }
+    check_overwrite(config, files.iter().map(|(path, _)| path))?;
+    
+    let mut written = Vec::new();
+    for (path, contents) in files {
+        fs::write(&path, contents)?;
+        written.push(path);
+    }
+    if to_stdout {
+        io::stdout().write_all(&contents)?;
+    }
+    Ok(written)
+}
+
+// answer_output - the file the answer key goes to, Config::answer_output, or answer_key.<extension>
+//                 when it isn't set, or the puzzle's name with _answer_key before its extension
+//                 when that has tokens in it, so "{seed}.csv" gets 42_answer_key.csv and each
+//                 puzzle of a batch run has an answer key of its own
+pub(crate) fn answer_output(config: &Config, extension: &str) -> String {
+    match (&config.answer_output, config.output.as_deref()) {
+        (Some(answer_output), _) => answer_output.clone(),
+        (None, Some(output)) if output.contains('{') => match output.rfind('.').filter(|dot| !output[*dot..].contains('/')) {
+            Some(dot) => format!("{}_answer_key{}", &output[..dot], &output[dot..]),
+            None => format!("{}_answer_key.{}", output, extension),
+        },
+        (None, _) => format!("answer_key.{}", extension),
+    }
+}
+
+// file_name - the name with the {date}, {seed}, and {title} tokens filled in, the day the puzzle was
+//             made, the seed it was made from, and the title with everything but its letters and
+//             digits turned into underscores, so batch runs can each have files of their own,
+//             "{title}_{seed}.csv" is zoo_animals_42.csv
+pub(crate) fn file_name(name: &str, metadata: Option<&Metadata>, title: &str) -> String {
+    if !name.contains('{') {
+        return name.to_string();
+    }
+    let created = metadata.map(|m| m.created).unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0));
+    let mut slug = String::new();
+    for c in title.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('_') {
+            slug.push('_');
+        }
+    }
+    name.replace("{date}", &utc_timestamp(created)[..10])
+        .replace("{seed}", &metadata.map(|m| m.seed.to_string()).unwrap_or_default())
+        .replace("{title}", slug.trim_end_matches('_'))
+}
+
+// check_overwrite - a FileExists error for the first of the files that's already there, unless
+//                   Config::force is set
+fn check_overwrite<'a>(config: &Config, mut files: impl Iterator<Item = &'a PathBuf>) -> Result<(), WordfindError> {
+    match files.find(|path| !config.force && path.exists()) {
+        Some(path) => Err(WordfindError::FileExists { path: path.clone() }),
+        None => Ok(()),
+    }
+}
+
+// write_book - write the puzzles of a book into one document, pdf with the pdf format and html for
+//              everything else, to Config::output or book.<extension>
+pub(crate) fn write_book(puzzles: &[PuzzleGrid], config: &Config) -> Result<Vec<PathBuf>, WordfindError> {
+    let title = config.title.as_deref().unwrap_or("Word Find Puzzles");
+    for puzzle in puzzles.iter() {
+        puzzle.check_page()?;
+        if config.format == Format::Pdf {
+            puzzle.check_pdf_text()?;
+        }
+    }
+    let (contents, extension) = match config.format {
+        #[cfg(feature = "pdf")]
+        Format::Pdf => (pdf::render_pdf_book(title, puzzles), "pdf"),
+        #[cfg(not(feature = "pdf"))]
+        Format::Pdf => return Err(WordfindError::MissingFeature { feature: String::from("pdf") }),
+        _ => (html::render_html_book(title, puzzles).into_bytes(), "html"),
+    };
+    let output = config.output.clone().unwrap_or_else(|| format!("book.{}", extension));
+    if output == "-" {
+        io::stdout().write_all(&contents)?;
+        return Ok(Vec::new());
+    }
+    let output = file_name(&output, puzzles.first().and_then(|p| p.metadata.as_ref()), title);
+    let output = match &config.output_dir {
+        Some(dir) => {
+            fs::create_dir_all(dir)?;
+            PathBuf::from(dir).join(output)
+        },
+        None => PathBuf::from(output),
+    };
+    check_overwrite(config, std::iter::once(&output))?;
+    fs::write(&output, contents)?;
+    Ok(vec![output])
+}
+
+// side_by_side - blocks of text next to each other, each padded out to its widest line
+fn side_by_side(blocks: &[String]) -> String {
+    let lines: Vec<Vec<&str>> = blocks.iter().map(|block| block.lines().collect()).collect();
+    let widths: Vec<usize> = lines.iter().map(|block| block.iter().map(|line| line.chars().count()).max().unwrap_or(0)).collect();
+    let rows = lines.iter().map(Vec::len).max().unwrap_or(0);
+    let mut text = String::new();
+    for row in 0..rows {
+        let parts: Vec<String> = lines.iter().zip(widths.iter()).map(|(block, width)| format!("{:w$}", block.get(row).copied().unwrap_or(""), w = width)).collect();
+        text.push_str(parts.join(SIDE_BY_SIDE_GAP).trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+// escape_xml - make text safe to put inside html, svg, or any other xml
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// escape_json - the text as a quoted json string
+//               '<' is escaped too so the json can sit inside an html script tag
+pub(crate) fn escape_json(text: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '<' => escaped.push_str("\\u003c"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            _ => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+// crc32 - the checksum png chunks and zip entries use
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
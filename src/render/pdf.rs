@@ -0,0 +1,414 @@
+use crate::{Metadata, PuzzleGrid};
+use super::{balanced_columns, PrintStyle, BANK_FONT, LETTER_FRACTION, PAGE_HEIGHT, PAGE_MARGIN as MARGIN, PAGE_WIDTH};
+
+// the word bank is laid out in this many columns under the grid unless the print style sets them
+const BANK_COLUMNS: usize = 3;
+
+// line spacing of a book's contents page
+const CONTENTS_LINE: f32 = 22.0;
+
+// cells never get bigger than half an inch, even for tiny grids
+const MAX_CELL: f32 = 36.0;
+
+impl PuzzleGrid {
+    // render_pdf - a pdf of the puzzle with its word bank, with answer_key set a second page
+    //              follows with the letters of the placed words shaded
+    //
+    // Only the standard fonts are used so nothing has to be embedded, see PrintStyle::font
+    pub fn render_pdf(&self, answer_key: bool) -> Vec<u8> {
+        let mut pages = vec![self.pdf_page(&self.pdf_heading(), None)];
+        if answer_key {
+            pages.push(self.pdf_answer_page());
+        }
+        pdf_document(&pages, self.display_title(), self.metadata(), &self.print)
+    }
+    
+    // pdf_heading - the title, subtitle, and instructions above the puzzle with their font sizes
+    fn pdf_heading(&self) -> Vec<(String, f32)> {
+        let mut heading = vec![(self.display_title().to_string(), 24.0)];
+        if let Some(subtitle) = self.subtitle() {
+            heading.push((subtitle.to_string(), 16.0));
+        }
+        if let Some(instructions) = self.instructions() {
+            heading.push((instructions.to_string(), self.print.bank_font()));
+        }
+        heading
+    }
+    
+    // pdf_answer_page - the page with the letters of the placed words shaded
+    fn pdf_answer_page(&self) -> String {
+        self.pdf_page(&[(self.answer_title(), 24.0)], Some(&self.answer_mask()))
+    }
+    
+    // pdf_page - the drawing operators for one page, heading is the centered lines of text above the
+    //            grid and their font sizes, shade marks the cells to highlight
+    fn pdf_page(&self, heading: &[(String, f32)], shade: Option<&[Vec<bool>]>) -> String {
+        let mut ops = String::new();
+        
+        let mut top = PAGE_HEIGHT - MARGIN;
+        for (line, size) in heading.iter() {
+            top -= size;
+            ops.push_str(&text_op(line, (PAGE_WIDTH - text_width(line, *size)) / 2.0, top, *size));
+            top -= size * 0.4;
+        }
+        
+        // the grid gets whatever room is left over after the heading and the word bank, unless the
+        // print style sets the size of the letters
+        let bank_font = self.print.bank_font();
+        let bank_line = bank_font * 4.0 / 3.0;
+        let bank = self.printed_bank(shade.is_some());
+        let columns = self.print.columns(BANK_COLUMNS);
+        let bank_rows = bank.len().div_ceil(columns);
+        let bank_height = bank_rows as f32 * bank_line + bank_line;
+        let grid_top = top - bank_line;
+        let cell = match self.print.letter_size {
+            Some(size) => size / LETTER_FRACTION,
+            None => ((PAGE_WIDTH - 2.0 * MARGIN) / self.width as f32)
+                .min((grid_top - MARGIN - bank_height) / self.height as f32)
+                .clamp(4.0, MAX_CELL),
+        };
+        let left = (PAGE_WIDTH - cell * self.width as f32) / 2.0;
+        let bottom = grid_top - cell * self.height as f32;
+        
+        // high contrast shades the answers in black with the letters in white
+        let contrast = self.print.high_contrast;
+        if let Some(mask) = shade {
+            ops.push_str(if contrast { "0 g\n" } else { "0.8 g\n" });
+            for (x, y, _) in self.iter_cells().filter(|(x, y, _)| mask[*y][*x]) {
+                ops.push_str(&format!("{:.2} {:.2} {:.2} {:.2} re f\n", left + x as f32 * cell, grid_top - (y + 1) as f32 * cell, cell, cell));
+            }
+            ops.push_str("0 g\n");
+        }
+        
+        // borders around every cell, or only the cells inside the shape of a shaped grid so its
+        // outline stands out
+        ops.push_str(if contrast { "2 w\n" } else { "0.5 w\n" });
+        if self.mask.is_some() {
+            for (x, y, _) in self.iter_cells().filter(|(x, y, _)| self.usable(*x, *y)) {
+                ops.push_str(&format!("{:.2} {:.2} {:.2} {:.2} re S\n", left + x as f32 * cell, grid_top - (y + 1) as f32 * cell, cell, cell));
+            }
+        } else {
+            for y in 0..=self.height {
+                let line_y = grid_top - y as f32 * cell;
+                ops.push_str(&format!("{:.2} {:.2} m {:.2} {:.2} l S\n", left, line_y, left + cell * self.width as f32, line_y));
+            }
+            for x in 0..=self.width {
+                let line_x = left + x as f32 * cell;
+                ops.push_str(&format!("{:.2} {:.2} m {:.2} {:.2} l S\n", line_x, grid_top, line_x, bottom));
+            }
+        }
+        
+        // the cells between the words of a criss-cross are blacked out, and its puzzle page leaves
+        // the letters for the solver to write in
+        for (x, y, _) in self.iter_cells().filter(|(x, y, _)| self.blocked(*x, *y)) {
+            ops.push_str(&format!("{:.2} {:.2} {:.2} {:.2} re f\n", left + x as f32 * cell, grid_top - (y + 1) as f32 * cell, cell, cell));
+        }
+        
+        // letters centered in their cells, the first letters of PrintStyle::first_letters outlined
+        // as well as filled to bold them
+        let font = cell * LETTER_FRACTION;
+        let starts = self.bold_starts(shade.is_some());
+        for (x, y, letter) in self.iter_cells().filter(|c| c.2 != ' ' && !self.hides_letter(c.0, c.1, shade.is_some())) {
+            let letter = letter.to_string();
+            let text_x = left + x as f32 * cell + (cell - text_width(&letter, font)) / 2.0;
+            let text_y = grid_top - (y + 1) as f32 * cell + (cell - font * 0.7) / 2.0;
+            let reversed = contrast && shade.is_some_and(|mask| mask[y][x]);
+            if reversed {
+                ops.push_str("1 g\n");
+            }
+            if starts.contains(&(x, y)) {
+                ops.push_str(&format!("q 2 Tr {:.2} w\n{}Q\n", font * 0.04, text_op(&letter, text_x, text_y, font)));
+            } else {
+                ops.push_str(&text_op(&letter, text_x, text_y, font));
+            }
+            if reversed {
+                ops.push_str("0 g\n");
+            }
+        }
+        
+        // the number of each word in the corner of the cell it starts at, small enough to stay clear
+        // of the letter
+        if shade.is_some() {
+            let numbers = self.start_numbers();
+            for (x, y, _) in self.iter_cells().filter(|(x, y, _)| !numbers[*y][*x].is_empty()) {
+                ops.push_str(if contrast { "1 g\n" } else { "" });
+                ops.push_str(&text_op(&numbers[y][x], left + x as f32 * cell + cell * 0.06, grid_top - y as f32 * cell - cell * 0.28, cell * 0.22));
+                ops.push_str(if contrast { "0 g\n" } else { "" });
+            }
+        }
+        
+        // the hints are circled on the puzzle
+        if shade.is_none() {
+            ops.push_str("1 w\n");
+            for (x, y) in self.revealed.iter() {
+                ops.push_str(&circle_op(left + (*x as f32 + 0.5) * cell, grid_top - (*y as f32 + 0.5) * cell, cell * 0.4));
+            }
+        }
+        
+        // word bank, filling each column before moving on to the next
+        // Right to left puzzles start from the right hand column with the words right aligned
+        let column_width = (PAGE_WIDTH - 2.0 * MARGIN) / columns as f32;
+        for (column, entries) in balanced_columns(&bank, columns).into_iter().enumerate() {
+            for (row, entry) in entries.iter().enumerate() {
+                let x = if self.rtl() {
+                    PAGE_WIDTH - MARGIN - column as f32 * column_width - text_width(entry, bank_font)
+                } else {
+                    MARGIN + column as f32 * column_width
+                };
+                ops.push_str(&text_op(entry, x, bottom - bank_line * (row + 2) as f32, bank_font));
+            }
+        }
+        
+        // the puzzle id in the bottom margin, clear of the word bank
+        if let Some(id) = self.id_line() {
+            ops.push_str(&text_op(&id, PAGE_WIDTH - MARGIN - text_width(&id, 8.0), MARGIN / 2.0, 8.0));
+        }
+        
+        ops
+    }
+}
+
+// render_pdf_pages - the puzzles as one pdf with a page for each, followed by a page for each answer
+//                    key with answer_key set, for the two puzzles of a double
+pub(crate) fn render_pdf_pages(puzzles: &[PuzzleGrid], answer_key: bool) -> Vec<u8> {
+    let mut pages: Vec<String> = puzzles.iter().map(|p| p.pdf_page(&p.pdf_heading(), None)).collect();
+    if answer_key {
+        pages.extend(puzzles.iter().map(|p| {
+            let heading = [(p.answer_title(), 24.0), (p.subtitle().unwrap_or("").to_string(), 16.0)];
+            p.pdf_page(&heading, Some(&p.answer_mask()))
+        }));
+    }
+    let title = puzzles.first().map_or("Word Find", |p| p.display_title());
+    let style = puzzles.first().map(|p| p.print.clone()).unwrap_or_default();
+    pdf_document(&pages, title, puzzles.first().and_then(|p| p.metadata()), &style)
+}
+
+// render_pdf_book - the puzzles of a book as one pdf, a contents page listing the puzzles and their
+//                   page numbers, a page for each puzzle, and then the answer keys
+pub(crate) fn render_pdf_book(title: &str, puzzles: &[PuzzleGrid]) -> Vec<u8> {
+    let mut contents = String::new();
+    let mut y = PAGE_HEIGHT - MARGIN - 24.0;
+    contents.push_str(&text_op(title, (PAGE_WIDTH - text_width(title, 24.0)) / 2.0, y, 24.0));
+    y -= 24.0;
+    
+    // the contents are page 1, so the puzzles start on page 2 and the answer keys follow them
+    let lines = puzzles.iter().enumerate()
+        .map(|(i, puzzle)| (format!("{}. {}", i + 1, puzzle.display_title()), i + 2))
+        .chain(std::iter::once((String::from("Answer keys"), puzzles.len() + 2)));
+    for (line, page) in lines {
+        y -= CONTENTS_LINE;
+        let page = page.to_string();
+        contents.push_str(&text_op(&line, MARGIN, y, BANK_FONT));
+        contents.push_str(&text_op(&page, PAGE_WIDTH - MARGIN - text_width(&page, BANK_FONT), y, BANK_FONT));
+    }
+    
+    let mut pages = vec![contents];
+    pages.extend(puzzles.iter().map(|p| p.pdf_page(&p.pdf_heading(), None)));
+    pages.extend(puzzles.iter().map(|p| p.pdf_answer_page()));
+    let metadata = puzzles.first().and_then(|p| p.metadata()).map(|m| Metadata { puzzle: None, ..m.clone() });
+    let style = puzzles.first().map(|p| p.print.clone()).unwrap_or_default();
+    pdf_document(&pages, title, metadata.as_ref(), &style)
+}
+
+// circle_op - stroke a circle of radius r around cx, cy, drawn as four bezier curves
+fn circle_op(cx: f32, cy: f32, r: f32) -> String {
+    let k = r * 0.5523;
+    let points = [
+        (cx + r, cy + k), (cx + k, cy + r), (cx, cy + r),
+        (cx - k, cy + r), (cx - r, cy + k), (cx - r, cy),
+        (cx - r, cy - k), (cx - k, cy - r), (cx, cy - r),
+        (cx + k, cy - r), (cx + r, cy - k), (cx + r, cy),
+    ];
+    let mut op = format!("{:.2} {:.2} m\n", cx + r, cy);
+    for curve in points.chunks(3) {
+        let coords: Vec<String> = curve.iter().map(|(x, y)| format!("{:.2} {:.2}", x, y)).collect();
+        op.push_str(&format!("{} c\n", coords.join(" ")));
+    }
+    op.push_str("S\n");
+    op
+}
+
+// text_op - draw text with its baseline starting at x, y
+fn text_op(text: &str, x: f32, y: f32, size: f32) -> String {
+    format!("BT /F1 {:.2} Tf {:.2} {:.2} Td ({}) Tj ET\n", size, x, y, escape(text))
+}
+
+// escape - a pdf string literal in WinAnsi encoding, characters it can't hold become '?', the
+//          puzzles are kept to the ones it can by check_pdf_text
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            },
+            ' '..='~' => escaped.push(c),
+            '\u{a0}'..='\u{ff}' => escaped.push_str(&format!("\\{:03o}", c as u32)),
+            _ => escaped.push('?'),
+        }
+    }
+    escaped
+}
+
+// text_width - roughly how wide the text is in Helvetica, close enough to center letters
+fn text_width(text: &str, size: f32) -> f32 {
+    let units: u32 = text.chars().map(|c| match c {
+        'I' | ' ' => 278,
+        'J' => 500,
+        'L' => 556,
+        'F' | 'T' | 'Z' => 611,
+        'A' | 'B' | 'E' | 'K' | 'P' | 'S' | 'V' | 'X' | 'Y' => 667,
+        'C' | 'D' | 'H' | 'N' | 'R' | 'U' => 722,
+        'G' | 'O' | 'Q' => 778,
+        'M' => 833,
+        'W' => 944,
+        _ => 556,
+    }).sum();
+    units as f32 * size / 1000.0
+}
+
+// base_font - the standard pdf font for the print style, bold for high contrast
+fn base_font(style: &PrintStyle) -> &'static str {
+    let family = style.font.as_deref().unwrap_or("").to_lowercase();
+    let bold = style.high_contrast;
+    if family.contains("mono") || family.contains("courier") {
+        if bold { "Courier-Bold" } else { "Courier" }
+    } else if family.contains("serif") && !family.contains("sans") || family.contains("times") {
+        if bold { "Times-Bold" } else { "Times-Roman" }
+    } else if bold {
+        "Helvetica-Bold"
+    } else {
+        "Helvetica"
+    }
+}
+
+// pdf_document - wrap the pages up with the catalog, the font, and the cross reference table
+//                The document properties get the title, and the metadata when there is some
+fn pdf_document(pages: &[String], title: &str, metadata: Option<&Metadata>, style: &PrintStyle) -> Vec<u8> {
+    let mut objects = Vec::new();
+    let kids: Vec<String> = (0..pages.len()).map(|i| format!("{} 0 R", 4 + 2 * i)).collect();
+    objects.push(String::from("<< /Type /Catalog /Pages 2 0 R >>"));
+    objects.push(format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()));
+    objects.push(format!("<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>", base_font(style)));
+    for (i, content) in pages.iter().enumerate() {
+        objects.push(format!("<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>", PAGE_WIDTH, PAGE_HEIGHT, 5 + 2 * i));
+        objects.push(format!("<< /Length {} >>\nstream\n{}\nendstream", content.len(), content));
+    }
+    let mut info = format!("<< /Title ({})", escape(title));
+    if let Some(metadata) = metadata {
+        let created: String = metadata.timestamp().chars().filter(char::is_ascii_digit).collect();
+        info.push_str(&format!(" /Producer (wordfindgen {}) /Subject ({}) /CreationDate (D:{}Z)", metadata.version, escape(&metadata.summary()), created));
+    }
+    info.push_str(" >>");
+    objects.push(info);
+    
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+    }
+    
+    let xref = pdf.len();
+    pdf.push_str(&format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1));
+    for offset in offsets {
+        pdf.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    pdf.push_str(&format!("trailer\n<< /Size {} /Root 1 0 R /Info {} 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, objects.len(), xref));
+    pdf.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render_pdf_book;
+    use crate::{generate, Config, PrintStyle, SeedableRng, StdRng, WordfindError};
+    
+    #[test]
+    fn two_page_pdf() {
+        let words: Vec<String> = ["Hello", "Thanks", "Goodbye", "How", "Are", "You"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 10, height: 10, ..Config::default() };
+        let mut rng = StdRng::seed_from_u64(1234);
+        let mut puzzle = generate(&config, &words, &mut rng).unwrap();
+        puzzle.fill_in(&mut rng);
+        
+        let pdf = String::from_utf8(puzzle.render_pdf(true)).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4"));
+        assert!(pdf.contains("/Count 2"));
+        assert!(pdf.contains("(GOODBYE) Tj"));
+        assert!(pdf.contains("/Info 8 0 R"));
+        
+        // one shaded square on the answer page for each letter of a placed word
+        let placed = puzzle.answer_mask().iter().flatten().filter(|p| **p).count();
+        assert_eq!(pdf.matches(" re f").count(), placed);
+        
+        // numbered answers put the numbers in the starting cells and before the words
+        puzzle.set_print_style(PrintStyle { numbered_answers: true, ..PrintStyle::default() });
+        let numbered = String::from_utf8(puzzle.render_pdf(true)).unwrap();
+        assert!(numbered.contains("(1. HELLO) Tj") && numbered.contains("(6. YOU) Tj"));
+        assert!((1..=6).all(|n| numbered.contains(&format!("({}) Tj", n))));
+        assert!(!pdf.contains("(1. HELLO) Tj"));
+        
+        // first letters are outlined to bold them on the puzzle page only
+        assert!(!pdf.contains(" Tr "));
+        puzzle.set_print_style(PrintStyle { first_letters: true, ..PrintStyle::default() });
+        let bolded = String::from_utf8(puzzle.render_pdf(true)).unwrap();
+        assert_eq!(bolded.matches("q 2 Tr ").count(), puzzle.entries().len());
+        
+        // every entry in the cross reference table points at the start of its object
+        let xref = pdf.find("\nxref\n").unwrap() + 1;
+        for (i, line) in pdf[xref..].lines().skip(3).take(7).enumerate() {
+            let offset: usize = line[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj", i + 1)));
+        }
+    }
+    
+    #[test]
+    fn pdf_letters() {
+        let words: Vec<String> = ["Café", "Crème"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 8, height: 8, ..Config::default() };
+        let mut rng = StdRng::seed_from_u64(1234);
+        let puzzle = generate(&config, &words, &mut rng).unwrap();
+        assert!(puzzle.check_pdf_text().is_ok());
+        
+        let words: Vec<String> = ["Кошка", "Собака"].iter().map(|w| w.to_string()).collect();
+        let puzzle = generate(&config, &words, &mut rng).unwrap();
+        assert!(matches!(puzzle.check_pdf_text(), Err(WordfindError::NotInPdfFont { .. })));
+    }
+    
+    #[test]
+    fn large_print_pdf() {
+        let words: Vec<String> = ["Hello", "Thanks"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 8, height: 8, ..Config::default() };
+        let mut rng = StdRng::seed_from_u64(1234);
+        let mut puzzle = generate(&config, &words, &mut rng).unwrap();
+        puzzle.fill_in(&mut rng);
+        puzzle.set_print_style(PrintStyle { font: Some(String::from("Times New Roman")), ..PrintStyle::large_print() });
+        
+        let pdf = String::from_utf8(puzzle.render_pdf(true)).unwrap();
+        assert!(pdf.contains("/BaseFont /Times-Bold "));
+        assert!(pdf.contains("/F1 24.00 Tf"));
+        assert!(pdf.contains("/F1 18.00 Tf"));
+        // the letters of the words are white on the black shading of the answer key
+        let placed = puzzle.answer_mask().iter().flatten().filter(|p| **p).count();
+        assert_eq!(pdf.matches("1 g\n").count(), placed);
+    }
+    
+    #[test]
+    fn pdf_book() {
+        let words: Vec<String> = ["Hello", "Thanks"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 8, height: 8, ..Config::default() };
+        let mut rng = StdRng::seed_from_u64(1234);
+        let mut puzzles = Vec::new();
+        for title in ["Greetings", "Manners"].iter() {
+            let mut puzzle = generate(&config, &words, &mut rng).unwrap();
+            puzzle.fill_in(&mut rng);
+            puzzle.set_title(Some(title.to_string()));
+            puzzles.push(puzzle);
+        }
+        let pdf = String::from_utf8(render_pdf_book("Book", &puzzles)).unwrap();
+        assert!(pdf.contains("/Count 5"));
+        assert!(pdf.contains("(2. Manners) Tj"));
+        assert!(pdf.contains("(Manners - Answer Key) Tj"));
+    }
+}
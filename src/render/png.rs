@@ -0,0 +1,317 @@
+use crate::PuzzleGrid;
+use super::{balanced_columns, crc32, ImageOptions};
+
+// A 5 x 7 pixel font, one byte per row with the leftmost pixel in bit 4
+// Lowercase letters use the uppercase glyphs and anything else is drawn as a hollow box
+fn glyph(c: char) -> [u8; 7] {
+    match c.to_ascii_uppercase() {
+        'A' => [0x0e, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11],
+        'B' => [0x1e, 0x11, 0x11, 0x1e, 0x11, 0x11, 0x1e],
+        'C' => [0x0e, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0e],
+        'D' => [0x1e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1e],
+        'E' => [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x1f],
+        'F' => [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x10],
+        'G' => [0x0e, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0f],
+        'H' => [0x11, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11],
+        'I' => [0x0e, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0c],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f],
+        'M' => [0x11, 0x1b, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e],
+        'P' => [0x1e, 0x11, 0x11, 0x1e, 0x10, 0x10, 0x10],
+        'Q' => [0x0e, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0d],
+        'R' => [0x1e, 0x11, 0x11, 0x1e, 0x14, 0x12, 0x11],
+        'S' => [0x0f, 0x10, 0x10, 0x0e, 0x01, 0x01, 0x1e],
+        'T' => [0x1f, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0a, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0a],
+        'X' => [0x11, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0a, 0x04, 0x04, 0x04],
+        'Z' => [0x1f, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1f],
+        '0' => [0x0e, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0e],
+        '1' => [0x04, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x0e],
+        '2' => [0x0e, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1f],
+        '3' => [0x1f, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0e],
+        '4' => [0x02, 0x06, 0x0a, 0x12, 0x1f, 0x02, 0x02],
+        '5' => [0x1f, 0x10, 0x1e, 0x01, 0x01, 0x11, 0x0e],
+        '6' => [0x06, 0x08, 0x10, 0x1e, 0x11, 0x11, 0x0e],
+        '7' => [0x1f, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0e, 0x11, 0x11, 0x0e, 0x11, 0x11, 0x0e],
+        '9' => [0x0e, 0x11, 0x11, 0x0f, 0x01, 0x02, 0x0c],
+        '-' => [0x00, 0x00, 0x00, 0x1f, 0x00, 0x00, 0x00],
+        ' ' => [0x00; 7],
+        _ => [0x1f, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1f],
+    }
+}
+
+// the word bank is laid out in this many columns under the grid unless the print style sets them
+const BANK_COLUMNS: usize = 3;
+
+// gray levels for the 8 bit grayscale image
+const WHITE: u8 = 255;
+const SHADE: u8 = 200;
+const BLACK: u8 = 0;
+
+// Canvas - a grayscale image that is drawn on before being encoded
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    // paste - draw another canvas onto this one with its top left corner at x along the top
+    fn paste(&mut self, other: &Canvas, x: usize) {
+        for row in 0..other.height.min(self.height) {
+            let start = row * self.width + x;
+            let width = other.width.min(self.width.saturating_sub(x));
+            self.pixels[start..start + width].copy_from_slice(&other.pixels[row * other.width..row * other.width + width]);
+        }
+    }
+    
+    fn fill_rect(&mut self, x: usize, y: usize, w: usize, h: usize, level: u8) {
+        for row in y..(y + h).min(self.height) {
+            for col in x..(x + w).min(self.width) {
+                self.pixels[row * self.width + col] = level;
+            }
+        }
+    }
+    
+    // ring - draw a circle two pixels thick around cx, cy
+    fn ring(&mut self, cx: usize, cy: usize, radius: usize) {
+        let (outer, inner) = (radius * radius, radius.saturating_sub(2) * radius.saturating_sub(2));
+        for y in cy.saturating_sub(radius)..=cy + radius {
+            for x in cx.saturating_sub(radius)..=cx + radius {
+                let distance = x.abs_diff(cx).pow(2) + y.abs_diff(cy).pow(2);
+                if distance <= outer && distance > inner {
+                    self.fill_rect(x, y, 1, 1, BLACK);
+                }
+            }
+        }
+    }
+    
+    // text - draw text with its top left corner at x, y, each font pixel is scale pixels square
+    fn text(&mut self, text: &str, x: usize, y: usize, scale: usize) {
+        for (i, c) in text.chars().enumerate() {
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for col in 0..5 {
+                    if bits & (0x10 >> col) != 0 {
+                        self.fill_rect(x + (i * 6 + col) * scale, y + row * scale, scale, scale, BLACK);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl PuzzleGrid {
+    // render_png - the grid and word bank as a grayscale png image, under the title, subtitle, and
+    //              instructions when the puzzle has them
+    //              With answer set the letters of the placed words are shaded
+    //
+    // The letters come from a built in 5 x 7 pixel font, scaled up to the font size in options
+    pub fn render_png(&self, answer: bool, options: &ImageOptions) -> Vec<u8> {
+        encode(&self.png_canvas(answer, options), self.id_line())
+    }
+    
+    // render_png_combined - the puzzle with its answer key beside it, drawn with cells and letters
+    //                       half the size, for a teacher's copy in one image
+    pub fn render_png_combined(&self, options: &ImageOptions) -> Vec<u8> {
+        let puzzle = self.png_canvas(false, options);
+        let key = self.png_canvas(true, &ImageOptions { cell_size: options.cell_size / 2, font_size: options.font_size / 2, ..*options });
+        let (width, height) = (puzzle.width + key.width, puzzle.height.max(key.height));
+        let mut canvas = Canvas { width, height, pixels: vec![WHITE; width * height] };
+        canvas.paste(&puzzle, 0);
+        canvas.paste(&key, puzzle.width);
+        encode(&canvas, self.id_line())
+    }
+    
+    // png_canvas - the puzzle or its answer key drawn on a canvas
+    fn png_canvas(&self, answer: bool, options: &ImageOptions) -> Canvas {
+        let cell = options.cell_size.max(8);
+        let margin = options.margin;
+        let scale = (options.font_size / 7).max(1);
+        let bank_scale = (scale * 3 / 4).max(1);
+        let bank_line = 9 * bank_scale;
+        let bank = self.printed_bank(answer);
+        let columns = self.print.columns(BANK_COLUMNS);
+        let bank_rows = bank.len().div_ceil(columns);
+        
+        // the title is drawn at the grid letter size, the subtitle and instructions at the word bank size
+        let heading: Vec<(&str, usize)> = [(self.title(), scale), (self.subtitle(), bank_scale), (self.instructions(), bank_scale)].iter()
+            .filter_map(|(line, s)| line.map(|l| (l, *s)))
+            .collect();
+        let heading_height: usize = heading.iter().map(|(_, s)| 9 * s).sum();
+        
+        let grid_width = cell * self.width;
+        let grid_height = cell * self.height;
+        let width = grid_width + 2 * margin;
+        let height = heading_height + grid_height + 2 * margin + if bank_rows > 0 { (bank_rows + 1) * bank_line } else { 0 };
+        let mut canvas = Canvas { width, height, pixels: vec![WHITE; width * height] };
+        
+        let mut y = margin;
+        for (line, s) in heading.iter() {
+            let text_width = line.chars().count() * 6 * s;
+            canvas.text(line, width.saturating_sub(text_width) / 2, y, *s);
+            y += 9 * s;
+        }
+        let top = margin + heading_height;
+        
+        if answer {
+            let mask = self.answer_mask();
+            for (x, y, _) in self.iter_cells().filter(|(x, y, _)| mask[*y][*x]) {
+                canvas.fill_rect(margin + x * cell, top + y * cell, cell, cell, SHADE);
+            }
+        }
+        
+        // a line on the edge of every cell
+        for y in 0..=self.height {
+            canvas.fill_rect(margin, top + y * cell, grid_width + 1, 1, BLACK);
+        }
+        for x in 0..=self.width {
+            canvas.fill_rect(margin + x * cell, top, 1, grid_height + 1, BLACK);
+        }
+        
+        // the cells between the words of a criss-cross are blacked out, and its puzzle leaves the
+        // letters for the solver to write in
+        for (x, y, _) in self.iter_cells().filter(|(x, y, _)| self.blocked(*x, *y)) {
+            canvas.fill_rect(margin + x * cell, top + y * cell, cell, cell, BLACK);
+        }
+        
+        // letters centered in their cells, the first letters of PrintStyle::first_letters drawn
+        // twice a pixel apart to bold them
+        let starts = self.bold_starts(answer);
+        for (x, y, letter) in self.iter_cells().filter(|c| c.2 != ' ' && !self.hides_letter(c.0, c.1, answer)) {
+            let left = margin + x * cell + cell.saturating_sub(5 * scale) / 2;
+            let cell_top = top + y * cell + cell.saturating_sub(7 * scale) / 2;
+            canvas.text(&letter.to_string(), left, cell_top, scale);
+            if starts.contains(&(x, y)) {
+                canvas.text(&letter.to_string(), left + 1, cell_top, scale);
+            }
+        }
+        
+        // the hints are circled on the puzzle
+        if !answer {
+            for (x, y) in self.revealed.iter() {
+                canvas.ring(margin + x * cell + cell / 2, top + y * cell + cell / 2, (cell / 2).saturating_sub(1));
+            }
+        }
+        
+        // word bank, filling each column before moving on to the next
+        let column_width = grid_width / columns;
+        for (column, entries) in balanced_columns(&bank, columns).into_iter().enumerate() {
+            for (row, entry) in entries.iter().enumerate() {
+                let x = margin + column * column_width;
+                let y = top + grid_height + bank_line + row * bank_line;
+                canvas.text(entry, x, y, bank_scale);
+            }
+        }
+        
+        canvas
+    }
+}
+
+// encode - the canvas as a png file, the image data is stored without compression
+fn encode(canvas: &Canvas, comment: Option<String>) -> Vec<u8> {
+    let mut png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+    
+    let mut header = Vec::new();
+    header.extend_from_slice(&(canvas.width as u32).to_be_bytes());
+    header.extend_from_slice(&(canvas.height as u32).to_be_bytes());
+    // 8 bit grayscale, deflate, adaptive filtering, no interlace
+    header.extend_from_slice(&[8, 0, 0, 0, 0]);
+    chunk(&mut png, b"IHDR", &header);
+    if let Some(comment) = comment {
+        chunk(&mut png, b"tEXt", format!("Comment\0{}", comment).as_bytes());
+    }
+    
+    // every row starts with filter type 0
+    let mut raw = Vec::with_capacity((canvas.width + 1) * canvas.height);
+    for row in canvas.pixels.chunks(canvas.width) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+    chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+    chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+// chunk - append a png chunk, its length, type, data, and crc
+fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+// zlib_stored - a zlib stream made of uncompressed deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xffff).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        out.push(if blocks.peek().is_none() { 1 } else { 0 });
+        let len = block.len() as u16;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Difficulty, Direction, ImageOptions, PuzzleGrid};
+    
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+    
+    #[test]
+    fn png_image() {
+        let mut puzzle = PuzzleGrid::new(4, 3, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &0, &1, &Direction::Right);
+        puzzle.entries.push(String::from("CAT"));
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        
+        let options = ImageOptions { cell_size: 20, margin: 10, font_size: 14 };
+        let png = puzzle.render_png(true, &options);
+        assert_eq!(&png[1..4], b"PNG");
+        assert_eq!(&png[12..16], b"IHDR");
+        // 4 cells of 20 plus the margins across, 3 cells, the margins, and two lines of word bank down
+        assert_eq!(u32::from_be_bytes([png[16], png[17], png[18], png[19]]), 100);
+        assert_eq!(u32::from_be_bytes([png[20], png[21], png[22], png[23]]), 80 + 2 * 9);
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+        
+        // a title line at the grid letter size, 2 pixel font pixels with a 2 pixel row between lines
+        puzzle.set_title(Some(String::from("Pets")));
+        let png = puzzle.render_png(false, &options);
+        assert_eq!(u32::from_be_bytes([png[20], png[21], png[22], png[23]]), 80 + 2 * 9 + 18);
+        
+        // the answer key beside it with cells of 10 and the margins, as tall as the puzzle
+        puzzle.set_title(None);
+        let png = puzzle.render_png_combined(&options);
+        assert_eq!(u32::from_be_bytes([png[16], png[17], png[18], png[19]]), 100 + 60);
+        assert_eq!(u32::from_be_bytes([png[20], png[21], png[22], png[23]]), 80 + 2 * 9);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::PuzzleGrid;
+use super::{balanced_columns, escape_xml};
+
+// Sizes in svg user units, the picture scales to whatever size it is shown at
+// The cells are twice the size of their letters, which are 20 unless the print style sets them,
+// and then the word bank is the same size as the letters, in BANK_COLUMNS unless the print style
+// sets them
+const CELL: usize = 40;
+const MARGIN: usize = 20;
+const BANK_COLUMNS: usize = 3;
+const BANK_LINE: usize = 28;
+
+impl PuzzleGrid {
+    // render_svg - the grid as vector text with the word bank under it and the title, subtitle,
+    //              and instructions above it
+    //              With answer set a rounded band is drawn over each placed word
+    pub fn render_svg(&self, answer: bool) -> String {
+        self.svg_picture(answer).2
+    }
+    
+    // render_svg_combined - the puzzle with its answer key beside it at half the size, for a
+    //                       teacher's copy in one picture
+    pub fn render_svg_combined(&self) -> String {
+        let (width, height, puzzle) = self.svg_picture(false);
+        let (key_width, key_height, key) = self.svg_picture(true);
+        let (total_width, total_height) = (width + key_width / 2, height.max(key_height / 2));
+        // the answer key keeps its viewBox, so giving it half the width and height shrinks it
+        let key = key.replacen(&format!("width=\"{}\" height=\"{}\"", key_width, key_height), &format!("x=\"{}\" width=\"{}\" height=\"{}\"", width, key_width / 2, key_height / 2), 1);
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n", total_width, total_height, total_width, total_height);
+        svg.push_str(&format!("<rect width=\"{}\" height=\"{}\" fill=\"white\"/>\n", total_width, total_height));
+        svg.push_str(&puzzle);
+        svg.push_str(&key);
+        svg.push_str("</svg>\n");
+        svg
+    }
+    
+    // svg_picture - the width, height, and svg of the puzzle or its answer key
+    fn svg_picture(&self, answer: bool) -> (usize, usize, String) {
+        let cell = self.print.letter_size.map_or(CELL, |size| (size * 2.0).round() as usize);
+        let bank_line = self.print.letter_size.map_or(BANK_LINE, |size| (size * 1.5).round() as usize);
+        let center = |i: usize| MARGIN + i * cell + cell / 2;
+        let grid_width = cell * self.width;
+        let grid_height = cell * self.height;
+        let bank = self.printed_bank(answer);
+        let columns = self.print.columns(BANK_COLUMNS);
+        let bank_rows = bank.len().div_ceil(columns);
+        let width = grid_width + 2 * MARGIN;
+        
+        // the heading lines get smaller going down, title then subtitle then instructions
+        let heading: Vec<(&str, usize)> = [(self.title(), 28), (self.subtitle(), 20), (self.instructions(), 16)].iter()
+            .filter_map(|(line, size)| line.map(|l| (l, *size)))
+            .collect();
+        let heading_height: usize = heading.iter().map(|(_, size)| size + 8).sum();
+        let height = heading_height + grid_height + 2 * MARGIN + (bank_rows + 1) * bank_line;
+        
+        let contrast = self.print.high_contrast;
+        let font: String = self.print.font.as_deref().unwrap_or("Helvetica, Arial, sans-serif").chars().filter(|c| c.is_alphanumeric() || " ,-".contains(*c)).collect();
+        let weight = if contrast { " font-weight=\"bold\"" } else { "" };
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" font-family=\"{}\"{}>\n", width, height, width, height, font, weight);
+        svg.push_str(&format!("<rect width=\"{}\" height=\"{}\" fill=\"white\"/>\n", width, height));
+        if let Some(id) = self.id_line() {
+            svg.push_str(&format!("<desc>{}</desc>\n", id));
+        }
+        let mut y = MARGIN;
+        for (line, size) in heading.iter() {
+            y += size;
+            svg.push_str(&format!("<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\">{}</text>\n", width / 2, y, size, escape_xml(line)));
+            y += 8;
+        }
+        
+        // everything below is laid out as if the grid were at the top
+        svg.push_str(&format!("<g transform=\"translate(0 {})\">\n", heading_height));
+        
+        // the bands go under the letters so they stay readable, in high contrast they're solid black
+        // with the letters on them in white
+        if answer {
+            let band = if contrast { "stroke=\"black\"" } else { "stroke=\"#f4c430\" stroke-opacity=\"0.6\"" };
+            svg.push_str(&format!("<g {} stroke-width=\"{}\" stroke-linecap=\"round\">\n", band, cell * 3 / 4));
+            for p in self.placements.iter() {
+                let cells = p.cells();
+                let (x1, y1) = cells[0];
+                let (x2, y2) = cells[cells.len() - 1];
+                match p.bend {
+                    // a bent word's band goes around the corner
+                    Some((at, _)) => {
+                        let (xb, yb) = cells[at];
+                        svg.push_str(&format!("<polyline fill=\"none\" stroke-linejoin=\"round\" points=\"{},{} {},{} {},{}\"/>\n", center(x1), center(y1), center(xb), center(yb), center(x2), center(y2)));
+                    },
+                    None => svg.push_str(&format!("<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>\n", center(x1), center(y1), center(x2), center(y2))),
+                }
+            }
+            svg.push_str("</g>\n");
+        }
+        
+        svg.push_str(&format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"black\" stroke-width=\"{}\"/>\n", MARGIN, MARGIN, grid_width, grid_height, if contrast { 4 } else { 2 }));
+        
+        // a criss-cross is drawn as boxes for the letters with the cells between the words blacked
+        // out, and its puzzle leaves the letters for the solver to write in
+        if self.crisscross {
+            svg.push_str("<g stroke=\"black\">\n");
+            for (x, y, _) in self.iter_cells() {
+                let fill = if self.blocked(x, y) { "black" } else { "none" };
+                svg.push_str(&format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n", MARGIN + x * cell, MARGIN + y * cell, cell, cell, fill));
+            }
+            svg.push_str("</g>\n");
+        }
+        svg.push_str(&format!("<g font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">\n", cell / 2));
+        let placed = self.answer_mask();
+        let starts = self.bold_starts(answer);
+        for (x, y, letter) in self.iter_cells().filter(|c| c.2 != ' ' && !self.hides_letter(c.0, c.1, answer)) {
+            let fill = if answer && contrast && placed[y][x] { " fill=\"white\"" } else { "" };
+            let weight = if starts.contains(&(x, y)) { " font-weight=\"bold\" text-decoration=\"underline\"" } else { "" };
+            svg.push_str(&format!("<text x=\"{}\" y=\"{}\"{}{}>{}</text>\n", center(x), center(y), fill, weight, escape_xml(&letter.to_string())));
+        }
+        svg.push_str("</g>\n");
+        
+        // the hints are circled on the puzzle
+        if !answer {
+            for (x, y) in self.revealed.iter() {
+                svg.push_str(&format!("<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" stroke=\"black\" stroke-width=\"2\"/>\n", center(*x), center(*y), cell * 2 / 5));
+            }
+        }
+        
+        // word bank, filling each column before moving on to the next
+        let column_width = grid_width / columns;
+        svg.push_str(&format!("<g font-size=\"{}\">\n", bank_line * 2 / 3));
+        for (column, entries) in balanced_columns(&bank, columns).into_iter().enumerate() {
+            for (row, entry) in entries.iter().enumerate() {
+                let x = MARGIN + column * column_width;
+                let y = MARGIN + grid_height + (row + 2) * bank_line;
+                svg.push_str(&format!("<text x=\"{}\" y=\"{}\">{}</text>\n", x, y, escape_xml(entry)));
+            }
+        }
+        svg.push_str("</g>\n</g>\n</svg>\n");
+        (width, height, svg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Difficulty, Direction, PuzzleGrid};
+    
+    #[test]
+    fn svg_overlay() {
+        let mut puzzle = PuzzleGrid::new(4, 3, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &0, &1, &Direction::Right);
+        puzzle.entries.push(String::from("CAT"));
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        
+        let svg = puzzle.render_svg(false);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert_eq!(svg.matches("<text ").count(), 12 + 1);
+        assert!(!svg.contains("<line"));
+        
+        let svg = puzzle.render_svg(true);
+        assert!(svg.contains("<line x1=\"40\" y1=\"80\" x2=\"120\" y2=\"80\"/>"));
+        assert!(svg.contains("<g transform=\"translate(0 0)\">"));
+        
+        puzzle.set_title(Some(String::from("Pets")));
+        let svg = puzzle.render_svg(false);
+        assert!(svg.contains("<text x=\"100\" y=\"48\" font-size=\"28\" text-anchor=\"middle\">Pets</text>"));
+        assert!(svg.contains("<g transform=\"translate(0 36)\">"));
+        
+        // the answer key at half size to the right of the puzzle
+        puzzle.set_title(None);
+        let svg = puzzle.render_svg_combined();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"300\" height=\"216\" viewBox=\"0 0 300 216\">"));
+        assert!(svg.contains("<svg xmlns=\"http://www.w3.org/2000/svg\" x=\"200\" width=\"100\" height=\"108\" viewBox=\"0 0 200 216\""));
+        assert_eq!(svg.matches("<line").count(), 1);
+    }
+    
+    #[test]
+    fn large_print_svg() {
+        let mut puzzle = PuzzleGrid::new(4, 3, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &0, &1, &Direction::Right);
+        puzzle.entries.push(String::from("CAT"));
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        puzzle.set_print_style(crate::PrintStyle { font: Some(String::from("Verdana")), ..crate::PrintStyle::large_print() });
+        
+        let svg = puzzle.render_svg(true);
+        assert!(svg.contains("font-family=\"Verdana\" font-weight=\"bold\">"));
+        assert!(svg.contains("<g font-size=\"24\" text-anchor=\"middle\""));
+        assert!(svg.contains("<g stroke=\"black\" stroke-width=\"36\""));
+        assert_eq!(svg.matches(" fill=\"white\">").count(), 3);
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{AnswerStyle, PuzzleGrid, WordfindError};
+
+// The template language, for a layout of a school's own without a renderer of its own
+//
+// {{name}} is replaced with the value of the placeholder, {{#name}}...{{/name}} is repeated for
+// each item of a list or shown when a value isn't empty, {{^name}}...{{/name}} is shown when it is
+// empty, and {{! ...}} is a comment. A line with nothing on it but a section tag or a comment is
+// left out, so the tags can go on lines of their own.
+//
+//   title, subtitle, instructions  the headings, title is the answer key title on the answer key
+//   answer                         set on the answer key
+//   width, height, count           the size of the grid and the number of words
+//   id                             the puzzle id, see PuzzleGrid::puzzle_id
+//   grid                           the rows of letters with spaces between them, only the words
+//                                  show on the answer key
+//   rows                           each row, with row, its number from 1, and cells
+//   cells                          each cell of a row, with letter, column, found when it's part of
+//                                  a word on the answer key, start when it's the first letter of a
+//                                  word bolded with PrintStyle::first_letters, and blank when it's
+//                                  outside the shape
+//   words                          each line of the word bank, with word
+//
+// Nothing is escaped, so a value goes into html or latex exactly as it was typed
+
+// Value - what a placeholder stands for
+enum Value {
+    Text(String),
+    Flag(bool),
+    List(Vec<Scope>),
+}
+
+// Scope - the placeholders of the puzzle, or of one item of a list
+type Scope = Vec<(&'static str, Value)>;
+
+impl Value {
+    // shown - whether a section for the value is shown
+    fn shown(&self) -> bool {
+        match self {
+            Value::Text(text) => !text.is_empty(),
+            Value::Flag(flag) => *flag,
+            Value::List(items) => !items.is_empty(),
+        }
+    }
+}
+
+impl PuzzleGrid {
+    // render_template - the puzzle, or the answer key with answer set, laid out by the template,
+    //                   a usage error when the template has a placeholder that doesn't exist or a
+    //                   section that isn't closed
+    pub fn render_template(&self, template: &str, answer: bool) -> Result<String, WordfindError> {
+        let mask = self.answer_mask();
+        let starts = self.bold_starts(answer);
+        let rows = self.shown_grid(answer).into_iter().enumerate().map(|(y, row)| {
+            let cells = row.into_iter().enumerate().map(|(x, letter)| vec![
+                ("letter", Value::Text(letter)),
+                ("column", Value::Text((x + 1).to_string())),
+                ("found", Value::Flag(answer && mask[y][x])),
+                ("start", Value::Flag(starts.contains(&(x, y)))),
+                ("blank", Value::Flag(!self.usable(x, y))),
+            ]).collect();
+            vec![("row", Value::Text((y + 1).to_string())), ("cells", Value::List(cells))]
+        }).collect();
+        let grid = if answer { self.answer_grid(AnswerStyle::Blank) } else { self.shown_grid(false) };
+        let grid: Vec<String> = grid.iter().map(|row| row.join(" ").trim_end().to_string()).collect();
+        
+        let scope = vec![
+            ("title", Value::Text(if answer { self.answer_title() } else { self.display_title().to_string() })),
+            ("subtitle", Value::Text(self.subtitle().unwrap_or("").to_string())),
+            ("instructions", Value::Text(self.instructions().unwrap_or("").to_string())),
+            ("answer", Value::Flag(answer)),
+            ("width", Value::Text(self.width.to_string())),
+            ("height", Value::Text(self.height.to_string())),
+            ("count", Value::Text(self.entries.len().to_string())),
+            ("id", Value::Text(self.puzzle_id())),
+            ("grid", Value::Text(grid.join("\n"))),
+            ("rows", Value::List(rows)),
+            ("words", Value::List(self.printed_bank(answer).into_iter().map(|word| vec![("word", Value::Text(word))]).collect())),
+        ];
+        expand(&standalone(template), &[&scope])
+    }
+}
+
+// expand - the template with its placeholders filled in from the scopes, the innermost last
+fn expand(template: &str, stack: &[&Scope]) -> Result<String, WordfindError> {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some((before, tag, after)) = next_tag(rest)? {
+        out.push_str(before);
+        rest = after;
+        match tag.chars().next() {
+            Some('#') | Some('^') => {
+                let name = tag[1..].trim();
+                let (inner, after) = section(rest, name)?;
+                rest = after;
+                let inverted = tag.starts_with('^');
+                match lookup(stack, name)? {
+                    Value::List(items) if !inverted => {
+                        for item in items {
+                            let mut inner_stack = stack.to_vec();
+                            inner_stack.push(item);
+                            out.push_str(&expand(inner, &inner_stack)?);
+                        }
+                    },
+                    value if value.shown() != inverted => out.push_str(&expand(inner, stack)?),
+                    _ => {},
+                }
+            },
+            Some('/') => return Err(WordfindError::Usage(format!("the template closes {{{{{}}}}} without opening it", tag))),
+            Some('!') => {},
+            _ => match lookup(stack, tag)? {
+                Value::Text(text) => out.push_str(text),
+                _ => return Err(WordfindError::Usage(format!("{{{{{}}}}} in the template is a section, use {{{{#{}}}}}...{{{{/{}}}}}", tag, tag, tag))),
+            },
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+// next_tag - the text before the next tag, the tag without its braces, and the text after it
+fn next_tag(text: &str) -> Result<Option<(&str, &str, &str)>, WordfindError> {
+    let open = match text.find("{{") {
+        Some(open) => open,
+        None => return Ok(None),
+    };
+    let close = text[open..].find("}}").ok_or_else(|| WordfindError::Usage(String::from("a {{ in the template isn't closed with }}")))? + open;
+    Ok(Some((&text[..open], text[open + 2..close].trim(), &text[close + 2..])))
+}
+
+// section - the inside of the section that was just opened and the text after its closing tag,
+//           sections of the same name inside it are skipped over
+fn section<'a>(text: &'a str, name: &str) -> Result<(&'a str, &'a str), WordfindError> {
+    let mut depth = 0;
+    let mut rest = text;
+    while let Some((before, tag, after)) = next_tag(rest)? {
+        let start = text.len() - rest.len() + before.len();
+        rest = after;
+        if (tag.starts_with('#') || tag.starts_with('^')) && tag[1..].trim() == name {
+            depth += 1;
+        } else if tag.starts_with('/') && tag[1..].trim() == name {
+            if depth == 0 {
+                return Ok((&text[..start], rest));
+            }
+            depth -= 1;
+        }
+    }
+    Err(WordfindError::Usage(format!("{{{{#{}}}}} in the template isn't closed with {{{{/{}}}}}", name, name)))
+}
+
+// lookup - the value of a placeholder from the innermost scope that has it
+fn lookup<'a>(stack: &[&'a Scope], name: &str) -> Result<&'a Value, WordfindError> {
+    stack.iter().rev().flat_map(|scope| scope.iter()).find(|(key, _)| *key == name).map(|(_, value)| value)
+        .ok_or_else(|| WordfindError::Usage(format!("the template has {{{{{}}}}}, which isn't one of the placeholders", name)))
+}
+
+// standalone - the template with the lines that hold only a section tag or a comment cut down to
+//              the tag, so they don't leave blank lines in the output
+fn standalone(template: &str) -> String {
+    template.split_inclusive('\n').map(|line| {
+        let tag = line.trim();
+        let alone = tag.starts_with("{{") && tag.ends_with("}}") && tag.matches("{{").count() == 1
+            && tag[2..].trim_start().starts_with(['#', '^', '/', '!']);
+        if alone { tag } else { line }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Difficulty, Direction};
+    
+    #[test]
+    fn template_output() {
+        let mut puzzle = PuzzleGrid::new(3, 2, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &0, &0, &Direction::Right);
+        puzzle.entries.push(String::from("CAT"));
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        puzzle.set_title(Some(String::from("Pets")));
+        
+        let template = "<h1>{{title}}</h1>\n{{#subtitle}}\n<h2>{{subtitle}}</h2>\n{{/subtitle}}\n{{! the grid }}\n{{#rows}}\n<tr>{{#cells}}<td{{#found}} class=\"found\"{{/found}}>{{letter}}</td>{{/cells}}</tr>\n{{/rows}}\n{{#words}}{{word}}{{/words}} of {{count}}\n{{^answer}}good luck{{/answer}}\n";
+        assert_eq!(puzzle.render_template(template, false).unwrap(), "<h1>Pets</h1>\n<tr><td>C</td><td>A</td><td>T</td></tr>\n<tr><td>A</td><td>A</td><td>A</td></tr>\nCAT of 1\ngood luck\n");
+        assert_eq!(puzzle.render_template(template, true).unwrap(), "<h1>Pets - Answer Key</h1>\n<tr><td class=\"found\">C</td><td class=\"found\">A</td><td class=\"found\">T</td></tr>\n<tr><td>A</td><td>A</td><td>A</td></tr>\nCAT of 1\n\n");
+        assert_eq!(puzzle.render_template("{{grid}}", true).unwrap(), "C A T\n");
+        
+        assert!(puzzle.render_template("{{school}}", false).is_err());
+        assert!(puzzle.render_template("{{#rows}}{{row}}", false).is_err());
+        assert!(puzzle.render_template("{{rows}}", false).is_err());
+        assert!(puzzle.render_template("{{title", false).is_err());
+    }
+}
@@ -0,0 +1,245 @@
+use crate::PuzzleGrid;
+use super::{crc32, escape_xml};
+
+// the word bank is laid out in this many columns under the grid unless the print style sets them
+const BANK_COLUMNS: usize = 3;
+
+// Style indexes into the cellXfs of STYLES
+const STYLE_CELL: usize = 1;
+const STYLE_SHADED: usize = 2;
+const STYLE_TITLE: usize = 3;
+const STYLE_NOTE: usize = 4;
+const STYLE_HINT: usize = 5;
+
+// One bold font for the title, centered text for the lines under it, a gray fill for the answer key, a thin border all around the grid cells, and bold letters for the hints and first letters
+const STYLES: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<styleSheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
+<fonts count=\"3\"><font><sz val=\"11\"/><name val=\"Calibri\"/></font><font><b/><sz val=\"16\"/><name val=\"Calibri\"/></font><font><b/><sz val=\"11\"/><name val=\"Calibri\"/></font></fonts>\
+<fills count=\"3\"><fill><patternFill patternType=\"none\"/></fill><fill><patternFill patternType=\"gray125\"/></fill>\
+<fill><patternFill patternType=\"solid\"><fgColor rgb=\"FFD9D9D9\"/><bgColor indexed=\"64\"/></patternFill></fill></fills>\
+<borders count=\"2\"><border><left/><right/><top/><bottom/><diagonal/></border>\
+<border><left style=\"thin\"><color auto=\"1\"/></left><right style=\"thin\"><color auto=\"1\"/></right>\
+<top style=\"thin\"><color auto=\"1\"/></top><bottom style=\"thin\"><color auto=\"1\"/></bottom><diagonal/></border></borders>\
+<cellStyleXfs count=\"1\"><xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\"/></cellStyleXfs>\
+<cellXfs count=\"6\"><xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\"/>\
+<xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"1\" xfId=\"0\" applyBorder=\"1\" applyAlignment=\"1\"><alignment horizontal=\"center\" vertical=\"center\"/></xf>\
+<xf numFmtId=\"0\" fontId=\"0\" fillId=\"2\" borderId=\"1\" xfId=\"0\" applyFill=\"1\" applyBorder=\"1\" applyAlignment=\"1\"><alignment horizontal=\"center\" vertical=\"center\"/></xf>\
+<xf numFmtId=\"0\" fontId=\"1\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyFont=\"1\" applyAlignment=\"1\"><alignment horizontal=\"center\"/></xf>\
+<xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyAlignment=\"1\"><alignment horizontal=\"center\"/></xf>\
+<xf numFmtId=\"0\" fontId=\"2\" fillId=\"0\" borderId=\"1\" xfId=\"0\" applyFont=\"1\" applyBorder=\"1\" applyAlignment=\"1\"><alignment horizontal=\"center\" vertical=\"center\"/></xf></cellXfs>\
+</styleSheet>\n";
+
+const ROOT_RELS: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"xl/workbook.xml\"/>\
+</Relationships>\n";
+
+impl PuzzleGrid {
+    // render_xlsx - an excel workbook with the puzzle on the first sheet, with answer_key set a second
+    //               sheet has the answer key
+    //
+    // The grid cells are centered with borders all around under merged rows for the title, subtitle,
+    // and instructions, and the letters of the placed words are shaded on the answer key sheet
+    pub fn render_xlsx(&self, answer_key: bool) -> Vec<u8> {
+        let mut heading = vec![(self.display_title(), STYLE_TITLE)];
+        heading.extend(self.subtitle().map(|s| (s, STYLE_NOTE)));
+        heading.extend(self.instructions().map(|s| (s, STYLE_NOTE)));
+        let mut sheets = vec![("Puzzle", self.xlsx_sheet(&heading, None))];
+        if answer_key {
+            let answer_title = self.answer_title();
+            sheets.push(("Answer Key", self.xlsx_sheet(&[(&answer_title, STYLE_TITLE)], Some(&self.answer_mask()))));
+        }
+        
+        let mut content_types = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+            <Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+            <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+            <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+            <Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>\
+            <Override PartName=\"/xl/styles.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml\"/>");
+        let mut workbook = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+            <workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
+            xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\"><sheets>");
+        let mut workbook_rels = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+            <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+            <Relationship Id=\"rId0\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles\" Target=\"styles.xml\"/>");
+        let mut files = Vec::new();
+        for (i, (name, sheet)) in sheets.into_iter().enumerate() {
+            let n = i + 1;
+            content_types.push_str(&format!("<Override PartName=\"/xl/worksheets/sheet{}.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>", n));
+            workbook.push_str(&format!("<sheet name=\"{}\" sheetId=\"{}\" r:id=\"rId{}\"/>", name, n, n));
+            workbook_rels.push_str(&format!("<Relationship Id=\"rId{}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" Target=\"worksheets/sheet{}.xml\"/>", n, n));
+            files.push((format!("xl/worksheets/sheet{}.xml", n), sheet.into_bytes()));
+        }
+        content_types.push_str("</Types>\n");
+        workbook.push_str("</sheets></workbook>\n");
+        workbook_rels.push_str("</Relationships>\n");
+        
+        files.insert(0, (String::from("[Content_Types].xml"), content_types.into_bytes()));
+        files.insert(1, (String::from("_rels/.rels"), ROOT_RELS.as_bytes().to_vec()));
+        files.insert(2, (String::from("xl/workbook.xml"), workbook.into_bytes()));
+        files.insert(3, (String::from("xl/_rels/workbook.xml.rels"), workbook_rels.into_bytes()));
+        files.insert(4, (String::from("xl/styles.xml"), STYLES.as_bytes().to_vec()));
+        zip(&files)
+    }
+    
+    // xlsx_sheet - the worksheet xml for the heading lines with their styles, grid, and word bank,
+    //              shade marks the cells to highlight
+    fn xlsx_sheet(&self, heading: &[(&str, usize)], shade: Option<&[Vec<bool>]>) -> String {
+        let last_column = column_name(self.width - 1);
+        let mut sheet = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+            <worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">");
+        sheet.push_str(&format!("<cols><col min=\"1\" max=\"{}\" width=\"4\" customWidth=\"1\"/></cols><sheetData>", self.width));
+        
+        // a row for each heading line, the grid starts after a blank row under them
+        for (i, (line, style)) in heading.iter().enumerate() {
+            let height = if *style == STYLE_TITLE { 24 } else { 18 };
+            sheet.push_str(&format!("<row r=\"{}\" ht=\"{}\" customHeight=\"1\">{}</row>", i + 1, height, cell(0, i + 1, line, *style)));
+        }
+        let grid_top = heading.len() + 2;
+        let starts = self.bold_starts(shade.is_some());
+        for (y, row) in self.shown_grid(shade.is_some()).iter().enumerate() {
+            let r = y + grid_top;
+            sheet.push_str(&format!("<row r=\"{}\" ht=\"20\" customHeight=\"1\">", r));
+            for (x, letter) in row.iter().enumerate() {
+                let style = match shade {
+                    Some(mask) if mask[y][x] => STYLE_SHADED,
+                    None if self.revealed.contains(&(x, y)) || starts.contains(&(x, y)) => STYLE_HINT,
+                    _ => STYLE_CELL,
+                };
+                sheet.push_str(&cell(x, r, letter, style));
+            }
+            sheet.push_str("</row>");
+        }
+        
+        // word bank, a row at a time with the words spread across the width of the grid
+        let bank_top = grid_top + self.height + 1;
+        let columns = self.print.columns(BANK_COLUMNS);
+        let spacing = (self.width / columns).max(1);
+        let bank = self.printed_bank(shade.is_some());
+        for (i, line) in bank.chunks(columns).enumerate() {
+            let r = bank_top + i;
+            sheet.push_str(&format!("<row r=\"{}\">", r));
+            for (j, entry) in line.iter().enumerate() {
+                sheet.push_str(&cell(j * spacing, r, entry, 0));
+            }
+            sheet.push_str("</row>");
+        }
+        
+        // the puzzle id a row under the word bank
+        if let Some(id) = self.id_line() {
+            let r = bank_top + bank.len().div_ceil(columns) + 1;
+            sheet.push_str(&format!("<row r=\"{}\">{}</row>", r, cell(0, r, &id, 0)));
+        }
+        
+        let merges: Vec<String> = (1..=heading.len()).map(|r| format!("<mergeCell ref=\"A{}:{}{}\"/>", r, last_column, r)).collect();
+        sheet.push_str(&format!("</sheetData><mergeCells count=\"{}\">{}</mergeCells></worksheet>\n", merges.len(), merges.concat()));
+        sheet
+    }
+}
+
+// cell - an inline string cell at column x of row r
+fn cell(x: usize, r: usize, text: &str, style: usize) -> String {
+    format!("<c r=\"{}{}\" s=\"{}\" t=\"inlineStr\"><is><t>{}</t></is></c>", column_name(x), r, style, escape_xml(text))
+}
+
+// column_name - the spreadsheet name of column x, A through Z then AA, AB, ...
+fn column_name(x: usize) -> String {
+    let mut name = String::new();
+    let mut n = x + 1;
+    while n > 0 {
+        let rem = (n - 1) % 26;
+        name.insert(0, char::from(b'A' + rem as u8));
+        n = (n - 1) / 26;
+    }
+    name
+}
+
+// zip - a zip archive of the files, stored without compression
+fn zip(files: &[(String, Vec<u8>)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut directory = Vec::new();
+    for (name, data) in files.iter() {
+        let offset = out.len() as u32;
+        let crc = crc32(data);
+        let size = data.len() as u32;
+        
+        // version 2.0, no flags, stored, dated 1980-01-01
+        let mut header = Vec::new();
+        header.extend_from_slice(&20u16.to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes());
+        header.extend_from_slice(&0x21u16.to_le_bytes());
+        header.extend_from_slice(&crc.to_le_bytes());
+        header.extend_from_slice(&size.to_le_bytes());
+        header.extend_from_slice(&size.to_le_bytes());
+        header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes());
+        
+        out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        out.extend_from_slice(&header);
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(data);
+        
+        // the central directory repeats the header with a few more fields
+        directory.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        directory.extend_from_slice(&20u16.to_le_bytes());
+        directory.extend_from_slice(&header);
+        directory.extend_from_slice(&[0; 6]);
+        directory.extend_from_slice(&0u32.to_le_bytes());
+        directory.extend_from_slice(&offset.to_le_bytes());
+        directory.extend_from_slice(name.as_bytes());
+    }
+    
+    let directory_offset = out.len() as u32;
+    out.extend_from_slice(&directory);
+    out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    out.extend_from_slice(&[0; 4]);
+    out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+    out.extend_from_slice(&directory_offset.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Difficulty, Direction};
+    
+    #[test]
+    fn column_names() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(27), "AB");
+    }
+    
+    #[test]
+    fn xlsx_workbook() {
+        let mut puzzle = PuzzleGrid::new(4, 2, 10000, Difficulty::Medium);
+        puzzle.write_word("CAT", &1, &1, &Direction::Right);
+        puzzle.entries.push(String::from("CAT"));
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        
+        let answer = puzzle.xlsx_sheet(&[("Answer Key", STYLE_TITLE)], Some(&puzzle.answer_mask()));
+        assert!(answer.contains("<mergeCell ref=\"A1:D1\"/>"));
+        assert_eq!(answer.matches(&format!("s=\"{}\"", STYLE_SHADED)).count(), 3);
+        assert!(answer.contains("<c r=\"B4\" s=\"2\" t=\"inlineStr\"><is><t>C</t></is></c>"));
+        
+        // the subtitle and instructions push the grid down a row each
+        let sheet = puzzle.xlsx_sheet(&[("Pets", STYLE_TITLE), ("Level 1", STYLE_NOTE), ("Find the pets", STYLE_NOTE)], None);
+        assert!(sheet.contains("<mergeCells count=\"3\">"));
+        assert!(sheet.contains("<c r=\"B6\" s=\"1\" t=\"inlineStr\"><is><t>C</t></is></c>"));
+        
+        let xlsx = puzzle.render_xlsx(true);
+        assert!(xlsx.starts_with(b"PK\x03\x04"));
+        // the end of central directory record counts all 7 parts of the workbook
+        let end = xlsx.len() - 22;
+        assert_eq!(&xlsx[end..end + 4], b"PK\x05\x06");
+        assert_eq!(xlsx[end + 10], 7);
+        let xlsx = puzzle.render_xlsx(false);
+        assert_eq!(xlsx[xlsx.len() - 12], 6);
+    }
+}
@@ -0,0 +1,224 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+use rand::Rng;
+use crate::cli::json_config;
+use crate::load::Json;
+use crate::render::render_files;
+use crate::{make_from_text, start_timeout, ErrorKind, Format, Metadata, WordfindError};
+
+// The service mode, a small http api for making puzzles without the command line
+//
+// POST /puzzle with a json body of the words, either one string with a word to a line or an array,
+// the options as a json object of command line options, and an optional seed
+//
+// {"words": ["cat", "dog"], "options": {"size": 12, "format": "html"}, "seed": 42}
+//
+// and the answer is the puzzle in the format from the options, json with the placements of the
+// words when it isn't given, with the answer key in the same file
+// GET /health answers ok, for a load balancer to check on the service
+// A failure answers with the error as a json object, the way --error-format json prints it
+
+// Most bytes a request can send, a word list much longer than this won't fit in a puzzle anyway
+const MAX_REQUEST: usize = 1 << 20;
+
+// How long a connection can sit without sending anything before it's dropped
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Response - the status, content type, and body to send back
+type Response = (u16, &'static str, Vec<u8>);
+
+// serve - answer requests on the address, like 127.0.0.1:8080, until the process is stopped
+//         Each connection is handled on its own thread
+pub fn serve(address: &str) -> Result<(), WordfindError> {
+    serve_on(TcpListener::bind(address)?)
+}
+
+// serve_on - serve with a listener that's already bound
+pub(crate) fn serve_on(listener: TcpListener) -> Result<(), WordfindError> {
+    for stream in listener.incoming() {
+        let stream = stream?;
+        thread::spawn(move || {
+            // a client that goes away early has nobody left to tell
+            let _ = handle(stream);
+        });
+    }
+    Ok(())
+}
+
+// handle - read one request from the connection and send back the response, the connection is
+//          closed after it
+fn handle(stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or("").to_string(), parts.next().unwrap_or("").to_string());
+    let mut length = 0;
+    let mut head = line.len();
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            break;
+        }
+        head += header.len();
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            match name.trim().to_lowercase().as_str() {
+                "content-length" => length = value.trim().parse().unwrap_or(usize::MAX),
+                // curl waits for this before sending a body of more than a kilobyte
+                "expect" if value.trim().eq_ignore_ascii_case("100-continue") => writer.write_all(b"HTTP/1.1 100 Continue\r\n\r\n")?,
+                _ => {},
+            }
+        }
+        if head > MAX_REQUEST {
+            return respond(&mut writer, error(431, "the request headers are too long"));
+        }
+    }
+    if length > MAX_REQUEST {
+        return respond(&mut writer, error(413, "the request is too long"));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    respond(&mut writer, route(&method, &target, &body))
+}
+
+// respond - write the response with the headers that go with it
+fn respond(writer: &mut TcpStream, (status, content_type, body): Response) -> io::Result<()> {
+    let head = format!("HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", status, reason(status), content_type, body.len());
+    writer.write_all(head.as_bytes())?;
+    writer.write_all(&body)?;
+    writer.flush()
+}
+
+// route - the response for a request, by its method and path
+fn route(method: &str, target: &str, body: &[u8]) -> Response {
+    let path = target.split('?').next().unwrap_or("");
+    match (method, path) {
+        ("POST", "/puzzle") => match puzzle(body) {
+            Ok(response) => response,
+            Err(err) => {
+                let status = match err.kind() {
+                    ErrorKind::Usage | ErrorKind::Invalid => 400,
+                    ErrorKind::Placement => 422,
+                    ErrorKind::Io => 500,
+                };
+                (status, "application/json", err.to_json().into_bytes())
+            },
+        },
+        ("GET", "/health") => (200, "text/plain; charset=utf-8", b"ok\n".to_vec()),
+        (_, "/puzzle") | (_, "/health") => error(405, &format!("{} isn't allowed on {}", method, path)),
+        _ => error(404, &format!("there's nothing at {}", path)),
+    }
+}
+
+// puzzle - make the puzzle a POST /puzzle asks for and render it
+fn puzzle(body: &[u8]) -> Result<Response, WordfindError> {
+    let body = std::str::from_utf8(body).map_err(|_| WordfindError::Usage(String::from("the request isn't utf-8")))?;
+    let request = Json::parse(body)?;
+    let words = match request.get("words") {
+        Json::Str(words) => words.clone(),
+        Json::Array(items) => items.iter().map(|w| w.as_str().ok_or_else(|| WordfindError::Usage(String::from("the words have to be strings")))).collect::<Result<Vec<&str>, _>>()?.join("\n"),
+        Json::Null => String::new(),
+        _ => return Err(WordfindError::Usage(String::from("the words have to be a string or an array of strings"))),
+    };
+    let options = match request.get("options") {
+        Json::Null => Json::Object(Vec::new()),
+        options => options.clone(),
+    };
+    let given_format = options.get("format") != &Json::Null;
+    let mut config = json_config(options)?;
+    if !given_format {
+        config.format = Format::Json;
+    }
+    if config.template.is_some() {
+        return Err(WordfindError::Usage(String::from("serve doesn't read templates, they're files on the server")));
+    }
+    let seed = match request.get("seed") {
+        Json::Null => rand::thread_rng().gen(),
+        seed => seed.as_u64().ok_or_else(|| WordfindError::Usage(String::from("the seed has to be a whole number")))?,
+    };
+    
+    let content_type = match config.format {
+        Format::Json | Format::Ipuz => "application/json",
+        Format::Html | Format::HtmlInteractive => "text/html; charset=utf-8",
+        Format::Pdf => "application/pdf",
+        Format::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        Format::Markdown => "text/markdown; charset=utf-8",
+        _ => return Err(WordfindError::Usage(String::from("serve sends the formats with the answer key in the same file, json, html, html-interactive, pdf, xlsx, ipuz, or markdown"))),
+    };
+    
+    start_timeout(&mut config);
+    let mut puzzle = make_from_text(config.clone(), &words, seed)?;
+    puzzle.metadata = Some(Metadata::new(&puzzle, seed, None));
+    let (contents, _) = render_files(&puzzle, &config)?;
+    Ok((200, content_type, contents))
+}
+
+// error - a response for a request that can't be answered, with the message as a usage error
+fn error(status: u16, message: &str) -> Response {
+    (status, "application/json", WordfindError::Usage(message.to_string()).to_json().into_bytes())
+}
+
+// reason - the reason phrase that goes with a status
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PuzzleGrid;
+    
+    #[test]
+    fn requests_answered() {
+        let (status, content_type, body) = route("POST", "/puzzle", b"{\"words\": [\"cat\", \"dog\", \"owl\"], \"options\": {\"size\": 6, \"title\": \"Pets\"}, \"seed\": 42}");
+        assert_eq!((status, content_type), (200, "application/json"));
+        let puzzle = PuzzleGrid::from_json(&String::from_utf8(body).unwrap()).unwrap();
+        assert_eq!((puzzle.title(), puzzle.entries().len(), puzzle.grid().len()), (Some("Pets"), 3, 6));
+        assert_eq!(puzzle.metadata().map(|m| m.seed), Some(42));
+        
+        let (status, content_type, body) = route("POST", "/puzzle?x=1", b"{\"words\": \"cat\\ndog\\n\", \"options\": {\"size\": 6, \"format\": \"html\"}}");
+        assert_eq!((status, content_type), (200, "text/html; charset=utf-8"));
+        assert!(String::from_utf8(body).unwrap().contains("<table"));
+        
+        assert_eq!(route("POST", "/puzzle", b"{\"words\": \"hippopotamus\", \"options\": {\"size\": 5}}").0, 400);
+        assert_eq!(route("POST", "/puzzle", b"{\"words\": \"cat\", \"options\": {\"format\": \"csv\"}}").0, 400);
+        assert_eq!(route("POST", "/puzzle", b"not json").0, 400);
+        assert_eq!(route("GET", "/puzzle", b"").0, 405);
+        assert_eq!(route("GET", "/health", b"").0, 200);
+        assert_eq!(route("GET", "/", b"").0, 404);
+    }
+    
+    #[test]
+    fn served_over_http() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || serve_on(listener));
+        
+        let body = "{\"words\": \"cat\\ndog\", \"options\": {\"size\": 5}, \"seed\": 7}";
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(stream, "POST /puzzle HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        let json = response.split("\r\n\r\n").nth(1).unwrap();
+        assert_eq!(PuzzleGrid::from_json(json).unwrap().grid().len(), 5);
+    }
+}
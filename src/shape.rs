@@ -0,0 +1,174 @@
+use crate::WordfindError;
+
+// Shape - the shaped grids built into the program, drawn for whatever size the grid is so there's
+//         no mask file to make by hand
+//
+#[derive(Debug,Clone,Copy,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Shape {
+    Circle,
+    Heart,
+    Star,
+    Diamond,
+}
+
+// How far in the inner corners of the star are, as a fraction of the points
+const STAR_INNER: f32 = 0.5;
+
+// A 5 x 7 font for the giant letters of letter_mask, # is a filled pixel
+const FONT: &[(char, [&str; 7])] = &[
+    ('A', [".###.", "#...#", "#...#", "#####", "#...#", "#...#", "#...#"]),
+    ('B', ["####.", "#...#", "#...#", "####.", "#...#", "#...#", "####."]),
+    ('C', [".###.", "#...#", "#....", "#....", "#....", "#...#", ".###."]),
+    ('D', ["####.", "#...#", "#...#", "#...#", "#...#", "#...#", "####."]),
+    ('E', ["#####", "#....", "#....", "####.", "#....", "#....", "#####"]),
+    ('F', ["#####", "#....", "#....", "####.", "#....", "#....", "#...."]),
+    ('G', [".###.", "#...#", "#....", "#.###", "#...#", "#...#", ".####"]),
+    ('H', ["#...#", "#...#", "#...#", "#####", "#...#", "#...#", "#...#"]),
+    ('I', [".###.", "..#..", "..#..", "..#..", "..#..", "..#..", ".###."]),
+    ('J', ["..###", "...#.", "...#.", "...#.", "...#.", "#..#.", ".##.."]),
+    ('K', ["#...#", "#..#.", "#.#..", "##...", "#.#..", "#..#.", "#...#"]),
+    ('L', ["#....", "#....", "#....", "#....", "#....", "#....", "#####"]),
+    ('M', ["#...#", "##.##", "#.#.#", "#.#.#", "#...#", "#...#", "#...#"]),
+    ('N', ["#...#", "#...#", "##..#", "#.#.#", "#..##", "#...#", "#...#"]),
+    ('O', [".###.", "#...#", "#...#", "#...#", "#...#", "#...#", ".###."]),
+    ('P', ["####.", "#...#", "#...#", "####.", "#....", "#....", "#...."]),
+    ('Q', [".###.", "#...#", "#...#", "#...#", "#.#.#", "#..#.", ".##.#"]),
+    ('R', ["####.", "#...#", "#...#", "####.", "#.#..", "#..#.", "#...#"]),
+    ('S', [".####", "#....", "#....", ".###.", "....#", "....#", "####."]),
+    ('T', ["#####", "..#..", "..#..", "..#..", "..#..", "..#..", "..#.."]),
+    ('U', ["#...#", "#...#", "#...#", "#...#", "#...#", "#...#", ".###."]),
+    ('V', ["#...#", "#...#", "#...#", "#...#", "#...#", ".#.#.", "..#.."]),
+    ('W', ["#...#", "#...#", "#...#", "#.#.#", "#.#.#", "#.#.#", ".#.#."]),
+    ('X', ["#...#", "#...#", ".#.#.", "..#..", ".#.#.", "#...#", "#...#"]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#..", "..#.."]),
+    ('Z', ["#####", "....#", "...#.", "..#..", ".#...", "#....", "#####"]),
+    ('0', [".###.", "#...#", "#..##", "#.#.#", "##..#", "#...#", ".###."]),
+    ('1', ["..#..", ".##..", "..#..", "..#..", "..#..", "..#..", ".###."]),
+    ('2', [".###.", "#...#", "....#", "...#.", "..#..", ".#...", "#####"]),
+    ('3', ["#####", "...#.", "..#..", "...#.", "....#", "#...#", ".###."]),
+    ('4', ["...#.", "..##.", ".#.#.", "#..#.", "#####", "...#.", "...#."]),
+    ('5', ["#####", "#....", "####.", "....#", "....#", "#...#", ".###."]),
+    ('6', ["..##.", ".#...", "#....", "####.", "#...#", "#...#", ".###."]),
+    ('7', ["#####", "....#", "...#.", "..#..", ".#...", ".#...", ".#..."]),
+    ('8', [".###.", "#...#", "#...#", ".###.", "#...#", "#...#", ".###."]),
+    ('9', [".###.", "#...#", "#...#", ".####", "....#", "...#.", ".##.."]),
+];
+
+impl Shape {
+    // from_name - the shape for a name given on the command line
+    pub fn from_name(name: &str) -> Option<Shape> {
+        match name.to_ascii_lowercase().as_str() {
+            "circle" => Some(Shape::Circle),
+            "heart" => Some(Shape::Heart),
+            "star" => Some(Shape::Star),
+            "diamond" => Some(Shape::Diamond),
+            _ => None,
+        }
+    }
+    
+    // mask - the cells of a width x height grid inside the shape, for Config::mask
+    //        Each cell is in when its center is, the shape is stretched to fill the grid
+    pub fn mask(&self, width: usize, height: usize) -> Vec<Vec<bool>> {
+        (0..height).map(|y| (0..width).map(|x| {
+            // the cell center from -1 to 1 across the grid, with up positive
+            let u = (x as f32 + 0.5) / width as f32 * 2.0 - 1.0;
+            let v = 1.0 - (y as f32 + 0.5) / height as f32 * 2.0;
+            self.contains(u, v)
+        }).collect()).collect()
+    }
+    
+    // contains - whether the point is inside the shape drawn in the square from -1 to 1
+    fn contains(&self, u: f32, v: f32) -> bool {
+        match self {
+            Shape::Circle => u * u + v * v <= 1.0,
+            Shape::Diamond => u.abs() + v.abs() <= 1.0,
+            Shape::Heart => {
+                // the heart curve (x² + y² - 1)³ = x²y³, moved and scaled to fill the square
+                let (x, y) = (u * 1.15, v * 1.15 + 0.1);
+                let a = x * x + y * y - 1.0;
+                a * a * a - x * x * y * y * y <= 0.0
+            },
+            Shape::Star => {
+                // a five pointed star with a point straight up, the polygon's corners alternate
+                // between the points and the inner corners
+                let corners: Vec<(f32, f32)> = (0..10).map(|i| {
+                    let angle = std::f32::consts::FRAC_PI_2 + i as f32 * std::f32::consts::PI / 5.0;
+                    let radius = if i % 2 == 0 { 1.0 } else { STAR_INNER };
+                    (radius * angle.cos(), radius * angle.sin() - 0.09)
+                }).collect();
+                inside(&corners, u, v)
+            },
+        }
+    }
+}
+
+// letter_mask - the cells of a width x height grid covered by the text drawn in giant letters, for a
+//               puzzle in the shape of a name or an initial
+//               The letters come from a 5 x 7 font stretched to fill the grid, with a column gap
+//               between them, and only letters, digits, and spaces can be drawn
+pub fn letter_mask(text: &str, width: usize, height: usize) -> Result<Vec<Vec<bool>>, WordfindError> {
+    let mut glyphs = Vec::new();
+    for c in text.trim().chars().map(|c| c.to_ascii_uppercase()) {
+        match FONT.iter().find(|(letter, _)| *letter == c) {
+            Some((_, rows)) => glyphs.push(*rows),
+            None if c == ' ' => glyphs.push(["....."; 7]),
+            None => return Err(WordfindError::InvalidMask { reason: format!("there's no giant letter for {}", c) }),
+        }
+    }
+    if glyphs.is_empty() {
+        return Err(WordfindError::InvalidMask { reason: String::from("there are no letters to draw") });
+    }
+    
+    // the pixels of the text, every letter five wide with one blank column between them
+    let columns = glyphs.len() * 6 - 1;
+    let pixel = |column: usize, row: usize| column % 6 < 5 && glyphs[column / 6][row].as_bytes()[column % 6] == b'#';
+    Ok((0..height).map(|y| (0..width).map(|x| pixel(x * columns / width, y * 7 / height)).collect()).collect())
+}
+
+// inside - whether the point is inside the polygon, by counting the edges a ray from it crosses
+fn inside(corners: &[(f32, f32)], u: f32, v: f32) -> bool {
+    let mut crossings = 0;
+    for (i, (x1, y1)) in corners.iter().enumerate() {
+        let (x2, y2) = corners[(i + 1) % corners.len()];
+        if (*y1 > v) != (y2 > v) && u < x1 + (v - y1) / (y2 - y1) * (x2 - x1) {
+            crossings += 1;
+        }
+    }
+    crossings % 2 == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn shapes_fit() {
+        for name in ["circle", "Heart", "star", "diamond"].iter() {
+            let shape = Shape::from_name(name).unwrap();
+            let mask = shape.mask(25, 25);
+            assert_eq!((mask.len(), mask[0].len()), (25, 25));
+            
+            // every shape reaches the middle but not the corners, and is the same on both sides
+            assert!(mask[12][12]);
+            assert!(!mask[0][0] && !mask[24][24]);
+            assert!(mask.iter().all(|row| row.iter().eq(row.iter().rev())));
+        }
+        assert_eq!(Shape::Diamond.mask(3, 3), vec![vec![false, true, false], vec![true, true, true], vec![false, true, false]]);
+        assert_eq!(Shape::from_name("square"), None);
+    }
+    
+    #[test]
+    fn giant_letters() {
+        let mask = letter_mask("l", 10, 14).unwrap();
+        assert_eq!((mask.len(), mask[0].len()), (14, 10));
+        assert!(mask[0][0] && mask[0][1] && !mask[0][2]);
+        assert!(mask[13].iter().all(|cell| *cell));
+        
+        // the gap between the letters is blank all the way down
+        let mask = letter_mask("HI", 11, 7).unwrap();
+        assert!(mask.iter().all(|row| !row[5]));
+        assert!(letter_mask("A!", 10, 10).is_err());
+        assert!(letter_mask(" ", 10, 10).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::HashMap;
+use crate::load::{json_rows, Json};
+use crate::{is_word, letters, placed_word, read_word_list, read_words, word_text, Config, Difficulty, Direction, Placement, PuzzleGrid, WordfindError};
+
+// Fewest letters a dictionary word needs for the sweep when Config::min_length doesn't say, shorter
+// words turn up all over any grid
+pub const SWEEP_MIN_LENGTH: usize = 3;
+
+// SolveReport - where solve found the words of the list in the grid
+//
+#[derive(Debug,Clone,Default)]
+pub struct SolveReport {
+    // each word found, as it's written in the word list, with every place it is in the grid, in
+    // the order of the word list
+    pub found: Vec<(String, Vec<Placement>)>,
+    // the words that aren't anywhere in the grid
+    pub missing: Vec<String>,
+    // the words of the Config::dictionary sweep that are in the grid without being in the word
+    // list, as they're written in the dictionary, with where each one is
+    pub hidden: Vec<(String, Placement)>,
+    // size of the grid that was searched
+    pub width: usize,
+    pub height: usize,
+}
+
+// solve - search the grid in grid_file for the words in Config::wordsfile and the rest of the
+//         word files, in all eight directions
+//         The grid can be the csv, json, or text this program writes, or a plain grid with a row
+//         of letters on each line, see read_grid
+//         With Config::dictionary the grid is also swept for every dictionary word at least
+//         Config::min_length long, the ones that are part of a word from the list are left out
+pub fn solve(config: Config, grid_file: &str) -> Result<SolveReport, WordfindError> {
+    let rows = read_grid(&read_words(grid_file)?.join("\n"), config.delimiter)?;
+    let mut words = Vec::new();
+    for file in std::iter::once(&config.wordsfile).chain(config.more_wordsfiles.iter()).filter(|f| !f.is_empty()) {
+        words.extend(read_word_list(&config, file)?);
+    }
+    let dictionary = match &config.dictionary {
+        Some(file) => Some(read_words(file)?),
+        None => None,
+    };
+    search(&config, rows, &words, dictionary.as_deref())
+}
+
+// search - solve for a grid and word list that have already been read, and the dictionary to sweep
+//          the grid with when there is one
+pub(crate) fn search(config: &Config, rows: Vec<Vec<String>>, words: &[String], dictionary: Option<&[String]>) -> Result<SolveReport, WordfindError> {
+    let mut puzzle = PuzzleGrid::new(rows[0].len(), rows.len(), 0, Difficulty::Hard);
+    puzzle.grid = rows;
+    
+    let mut report = SolveReport { width: puzzle.width, height: puzzle.height, ..SolveReport::default() };
+    for line in words.iter().filter(|w| is_word(w)) {
+        // a palindrome is found once from each end, only the first of the two is kept
+        let mut places: Vec<Placement> = Vec::new();
+        for place in puzzle.find_all(&placed_word(config, line)) {
+            let mut cells = place.cells();
+            cells.reverse();
+            if !places.iter().any(|p| p.cells() == cells) {
+                places.push(place);
+            }
+        }
+        if places.is_empty() {
+            report.missing.push(word_text(line).to_string());
+        } else {
+            report.found.push((word_text(line).to_string(), places));
+        }
+    }
+    
+    if let Some(dictionary) = dictionary {
+        let listed: Vec<String> = report.found.iter().map(|(w, _)| placed_word(config, w).to_uppercase()).collect();
+        let covered: Vec<Vec<(usize, usize)>> = report.found.iter().flat_map(|(_, places)| places.iter().map(Placement::cells)).collect();
+        let mut words = HashMap::new();
+        for line in dictionary.iter().filter(|w| is_word(w)) {
+            let word = placed_word(config, line).to_uppercase();
+            if letters(&word).len() >= config.min_length.max(SWEEP_MIN_LENGTH) && !listed.contains(&word) {
+                words.entry(word).or_insert_with(|| word_text(line).to_string());
+            }
+        }
+        for place in sweep(&puzzle, &words) {
+            let cells = place.cells();
+            if !covered.iter().any(|c| cells.iter().all(|cell| c.contains(cell))) {
+                report.hidden.push((words[&place.word].clone(), place));
+            }
+        }
+    } else if report.found.is_empty() && report.missing.is_empty() {
+        return Err(WordfindError::NoWords);
+    }
+    Ok(report)
+}
+
+// sweep - every place one of the words reads in the grid in any direction, the keys of words are the
+//         upper case words, a palindrome is only listed from the end it's found from first
+fn sweep(puzzle: &PuzzleGrid, words: &HashMap<String, String>) -> Vec<Placement> {
+    let longest = words.keys().map(|w| letters(w).len()).max().unwrap_or(0);
+    let mut found: Vec<Placement> = Vec::new();
+    for y in 0..puzzle.height {
+        for x in 0..puzzle.width {
+            for dir in Direction::ALL.iter() {
+                let (x_inc, y_inc) = dir.incrementors();
+                let (mut xi, mut yi) = (x as isize, y as isize);
+                let mut text = String::new();
+                for _ in 0..longest {
+                    if xi < 0 || yi < 0 || xi >= puzzle.width as isize || yi >= puzzle.height as isize {
+                        break;
+                    }
+                    text.push_str(&puzzle.grid[yi as usize][xi as usize].to_uppercase());
+                    if words.contains_key(&text) {
+                        let place = Placement { word: text.clone(), x, y, dir: *dir, bend: None };
+                        let mut cells = place.cells();
+                        cells.reverse();
+                        if !found.iter().any(|p| p.cells() == cells) {
+                            found.push(place);
+                        }
+                    }
+                    xi += x_inc as isize;
+                    yi += y_inc as isize;
+                }
+            }
+        }
+    }
+    found
+}
+
+// read_grid - the cells of the grid in a puzzle file
+//             A json puzzle has the rows in its grid array, anything else is read a line at a
+//             time with the cells split up by the delimiter, by spaces, or into letters, and the
+//             grid is the most lines in a row with the same number of cells, each a single
+//             letter, so the headings and the word bank around it are skipped
+pub fn read_grid(text: &str, delimiter: char) -> Result<Vec<Vec<String>>, WordfindError> {
+    if text.trim_start().starts_with('{') {
+        return json_rows(&Json::parse(text)?);
+    }
+    match find_grid(text, delimiter) {
+        Some((_, grid)) => Ok(grid),
+        None => Err(WordfindError::InvalidGrid { reason: String::from("there's no grid of letters in it") }),
+    }
+}
+
+// find_grid - the line the grid starts on and its rows, the most lines in a row that could be
+//             rows of the same grid, None when there aren't at least two
+pub(crate) fn find_grid(text: &str, delimiter: char) -> Option<(usize, Vec<Vec<String>>)> {
+    let mut grid: (usize, Vec<Vec<String>>) = (0, Vec::new());
+    let mut run: (usize, Vec<Vec<String>>) = (0, Vec::new());
+    for (i, line) in text.lines().enumerate() {
+        match grid_row(line, delimiter) {
+            Some(row) if run.1.last().is_none_or(|last| last.len() == row.len()) => run.1.push(row),
+            row => {
+                if run.1.len() > grid.1.len() {
+                    grid = run;
+                }
+                run = (if row.is_some() { i } else { i + 1 }, row.into_iter().collect());
+            },
+        }
+    }
+    if run.1.len() > grid.1.len() {
+        grid = run;
+    }
+    Some(grid).filter(|(_, rows)| rows.len() > 1)
+}
+
+// grid_row - the cells of a line that could be a row of the grid, at least two cells and each one
+//            a single letter or digit
+fn grid_row(line: &str, delimiter: char) -> Option<Vec<String>> {
+    let line = line.replace(['\u{200e}', '\u{200f}'], "");
+    let cells: Vec<String> = if line.contains(delimiter) {
+        csv_fields(&line, delimiter)
+    } else if line.trim().contains(char::is_whitespace) {
+        line.split_whitespace().map(String::from).collect()
+    } else {
+        letters(line.trim())
+    };
+    let letter = |cell: &String| letters(cell).len() == 1 && cell.chars().next().is_some_and(char::is_alphanumeric);
+    if cells.len() > 1 && cells.iter().all(letter) {
+        Some(cells)
+    } else {
+        None
+    }
+}
+
+// csv_fields - the fields of a line of csv that aren't blank, trimmed and with the quotes taken off
+pub(crate) fn csv_fields(line: &str, delimiter: char) -> Vec<String> {
+    csv_row(line, delimiter).into_iter().filter(|f| !f.is_empty()).collect()
+}
+
+// csv_row - every field of a line of csv, blank ones too so they keep their columns
+pub(crate) fn csv_row(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            },
+            '"' => quoted = !quoted,
+            _ if c == delimiter && !quoted => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields.iter().map(|f| f.trim().to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Direction;
+    use std::fs;
+    
+    #[test]
+    fn grids_read_back() {
+        let mut puzzle = PuzzleGrid::new(4, 3, 10000, Difficulty::Hard);
+        puzzle.write_word("CAT", &1, &0, &Direction::Right);
+        puzzle.write_word("DOG", &0, &2, &Direction::Right);
+        puzzle.entries.extend(vec![String::from("CAT"), String::from("DOG")]);
+        puzzle.fill_in(&mut rand::rngs::mock::StepRng::new(0, 0));
+        puzzle.set_title(Some(String::from("Pets")));
+        let grid = puzzle.grid().to_vec();
+        
+        assert_eq!(read_grid(&puzzle.render_json(), ',').unwrap(), grid);
+        assert_eq!(read_grid(&puzzle.to_string(), ',').unwrap(), grid);
+        let mut csv = Vec::new();
+        puzzle.write_csv(&mut csv, &Config::default(), false).unwrap();
+        assert_eq!(read_grid(&String::from_utf8(csv).unwrap(), ',').unwrap(), grid);
+        assert_eq!(read_grid("Pets\n\nACAT\nAAAA\nDOGA\n\nCAT\nDOG\n", ',').unwrap(), grid);
+        assert!(read_grid("CAT\n\nDOG\n", ',').is_err());
+        assert_eq!(csv_fields(",,\"A, \"\"B\"\"\",C,", ','), vec![String::from("A, \"B\""), String::from("C")]);
+    }
+    
+    #[test]
+    fn words_found() {
+        let dir = std::env::temp_dir().join("wordfindgen_words_found");
+        fs::create_dir_all(&dir).unwrap();
+        let grid = dir.join("grid.txt");
+        let words = dir.join("words.txt");
+        fs::write(&grid, "ACAT\nANNA\nDOGX\n").unwrap();
+        fs::write(&words, "# Pets\nCat\ndog | a pet\nAnna\nBird\n").unwrap();
+        let config = Config { wordsfile: words.to_string_lossy().to_string(), ..Config::default() };
+        
+        let report = solve(config, &grid.to_string_lossy()).unwrap();
+        let found: Vec<(&str, usize, usize, Direction, usize)> = report.found.iter().map(|(w, p)| (w.as_str(), p[0].x, p[0].y, p[0].dir, p.len())).collect();
+        assert_eq!(found, vec![("Cat", 1, 0, Direction::Right, 1), ("dog", 0, 2, Direction::Right, 1), ("Anna", 0, 1, Direction::Right, 1)]);
+        assert_eq!(report.missing, vec![String::from("Bird")]);
+        assert_eq!((report.width, report.height), (4, 3));
+        assert!(report.hidden.is_empty());
+        
+        // the sweep skips AT, too short, CAT and DOG, in the words, and ANN, part of ANNA
+        let dictionary = dir.join("dictionary.txt");
+        fs::write(&dictionary, "at\ncat\nann\nont\nAng\ndog\n").unwrap();
+        let config = Config { wordsfile: words.to_string_lossy().to_string(), dictionary: Some(dictionary.to_string_lossy().to_string()), ..Config::default() };
+        let report = solve(config, &grid.to_string_lossy()).unwrap();
+        let hidden: Vec<(&str, usize, usize, Direction)> = report.hidden.iter().map(|(w, p)| (w.as_str(), p.x, p.y, p.dir)).collect();
+        assert_eq!(hidden, vec![("Ang", 0, 0, Direction::DownRight), ("Ang", 2, 0, Direction::Down), ("ont", 1, 2, Direction::UpRight)]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,185 @@
+use rand::RngCore;
+use rand::seq::SliceRandom;
+use super::{letters, Direction, PuzzleGrid};
+
+// PlacementStrategy - decides where each word goes in the grid
+//
+// Implement this to try out a different placement heuristic, then hand it to
+// generate through Config::strategy (or PuzzleGrid::set_strategy)
+//
+pub trait PlacementStrategy: Send + Sync {
+    // choose_spot - the x, y, and direction for the word, None if it doesn't fit anywhere
+    //               The grid's placement_valid and candidates are the building blocks for this
+    fn choose_spot(&self, puzzle: &PuzzleGrid, word: &str, rng: &mut dyn RngCore) -> Option<(usize, usize, Direction)>;
+    
+    // place_all - place the whole word list and return the words that couldn't be placed
+    //             By default the words are placed one at a time using choose_spot
+    fn place_all(&self, puzzle: &mut PuzzleGrid, words: &[String], rng: &mut dyn RngCore) -> Vec<String> {
+        words.iter().filter(|word| puzzle.place(word, rng).is_err()).cloned().collect()
+    }
+}
+
+// RandomPlacement - try random positions until one fits or maxtries runs out
+//
+pub struct RandomPlacement;
+
+impl PlacementStrategy for RandomPlacement {
+    fn choose_spot(&self, puzzle: &PuzzleGrid, word: &str, rng: &mut dyn RngCore) -> Option<(usize, usize, Direction)> {
+        puzzle.random_spot(word, rng)
+    }
+}
+
+// OverlapPlacement - greedily pick the position that shares the most letters with the words already placed
+//
+pub struct OverlapPlacement;
+
+impl PlacementStrategy for OverlapPlacement {
+    fn choose_spot(&self, puzzle: &PuzzleGrid, word: &str, rng: &mut dyn RngCore) -> Option<(usize, usize, Direction)> {
+        puzzle.densest_spot(word, rng)
+    }
+}
+
+// CrissCrossPlacement - lay the words out as a criss-cross, longest first starting from the middle
+//                       of the grid, each one where it crosses the most of the words already placed
+//                       A word with nothing to cross yet is tried again once the others are in
+//
+pub struct CrissCrossPlacement;
+
+impl PlacementStrategy for CrissCrossPlacement {
+    fn choose_spot(&self, puzzle: &PuzzleGrid, word: &str, rng: &mut dyn RngCore) -> Option<(usize, usize, Direction)> {
+        if !puzzle.placements().is_empty() {
+            return puzzle.densest_spot(word, rng);
+        }
+        
+        // the first word goes through the middle so the rest have room on every side, measured in
+        // half cells so the middle of an even length word lands between two
+        let mut candidates = puzzle.candidates(word);
+        candidates.shuffle(rng);
+        let steps = letters(word).len() as isize - 1;
+        candidates.into_iter().min_by_key(|(x, y, dir)| {
+            let (x_inc, y_inc) = dir.incrementors();
+            let middle_x = 2 * *x as isize + x_inc as isize * steps;
+            let middle_y = 2 * *y as isize + y_inc as isize * steps;
+            (middle_x - (puzzle.width() as isize - 1)).abs() + (middle_y - (puzzle.height() as isize - 1)).abs()
+        })
+    }
+    
+    fn place_all(&self, puzzle: &mut PuzzleGrid, words: &[String], rng: &mut dyn RngCore) -> Vec<String> {
+        let mut left = words.to_vec();
+        left.sort_by_key(|w| std::cmp::Reverse(letters(w).len()));
+        loop {
+            let before = left.len();
+            left.retain(|word| puzzle.place(word, rng).is_err());
+            if left.is_empty() || left.len() == before {
+                return left;
+            }
+        }
+    }
+}
+
+// BalancedPlacement - put each word where it keeps the layout from leaning into one part of the
+//                     grid, see PuzzleGrid::balance, with symmetric set also where it matches
+//                     the words already placed when the grid is turned halfway round
+//
+pub struct BalancedPlacement {
+    pub symmetric: bool,
+}
+
+impl PlacementStrategy for BalancedPlacement {
+    fn choose_spot(&self, puzzle: &PuzzleGrid, word: &str, rng: &mut dyn RngCore) -> Option<(usize, usize, Direction)> {
+        let mut candidates = puzzle.candidates(word);
+        candidates.shuffle(rng);
+        let steps = letters(word).len();
+        let balance = |(x, y, dir): &(usize, usize, Direction)| {
+            let (x_inc, y_inc) = dir.incrementors();
+            let cells: Vec<(usize, usize)> = (0..steps as isize).map(|i| ((*x as isize + x_inc as isize * i) as usize, (*y as isize + y_inc as isize * i) as usize)).collect();
+            puzzle.balance(&cells, self.symmetric)
+        };
+        candidates.into_iter().map(|spot| (balance(&spot), spot)).max_by(|a, b| a.0.total_cmp(&b.0)).map(|(_, spot)| spot)
+    }
+}
+
+// MatchedPlacement - put each word in the spot it's matched with when it fits there, and at random
+//                    when it doesn't, for puzzles of the same list in different languages that
+//                    should look alike
+//
+pub struct MatchedPlacement {
+    // each word in uppercase with the start cell and direction it's matched with
+    pub spots: Vec<(String, (usize, usize, Direction))>,
+}
+
+impl PlacementStrategy for MatchedPlacement {
+    fn choose_spot(&self, puzzle: &PuzzleGrid, word: &str, rng: &mut dyn RngCore) -> Option<(usize, usize, Direction)> {
+        let word_upper = word.to_uppercase();
+        match self.spots.iter().find(|(w, _)| *w == word_upper) {
+            Some((_, (x, y, dir))) if puzzle.placement_valid(word, x, y, dir) => Some((*x, *y, *dir)),
+            _ => puzzle.random_spot(word, rng),
+        }
+    }
+}
+
+// BacktrackingPlacement - search every layout of the whole word list, only fails when nothing fits
+//
+pub struct BacktrackingPlacement;
+
+impl PlacementStrategy for BacktrackingPlacement {
+    fn choose_spot(&self, puzzle: &PuzzleGrid, word: &str, rng: &mut dyn RngCore) -> Option<(usize, usize, Direction)> {
+        puzzle.candidates(word).choose(rng).copied()
+    }
+    
+    fn place_all(&self, puzzle: &mut PuzzleGrid, words: &[String], rng: &mut dyn RngCore) -> Vec<String> {
+        if puzzle.place_all_backtracking(words, rng) {
+            Vec::new()
+        } else {
+            words.to_vec()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use super::*;
+    use super::super::{generate, Config, Objective};
+    
+    // TopLeft - a custom strategy that packs words into the first row that has room
+    struct TopLeft;
+    
+    impl PlacementStrategy for TopLeft {
+        fn choose_spot(&self, puzzle: &PuzzleGrid, word: &str, _rng: &mut dyn RngCore) -> Option<(usize, usize, Direction)> {
+            (0..puzzle.height()).map(|y| (0, y, Direction::Right)).find(|(x, y, dir)| puzzle.placement_valid(word, x, y, dir))
+        }
+    }
+    
+    #[test]
+    fn custom_strategy() {
+        let words: Vec<String> = ["Hello", "Thanks", "How"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 8, height: 8, strategy: Some(Arc::new(TopLeft)), ..Config::default() };
+        let puzzle = generate(&config, &words, &mut StdRng::seed_from_u64(1)).unwrap();
+        let starts: Vec<(usize, usize)> = puzzle.placements().iter().map(|p| (p.x, p.y)).collect();
+        assert_eq!(starts, [(0, 0), (0, 1), (0, 2)]);
+    }
+    
+    #[test]
+    fn backtracking_strategy() {
+        let words: Vec<String> = ["ABCDE", "FGHIJ", "KLMNO", "PQRST", "UVWXY"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 5, height: 5, backtrack: false, strategy: Some(Arc::new(BacktrackingPlacement)), ..Config::default() };
+        let puzzle = generate(&config, &words, &mut StdRng::seed_from_u64(1)).unwrap();
+        assert_eq!(puzzle.entries().len(), 5);
+    }
+    
+    #[test]
+    fn balanced_strategy() {
+        let words: Vec<String> = ["Apple", "Pear", "Plum", "Kiwi", "Lime", "Fig"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 11, height: 11, verify: true, strategy: Some(Arc::new(BalancedPlacement { symmetric: false })), ..Config::default() };
+        let balanced = generate(&config, &words, &mut StdRng::seed_from_u64(2)).unwrap();
+        assert!(balanced.measure(Objective::Centered) > 0.8);
+        
+        let config = Config { strategy: Some(Arc::new(BalancedPlacement { symmetric: true })), ..config };
+        let symmetric = generate(&config, &words, &mut StdRng::seed_from_u64(2)).unwrap();
+        assert_eq!(symmetric.entries().len(), 6);
+        assert!(symmetric.measure(Objective::Symmetric) > balanced.measure(Objective::Symmetric));
+    }
+}
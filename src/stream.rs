@@ -0,0 +1,174 @@
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use log::Level;
+use crate::{batch_config, check_words, filter_words, log, make_puzzle, run_seed, start_timeout, Config, Metadata, PuzzleGrid, WordfindError, MAX_REPEATS};
+
+// Puzzles - the puzzles of generate_many, made one at a time as they're asked for
+//
+// Each puzzle is handed over as soon as it's made and nothing of it is kept but two hashes, of its
+// grid and of where its words are, for spotting a repeat. So a bank of thousands only holds 16 bytes
+// a puzzle on top of the one being made, and each can be rendered or uploaded before the next one
+//
+// for puzzle in generate_many(&config, &words, 1000) {
+//     upload(puzzle?.render_json())?;
+// }
+//
+pub struct Puzzles {
+    config: Config,
+    words: Vec<String>,
+    seed: u64,
+    // the seed of each puzzle comes from seeds as it's made, and the seeds for making a repeated
+    // one again from retries, which starts where a Config::count batch of count draws its own
+    seeds: StdRng,
+    retries: StdRng,
+    count: usize,
+    next: usize,
+    // the hashes of the grids and layouts of the puzzles made so far, see fingerprint
+    grids: HashSet<u64>,
+    layouts: HashSet<u64>,
+    // a problem with the words, given back in place of the first puzzle
+    error: Option<WordfindError>,
+}
+
+// generate_many - count filled in puzzles of the words, made lazily one at a time by the iterator,
+//                 for a server making a bank of variants that renders each one as it comes
+//                 They're the same puzzles run makes with the same Config::seed and a Config::count
+//                 of count when it's more than one, numbered in their metadata, and a puzzle that
+//                 comes out the same as an earlier one is made again with a new seed
+//                 The words are checked first, a problem with them is the only item. A puzzle that
+//                 fails is an error item and the next one is still made, except after
+//                 WordfindError::Cancelled. Config::timeout counts for each puzzle on its own, and
+//                 Config::threads and Config::count aren't used
+pub fn generate_many(config: &Config, words: &[String], count: usize) -> Puzzles {
+    let seed = run_seed(config);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (words, error) = match check_words(config, words) {
+        Ok(()) => (filter_words(config, words, &mut rng), None),
+        Err(err) => (Vec::new(), Some(err)),
+    };
+    // a batch draws every seed before it makes any puzzle again, so the retries start past them,
+    // stepping over them takes a moment but no memory
+    let seeds = rng.clone();
+    for _ in 0..count {
+        rng.gen::<u64>();
+    }
+    let count = if error.is_some() { 0 } else { count };
+    Puzzles { config: config.clone(), words, seed, seeds, retries: rng, count, next: 0, grids: HashSet::new(), layouts: HashSet::new(), error }
+}
+
+impl Puzzles {
+    // seed - the seed the puzzles were made from, Config::seed or a random one when it isn't set
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+    
+    // make - puzzle i made with the seed
+    fn make(&self, i: usize, seed: u64) -> Result<PuzzleGrid, WordfindError> {
+        let mut config = batch_config(&self.config, i, self.count);
+        start_timeout(&mut config);
+        make_puzzle(&config, &self.words, &mut StdRng::seed_from_u64(seed))
+    }
+}
+
+impl Iterator for Puzzles {
+    type Item = Result<PuzzleGrid, WordfindError>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(err) = self.error.take() {
+            return Some(Err(err));
+        }
+        let i = self.next;
+        if i >= self.count {
+            return None;
+        }
+        let seed = self.seeds.gen();
+        self.next += 1;
+        let mut puzzle = match self.make(i, seed) {
+            Ok(puzzle) => puzzle,
+            Err(err) => {
+                // the rest would only be cancelled too
+                if matches!(err, WordfindError::Cancelled) {
+                    self.count = self.next;
+                }
+                return Some(Err(err));
+            },
+        };
+        let mut tries = 1;
+        let mut made = fingerprint(&puzzle);
+        while (self.grids.contains(&made.0) || self.layouts.contains(&made.1)) && tries < MAX_REPEATS {
+            log(&self.config, Level::Info, || format!("puzzle {} came out the same as an earlier one, making it again", i + 1));
+            let seed = self.retries.gen();
+            puzzle = match self.make(i, seed) {
+                Ok(puzzle) => puzzle,
+                Err(err) => return Some(Err(err)),
+            };
+            made = fingerprint(&puzzle);
+            tries += 1;
+        }
+        self.grids.insert(made.0);
+        self.layouts.insert(made.1);
+        puzzle.metadata = Some(Metadata::new(&puzzle, self.seed, Some(i + 1)));
+        Some(Ok(puzzle))
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.count - self.next + usize::from(self.error.is_some());
+        (left, Some(left))
+    }
+}
+
+// fingerprint - hashes of the puzzle's grid and of its layout, the size and the cells of each word,
+//               standing in for comparing with the puzzle itself once it's gone
+fn fingerprint(puzzle: &PuzzleGrid) -> (u64, u64) {
+    let mut grid = DefaultHasher::new();
+    puzzle.grid.hash(&mut grid);
+    let mut layout: Vec<(String, Vec<(usize, usize)>)> = puzzle.placements.iter().map(|p| (p.word.clone(), p.cells())).collect();
+    layout.sort();
+    let mut cells = DefaultHasher::new();
+    (puzzle.width, puzzle.height, layout).hash(&mut cells);
+    (grid.finish(), cells.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use crate::{run_with_words, Difficulty, Format};
+    
+    #[test]
+    fn puzzles_streamed() {
+        let dir = std::env::temp_dir().join("wordfindgen_puzzles_streamed");
+        let _ = fs::remove_dir_all(&dir);
+        let words: Vec<String> = ["cat", "dog", "owl", "bat"].iter().map(|w| w.to_string()).collect();
+        let config = Config { width: 6, height: 6, seed: Some(11), count: 4, format: Format::Json, output_dir: Some(dir.to_string_lossy().to_string()), ..Config::default() };
+        
+        let mut puzzles = generate_many(&config, &words, 4);
+        assert_eq!((puzzles.seed(), puzzles.size_hint()), (11, (4, Some(4))));
+        let first = puzzles.next().unwrap().unwrap();
+        assert_eq!(first.metadata().map(|m| m.puzzle), Some(Some(1)));
+        assert_eq!(puzzles.size_hint(), (3, Some(3)));
+        let mut ids = vec![first.puzzle_id()];
+        ids.extend(puzzles.map(|puzzle| puzzle.unwrap().puzzle_id()));
+        
+        // the same puzzles a batch writes out, none of them repeated
+        assert_eq!(run_with_words(config.clone(), &words).unwrap().ids, ids);
+        assert!(ids.iter().enumerate().all(|(i, id)| !ids[..i].contains(id)));
+        fs::remove_dir_all(&dir).unwrap();
+        
+        // and the same ones made again when there's only room for one layout
+        let words = vec![String::from("cat")];
+        let config = Config { width: 3, height: 1, difficulty: Difficulty::Easy, ..config };
+        let ids: Vec<String> = generate_many(&config, &words, 4).map(|puzzle| puzzle.unwrap().puzzle_id()).collect();
+        assert_eq!(run_with_words(config.clone(), &words).unwrap().ids, ids);
+        fs::remove_dir_all(&dir).unwrap();
+        
+        // a problem with the words is the only item
+        let words = vec![String::from("hippopotamus")];
+        let results: Vec<_> = generate_many(&config, &words, 4).collect();
+        assert!(matches!(results[..], [Err(WordfindError::InvalidWordList { .. })]));
+    }
+}
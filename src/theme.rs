@@ -0,0 +1,96 @@
+// Theme - the word lists built into the program, for a quick puzzle without a words file
+//
+#[derive(Debug,Clone,Copy,PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Theme {
+    Animals,
+    Countries,
+    States,
+    Colors,
+    Programming,
+}
+
+// Words picked from a theme when the command line doesn't say how many
+pub const THEME_WORDS: usize = 15;
+
+const ANIMALS: &[&str] = &[
+    "Alligator", "Antelope", "Badger", "Beaver", "Buffalo", "Camel", "Cheetah", "Chimpanzee", "Coyote", "Crocodile",
+    "Dolphin", "Donkey", "Eagle", "Elephant", "Ferret", "Flamingo", "Giraffe", "Gorilla", "Hamster", "Hedgehog",
+    "Hippopotamus", "Jaguar", "Kangaroo", "Koala", "Leopard", "Lion", "Llama", "Lobster", "Meerkat", "Moose",
+    "Octopus", "Ostrich", "Otter", "Panda", "Panther", "Parrot", "Peacock", "Penguin", "Porcupine", "Rabbit",
+    "Raccoon", "Rhinoceros", "Salamander", "Seal", "Shark", "Skunk", "Sloth", "Squirrel", "Tiger", "Tortoise",
+    "Walrus", "Whale", "Wolf", "Zebra",
+];
+
+const COUNTRIES: &[&str] = &[
+    "Argentina", "Australia", "Austria", "Belgium", "Brazil", "Canada", "Chile", "China", "Colombia", "Cuba",
+    "Denmark", "Egypt", "Ethiopia", "Finland", "France", "Germany", "Ghana", "Greece", "Iceland", "India",
+    "Indonesia", "Ireland", "Italy", "Jamaica", "Japan", "Kenya", "Mexico", "Morocco", "Nepal", "Netherlands",
+    "New Zealand", "Nigeria", "Norway", "Pakistan", "Peru", "Philippines", "Poland", "Portugal", "South Africa", "Spain",
+    "Sweden", "Switzerland", "Thailand", "Turkey", "Ukraine", "United Kingdom", "Uruguay", "Vietnam",
+];
+
+const STATES: &[&str] = &[
+    "Alabama", "Alaska", "Arizona", "Arkansas", "California", "Colorado", "Connecticut", "Delaware", "Florida", "Georgia",
+    "Hawaii", "Idaho", "Illinois", "Indiana", "Iowa", "Kansas", "Kentucky", "Louisiana", "Maine", "Maryland",
+    "Massachusetts", "Michigan", "Minnesota", "Mississippi", "Missouri", "Montana", "Nebraska", "Nevada", "New Hampshire", "New Jersey",
+    "New Mexico", "New York", "North Carolina", "North Dakota", "Ohio", "Oklahoma", "Oregon", "Pennsylvania", "Rhode Island", "South Carolina",
+    "South Dakota", "Tennessee", "Texas", "Utah", "Vermont", "Virginia", "Washington", "West Virginia", "Wisconsin", "Wyoming",
+];
+
+const COLORS: &[&str] = &[
+    "Amber", "Aqua", "Azure", "Beige", "Black", "Blue", "Bronze", "Brown", "Burgundy", "Charcoal",
+    "Coral", "Crimson", "Cyan", "Emerald", "Fuchsia", "Gold", "Gray", "Green", "Indigo", "Ivory",
+    "Jade", "Khaki", "Lavender", "Lilac", "Lime", "Magenta", "Maroon", "Mauve", "Mint", "Navy",
+    "Olive", "Orange", "Peach", "Pink", "Plum", "Purple", "Red", "Rose", "Ruby", "Rust",
+    "Salmon", "Scarlet", "Silver", "Tan", "Teal", "Turquoise", "Violet", "White", "Yellow",
+];
+
+const PROGRAMMING: &[&str] = &[
+    "Algorithm", "Array", "Boolean", "Branch", "Buffer", "Bug", "Byte", "Cache", "Class", "Compiler",
+    "Constant", "Debugger", "Function", "Garbage", "Hash", "Heap", "Integer", "Interface", "Iterator", "Kernel",
+    "Lambda", "Library", "Linker", "Loop", "Macro", "Memory", "Method", "Module", "Mutex", "Object",
+    "Parser", "Pointer", "Queue", "Recursion", "Register", "Runtime", "Socket", "Stack", "String", "Syntax",
+    "Thread", "Tuple", "Type", "Variable", "Vector",
+];
+
+impl Theme {
+    // from_name - the theme for a name given on the command line
+    pub fn from_name(name: &str) -> Option<Theme> {
+        match name.to_ascii_lowercase().as_str() {
+            "animals" => Some(Theme::Animals),
+            "countries" => Some(Theme::Countries),
+            "states" | "us-states" => Some(Theme::States),
+            "colors" | "colours" => Some(Theme::Colors),
+            "programming" => Some(Theme::Programming),
+            _ => None,
+        }
+    }
+    
+    // words - every word in the theme, a puzzle usually takes a sample of them
+    pub fn words(&self) -> &'static [&'static str] {
+        match self {
+            Theme::Animals => ANIMALS,
+            Theme::Countries => COUNTRIES,
+            Theme::States => STATES,
+            Theme::Colors => COLORS,
+            Theme::Programming => PROGRAMMING,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{letters, sanitize};
+    
+    #[test]
+    fn themes_fit() {
+        for name in ["animals", "countries", "us-states", "Colours", "programming"].iter() {
+            let words = Theme::from_name(name).unwrap().words();
+            assert!(words.len() > THEME_WORDS);
+            assert!(words.iter().all(|w| letters(&sanitize(w, "")).len() <= 20));
+        }
+        assert_eq!(Theme::from_name("cars"), None);
+    }
+}
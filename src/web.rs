@@ -0,0 +1,67 @@
+use crate::cli::options_config;
+use crate::render::escape_json;
+use crate::solve::search;
+use crate::{make_from_text, read_grid, Config, WordfindError};
+use wasm_bindgen::prelude::*;
+
+// The functions a web page calls, text in and json out so they're easy to hand across to
+// javascript, for running the generator in the browser built for wasm
+//
+// They're exported with wasm-bindgen, so a page loads the module wasm-pack builds and calls them as
+// javascript functions, the seed as a BigInt, generateJson(words, options, 42n), and a failure is
+// thrown as the error's json, the way --error-format json prints it
+//
+// The caller passes the seed so the same seed makes the same puzzle in the browser and on the
+// command line, and the puzzles carry no metadata since there's no clock to count on
+
+// generate_json - a filled in puzzle as render_json writes it, from the words, one to a line the
+//                 way a words file has them, and the options as a json object of command line
+//                 options, {"size": 12, "hard": true, "title": "Pets"}
+//                 A theme in the options takes the place of the words
+#[wasm_bindgen(js_name = generateJson)]
+pub fn generate_json(words: &str, options: &str, seed: u64) -> Result<String, WordfindError> {
+    Ok(make_from_text(options_config(options)?, words, seed)?.render_json())
+}
+
+// solve_json - where each word is in the grid, the grid in any of the forms solve reads and the
+//              words one to a line
+//
+// {"width":4,"height":3,"found":[{"word":"Cat","x":1,"y":0,"direction":"Right"}],"missing":["Bird"]}
+#[wasm_bindgen(js_name = solveJson)]
+pub fn solve_json(grid: &str, words: &str) -> Result<String, WordfindError> {
+    let config = Config::default();
+    let words: Vec<String> = words.lines().map(str::trim).map(String::from).collect();
+    let report = search(&config, read_grid(grid, config.delimiter)?, &words, None)?;
+    let found: Vec<String> = report.found.iter().map(|(word, places)| {
+        format!("{{\"word\":{},\"x\":{},\"y\":{},\"direction\":\"{:?}\"}}", escape_json(word), places[0].x, places[0].y, places[0].dir)
+    }).collect();
+    let missing: Vec<String> = report.missing.iter().map(|w| escape_json(w)).collect();
+    Ok(format!("{{\"width\":{},\"height\":{},\"found\":[{}],\"missing\":[{}]}}", report.width, report.height, found.join(","), missing.join(",")))
+}
+
+// the error javascript catches, its json as a string
+impl From<WordfindError> for JsValue {
+    fn from(err: WordfindError) -> JsValue {
+        JsValue::from_str(&err.to_json())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PuzzleGrid;
+    
+    #[test]
+    fn browser_puzzles() {
+        let json = generate_json("cat\ndog\nowl\n", "{\"size\": 6, \"hard\": true, \"title\": \"Pets\", \"answer_key\": false}", 42).unwrap();
+        assert_eq!(json, generate_json("cat\ndog\nowl\n", "{\"size\": 6, \"hard\": true, \"title\": \"Pets\"}", 42).unwrap());
+        let puzzle = PuzzleGrid::from_json(&json).unwrap();
+        assert_eq!((puzzle.title(), puzzle.entries().len(), puzzle.grid().len()), (Some("Pets"), 3, 6));
+        assert!(generate_json("", "{\"theme\": \"animals\"}", 7).unwrap().contains("\"grid\":["));
+        assert!(generate_json("cat", "{\"size\": \"big\"}", 1).is_err());
+        assert!(generate_json("cat", "[1]", 1).is_err());
+        
+        let solved = solve_json("ACAT\nANNA\nDOGX\n", "Cat\nBird\n").unwrap();
+        assert_eq!(solved, "{\"width\":4,\"height\":3,\"found\":[{\"word\":\"Cat\",\"x\":1,\"y\":0,\"direction\":\"Right\"}],\"missing\":[\"Bird\"]}");
+    }
+}
@@ -0,0 +1,209 @@
+use std::fmt;
+use std::ops::Deref;
+use rand::RngCore;
+use crate::{check_words, filter_words, is_word, letters, placed_word, plain_arabic, read_words, word_text, Config, WordfindError};
+
+// WordList - the lines of a word list the way a words file has them, words with their clues and
+//            pins, section headings, and comments, for loading and checking words without run
+//            It derefs to the lines, so it can be handed to generate as it is
+//
+// let words = WordList::load("animals.txt")?;
+// words.validate(&config)?;
+// let puzzle = generate(&config, &words.filter(&config, &mut rng), &mut rng)?;
+//
+#[derive(Debug,Clone,Default,PartialEq)]
+pub struct WordList {
+    lines: Vec<String>,
+}
+
+// WordStats - what's in a word list once it's been sanitized the way generate places the words
+//
+#[derive(Debug,Clone,Default,PartialEq)]
+pub struct WordStats {
+    // words that would go in the puzzle, without the duplicates
+    pub words: usize,
+    // words that would only be used once because they're already in the list, as they were typed
+    pub duplicates: Vec<String>,
+    // lines that would be left out because they have no letters
+    pub no_letters: Vec<String>,
+    // letters in all of the words together, and in the shortest and longest of them
+    pub letters: usize,
+    pub shortest: usize,
+    pub longest: usize,
+}
+
+impl WordStats {
+    // mean_length - the letters in an average word, 0 for no words
+    pub fn mean_length(&self) -> f32 {
+        if self.words == 0 { 0.0 } else { self.letters as f32 / self.words as f32 }
+    }
+}
+
+impl WordList {
+    pub fn new() -> WordList {
+        WordList::default()
+    }
+    
+    // from_text - the word list in a string, a line to a word, with a windows byte order mark and
+    //             the spaces around each line taken off
+    pub fn from_text(text: &str) -> WordList {
+        WordList { lines: text.trim_start_matches('\u{feff}').lines().map(str::trim).map(String::from).collect() }
+    }
+    
+    // load - the word list in a file, - for stdin or an http or https url with the http feature
+    pub fn load(file: &str) -> Result<WordList, WordfindError> {
+        Ok(WordList { lines: read_words(file)? })
+    }
+    
+    // push - add a line to the end, a word or a "[heading]" to start a section
+    pub fn push<S: Into<String>>(&mut self, line: S) {
+        self.lines.push(line.into().trim().to_string());
+    }
+    
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+    
+    pub fn into_lines(self) -> Vec<String> {
+        self.lines
+    }
+    
+    // words - each word as it was typed, without its clue or pin, leaving out the blank lines,
+    //         comments, and section headings
+    pub fn words(&self) -> Vec<&str> {
+        self.lines.iter().filter(|l| is_word(l)).map(|l| word_text(l)).collect()
+    }
+    
+    // placed - each word the way generate writes it into the grid, sanitized, in the config's case,
+    //          and without its accents with Config::fold_diacritics, leaving out the words that have
+    //          no letters and the ones already in the list
+    pub fn placed(&self, config: &Config) -> Vec<String> {
+        self.sorted(config).0
+    }
+    
+    // dedup - take out the lines for words already in the list and the ones with no letters, the
+    //         way generate would leave them out, and return them as they were typed
+    pub fn dedup(&mut self, config: &Config) -> Vec<String> {
+        let mut seen: Vec<String> = Vec::new();
+        let mut removed = Vec::new();
+        self.lines.retain(|line| {
+            if !is_word(line) {
+                return true;
+            }
+            let word = placed_word(config, &plain_arabic(word_text(line))).to_uppercase();
+            if word.is_empty() || seen.contains(&word) {
+                removed.push(word_text(line).to_string());
+                return false;
+            }
+            seen.push(word);
+            true
+        });
+        removed
+    }
+    
+    // validate - validate_words, with the problems as an error, and an InvalidSize error for a
+    //            grid with no room
+    pub fn validate(&self, config: &Config) -> Result<(), WordfindError> {
+        check_words(config, &self.lines)
+    }
+    
+    // filter - the words that go in the puzzle, see filter_words
+    pub fn filter(&self, config: &Config, rng: &mut dyn RngCore) -> WordList {
+        WordList { lines: filter_words(config, &self.lines, rng) }
+    }
+    
+    // stats - how many words there are and how long they are, once they're placed
+    pub fn stats(&self, config: &Config) -> WordStats {
+        let (placed, duplicates, no_letters) = self.sorted(config);
+        let lengths: Vec<usize> = placed.iter().map(|w| letters(w).len()).collect();
+        WordStats {
+            words: placed.len(),
+            duplicates,
+            no_letters,
+            letters: lengths.iter().sum(),
+            shortest: lengths.iter().copied().min().unwrap_or(0),
+            longest: lengths.iter().copied().max().unwrap_or(0),
+        }
+    }
+    
+    // sorted - the placed words, and the duplicates and the words with no letters as they were typed
+    fn sorted(&self, config: &Config) -> (Vec<String>, Vec<String>, Vec<String>) {
+        let (mut placed, mut duplicates, mut no_letters): (Vec<String>, Vec<String>, Vec<String>) = (Vec::new(), Vec::new(), Vec::new());
+        for typed in self.words() {
+            let word = placed_word(config, &plain_arabic(typed));
+            if word.is_empty() {
+                no_letters.push(typed.to_string());
+            } else if placed.iter().any(|w| w.to_uppercase() == word.to_uppercase()) {
+                duplicates.push(typed.to_string());
+            } else {
+                placed.push(word);
+            }
+        }
+        (placed, duplicates, no_letters)
+    }
+}
+
+impl Deref for WordList {
+    type Target = [String];
+    
+    fn deref(&self) -> &[String] {
+        &self.lines
+    }
+}
+
+impl From<Vec<String>> for WordList {
+    fn from(lines: Vec<String>) -> WordList {
+        WordList { lines }
+    }
+}
+
+impl<'a> From<&'a [&'a str]> for WordList {
+    fn from(lines: &'a [&'a str]) -> WordList {
+        WordList { lines: lines.iter().map(|l| l.trim().to_string()).collect() }
+    }
+}
+
+// the lines a line at a time, the way they'd be saved in a words file
+impl fmt::Display for WordList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in self.lines.iter() {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use crate::generate;
+    
+    #[test]
+    fn word_list() {
+        let mut list = WordList::from_text("\u{feff}# pets\r\n[Mammals]\r\n  Cat | says meow\r\nT-shirt\r\ncat\r\n--\r\nDog @ (0,0) Right\r\n");
+        assert_eq!(list.words(), ["Cat", "T-shirt", "cat", "--", "Dog"]);
+        let config = Config { width: 8, height: 8, ..Config::default() };
+        assert_eq!(list.placed(&config), ["CAT", "TSHIRT", "DOG"]);
+        
+        let stats = list.stats(&config);
+        assert_eq!((stats.words, stats.letters, stats.shortest, stats.longest), (3, 12, 3, 6));
+        assert_eq!(stats.duplicates, ["cat"]);
+        assert_eq!(stats.no_letters, ["--"]);
+        assert_eq!(stats.mean_length(), 4.0);
+        
+        assert_eq!(list.dedup(&config), ["cat", "--"]);
+        assert_eq!(list.to_string(), "# pets\n[Mammals]\nCat | says meow\nT-shirt\nDog @ (0,0) Right\n");
+        assert!(list.validate(&config).is_ok());
+        
+        // it goes to generate as it is
+        let mut rng = StdRng::seed_from_u64(5);
+        let puzzle = generate(&config, &list.filter(&config, &mut rng), &mut rng).unwrap();
+        assert_eq!(puzzle.entries().len(), 3);
+        
+        list.push("hippopotamus");
+        assert!(list.validate(&config).is_err());
+        assert_eq!(WordList::from(&["a", " b "][..]).lines(), ["a", "b"]);
+    }
+}